@@ -15,6 +15,18 @@ use syrillian_render::rendering::viewport::ViewportId;
 pub enum CameraError {
     #[snafu(display("The provided texture wasn't set to be renderable"))]
     NoRenderTexture,
+    #[snafu(display("Cameras can't render into render textures with the format {format:?}"))]
+    UnsupportedFormat { format: wgpu::TextureFormat },
+}
+
+/// How a camera projects the scene onto the screen.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum CameraProjection {
+    /// Perspective projection with the camera's field of view.
+    #[default]
+    Perspective,
+    /// Orthographic projection, showing [`CameraComponent::ortho_size`] world units vertically.
+    Orthographic,
 }
 
 #[derive(Debug, Reflect)]
@@ -29,11 +41,20 @@ pub struct CameraComponent {
     width: f32,
     height: f32,
     pub zoom_speed: f32,
+    ortho_size: f32,
+    pixels_per_unit: f32,
+    focus_distance: f32,
+    aperture: f32,
+    focal_length: f32,
 
+    #[dont_reflect]
+    projection_mode: CameraProjection,
     #[dont_reflect]
     projection_dirty: bool,
     #[dont_reflect]
     render_target: ViewportId,
+    #[dont_reflect]
+    cut: bool,
 }
 
 impl CameraComponent {
@@ -82,6 +103,116 @@ impl CameraComponent {
         (self.width, self.height)
     }
 
+    pub fn projection_mode(&self) -> CameraProjection {
+        self.projection_mode
+    }
+
+    pub fn set_projection_mode(&mut self, mode: CameraProjection) {
+        self.projection_mode = mode;
+        self.regenerate();
+    }
+
+    pub fn is_orthographic(&self) -> bool {
+        self.projection_mode == CameraProjection::Orthographic
+    }
+
+    /// Switches to an orthographic projection that shows `size` world units vertically.
+    pub fn set_orthographic(&mut self, size: f32) {
+        self.ortho_size = size.max(f32::EPSILON);
+        self.set_projection_mode(CameraProjection::Orthographic);
+    }
+
+    pub fn set_perspective(&mut self) {
+        self.set_projection_mode(CameraProjection::Perspective);
+    }
+
+    /// The world units shown vertically by the orthographic projection.
+    pub fn ortho_size(&self) -> f32 {
+        self.ortho_size
+    }
+
+    pub fn set_ortho_size(&mut self, size: f32) {
+        self.ortho_size = size.max(f32::EPSILON);
+        self.regenerate();
+    }
+
+    /// Pixels per world unit the orthographic projection is snapped to, if pixel perfect.
+    pub fn pixels_per_unit(&self) -> Option<f32> {
+        (self.pixels_per_unit > 0.0).then_some(self.pixels_per_unit)
+    }
+
+    /// Makes the orthographic projection pixel perfect for sprites with `pixels_per_unit`.
+    ///
+    /// Every texel is scaled to a whole number of screen pixels, as large as possible while
+    /// still showing at least [`ortho_size`](Self::ortho_size) world units vertically, and the
+    /// camera position is snapped to the screen pixels. `None` turns it off.
+    pub fn set_pixel_perfect(&mut self, pixels_per_unit: Option<f32>) {
+        self.pixels_per_unit = pixels_per_unit.unwrap_or(0.0).max(0.0);
+        self.regenerate();
+    }
+
+    /// Screen pixels per world unit of a pixel perfect orthographic projection.
+    pub fn pixel_scale(&self) -> Option<f32> {
+        if !self.is_orthographic() {
+            return None;
+        }
+        let pixels_per_unit = self.pixels_per_unit()?;
+        let texel_scale = (self.height / (self.ortho_size * pixels_per_unit))
+            .floor()
+            .max(1.0);
+        Some(pixels_per_unit * texel_scale)
+    }
+
+    /// Half of the width and height the orthographic projection shows, in world units.
+    pub fn ortho_half_extents(&self) -> Vec2 {
+        let half_height = match self.pixel_scale() {
+            Some(pixel_scale) => self.height / pixel_scale * 0.5,
+            None => self.ortho_size * 0.5,
+        };
+        Vec2::new(half_height * self.width / self.height, half_height)
+    }
+
+    /// Distance to the plane that is in perfect focus for depth of field, in world units.
+    pub fn focus_distance(&self) -> f32 {
+        self.focus_distance
+    }
+
+    pub fn set_focus_distance(&mut self, distance: f32) {
+        self.focus_distance = distance.max(f32::EPSILON);
+        self.projection_dirty = true;
+    }
+
+    /// The f-number of the lens. Zero is a pinhole camera without depth of field.
+    pub fn aperture(&self) -> f32 {
+        self.aperture
+    }
+
+    /// Sets the f-number of the lens. Smaller f-numbers blur everything outside the focus
+    /// plane more, like f/1.4 for a shallow and f/16 for a deep depth of field.
+    pub fn set_aperture(&mut self, f_number: f32) {
+        self.aperture = f_number.max(0.0);
+        self.projection_dirty = true;
+    }
+
+    /// Focal length of the lens in millimeters, on a full frame sensor.
+    pub fn focal_length(&self) -> f32 {
+        self.focal_length
+    }
+
+    /// Sets the focal length of the lens in millimeters. It only shapes the depth of field,
+    /// the field of view stays as it is.
+    pub fn set_focal_length(&mut self, millimeters: f32) {
+        self.focal_length = millimeters.max(1.0);
+        self.projection_dirty = true;
+    }
+
+    /// Sets all lens parameters of the depth of field at once.
+    pub fn set_depth_of_field(&mut self, focus_distance: f32, f_number: f32, focal_length: f32) {
+        self.set_focus_distance(focus_distance);
+        self.set_aperture(f_number);
+        self.set_focal_length(focal_length);
+    }
+
     #[inline]
     pub fn mouse_viewport_position(&self, x: f32, y: f32) -> Vec2 {
         Vec2::new(x.max(0.), y.max(0.))
@@ -105,9 +236,19 @@ impl CameraComponent {
     }
 
     pub fn click_ray(&self, x: f32, y: f32) -> Ray {
-        let eye = self.mouse_eye_dir(x, y);
+        let cam_to_world = self.parent().transform.rigid_global_isometry().to_mat4();
+
+        if self.is_orthographic() {
+            let ndc = self.mouse_viewport_ndc(x, y);
+            let near = self
+                .projection_inverse
+                .project_point3(Vec3::new(ndc.x, ndc.y, 0.0));
+            let origin = cam_to_world.transform_point3(near);
+            let dir_world = cam_to_world.transform_vector3(Vec3::NEG_Z).normalize();
+            return Ray::new(origin, dir_world);
+        }
 
-        let cam_to_world = self.parent().transform.view_matrix_rigid().to_mat4();
+        let eye = self.mouse_eye_dir(x, y);
 
         let origin = cam_to_world.transform_point3(Vec3::ZERO);
         let dir_world = (cam_to_world * eye).xyz().normalize();
@@ -116,12 +257,18 @@ impl CameraComponent {
     }
 
     pub fn regenerate(&mut self) {
-        self.projection = Mat4::perspective_rh(
-            self.fov_active.to_radians(),
-            self.width / self.height,
-            self.near,
-            self.far,
-        );
+        self.projection = match self.projection_mode {
+            CameraProjection::Perspective => Mat4::perspective_rh(
+                self.fov_active.to_radians(),
+                self.width / self.height,
+                self.near,
+                self.far,
+            ),
+            CameraProjection::Orthographic => {
+                let half = self.ortho_half_extents();
+                Mat4::orthographic_rh(-half.x, half.x, -half.y, half.y, self.near, self.far)
+            }
+        };
         self.projection_inverse = self.projection.inverse();
         self.projection_dirty = true;
     }
@@ -146,6 +293,18 @@ impl CameraComponent {
 
     pub fn set_render_target(&mut self, target: ViewportId) {
         self.render_target = target;
+        self.cut = true;
+    }
+
+    /// Marks a camera cut, like after teleporting the camera. The next frame doesn't blend in
+    /// or reproject from the frames before it, which would otherwise smear across the cut with
+    /// temporal anti-aliasing. Switching the active camera of a viewport does this on its own.
+    pub fn cut(&mut self) {
+        self.cut = true;
+    }
+
+    pub fn is_cut_pending(&self) -> bool {
+        self.cut
     }
 
     #[cfg(debug_assertions)]
@@ -168,7 +327,13 @@ impl CameraComponent {
         }
 
         let pos = obj.transform.position();
-        let view_mat = obj.transform.view_matrix_rigid().to_mat4();
+        let mut view_mat = obj.transform.view_matrix_rigid().to_mat4();
+        if let Some(pixel_scale) = self.pixel_scale() {
+            // snap the camera to whole screen pixels, so sprites don't shimmer while it moves
+            let translation = &mut view_mat.w_axis;
+            translation.x = (translation.x * pixel_scale).round() / pixel_scale;
+            translation.y = (translation.y * pixel_scale).round() / pixel_scale;
+        }
         Some(RenderMsg::UpdateActiveCamera(
             target_id,
             Box::new(move |cam| {
@@ -180,6 +345,15 @@ impl CameraComponent {
         ))
     }
 
+    pub fn maybe_cut(&mut self, target_id: ViewportId) -> Option<RenderMsg> {
+        if !self.cut {
+            return None;
+        }
+
+        self.cut = false;
+        Some(RenderMsg::CameraCut(target_id))
+    }
+
     #[profiling::function]
     pub fn maybe_projection_update(&mut self, target_id: ViewportId) -> Option<RenderMsg> {
         if !self.is_projection_dirty() {
@@ -194,6 +368,9 @@ impl CameraComponent {
         let far = self.far();
         let fov_target = self.fov_target();
         let zoom_speed = self.zoom_speed;
+        let focus_distance = self.focus_distance;
+        let aperture = self.aperture;
+        let focal_length = self.focal_length;
 
         Some(RenderMsg::UpdateActiveCamera(
             target_id,
@@ -206,6 +383,9 @@ impl CameraComponent {
                 cam.far = far;
                 cam.fov_target = fov_target;
                 cam.zoom_speed = zoom_speed;
+                cam.focus_distance = focus_distance;
+                cam.aperture = aperture;
+                cam.focal_length = focal_length;
             }),
         ))
     }
@@ -222,12 +402,19 @@ impl Default for CameraComponent {
             fov_active: 60.0,
             fov_target: 0.0,
             zoom_speed: 10.0,
+            ortho_size: 10.0,
+            pixels_per_unit: 0.0,
+            focus_distance: 10.0,
+            aperture: 0.0,
+            focal_length: 50.0,
+            projection_mode: CameraProjection::Perspective,
             near: 0.01,
             far: 1000.0,
             width: 800.0,
             height: 600.0,
             projection_dirty: true,
             render_target: ViewportId::PRIMARY,
+            cut: true,
         }
     }
 }
@@ -241,7 +428,10 @@ impl Component for CameraComponent {
     fn update(&mut self, world: &mut World) {
         let delta_time = world.delta_time().as_secs_f32();
 
-        if self.fov_target != 0.0 && (self.fov_active - self.fov_target).abs() > f32::EPSILON {
+        if !self.is_orthographic()
+            && self.fov_target != 0.0
+            && (self.fov_active - self.fov_target).abs() > f32::EPSILON
+        {
             self.fov_active = self
                 .fov_active
                 .lerp(self.fov_target, self.zoom_speed * delta_time);
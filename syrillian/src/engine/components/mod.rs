@@ -41,7 +41,7 @@ pub mod camera;
 pub mod camera_debug;
 mod ui_context;
 
-pub use camera::CameraComponent;
+pub use camera::{CameraComponent, CameraError, CameraProjection};
 pub use ui_context::UiContext;
 
 #[cfg(debug_assertions)]
@@ -52,6 +52,7 @@ use crate::core::GameObjectId;
 use crate::core::component_context_inference::ComponentContextInference;
 use crate::core::reflection::{ReflectedTypeInfo, Value, type_info};
 use crate::utils::TypedComponentHelper;
+use crate::utils::animation::AnimationEvent;
 use delegate::delegate;
 use slotmap::Key;
 use std::any::{Any, TypeId};
@@ -406,6 +407,9 @@ pub trait Component: Any {
 
     fn on_click(&mut self, _world: &mut World) {}
 
+    // Gets called when an animation on this game object passes one of its clip's events
+    fn on_animation_event(&mut self, world: &mut World, event: &AnimationEvent) {}
+
     fn on_gui(&mut self, world: &mut World, ctx: UiContext) {}
 
     // Gets called when the component is about to be deleted
@@ -16,3 +16,27 @@ pub enum Value {
     Object(BTreeMap<String, Value>),
     Array(Vec<Value>),
 }
+
+impl Value {
+    /// The value as a number, if it is one.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Float(v) => Some(v as f64),
+            Value::Double(v) => Some(v),
+            Value::UInt(v) => Some(v as f64),
+            Value::Int(v) => Some(v as f64),
+            Value::BigUInt(v) => Some(v as f64),
+            Value::BigInt(v) => Some(v as f64),
+            Value::VeryBigUInt(v) => Some(v as f64),
+            Value::VeryBigInt(v) => Some(v as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Named collision layers with a project-wide interaction matrix.
+//!
+//! Every collider is on exactly one [`CollisionLayer`]. Whether two colliders touch, and whether a
+//! query sees a collider, is decided by the [`CollisionLayers`] matrix of the
+//! [`PhysicsSimulation`](super::PhysicsSimulation). By default, all layers interact with each
+//! other.
+
+use rapier3d::geometry::{Group, InteractionGroups, InteractionTestMode};
+
+/// Maximum number of collision layers, given by the width of rapier's interaction groups.
+pub const MAX_COLLISION_LAYERS: usize = 32;
+
+/// A collision layer, see [`CollisionLayers`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CollisionLayer(u8);
+
+impl CollisionLayer {
+    /// The layer every collider starts on.
+    pub const DEFAULT: CollisionLayer = CollisionLayer(0);
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    fn bit(self) -> u32 {
+        1 << self.0
+    }
+}
+
+/// Names of the collision layers and which layers interact with each other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollisionLayers {
+    names: Vec<String>,
+    /// Per layer, the layers it interacts with as a bit mask
+    masks: [u32; MAX_COLLISION_LAYERS],
+    revision: u64,
+}
+
+impl Default for CollisionLayers {
+    fn default() -> Self {
+        Self {
+            names: vec!["Default".to_string()],
+            masks: [u32::MAX; MAX_COLLISION_LAYERS],
+            revision: 0,
+        }
+    }
+}
+
+impl CollisionLayers {
+    /// Returns the layer called `name`, and adds it if there is none yet. New layers interact
+    /// with all layers. Returns `None` if all layers are used already.
+    pub fn add(&mut self, name: impl Into<String>) -> Option<CollisionLayer> {
+        let name = name.into();
+        if let Some(layer) = self.layer(&name) {
+            return Some(layer);
+        }
+        if self.names.len() >= MAX_COLLISION_LAYERS {
+            return None;
+        }
+
+        self.names.push(name);
+        Some(CollisionLayer((self.names.len() - 1) as u8))
+    }
+
+    /// The layer called `name`.
+    pub fn layer(&self, name: &str) -> Option<CollisionLayer> {
+        self.names
+            .iter()
+            .position(|layer| layer == name)
+            .map(|index| CollisionLayer(index as u8))
+    }
+
+    pub fn name(&self, layer: CollisionLayer) -> Option<&str> {
+        self.names.get(layer.index()).map(String::as_str)
+    }
+
+    /// Number of layers that were added, including the default layer.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Sets whether colliders on `a` and `b` touch each other, and whether queries made for one
+    /// layer see colliders on the other. The matrix is symmetric.
+    pub fn set_interaction(&mut self, a: CollisionLayer, b: CollisionLayer, interacts: bool) {
+        for (layer, other) in [(a, b), (b, a)] {
+            let mask = &mut self.masks[layer.index()];
+            if interacts {
+                *mask |= other.bit();
+            } else {
+                *mask &= !other.bit();
+            }
+        }
+        self.revision += 1;
+    }
+
+    /// Sets whether `layer` interacts with every layer, including itself.
+    pub fn set_interacts_with_all(&mut self, layer: CollisionLayer, interacts: bool) {
+        for other in 0..MAX_COLLISION_LAYERS {
+            self.set_interaction(layer, CollisionLayer(other as u8), interacts);
+        }
+    }
+
+    pub fn interacts(&self, a: CollisionLayer, b: CollisionLayer) -> bool {
+        self.masks[a.index()] & b.bit() != 0
+    }
+
+    /// Interaction groups of a collider on `layer`.
+    pub fn groups(&self, layer: CollisionLayer) -> InteractionGroups {
+        InteractionGroups::new(
+            Group::from_bits_retain(layer.bit()),
+            Group::from_bits_retain(self.masks[layer.index()]),
+            InteractionTestMode::And,
+        )
+    }
+
+    /// Increases whenever the interaction matrix changes, so colliders know when to update.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interactions_are_symmetric() {
+        let mut layers = CollisionLayers::default();
+        let player = layers.add("Player").unwrap();
+        let debris = layers.add("Debris").unwrap();
+        assert_eq!(layers.add("Player"), Some(player));
+
+        layers.set_interaction(player, debris, false);
+        assert!(!layers.interacts(player, debris));
+        assert!(!layers.interacts(debris, player));
+        assert!(layers.interacts(player, CollisionLayer::DEFAULT));
+        assert!(!layers.groups(player).test(layers.groups(debris)));
+        assert!(layers.groups(player).test(layers.groups(player)));
+    }
+}
@@ -0,0 +1,118 @@
+//! Surface properties of colliders.
+
+use rapier3d::dynamics::CoefficientCombineRule;
+use rapier3d::geometry::Collider;
+use std::collections::HashMap;
+
+/// Friction, bounciness and density of a collider.
+///
+/// A material set directly on a collider is a plain copy. To share one material between
+/// colliders, register it by name in the [`PhysicsMaterials`] of the
+/// [`PhysicsSimulation`](super::PhysicsSimulation). Colliders using a registered material pick up
+/// changes when it's registered again. Registered materials can also be picked with the
+/// `physics_material` property of an object, e.g. from glTF extras.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PhysicsMaterial {
+    pub friction: f32,
+    /// How much of the speed is kept when bouncing off something, from 0 to 1
+    pub restitution: f32,
+    /// How the friction of two touching colliders is combined
+    pub friction_combine: CoefficientCombineRule,
+    /// How the restitution of two touching colliders is combined
+    pub restitution_combine: CoefficientCombineRule,
+    /// Mass per cubic meter
+    pub density: f32,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl PhysicsMaterial {
+    pub const DEFAULT: PhysicsMaterial = PhysicsMaterial::new(0.999, 0.0);
+    pub const ICE: PhysicsMaterial =
+        PhysicsMaterial::new(0.02, 0.05).with_friction_combine(CoefficientCombineRule::Min);
+    pub const RUBBER: PhysicsMaterial =
+        PhysicsMaterial::new(1.0, 0.8).with_restitution_combine(CoefficientCombineRule::Max);
+
+    pub const fn new(friction: f32, restitution: f32) -> Self {
+        Self {
+            friction,
+            restitution,
+            friction_combine: CoefficientCombineRule::Average,
+            restitution_combine: CoefficientCombineRule::Average,
+            density: 1.0,
+        }
+    }
+
+    pub const fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    pub const fn with_friction_combine(mut self, rule: CoefficientCombineRule) -> Self {
+        self.friction_combine = rule;
+        self
+    }
+
+    pub const fn with_restitution_combine(mut self, rule: CoefficientCombineRule) -> Self {
+        self.restitution_combine = rule;
+        self
+    }
+
+    /// Sets the material of `collider`.
+    pub fn apply(&self, collider: &mut Collider) {
+        collider.set_friction(self.friction);
+        collider.set_restitution(self.restitution);
+        collider.set_friction_combine_rule(self.friction_combine);
+        collider.set_restitution_combine_rule(self.restitution_combine);
+        collider.set_density(self.density);
+    }
+
+    /// Parses a combine rule by its name, like `"average"`, `"min"`, `"multiply"` or `"max"`.
+    pub fn parse_combine_rule(name: &str) -> Option<CoefficientCombineRule> {
+        match name.to_ascii_lowercase().as_str() {
+            "average" => Some(CoefficientCombineRule::Average),
+            "min" => Some(CoefficientCombineRule::Min),
+            "multiply" => Some(CoefficientCombineRule::Multiply),
+            "max" => Some(CoefficientCombineRule::Max),
+            "clamped_sum" => Some(CoefficientCombineRule::ClampedSum),
+            _ => None,
+        }
+    }
+}
+
+/// Physics materials shared between colliders by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhysicsMaterials {
+    materials: HashMap<String, PhysicsMaterial>,
+    revision: u64,
+}
+
+impl PhysicsMaterials {
+    /// Registers `material` under `name`, replacing a material registered before. Colliders using
+    /// the name switch to the new material.
+    pub fn register(&mut self, name: impl Into<String>, material: PhysicsMaterial) {
+        self.materials.insert(name.into(), material);
+        self.revision += 1;
+    }
+
+    pub fn get(&self, name: &str) -> Option<PhysicsMaterial> {
+        self.materials.get(name).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.materials.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.materials.is_empty()
+    }
+
+    /// Increases whenever a material is registered, so colliders know when to update.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
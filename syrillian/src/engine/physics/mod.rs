@@ -1,10 +1,16 @@
 //! Physics simulation powered by `rapier`.
 //!
 //! The [`PhysicsSimulation`] struct manages rigid bodies / joints, etc.
-//! and executes physics steps each frame.
+//! and executes physics steps each frame. Colliders get their surface from a
+//! [`PhysicsMaterial`], optionally shared through [`PhysicsMaterials`], and are sorted into
+//! [`CollisionLayers`].
 
+pub mod layers;
+pub mod material;
 pub mod simulator;
 
+pub use layers::{CollisionLayer, CollisionLayers};
+pub use material::{PhysicsMaterial, PhysicsMaterials};
 pub use simulator::*;
 
 pub use ::rapier3d;
@@ -1,5 +1,6 @@
 use crate::World;
 use crate::core::GameObjectId;
+use crate::physics::{CollisionLayer, CollisionLayers, PhysicsMaterial, PhysicsMaterials};
 use rapier3d::dynamics::{
     CCDSolver, ImpulseJointSet, IntegrationParameters, IslandManager, MultibodyJointSet,
     RigidBodySet,
@@ -31,6 +32,11 @@ pub struct PhysicsSimulation {
     pub physics_hooks: (),
     pub event_handler: (),
 
+    /// Collision layers of the project and which of them interact
+    pub layers: CollisionLayers,
+    /// Materials that colliders can share by name
+    pub materials: PhysicsMaterials,
+
     /// This is the "timepoint" of where the simulation is currently at,
     /// which works as an accumulator.
     ///
@@ -71,6 +77,8 @@ impl Default for PhysicsSimulation {
             ccd_solver: CCDSolver,
             physics_hooks: (),
             event_handler: (),
+            layers: CollisionLayers::default(),
+            materials: PhysicsMaterials::default(),
             current_timepoint: Instant::now(),
             timestep,
             alpha: 0.0,
@@ -108,6 +116,23 @@ impl PhysicsSimulation {
         }
     }
 
+    /// Registers `material` under `name`, replacing a material registered before. Colliders using
+    /// the name switch to the new material in their next fixed update.
+    pub fn register_material(&mut self, name: impl Into<String>, material: PhysicsMaterial) {
+        self.materials.register(name, material);
+    }
+
+    pub fn material(&self, name: &str) -> Option<PhysicsMaterial> {
+        self.materials.get(name)
+    }
+
+    /// Query filter that only sees colliders on layers interacting with `layer`.
+    pub fn layer_filter(&self, layer: CollisionLayer) -> QueryFilter<'static> {
+        QueryFilter::default().groups(self.layers.groups(layer))
+    }
+
+    /// Closest collider hit by `ray` and the distance to it. Only `filter` decides which colliders
+    /// are hit, use [`Self::cast_ray_on_layer`] to respect collision layers.
     pub fn cast_ray(
         &self,
         ray: &Ray,
@@ -129,6 +154,22 @@ impl PhysicsSimulation {
         object.exists().then_some((distance, object))
     }
 
+    /// [`Self::cast_ray`] for a query on `layer`, so it only hits colliders on layers interacting
+    /// with `layer`. Collision groups set on `filter` are replaced.
+    pub fn cast_ray_on_layer(
+        &self,
+        ray: &Ray,
+        max_toi: f32,
+        solid: bool,
+        layer: CollisionLayer,
+        filter: QueryFilter,
+    ) -> Option<(f32, GameObjectId)> {
+        let filter = filter.groups(self.layers.groups(layer));
+        self.cast_ray(ray, max_toi, solid, filter)
+    }
+
+    /// Closest collider hit by a sphere moving along `dir`. Like [`Self::cast_ray`], only `filter`
+    /// decides which colliders are hit, use [`Self::cast_sphere_on_layer`] to respect layers.
     pub fn cast_sphere<P: Into<Pose>, V: Into<Vector>>(
         &self,
         radius: f32,
@@ -155,6 +196,21 @@ impl PhysicsSimulation {
         object.exists().then_some((hit, object))
     }
 
+    /// [`Self::cast_sphere`] for a query on `layer`, so it only hits colliders on layers
+    /// interacting with `layer`. Collision groups set on `filter` are replaced.
+    pub fn cast_sphere_on_layer<P: Into<Pose>, V: Into<Vector>>(
+        &self,
+        radius: f32,
+        max_toi: f32,
+        shape_pos: P,
+        dir: V,
+        layer: CollisionLayer,
+        filter: QueryFilter,
+    ) -> Option<(ShapeCastHit, GameObjectId)> {
+        let filter = filter.groups(self.layers.groups(layer));
+        self.cast_sphere(radius, max_toi, shape_pos, dir, filter)
+    }
+
     pub fn cursor_ray(&self, world: &World) -> Option<Ray> {
         let cursor_pos = world.input.mouse_position();
         world
@@ -163,6 +219,7 @@ impl PhysicsSimulation {
             .map(|cam| cam.click_ray(cursor_pos.x, cursor_pos.y))
     }
 
+    /// [`Self::cast_ray`] along the [`Self::cursor_ray`].
     pub fn cast_cursor_ray(
         &self,
         world: &World,
@@ -174,6 +231,19 @@ impl PhysicsSimulation {
         self.cast_ray(&ray, max_toi, solid, filter)
     }
 
+    /// [`Self::cast_ray_on_layer`] along the [`Self::cursor_ray`].
+    pub fn cast_cursor_ray_on_layer(
+        &self,
+        world: &World,
+        max_toi: f32,
+        solid: bool,
+        layer: CollisionLayer,
+        filter: QueryFilter,
+    ) -> Option<(f32, GameObjectId)> {
+        let ray = self.cursor_ray(world)?;
+        self.cast_ray_on_layer(&ray, max_toi, solid, layer, filter)
+    }
+
     pub fn shutdown(&mut self) {
         self.is_shutting_down = true;
     }
@@ -5,7 +5,7 @@
 //! offers utilities such as methods to create, find and remove game objects.
 
 use crate::audio::AudioScene;
-use crate::components::{CRef, CWeak, CameraComponent, Component, UiContext};
+use crate::components::{CRef, CWeak, CameraComponent, CameraError, Component, UiContext};
 use crate::core::component_storage::ComponentStorage;
 use crate::core::{EventType, GameObject, GameObjectId, GameObjectRef, ObjectHash, Transform};
 use crate::engine::prefabs::Prefab;
@@ -22,8 +22,8 @@ use std::sync::Arc;
 use syrillian_asset::store::Store;
 use syrillian_asset::{AssetStore, ComputeShader};
 use syrillian_asset::{
-    BGL, Cubemap, HCubemap, Material, MaterialInstance, Mesh, RenderCubemap, RenderTexture2D,
-    RenderTexture2DArray, Shader, Sound, Texture2D, Texture2DArray,
+    BGL, Cubemap, HCubemap, HRenderTexture2D, HShader, Material, MaterialInstance, Mesh,
+    RenderCubemap, RenderTexture2D, RenderTexture2DArray, Shader, Sound, Texture2D, Texture2DArray,
 };
 use syrillian_render::strobe::StrobeFrame;
 use tracing::info;
@@ -34,6 +34,10 @@ use crate::math::UVec2;
 use crossbeam_channel::unbounded;
 use crossbeam_channel::{Receiver, Sender};
 use syrillian_macros::Reflect;
+use syrillian_render::passes::post_process::{
+    ColorGradingSettings, ExposureSettings, PostProcessId, PostProcessMaterialPass,
+    PostProcessParams, PostProcessPass, PostProcessStage,
+};
 use syrillian_render::rendering::CPUDrawCtx;
 use syrillian_render::rendering::message::{GBufferDebugTargets, RenderMsg};
 use syrillian_render::rendering::picking::{PickRequest, PickResult};
@@ -151,6 +155,10 @@ impl WorldChannels {
         self.next_target_id += 1;
         target_id
     }
+
+    pub fn remove_target(&mut self, target: ViewportId) -> bool {
+        target != ViewportId::PRIMARY && self.viewports.remove(&target).is_some()
+    }
 }
 
 /// Central structure representing the running scene.
@@ -644,6 +652,52 @@ impl World {
         target_id
     }
 
+    /// Renders `camera` into the render `texture` every frame, instead of into a window.
+    ///
+    /// The texture has to be made with [`RenderTexture2D::new_camera_target`]. Materials can
+    /// sample it with [`MaterialInstance::set_render_texture`]. The camera shouldn't be the
+    /// active camera of a window at the same time.
+    pub fn render_camera_to_texture(
+        &mut self,
+        mut camera: CRef<CameraComponent>,
+        texture: HRenderTexture2D,
+    ) -> Result<ViewportId, CameraError> {
+        let size = {
+            let Some(render_texture) = self.assets.render_textures.try_get(texture) else {
+                return Err(CameraError::NoRenderTexture);
+            };
+            if !render_texture.is_camera_target() {
+                return Err(CameraError::UnsupportedFormat {
+                    format: render_texture.format,
+                });
+            }
+            PhysicalSize::new(render_texture.width, render_texture.height)
+        };
+
+        let target_id = self.channels.add_window(camera.clone().downgrade(), size);
+        camera.set_render_target(target_id);
+        camera.resize(size.width as f32, size.height as f32);
+        camera.parent().transform.set_dirty();
+
+        let _ = self
+            .channels
+            .render_tx
+            .send(RenderMsg::AddTextureTarget(target_id, texture));
+        Ok(target_id)
+    }
+
+    /// Stops rendering into the render texture of `target`, which was returned by
+    /// [`World::render_camera_to_texture`].
+    pub fn remove_texture_target(&mut self, target: ViewportId) -> bool {
+        if !self.channels.remove_target(target) {
+            return false;
+        }
+        self.channels
+            .render_tx
+            .send(RenderMsg::RemoveTextureTarget(target))
+            .is_ok()
+    }
+
     pub fn active_camera(&self) -> CWeak<CameraComponent> {
         self.main_active_camera
     }
@@ -941,6 +995,10 @@ impl World {
         if let Some(update) = active_camera.maybe_projection_update(target_id) {
             batch.push(update);
         }
+
+        if let Some(cut) = active_camera.maybe_cut(target_id) {
+            batch.push(cut);
+        }
     }
 
     /// Internally sync removed components to the Render Thread for proxy deletion
@@ -1135,6 +1193,113 @@ impl World {
         self.set_viewport_sky_atmosphere(ViewportId::PRIMARY, settings)
     }
 
+    /// Sets how the scene color of the viewport is exposed, fixed or adapting to the scene
+    /// brightness over time.
+    pub fn set_viewport_exposure(&self, target: ViewportId, settings: ExposureSettings) -> bool {
+        self.channels
+            .render_tx
+            .send(RenderMsg::SetExposure(target, settings))
+            .is_ok()
+    }
+
+    pub fn set_exposure(&self, settings: ExposureSettings) -> bool {
+        self.set_viewport_exposure(ViewportId::PRIMARY, settings)
+    }
+
+    /// Sets the tonemapper, white balance, contrast, saturation and lookup table of the viewport
+    pub fn set_viewport_color_grading(
+        &self,
+        target: ViewportId,
+        settings: ColorGradingSettings,
+    ) -> bool {
+        self.channels
+            .render_tx
+            .send(RenderMsg::SetColorGrading(target, settings))
+            .is_ok()
+    }
+
+    pub fn set_color_grading(&self, settings: ColorGradingSettings) -> bool {
+        self.set_viewport_color_grading(ViewportId::PRIMARY, settings)
+    }
+
+    /// Sets the global level of detail bias. Values above 1 switch meshes to their simplified
+    /// levels earlier, `0.0` always renders full detail.
+    pub fn set_lod_bias(&self, bias: f32) -> bool {
+        self.channels
+            .render_tx
+            .send(RenderMsg::SetLodBias(bias))
+            .is_ok()
+    }
+
+    /// Adds a custom post process `pass` to the viewport `target`. Passes of the same `stage`
+    /// run by ascending `order`. Returns `None` if the renderer is gone.
+    pub fn add_viewport_post_process(
+        &self,
+        target: ViewportId,
+        stage: PostProcessStage,
+        order: i32,
+        pass: impl PostProcessPass + Send + 'static,
+    ) -> Option<PostProcessId> {
+        let id = PostProcessId::next();
+        self.channels
+            .render_tx
+            .send(RenderMsg::AddPostProcess(
+                target,
+                id,
+                stage,
+                order,
+                Box::new(pass),
+            ))
+            .ok()?;
+        Some(id)
+    }
+
+    /// Adds a custom post process `pass` to the primary viewport.
+    pub fn add_post_process(
+        &self,
+        stage: PostProcessStage,
+        order: i32,
+        pass: impl PostProcessPass + Send + 'static,
+    ) -> Option<PostProcessId> {
+        self.add_viewport_post_process(ViewportId::PRIMARY, stage, order, pass)
+    }
+
+    /// Adds a post process pass drawing the post process `shader` to the primary viewport,
+    /// see [`Shader::new_post_process_material`](syrillian_asset::Shader::new_post_process_material).
+    pub fn add_post_process_material(
+        &self,
+        stage: PostProcessStage,
+        order: i32,
+        shader: HShader,
+        params: PostProcessParams,
+    ) -> Option<PostProcessId> {
+        let pass = PostProcessMaterialPass::new(shader).with_params(params);
+        self.add_viewport_post_process(ViewportId::PRIMARY, stage, order, pass)
+    }
+
+    pub fn remove_post_process(&self, id: PostProcessId) -> bool {
+        self.channels
+            .render_tx
+            .send(RenderMsg::RemovePostProcess(id))
+            .is_ok()
+    }
+
+    pub fn set_post_process_enabled(&self, id: PostProcessId, enabled: bool) -> bool {
+        self.channels
+            .render_tx
+            .send(RenderMsg::SetPostProcessEnabled(id, enabled))
+            .is_ok()
+    }
+
+    /// Sets the parameters of a custom post process pass, which its shader reads with
+    /// `post_param(index)`.
+    pub fn set_post_process_params(&self, id: PostProcessId, params: PostProcessParams) -> bool {
+        self.channels
+            .render_tx
+            .send(RenderMsg::SetPostProcessParams(id, params))
+            .is_ok()
+    }
+
     /// Prints information about all game objects in the world to the log
     ///
     /// This method will print out the scene graph to the console and add some information about
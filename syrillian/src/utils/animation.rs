@@ -2,19 +2,64 @@ use crate::core::GameObjectId;
 use crate::math::{Quat, Vec3};
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+use std::ops::{Add, Mul};
+
+/// How values between two keys of a track are computed, matching the glTF sampler modes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    /// Holds the value of a key until the next key is reached.
+    Step,
+    /// Cubic Hermite spline through the keys, shaped by the [`Tangents`] of each key.
+    CubicSpline,
+}
+
+/// Tangents of one key of a [`Interpolation::CubicSpline`] track, in units per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tangents<T> {
+    pub in_tangent: T,
+    pub out_tangent: T,
+}
 
 /// Per-node keyframes.
 /// Times are in **seconds**.
+///
+/// Every track has its own interpolation mode. Cubic spline tracks have one set of tangents per
+/// key, other tracks leave them empty.
 #[derive(Debug, Clone, Default)]
 pub struct TransformKeys {
     pub t_times: Vec<f32>,
     pub t_values: Vec<Vec3>,
+    pub t_interpolation: Interpolation,
+    pub t_tangents: Vec<Tangents<Vec3>>,
 
     pub r_times: Vec<f32>,
     pub r_values: Vec<Quat>,
+    pub r_interpolation: Interpolation,
+    pub r_tangents: Vec<Tangents<Quat>>,
 
     pub s_times: Vec<f32>,
     pub s_values: Vec<Vec3>,
+    pub s_interpolation: Interpolation,
+    pub s_tangents: Vec<Tangents<Vec3>>,
+
+    /// Morph target weights, one list holding the weight of every target per key.
+    pub w_times: Vec<f32>,
+    pub w_values: Vec<Vec<f32>>,
+    pub w_interpolation: Interpolation,
+    pub w_tangents: Vec<Tangents<Vec<f32>>>,
+}
+
+impl TransformKeys {
+    pub fn has_transform(&self) -> bool {
+        !self.t_times.is_empty() || !self.r_times.is_empty() || !self.s_times.is_empty()
+    }
+
+    pub fn has_morph_weights(&self) -> bool {
+        !self.w_times.is_empty()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -23,12 +68,68 @@ pub struct Channel {
     pub keys: TransformKeys,
 }
 
+/// A named point in time of a clip. When playback passes it, the event is sent to the components
+/// of the animated object through
+/// [`Component::on_animation_event`](crate::components::Component::on_animation_event).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationEvent {
+    pub name: String,
+    /// Time in seconds
+    pub time: f32,
+}
+
+impl AnimationEvent {
+    pub fn new(name: impl Into<String>, time: f32) -> Self {
+        Self {
+            name: name.into(),
+            time,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AnimationClip {
     pub name: String,
     /// Duration in seconds
     pub duration: f32,
     pub channels: Vec<Channel>,
+    pub events: Vec<AnimationEvent>,
+}
+
+impl AnimationClip {
+    /// Events passed when playback moves by `delta` seconds and ends up at `time`.
+    ///
+    /// Playback covers `[time - delta, time)`, so an event is reached exactly once, even if it sits
+    /// on a frame boundary. Looping clips report every loop that was passed, and reversed playback
+    /// (negative `delta`) covers the range backwards. Events at the very end of a clip that doesn't
+    /// loop fire once playback reaches the end.
+    pub fn events_between(
+        &self,
+        time: f32,
+        delta: f32,
+        looping: bool,
+    ) -> impl Iterator<Item = &AnimationEvent> {
+        let start = time - delta;
+        let duration = self.duration;
+
+        self.events.iter().flat_map(move |event| {
+            let te = event.time;
+            let count = if delta == 0.0 || duration <= 0.0 {
+                0
+            } else if looping && delta > 0.0 {
+                ((time - te) / duration).ceil() as i64 - ((start - te) / duration).ceil() as i64
+            } else if looping {
+                ((start - te) / duration).floor() as i64 - ((time - te) / duration).floor() as i64
+            } else if delta > 0.0 {
+                let at_end = time >= duration && te <= time;
+                (te >= start && (te < time || at_end)) as i64
+            } else {
+                let at_start = time <= 0.0 && te >= time;
+                (te <= start && (te > time || at_start)) as i64
+            };
+            std::iter::repeat_n(event, count.max(0) as usize)
+        })
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -49,7 +150,12 @@ impl ClipIndex {
 #[derive(Debug, Clone, Copy)]
 pub enum Binding {
     Transform(GameObjectId),
-    Bone { skel: GameObjectId, idx: usize },
+    Bone {
+        skel: GameObjectId,
+        idx: usize,
+    },
+    /// Morph target weights of the mesh renderer on the object.
+    MorphWeights(GameObjectId),
 }
 
 #[derive(Debug, Clone)]
@@ -92,68 +198,197 @@ fn find_key(times: &[f32], t: f32) -> usize {
     }
     times
         .binary_search_by(|k| k.partial_cmp(&t).unwrap_or(Ordering::Equal))
-        .unwrap_or_else(|i| (i - 1).max(0))
+        .unwrap_or_else(|i| i - 1)
 }
 
 fn lerp_vec3(a: &Vec3, b: &Vec3, alpha: f32) -> Vec3 {
     a * (1.0 - alpha) + b * alpha
 }
 
-pub fn sample_translation(keys: &TransformKeys, t: f32) -> Option<Vec3> {
-    let n = keys.t_times.len();
-    if n == 0 {
-        return None;
-    }
-    if n == 1 {
-        return Some(keys.t_values[0]);
-    }
+/// Cubic Hermite spline between `p0` and `p1`, with tangents already scaled to the key distance.
+fn hermite<T>(p0: T, m0: T, p1: T, m1: T, s: f32) -> T
+where
+    T: Add<Output = T> + Mul<f32, Output = T>,
+{
+    let s2 = s * s;
+    let s3 = s2 * s;
+    p0 * (2.0 * s3 - 3.0 * s2 + 1.0)
+        + m0 * (s3 - 2.0 * s2 + s)
+        + p1 * (-2.0 * s3 + 3.0 * s2)
+        + m1 * (s3 - s2)
+}
 
-    let i = find_key(&keys.t_times, t);
-    if i == n - 1 {
-        return Some(keys.t_values[i]);
-    }
-    let t0 = keys.t_times[i];
-    let t1 = keys.t_times[i + 1];
-    let a = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
-    Some(lerp_vec3(&keys.t_values[i], &keys.t_values[i + 1], a))
+enum KeySpan {
+    /// Exactly one key applies, e.g. after the last key or with step interpolation.
+    At(usize),
+    /// Between key `i` and `i + 1`, `alpha` being the normalized position and `dt` the key
+    /// distance.
+    Between { i: usize, alpha: f32, dt: f32 },
 }
 
-pub fn sample_scale(keys: &TransformKeys, t: f32) -> Option<Vec3> {
-    let n = keys.s_times.len();
+fn key_span(times: &[f32], t: f32, interpolation: Interpolation) -> Option<KeySpan> {
+    let n = times.len();
     if n == 0 {
         return None;
     }
-    if n == 1 {
-        return Some(keys.s_values[0]);
+
+    let i = find_key(times, t);
+    if n == 1 || i == n - 1 || interpolation == Interpolation::Step {
+        return Some(KeySpan::At(i));
     }
 
-    let i = find_key(&keys.s_times, t);
-    if i == n - 1 {
-        return Some(keys.s_values[i]);
+    let dt = times[i + 1] - times[i];
+    let alpha = if dt > 0.0 { (t - times[i]) / dt } else { 0.0 };
+    let alpha = match interpolation {
+        // splines overshoot quickly, so they aren't extrapolated before the first key
+        Interpolation::CubicSpline => alpha.clamp(0.0, 1.0),
+        _ => alpha,
+    };
+
+    Some(KeySpan::Between { i, alpha, dt })
+}
+
+fn sample_track<T>(
+    times: &[f32],
+    values: &[T],
+    interpolation: Interpolation,
+    tangents: &[Tangents<T>],
+    t: f32,
+    lerp: impl Fn(T, T, f32) -> T,
+) -> Option<T>
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    let (i, alpha, dt) = match key_span(times, t, interpolation)? {
+        KeySpan::At(i) => return Some(values[i]),
+        KeySpan::Between { i, alpha, dt } => (i, alpha, dt),
+    };
+
+    // tracks without tangents for every key fall back to linear interpolation
+    match (interpolation, tangents.get(i), tangents.get(i + 1)) {
+        (Interpolation::CubicSpline, Some(a), Some(b)) => Some(hermite(
+            values[i],
+            a.out_tangent * dt,
+            values[i + 1],
+            b.in_tangent * dt,
+            alpha,
+        )),
+        _ => Some(lerp(values[i], values[i + 1], alpha)),
     }
-    let t0 = keys.s_times[i];
-    let t1 = keys.s_times[i + 1];
-    let a = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
-    Some(lerp_vec3(&keys.s_values[i], &keys.s_values[i + 1], a))
+}
+
+pub fn sample_translation(keys: &TransformKeys, t: f32) -> Option<Vec3> {
+    sample_track(
+        &keys.t_times,
+        &keys.t_values,
+        keys.t_interpolation,
+        &keys.t_tangents,
+        t,
+        |a, b, s| lerp_vec3(&a, &b, s),
+    )
+}
+
+pub fn sample_scale(keys: &TransformKeys, t: f32) -> Option<Vec3> {
+    sample_track(
+        &keys.s_times,
+        &keys.s_values,
+        keys.s_interpolation,
+        &keys.s_tangents,
+        t,
+        |a, b, s| lerp_vec3(&a, &b, s),
+    )
 }
 
 pub fn sample_rotation(keys: &TransformKeys, t: f32) -> Option<Quat> {
-    let n = keys.r_times.len();
-    if n == 0 {
-        return None;
-    }
-    if n == 1 {
-        return Some(keys.r_values[0]);
+    sample_track(
+        &keys.r_times,
+        &keys.r_values,
+        keys.r_interpolation,
+        &keys.r_tangents,
+        t,
+        |a, b, s| a.slerp(b, s),
+    )
+    .map(Quat::normalize)
+}
+
+pub fn sample_morph_weights(keys: &TransformKeys, t: f32) -> Option<Vec<f32>> {
+    let (i, alpha, dt) = match key_span(&keys.w_times, t, keys.w_interpolation)? {
+        KeySpan::At(i) => return Some(keys.w_values[i].clone()),
+        KeySpan::Between { i, alpha, dt } => (i, alpha, dt),
+    };
+
+    let (w0, w1) = (&keys.w_values[i], &keys.w_values[i + 1]);
+    let weights = match (
+        keys.w_interpolation,
+        keys.w_tangents.get(i),
+        keys.w_tangents.get(i + 1),
+    ) {
+        (Interpolation::CubicSpline, Some(a), Some(b)) => (0..w0.len().min(w1.len()))
+            .map(|j| {
+                let m0 = a.out_tangent.get(j).copied().unwrap_or(0.0) * dt;
+                let m1 = b.in_tangent.get(j).copied().unwrap_or(0.0) * dt;
+                hermite(w0[j], m0, w1[j], m1, alpha)
+            })
+            .collect(),
+        _ => w0
+            .iter()
+            .zip(w1)
+            .map(|(w0, w1)| w0 * (1.0 - alpha) + w1 * alpha)
+            .collect(),
+    };
+    Some(weights)
+}
+
+/// Angle in radians that `q` rotates around `axis`, ignoring any rotation around other axes.
+pub fn twist_angle(q: Quat, axis: Vec3) -> f32 {
+    let projection = Vec3::new(q.x, q.y, q.z).dot(axis);
+    wrap_angle(2.0 * projection.atan2(q.w))
+}
+
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// Translation and rotation around `up` (in radians) that the keys move through while playback
+/// advances by `delta` seconds to `time`, as used for root motion.
+///
+/// Looping clips keep adding the motion of a full cycle for every loop that was passed, so an
+/// object walking in a loop keeps moving forward instead of snapping back.
+pub fn sample_motion(
+    keys: &TransformKeys,
+    duration: f32,
+    time: f32,
+    delta: f32,
+    looping: bool,
+    up: Vec3,
+) -> (Vec3, f32) {
+    if delta == 0.0 {
+        return (Vec3::ZERO, 0.0);
     }
 
-    let i = find_key(&keys.r_times, t);
-    if i == n - 1 {
-        return Some(keys.r_values[i]);
+    let translation = |t: f32| sample_translation(keys, t).unwrap_or(Vec3::ZERO);
+    let angle = |t: f32| sample_rotation(keys, t).map_or(0.0, |r| twist_angle(r, up));
+
+    let start = time - delta;
+    if !looping || duration <= 0.0 {
+        let start = start.clamp(0.0, duration.max(0.0));
+        return (
+            translation(time) - translation(start),
+            wrap_angle(angle(time) - angle(start)),
+        );
     }
-    let t0 = keys.r_times[i];
-    let t1 = keys.r_times[i + 1];
-    let a = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
-    Some(keys.r_values[i].slerp(keys.r_values[i + 1], a).normalize())
+
+    let (start_loop, end_loop) = ((start / duration).floor(), (time / duration).floor());
+    let (start_local, end_local) = (start - start_loop * duration, time - end_loop * duration);
+    let loops = end_loop - start_loop;
+
+    let cycle_translation = translation(duration) - translation(0.0);
+    let cycle_angle = wrap_angle(angle(duration) - angle(0.0));
+
+    (
+        translation(end_local) - translation(start_local) + cycle_translation * loops,
+        wrap_angle(angle(end_local) - angle(start_local)) + cycle_angle * loops,
+    )
 }
 
 #[cfg(test)]
@@ -188,6 +423,14 @@ mod tests {
         }
     }
 
+    fn keyed_weights(times: &[f32], values: &[&[f32]]) -> TransformKeys {
+        TransformKeys {
+            w_times: times.to_vec(),
+            w_values: values.iter().map(|v| v.to_vec()).collect(),
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn translation_samples_edges_and_interpolates() {
         let keys = keyed_translation(
@@ -239,5 +482,244 @@ mod tests {
         assert!(sample_translation(&keys, 0.0).is_none());
         assert!(sample_scale(&keys, 0.0).is_none());
         assert!(sample_rotation(&keys, 0.0).is_none());
+        assert!(sample_morph_weights(&keys, 0.0).is_none());
+    }
+
+    /// Keys on `f(t) = t^3 - t` with its exact derivative as tangents. A cubic Hermite spline
+    /// reproduces any cubic polynomial, so samples have to lie on the reference curve.
+    fn cubic_reference_keys() -> TransformKeys {
+        let f = |t: f32| t * t * t - t;
+        let df = |t: f32| 3.0 * t * t - 1.0;
+        let times = [0.0, 0.5, 2.0];
+
+        TransformKeys {
+            t_times: times.to_vec(),
+            t_values: times.iter().map(|&t| Vec3::new(f(t), 1.0, -f(t))).collect(),
+            t_interpolation: Interpolation::CubicSpline,
+            t_tangents: times
+                .iter()
+                .map(|&t| Tangents {
+                    in_tangent: Vec3::new(df(t), 0.0, -df(t)),
+                    out_tangent: Vec3::new(df(t), 0.0, -df(t)),
+                })
+                .collect(),
+            w_times: times.to_vec(),
+            w_values: times.iter().map(|&t| vec![f(t)]).collect(),
+            w_interpolation: Interpolation::CubicSpline,
+            w_tangents: times
+                .iter()
+                .map(|&t| Tangents {
+                    in_tangent: vec![df(t)],
+                    out_tangent: vec![df(t)],
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn cubic_spline_follows_reference_curve() {
+        let keys = cubic_reference_keys();
+
+        for step in 0..=40 {
+            let t = step as f32 * 0.05;
+            let expected = t * t * t - t;
+
+            let translation = sample_translation(&keys, t).unwrap();
+            assert!(
+                translation.abs_diff_eq(Vec3::new(expected, 1.0, -expected), 1e-5),
+                "translation at {t}: {translation} != {expected}"
+            );
+
+            let weights = sample_morph_weights(&keys, t).unwrap();
+            assert!((weights[0] - expected).abs() < 1e-5);
+        }
+
+        // no extrapolation outside of the keyed range
+        assert_eq!(
+            sample_translation(&keys, -1.0),
+            sample_translation(&keys, 0.0)
+        );
+        assert_eq!(sample_translation(&keys, 3.0), Some(keys.t_values[2]));
+    }
+
+    #[test]
+    fn cubic_spline_with_flat_tangents_eases() {
+        let keys = TransformKeys {
+            s_interpolation: Interpolation::CubicSpline,
+            s_tangents: vec![Tangents::default(); 2],
+            ..keyed_scale(&[0.0, 2.0], &[[0.0, 0.0, 0.0], [1.0, 2.0, 4.0]])
+        };
+
+        // smoothstep: 3s^2 - 2s^3
+        let quarter = sample_scale(&keys, 0.5).unwrap();
+        assert!(quarter.abs_diff_eq(Vec3::new(1.0, 2.0, 4.0) * 0.15625, 1e-6));
+        let mid = sample_scale(&keys, 1.0).unwrap();
+        assert!(mid.abs_diff_eq(Vec3::new(0.5, 1.0, 2.0), 1e-6));
+    }
+
+    #[test]
+    fn cubic_rotation_hits_keys_and_stays_normalized() {
+        let mut keys = keyed_rotation(&[0.0, 1.0], &[0.0, std::f32::consts::FRAC_PI_2]);
+        keys.r_interpolation = Interpolation::CubicSpline;
+        keys.r_tangents = vec![
+            Tangents {
+                in_tangent: Quat::from_xyzw(0.0, 0.0, 0.0, 0.0),
+                out_tangent: Quat::from_xyzw(0.0, 0.0, 0.0, 0.0),
+            };
+            2
+        ];
+
+        let start = sample_rotation(&keys, 0.0).unwrap();
+        assert!(start.angle_between(keys.r_values[0]) < 1e-3);
+        let end = sample_rotation(&keys, 1.0).unwrap();
+        assert!(end.angle_between(keys.r_values[1]) < 1e-3);
+
+        let mid = sample_rotation(&keys, 0.5).unwrap();
+        assert!((mid.length() - 1.0).abs() < 1e-5);
+        assert!((mid.to_axis_angle().1 - std::f32::consts::FRAC_PI_4).abs() < 1e-3);
+    }
+
+    #[test]
+    fn missing_tangents_fall_back_to_linear() {
+        let mut keys = keyed_translation(&[0.0, 1.0], &[[0.0, 0.0, 0.0], [2.0, 0.0, 0.0]]);
+        keys.t_interpolation = Interpolation::CubicSpline;
+
+        let mid = sample_translation(&keys, 0.25).unwrap();
+        assert!(mid.abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 1e-6));
+    }
+
+    #[test]
+    fn step_holds_previous_key() {
+        let mut keys = keyed_translation(
+            &[0.0, 1.0, 2.0],
+            &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]],
+        );
+        keys.t_interpolation = Interpolation::Step;
+
+        assert_eq!(sample_translation(&keys, -1.0).unwrap(), Vec3::ZERO);
+        assert_eq!(sample_translation(&keys, 0.99).unwrap(), Vec3::ZERO);
+        assert_eq!(sample_translation(&keys, 1.0).unwrap(), Vec3::X);
+        assert_eq!(sample_translation(&keys, 1.5).unwrap(), Vec3::X);
+        assert_eq!(sample_translation(&keys, 5.0).unwrap(), Vec3::X * 2.0);
+
+        let mut rotation = keyed_rotation(&[0.0, 1.0], &[0.0, std::f32::consts::FRAC_PI_2]);
+        rotation.r_interpolation = Interpolation::Step;
+        assert_eq!(
+            sample_rotation(&rotation, 0.9).unwrap(),
+            rotation.r_values[0]
+        );
+
+        let mut weights = keyed_weights(&[0.0, 1.0], &[&[0.0], &[1.0]]);
+        weights.w_interpolation = Interpolation::Step;
+        assert_eq!(sample_morph_weights(&weights, 0.5).unwrap(), vec![0.0]);
+    }
+
+    #[test]
+    fn morph_weights_interpolate_per_target() {
+        let keys = keyed_weights(&[0.0, 1.0], &[&[0.0, 1.0], &[1.0, 0.5]]);
+        assert!(keys.has_morph_weights());
+        assert!(!keys.has_transform());
+
+        assert_eq!(sample_morph_weights(&keys, 0.0).unwrap(), vec![0.0, 1.0]);
+        assert_eq!(sample_morph_weights(&keys, 2.0).unwrap(), vec![1.0, 0.5]);
+
+        let mid = sample_morph_weights(&keys, 0.5).unwrap();
+        assert!((mid[0] - 0.5).abs() < f32::EPSILON);
+        assert!((mid[1] - 0.75).abs() < f32::EPSILON);
+    }
+
+    fn evented_clip() -> AnimationClip {
+        AnimationClip {
+            name: "Walk".to_string(),
+            duration: 1.0,
+            channels: Vec::new(),
+            events: vec![
+                AnimationEvent::new("start", 0.0),
+                AnimationEvent::new("step", 0.5),
+                AnimationEvent::new("end", 1.0),
+            ],
+        }
+    }
+
+    fn passed(clip: &AnimationClip, time: f32, delta: f32, looping: bool) -> Vec<&str> {
+        clip.events_between(time, delta, looping)
+            .map(|e| e.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn looping_events_fire_once_per_loop() {
+        let clip = evented_clip();
+
+        let mut fired = Vec::new();
+        let mut time = 0.0f32;
+        for _ in 0..8 {
+            let delta = 0.25;
+            time = (time + delta).rem_euclid(clip.duration);
+            fired.extend(passed(&clip, time, delta, true));
+        }
+        // the end of a loop is the start of the next one
+        assert_eq!(fired, ["start", "end", "step", "start", "end", "step"]);
+
+        // a frame spanning two loops reports both
+        assert_eq!(
+            passed(&clip, 0.75, 2.0, true),
+            ["start", "start", "step", "step", "end", "end"]
+        );
+
+        // playing backwards crosses the same events in reverse
+        assert_eq!(passed(&clip, 0.25, -0.5, true), ["step"]);
+        assert_eq!(passed(&clip, 0.9, -0.2, true), ["start", "end"]);
+        assert!(passed(&clip, 0.5, 0.0, true).is_empty());
+    }
+
+    #[test]
+    fn clamped_events_fire_at_the_ends() {
+        let clip = evented_clip();
+
+        assert_eq!(passed(&clip, 0.5, 0.5, false), ["start"]);
+        assert_eq!(passed(&clip, 1.0, 0.5, false), ["step", "end"]);
+        // once clamped at the end, nothing fires again
+        assert!(passed(&clip, 1.0, 0.0, false).is_empty());
+
+        assert_eq!(passed(&clip, 0.0, -0.5, false), ["start", "step"]);
+    }
+
+    #[test]
+    fn motion_accumulates_across_loops() {
+        let keys = TransformKeys {
+            r_times: vec![0.0, 1.0],
+            r_values: vec![Quat::IDENTITY, Quat::from_rotation_y(0.5)],
+            ..keyed_translation(&[0.0, 1.0], &[[0.0, 0.0, 0.0], [0.0, 0.5, 2.0]])
+        };
+
+        let (t, a) = sample_motion(&keys, 1.0, 0.75, 0.5, true, Vec3::Y);
+        assert!(t.abs_diff_eq(Vec3::new(0.0, 0.25, 1.0), 1e-5));
+        assert!((a - 0.25).abs() < 1e-5);
+
+        // wrapping from 0.75 to 0.25 still moves forward by half a cycle
+        let (t, a) = sample_motion(&keys, 1.0, 0.25, 0.5, true, Vec3::Y);
+        assert!(t.abs_diff_eq(Vec3::new(0.0, 0.25, 1.0), 1e-5));
+        assert!((a - 0.25).abs() < 1e-5);
+
+        let (t, _) = sample_motion(&keys, 1.0, 0.25, 2.0, true, Vec3::Y);
+        assert!(t.abs_diff_eq(Vec3::new(0.0, 1.0, 4.0), 1e-5));
+
+        // clamped clips stop at their end
+        let (t, _) = sample_motion(&keys, 1.0, 1.0, 0.5, false, Vec3::Y);
+        assert!(t.abs_diff_eq(Vec3::new(0.0, 0.25, 1.0), 1e-5));
+        assert_eq!(
+            sample_motion(&keys, 1.0, 0.5, 0.0, true, Vec3::Y).0,
+            Vec3::ZERO
+        );
+    }
+
+    #[test]
+    fn twist_ignores_other_axes() {
+        let q = Quat::from_rotation_y(1.0) * Quat::from_rotation_x(0.3);
+        assert!((twist_angle(q, Vec3::Y) - 1.0).abs() < 1e-5);
+        assert!((twist_angle(Quat::from_rotation_y(-3.0), Vec3::Y) + 3.0).abs() < 1e-5);
+        assert!(twist_angle(Quat::from_rotation_x(0.7), Vec3::Y).abs() < 1e-6);
     }
 }
@@ -3,6 +3,7 @@ pub mod fat_ptr;
 pub mod frame_counter;
 pub(crate) mod hacks;
 pub mod iter;
+pub mod retarget;
 mod typed_component_helpers;
 pub mod uniform_traits;
 
@@ -0,0 +1,280 @@
+//! Retargeting of animation clips between skeletons with different bone names and proportions.
+//!
+//! A [`Retargeting`] converts a clip made for a source skeleton, like a Mixamo rig, into a clip
+//! for another skeleton. Channels are renamed with a [`BoneMap`], and rotations are compensated
+//! for the different rest poses of both skeletons, so a bone turns the same way in world space
+//! on both of them. Translations are only kept for the root bone, scaled to the proportions of
+//! the target; all other bones keep the lengths of the target skeleton.
+
+use crate::core::Bones;
+use crate::math::{Mat4, Quat, Vec3};
+use crate::utils::animation::{AnimationClip, Channel, Tangents};
+use std::collections::HashMap;
+
+/// Maps bone names of a source skeleton to the bone names of a target skeleton.
+///
+/// Names without an explicit mapping have their prefix replaced if a prefix rule is set, and are
+/// kept unchanged otherwise.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoneMap {
+    names: HashMap<String, String>,
+    prefix: Option<(String, String)>,
+}
+
+impl BoneMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the bone `source` to the bone `target`.
+    pub fn with(mut self, source: impl Into<String>, target: impl Into<String>) -> Self {
+        self.names.insert(source.into(), target.into());
+        self
+    }
+
+    /// Replaces the prefix `source` of unmapped names by `target`, e.g. `"mixamorig:"` by `""`.
+    pub fn with_prefix(mut self, source: impl Into<String>, target: impl Into<String>) -> Self {
+        self.prefix = Some((source.into(), target.into()));
+        self
+    }
+
+    /// Name of the target bone the source bone `source` is mapped to.
+    pub fn target(&self, source: &str) -> String {
+        if let Some(target) = self.names.get(source) {
+            return target.clone();
+        }
+
+        match &self.prefix {
+            Some((from, to)) if source.starts_with(from.as_str()) => {
+                format!("{to}{}", &source[from.len()..])
+            }
+            _ => source.to_string(),
+        }
+    }
+}
+
+impl<S: Into<String>, T: Into<String>> FromIterator<(S, T)> for BoneMap {
+    fn from_iter<I: IntoIterator<Item = (S, T)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(BoneMap::new(), |map, (source, target)| {
+                map.with(source, target)
+            })
+    }
+}
+
+/// Converts clips made for the `source` skeleton into clips for other skeletons.
+#[derive(Debug, Clone)]
+pub struct Retargeting {
+    /// Skeleton the clips were made for, with its rest pose
+    pub source: Bones,
+    pub map: BoneMap,
+    /// Source bone whose translation moves the character, usually the hips.
+    pub root: Option<String>,
+    /// Scale applied to the root translation. Without one, the root translation is scaled by how
+    /// much higher the target root is above the ground than the source root in the rest pose.
+    pub translation_scale: Option<f32>,
+}
+
+impl Retargeting {
+    pub fn new(source: Bones, map: BoneMap) -> Self {
+        Self {
+            source,
+            map,
+            root: None,
+            translation_scale: None,
+        }
+    }
+
+    pub fn with_root(mut self, root: impl Into<String>) -> Self {
+        self.root = Some(root.into());
+        self
+    }
+
+    pub fn with_translation_scale(mut self, scale: f32) -> Self {
+        self.translation_scale = Some(scale);
+        self
+    }
+
+    /// Converts `clip` into a clip for the `target` skeleton.
+    ///
+    /// Channels of bones that aren't part of both skeletons are renamed, but kept as they are.
+    pub fn retarget(&self, clip: &AnimationClip, target: &Bones) -> AnimationClip {
+        let scale = self.translation_scale(target);
+        let channels = clip
+            .channels
+            .iter()
+            .map(|channel| self.retarget_channel(channel, target, scale))
+            .collect();
+
+        AnimationClip {
+            name: clip.name.clone(),
+            duration: clip.duration,
+            channels,
+            events: clip.events.clone(),
+        }
+    }
+
+    fn translation_scale(&self, target: &Bones) -> f32 {
+        if let Some(scale) = self.translation_scale {
+            return scale;
+        }
+
+        let height = |bones: &Bones, name: &str| {
+            bones
+                .index(name)
+                .map(|bone| bones.bind_global[bone].w_axis.y)
+        };
+        let Some(root) = &self.root else {
+            return 1.0;
+        };
+
+        match (
+            height(&self.source, root),
+            height(target, &self.map.target(root)),
+        ) {
+            (Some(source), Some(target)) if source.abs() > f32::EPSILON => target / source,
+            _ => 1.0,
+        }
+    }
+
+    fn retarget_channel(&self, channel: &Channel, target: &Bones, scale: f32) -> Channel {
+        let target_name = self.map.target(&channel.target_name);
+        let mut keys = channel.keys.clone();
+
+        let (Some(s), Some(t)) = (
+            self.source.index(&channel.target_name),
+            target.index(&target_name),
+        ) else {
+            return Channel { target_name, keys };
+        };
+
+        // A bone turns the same way in world space on both skeletons when
+        // target_local = pre * source_local * post.
+        let source_parent = parent_rotation(&self.source, s);
+        let target_parent = parent_rotation(target, t);
+        let pre = target_parent.inverse() * source_parent;
+        let post = rest_rotation(&self.source.bind_global[s]).inverse()
+            * rest_rotation(&target.bind_global[t]);
+
+        for q in &mut keys.r_values {
+            *q = (pre * *q * post).normalize();
+        }
+        for tangents in &mut keys.r_tangents {
+            tangents.in_tangent = pre * tangents.in_tangent * post;
+            tangents.out_tangent = pre * tangents.out_tangent * post;
+        }
+
+        if self.root.as_deref() == Some(channel.target_name.as_str()) {
+            let source_rest = self.source.bind_local[s].w_axis.truncate();
+            let target_rest = target.bind_local[t].w_axis.truncate();
+            for v in &mut keys.t_values {
+                *v = target_rest + pre * ((*v - source_rest) * scale);
+            }
+            for tangents in &mut keys.t_tangents {
+                *tangents = Tangents {
+                    in_tangent: pre * (tangents.in_tangent * scale),
+                    out_tangent: pre * (tangents.out_tangent * scale),
+                };
+            }
+        } else {
+            clear_track(&mut keys.t_times, &mut keys.t_values, &mut keys.t_tangents);
+        }
+
+        clear_track(&mut keys.s_times, &mut keys.s_values, &mut keys.s_tangents);
+
+        Channel { target_name, keys }
+    }
+}
+
+fn rest_rotation(global: &Mat4) -> Quat {
+    global.to_scale_rotation_translation().1
+}
+
+fn parent_rotation(bones: &Bones, bone: usize) -> Quat {
+    bones.parents[bone].map_or(Quat::IDENTITY, |parent| {
+        rest_rotation(&bones.bind_global[parent])
+    })
+}
+
+fn clear_track(times: &mut Vec<f32>, values: &mut Vec<Vec3>, tangents: &mut Vec<Tangents<Vec3>>) {
+    times.clear();
+    values.clear();
+    tangents.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::animation::TransformKeys;
+
+    fn skeleton(names: [&str; 2], hips: Mat4, spine_local: Mat4) -> Bones {
+        Bones::from_bind_globals(names, vec![None, Some(0)], vec![hips, hips * spine_local])
+    }
+
+    #[test]
+    fn rotations_turn_the_same_way_in_world_space() {
+        let source = skeleton(
+            ["mixamorig:Hips", "mixamorig:Spine"],
+            Mat4::from_translation(Vec3::Y),
+            Mat4::from_translation(Vec3::new(0.0, 0.5, 0.0)),
+        );
+        let target = skeleton(
+            ["Hips", "Chest"],
+            Mat4::from_rotation_translation(Quat::from_rotation_z(0.5), Vec3::Y * 2.0),
+            Mat4::from_rotation_translation(Quat::from_rotation_x(1.0), Vec3::Y),
+        );
+
+        let turn = Quat::from_rotation_y(0.8);
+        let clip = AnimationClip {
+            name: "Walk".to_string(),
+            duration: 1.0,
+            channels: vec![
+                Channel {
+                    target_name: "mixamorig:Hips".to_string(),
+                    keys: TransformKeys {
+                        t_times: vec![0.0],
+                        t_values: vec![Vec3::new(0.5, 1.0, 0.0)],
+                        r_times: vec![0.0],
+                        r_values: vec![Quat::IDENTITY],
+                        ..TransformKeys::default()
+                    },
+                },
+                Channel {
+                    target_name: "mixamorig:Spine".to_string(),
+                    keys: TransformKeys {
+                        t_times: vec![0.0],
+                        t_values: vec![Vec3::new(0.0, 0.5, 0.0)],
+                        r_times: vec![0.0],
+                        r_values: vec![turn],
+                        ..TransformKeys::default()
+                    },
+                },
+            ],
+            events: Vec::new(),
+        };
+
+        let map = BoneMap::new()
+            .with_prefix("mixamorig:", "")
+            .with("mixamorig:Spine", "Chest");
+        let retargeting = Retargeting::new(source.clone(), map).with_root("mixamorig:Hips");
+        let retargeted = retargeting.retarget(&clip, &target);
+
+        let hips = &retargeted.channels[0];
+        assert_eq!(hips.target_name, "Hips");
+        assert!(hips.keys.r_values[0].abs_diff_eq(Quat::from_rotation_z(0.5), 1e-5));
+        assert!(hips.keys.t_values[0].abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1e-5));
+
+        let chest = &retargeted.channels[1];
+        assert_eq!(chest.target_name, "Chest");
+        assert!(chest.keys.t_values.is_empty());
+
+        // the spine turns by `turn` relative to its parent on the source, so the chest has to
+        // turn the same way in world space
+        let source_delta = rest_rotation(&source.bind_global[0])
+            * turn
+            * rest_rotation(&source.bind_global[1]).inverse();
+        let target_global = rest_rotation(&target.bind_global[0]) * chest.keys.r_values[0];
+        let target_delta = target_global * rest_rotation(&target.bind_global[1]).inverse();
+        assert!(source_delta.abs_diff_eq(target_delta, 1e-5));
+    }
+}
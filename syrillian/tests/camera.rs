@@ -0,0 +1,152 @@
+use syrillian::World;
+use syrillian::components::CameraProjection;
+use syrillian::math::{Vec3, Vec4};
+use syrillian::rendering::rendering::message::RenderMsg;
+use syrillian::rendering::rendering::render_data::CameraUniform;
+use syrillian::rendering::rendering::viewport::ViewportId;
+
+#[test]
+fn orthographic_projection_shows_ortho_size() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+    camera.resize(800.0, 600.0);
+    camera.set_orthographic(6.0);
+
+    assert_eq!(camera.projection_mode(), CameraProjection::Orthographic);
+    let top_right = camera.projection * Vec4::new(4.0, 3.0, -1.0, 1.0);
+    assert!((top_right.x - 1.0).abs() < 1e-5);
+    assert!((top_right.y - 1.0).abs() < 1e-5);
+    assert_eq!(top_right.w, 1.0);
+
+    camera.set_perspective();
+    assert!(!camera.is_orthographic());
+}
+
+#[test]
+fn pixel_perfect_scale_is_whole_texels() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+    camera.resize(640.0, 360.0);
+    camera.set_orthographic(10.0);
+    camera.set_pixel_perfect(Some(16.0));
+
+    // 360 / (10 * 16) = 2.25, so every texel is drawn with 2x2 pixels
+    assert_eq!(camera.pixel_scale(), Some(32.0));
+    let half = camera.ortho_half_extents();
+    assert_eq!(half.y, 360.0 / 32.0 * 0.5);
+    assert_eq!(half.x, 640.0 / 32.0 * 0.5);
+
+    camera.set_pixel_perfect(None);
+    assert_eq!(camera.pixel_scale(), None);
+    assert_eq!(camera.ortho_half_extents().y, 5.0);
+}
+
+#[test]
+fn click_rays_start_at_the_camera() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+    camera.resize(800.0, 600.0);
+    camera.parent().transform.set_position(1.0, 2.0, 10.0);
+
+    let ray = camera.click_ray(400.0, 300.0);
+    assert!((ray.dir - Vec3::NEG_Z).length() < 1e-4);
+    let off_center = camera.click_ray(0.0, 0.0);
+    assert!(off_center.dir.x < 0.0 && off_center.dir.y > 0.0);
+
+    camera.set_orthographic(6.0);
+    let center = camera.click_ray(400.0, 300.0);
+    assert!((center.origin.x - 1.0).abs() < 1e-4);
+    assert!((center.origin.y - 2.0).abs() < 1e-4);
+    assert!((center.dir - Vec3::NEG_Z).length() < 1e-4);
+
+    // the top left corner is 4 units left and 3 units up, rays stay parallel
+    let corner = camera.click_ray(0.0, 0.0);
+    assert!((corner.origin.x + 3.0).abs() < 1e-4);
+    assert!((corner.origin.y - 5.0).abs() < 1e-4);
+    assert!((corner.dir - Vec3::NEG_Z).length() < 1e-4);
+}
+
+#[test]
+fn perspective_click_rays_follow_the_camera_transform() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+    camera.resize(800.0, 600.0);
+    camera.parent().transform.set_position(1.0, 2.0, 10.0);
+    camera
+        .parent()
+        .transform
+        .set_euler_rotation_deg(0.0, 90.0, 0.0);
+
+    let forward = camera.parent().transform.forward();
+    let ray = camera.click_ray(400.0, 300.0);
+    assert!((ray.origin - Vec3::new(1.0, 2.0, 10.0)).length() < 1e-4);
+    assert!((ray.dir - forward).length() < 1e-4);
+
+    // the right half of the screen should pick towards the camera's right
+    let right = camera.click_ray(800.0, 300.0);
+    assert!(right.dir.dot(camera.parent().transform.right()) > 0.0);
+}
+
+#[test]
+fn camera_cuts_are_sent_once() {
+    fn count_cuts(msg: &RenderMsg) -> usize {
+        match msg {
+            RenderMsg::CameraCut(_) => 1,
+            RenderMsg::CommandBatch(batch) => batch.iter().map(count_cuts).sum(),
+            _ => 0,
+        }
+    }
+
+    let (mut world, render_rx, _event_rx, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+    world.set_active_camera(camera.clone());
+    assert!(camera.is_cut_pending());
+
+    world.post_update();
+    assert!(!camera.is_cut_pending());
+    let cuts: usize = render_rx.try_iter().map(|msg| count_cuts(&msg)).sum();
+    assert_eq!(cuts, 1);
+
+    world.post_update();
+    assert_eq!(
+        render_rx
+            .try_iter()
+            .map(|msg| count_cuts(&msg))
+            .sum::<usize>(),
+        0
+    );
+
+    camera.cut();
+    world.post_update();
+    assert_eq!(
+        render_rx
+            .try_iter()
+            .map(|msg| count_cuts(&msg))
+            .sum::<usize>(),
+        1
+    );
+}
+
+#[test]
+fn depth_of_field_reaches_camera_uniform() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut camera = world.new_camera();
+    assert_eq!(camera.aperture(), 0.0);
+
+    camera.set_depth_of_field(4.0, 1.8, 85.0);
+    camera.set_aperture(-2.0);
+    assert_eq!(camera.aperture(), 0.0);
+    camera.set_aperture(1.8);
+
+    let Some(RenderMsg::UpdateActiveCamera(_, update)) =
+        camera.maybe_projection_update(ViewportId::PRIMARY)
+    else {
+        panic!("changing the lens should update the camera");
+    };
+
+    let mut uniform = CameraUniform::empty();
+    update(&mut uniform);
+    assert_eq!(uniform.focus_distance, 4.0);
+    assert_eq!(uniform.aperture, 1.8);
+    assert_eq!(uniform.focal_length, 85.0);
+}
@@ -0,0 +1,166 @@
+use syrillian::assets::Mesh;
+use syrillian::assets::mesh::{LodChainSettings, MorphTarget, NormalMode};
+use syrillian::engine::core::Vertex3D;
+use syrillian::math::{Vec2, Vec3};
+
+fn grid(size: u32) -> Mesh {
+    let mut vertices = Vec::new();
+    for z in 0..size {
+        for x in 0..size {
+            let (x0, z0) = (x as f32, z as f32);
+            let corners = [
+                Vec3::new(x0, 0.0, z0),
+                Vec3::new(x0, 0.0, z0 + 1.0),
+                Vec3::new(x0 + 1.0, 0.0, z0),
+                Vec3::new(x0 + 1.0, 0.0, z0),
+                Vec3::new(x0, 0.0, z0 + 1.0),
+                Vec3::new(x0 + 1.0, 0.0, z0 + 1.0),
+            ];
+            vertices.extend(corners.map(|p| {
+                let uv = Vec2::new(p.x, p.z) / size as f32;
+                Vertex3D::basic(p, uv, Vec3::Y)
+            }));
+        }
+    }
+    Mesh::builder(vertices).build()
+}
+
+#[test]
+fn weld_merges_shared_corners() {
+    let mut mesh = grid(4);
+    assert_eq!(mesh.vertex_count(), 4 * 4 * 6);
+
+    mesh.weld_vertices(0.0);
+    assert!(mesh.has_indices());
+    assert_eq!(mesh.vertex_count(), 5 * 5);
+    assert_eq!(mesh.indices_count(), 4 * 4 * 6);
+    assert_eq!(mesh.triangle_count(), 4 * 4 * 2);
+}
+
+#[test]
+fn normals_point_along_the_face() {
+    let mut mesh = grid(2);
+    for vertex in &mut std::sync::Arc::make_mut(&mut mesh.data).vertices {
+        vertex.normal = Vec3::X;
+    }
+
+    mesh.recalculate_normals(NormalMode::Smooth);
+    assert!(
+        mesh.vertices()
+            .iter()
+            .all(|v| v.normal.abs_diff_eq(Vec3::Y, 1e-5))
+    );
+
+    mesh.weld_vertices(0.0);
+    mesh.recalculate_normals(NormalMode::Flat);
+    assert!(!mesh.has_indices());
+    assert_eq!(mesh.vertex_count(), 2 * 2 * 6);
+    assert!(
+        mesh.vertices()
+            .iter()
+            .all(|v| v.normal.abs_diff_eq(Vec3::Y, 1e-5))
+    );
+}
+
+fn bitangent(vertex: &Vertex3D) -> Vec3 {
+    vertex.normal.cross(vertex.tangent.truncate()) * vertex.tangent.w
+}
+
+#[test]
+fn tangents_follow_the_uv_direction() {
+    let mut mesh = grid(2);
+    mesh.generate_tangents();
+    assert!(mesh.vertices().iter().all(|v| {
+        v.tangent.truncate().abs_diff_eq(Vec3::X, 1e-4) && bitangent(v).abs_diff_eq(Vec3::Z, 1e-4)
+    }));
+}
+
+#[test]
+fn mirrored_uvs_split_tangent_frames() {
+    // two quads sharing the edge at x = 1, with the uvs of the right one mirrored along u
+    let vertex = |x: f32, z: f32| {
+        let u = 1.0 - (x - 1.0).abs();
+        Vertex3D::basic(Vec3::new(x, 0.0, z), Vec2::new(u, z), Vec3::Y)
+    };
+    let vertices = vec![
+        vertex(0.0, 0.0),
+        vertex(0.0, 1.0),
+        vertex(1.0, 0.0),
+        vertex(1.0, 1.0),
+        vertex(2.0, 0.0),
+        vertex(2.0, 1.0),
+    ];
+    let indices = vec![0, 1, 2, 2, 1, 3, 2, 3, 4, 4, 3, 5];
+    let mut mesh = Mesh::builder(vertices).with_indices(indices).build();
+
+    mesh.generate_tangents();
+    assert_eq!(mesh.vertex_count(), 8);
+
+    let indices = mesh.indices().unwrap();
+    for (triangle, corners) in indices.chunks_exact(3).enumerate() {
+        let expected = if triangle < 2 { Vec3::X } else { -Vec3::X };
+        for &i in corners {
+            let v = &mesh.vertices()[i as usize];
+            assert!(v.tangent.truncate().abs_diff_eq(expected, 1e-4));
+            assert!(bitangent(v).abs_diff_eq(Vec3::Z, 1e-4));
+        }
+    }
+}
+
+#[test]
+fn lod_chain_shrinks_per_level() {
+    let mut mesh = grid(16);
+    mesh.generate_lods(&LodChainSettings {
+        lock_borders: false,
+        max_error: 1.0,
+        min_triangles: 2,
+        ..LodChainSettings::default()
+    });
+
+    assert!(mesh.has_indices());
+    assert!(!mesh.lods().is_empty());
+
+    let mut previous = mesh.triangle_count();
+    for lod in mesh.lods() {
+        assert!(lod.triangle_count() < previous);
+        assert_eq!(lod.material_ranges.len(), 1);
+        assert!(
+            lod.indices
+                .iter()
+                .all(|&i| (i as usize) < mesh.vertex_count())
+        );
+        previous = lod.triangle_count();
+    }
+}
+
+#[test]
+fn morph_targets_follow_welding() {
+    let base = grid(2);
+    let lift = base
+        .vertices()
+        .iter()
+        .map(|v| Vec3::new(0.0, v.position.x, 0.0))
+        .collect();
+    let mut mesh = Mesh::builder(base.vertices().to_vec())
+        .with_morph_targets(vec![MorphTarget::from_positions(lift)])
+        .build();
+    assert_eq!(mesh.morph_weights, vec![0.0]);
+
+    mesh.weld_vertices(0.0);
+    assert_eq!(mesh.vertex_count(), 3 * 3);
+    assert_eq!(mesh.morph_targets()[0].vertex_count(), mesh.vertex_count());
+
+    let morphed = mesh.morphed_vertices(&[0.5]);
+    for (vertex, original) in morphed.iter().zip(mesh.vertices()) {
+        let expected = original.position + Vec3::new(0.0, original.position.x * 0.5, 0.0);
+        assert!(vertex.position.abs_diff_eq(expected, 1e-6));
+    }
+
+    let unweighted = mesh.morphed_vertices(&[]);
+    assert!(
+        unweighted
+            .iter()
+            .zip(mesh.vertices())
+            .all(|(a, b)| a.position == b.position)
+    );
+}
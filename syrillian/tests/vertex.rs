@@ -1,5 +1,6 @@
+use syrillian::assets::Mesh;
 use syrillian::engine::core::Vertex3D;
-use syrillian::math::{Vec2, Vec3};
+use syrillian::math::{Vec2, Vec3, Vec4};
 
 #[test]
 fn vertex_creation_pads_indices() {
@@ -14,3 +15,32 @@ fn vertex_creation_pads_indices() {
     assert_eq!(v.bone_indices, [1, 2, 0, 0]);
     assert_eq!(v.bone_weights, [0.5, 0.5, 0.0, 0.0]);
 }
+
+#[test]
+fn secondary_stream_defaults_and_overrides() {
+    let vertices = vec![
+        Vertex3D::basic(Vec3::ZERO, Vec2::new(0.25, 0.5), Vec3::Y),
+        Vertex3D::basic(Vec3::X, Vec2::new(0.75, 1.0), Vec3::Y),
+        Vertex3D::basic(Vec3::Z, Vec2::new(1.0, 0.0), Vec3::Y),
+    ];
+
+    let plain = Mesh::builder(vertices.clone()).build();
+    assert!(!plain.has_vertex_colors());
+    assert!(!plain.has_uv1());
+    let secondary = plain.data.secondary_vertices();
+    assert_eq!(secondary.len(), 3);
+    assert_eq!(secondary[1].color, [1.0; 4]);
+    assert_eq!(secondary[1].uv1, Vec2::new(0.75, 1.0));
+
+    let colors = vec![Vec4::new(1.0, 0.0, 0.0, 1.0); 3];
+    let uv1 = vec![Vec2::new(0.1, 0.2); 3];
+    let painted = Mesh::builder(vertices)
+        .with_vertex_colors(colors.clone())
+        .with_uv1(uv1.clone())
+        .build();
+    assert_eq!(painted.vertex_colors(), Some(colors.as_slice()));
+    assert_eq!(painted.uv1(), Some(uv1.as_slice()));
+    let secondary = painted.data.secondary_vertices();
+    assert_eq!(secondary[2].color, colors[2].to_array());
+    assert_eq!(secondary[2].uv1, uv1[2]);
+}
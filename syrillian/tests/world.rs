@@ -1,9 +1,13 @@
 use std::cell::Cell;
 use syrillian::World;
-use syrillian::components::{CameraComponent, Component};
+use syrillian::assets::{HShader, RenderTexture2D};
+use syrillian::components::{CameraComponent, CameraError, Component};
 use syrillian::core::EventType;
 use syrillian::core::GameObjectId;
 use syrillian::math::{Quat, Vec3};
+use syrillian::rendering::passes::post_process::{PostProcessParams, PostProcessStage};
+use syrillian::rendering::rendering::message::RenderMsg;
+use syrillian::wgpu::TextureFormat;
 use web_time::Duration;
 
 thread_local! {
@@ -447,3 +451,78 @@ fn removing_sibling_component_mid_phase_is_safe() {
     assert_eq!(removable_component_updates(), 0);
     assert_eq!(component_count::<RemovableInUpdateComponent>(&world), 0);
 }
+
+#[test]
+fn post_process_passes_are_sent_to_the_renderer() {
+    let (world, render_rx, _event_rx, _pick_tx) = World::fresh();
+    while render_rx.try_recv().is_ok() {}
+
+    let params = PostProcessParams::default().with(0, 0.5);
+    let id = world
+        .add_post_process_material(
+            PostProcessStage::AfterBloom,
+            3,
+            HShader::POST_PROCESS,
+            params,
+        )
+        .unwrap();
+    match render_rx.try_recv().unwrap() {
+        RenderMsg::AddPostProcess(_, sent_id, stage, order, _) => {
+            assert_eq!(sent_id, id);
+            assert_eq!(stage, PostProcessStage::AfterBloom);
+            assert_eq!(order, 3);
+        }
+        msg => panic!("unexpected message {msg:?}"),
+    }
+
+    assert!(world.set_post_process_params(id, params.with(1, 2.0)));
+    match render_rx.try_recv().unwrap() {
+        RenderMsg::SetPostProcessParams(sent_id, sent) => {
+            assert_eq!(sent_id, id);
+            assert_eq!(sent.get(0), 0.5);
+            assert_eq!(sent.get(1), 2.0);
+        }
+        msg => panic!("unexpected message {msg:?}"),
+    }
+}
+
+#[test]
+fn cameras_render_into_render_textures() {
+    let (mut world, render_rx, _event_rx, _pick_tx) = World::fresh();
+    let camera = world.new_camera();
+    while render_rx.try_recv().is_ok() {}
+
+    let depth =
+        world
+            .assets
+            .render_textures
+            .add(RenderTexture2D::new(64, 64, TextureFormat::Rgba16Float));
+    assert!(matches!(
+        world.render_camera_to_texture(camera.clone(), depth),
+        Err(CameraError::UnsupportedFormat { .. })
+    ));
+
+    let texture = world
+        .assets
+        .render_textures
+        .add(RenderTexture2D::new_camera_target(320, 180));
+    let target = world
+        .render_camera_to_texture(camera.clone(), texture)
+        .unwrap();
+    assert_eq!(camera.render_target(), target);
+    assert_eq!(camera.resolution(), (320.0, 180.0));
+    match render_rx.try_recv().unwrap() {
+        RenderMsg::AddTextureTarget(sent_target, sent_texture) => {
+            assert_eq!(sent_target, target);
+            assert_eq!(sent_texture, texture);
+        }
+        msg => panic!("unexpected message {msg:?}"),
+    }
+
+    assert!(world.remove_texture_target(target));
+    assert!(matches!(
+        render_rx.try_recv().unwrap(),
+        RenderMsg::RemoveTextureTarget(sent) if sent == target
+    ));
+    assert!(!world.remove_texture_target(target));
+}
@@ -25,8 +25,15 @@ impl HBGL {
     pub const BLOOM_COMPUTE_ID: u32 = 10;
     pub const SSAO_COMPUTE_ID: u32 = 11;
     pub const SSAO_APPLY_COMPUTE_ID: u32 = 12;
+    pub const MESH_MORPH_COMPUTE_ID: u32 = 13;
+    pub const VELOCITY_COMPUTE_ID: u32 = 14;
+    pub const TAA_COMPUTE_ID: u32 = 15;
+    pub const EXPOSURE_COMPUTE_ID: u32 = 16;
+    pub const TONEMAP_COMPUTE_ID: u32 = 17;
+    pub const LIGHT_CLUSTER_COMPUTE_ID: u32 = 18;
+    pub const IBL_COMPUTE_ID: u32 = 19;
 
-    const MAX_BUILTIN_ID: u32 = 12;
+    const MAX_BUILTIN_ID: u32 = 19;
 
     pub const RENDER: HBGL = HBGL::new(Self::RENDER_ID);
     pub const MODEL: HBGL = HBGL::new(Self::MODEL_ID);
@@ -41,6 +48,13 @@ impl HBGL {
     pub const BLOOM_COMPUTE: HBGL = HBGL::new(Self::BLOOM_COMPUTE_ID);
     pub const SSAO_COMPUTE: HBGL = HBGL::new(Self::SSAO_COMPUTE_ID);
     pub const SSAO_APPLY_COMPUTE: HBGL = HBGL::new(Self::SSAO_APPLY_COMPUTE_ID);
+    pub const MESH_MORPH_COMPUTE: HBGL = HBGL::new(Self::MESH_MORPH_COMPUTE_ID);
+    pub const VELOCITY_COMPUTE: HBGL = HBGL::new(Self::VELOCITY_COMPUTE_ID);
+    pub const TAA_COMPUTE: HBGL = HBGL::new(Self::TAA_COMPUTE_ID);
+    pub const EXPOSURE_COMPUTE: HBGL = HBGL::new(Self::EXPOSURE_COMPUTE_ID);
+    pub const TONEMAP_COMPUTE: HBGL = HBGL::new(Self::TONEMAP_COMPUTE_ID);
+    pub const LIGHT_CLUSTER_COMPUTE: HBGL = HBGL::new(Self::LIGHT_CLUSTER_COMPUTE_ID);
+    pub const IBL_COMPUTE: HBGL = HBGL::new(Self::IBL_COMPUTE_ID);
 }
 
 impl StoreType for BGL {
@@ -66,6 +80,17 @@ impl StoreType for BGL {
             HBGL::SSAO_APPLY_COMPUTE_ID => {
                 HandleName::Static("SSAO Apply Compute Bind Group Layout")
             }
+            HBGL::MESH_MORPH_COMPUTE_ID => {
+                HandleName::Static("Mesh Morph Compute Bind Group Layout")
+            }
+            HBGL::VELOCITY_COMPUTE_ID => HandleName::Static("Velocity Compute Bind Group Layout"),
+            HBGL::TAA_COMPUTE_ID => HandleName::Static("TAA Compute Bind Group Layout"),
+            HBGL::EXPOSURE_COMPUTE_ID => HandleName::Static("Exposure Compute Bind Group Layout"),
+            HBGL::TONEMAP_COMPUTE_ID => HandleName::Static("Tonemap Compute Bind Group Layout"),
+            HBGL::LIGHT_CLUSTER_COMPUTE_ID => {
+                HandleName::Static("Light Cluster Compute Bind Group Layout")
+            }
+            HBGL::IBL_COMPUTE_ID => HandleName::Static("IBL Compute Bind Group Layout"),
             _ => HandleName::Id(handle),
         }
     }
@@ -75,7 +100,7 @@ impl StoreType for BGL {
     }
 }
 
-const RENDER_ENTRIES: [BindGroupLayoutEntry; 5] = [
+const RENDER_ENTRIES: [BindGroupLayoutEntry; 9] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::all(),
@@ -122,6 +147,42 @@ const RENDER_ENTRIES: [BindGroupLayoutEntry; 5] = [
         },
         count: None,
     },
+    BindGroupLayoutEntry {
+        binding: 5,
+        visibility: ShaderStages::all(),
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::Cube,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 6,
+        visibility: ShaderStages::all(),
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::Cube,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 7,
+        visibility: ShaderStages::all(),
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 8,
+        visibility: ShaderStages::all(),
+        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+        count: None,
+    },
 ];
 
 const MODEL_ENTRIES: [BindGroupLayoutEntry; 2] = [
@@ -198,7 +259,7 @@ const MATERIAL_ENTRIES: [BindGroupLayoutEntry; 6] = [
     },
 ];
 
-const LIGHT_ENTRIES: [BindGroupLayoutEntry; 2] = [
+const LIGHT_ENTRIES: [BindGroupLayoutEntry; 4] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::VERTEX_FRAGMENT,
@@ -219,6 +280,26 @@ const LIGHT_ENTRIES: [BindGroupLayoutEntry; 2] = [
         },
         count: None,
     },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::VERTEX_FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::VERTEX_FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
 ];
 
 const SHADOW_ENTRIES: [BindGroupLayoutEntry; 4] = [
@@ -260,7 +341,7 @@ const SHADOW_ENTRIES: [BindGroupLayoutEntry; 4] = [
     },
 ];
 
-const PP_ENTRIES: [BindGroupLayoutEntry; 5] = [
+const PP_ENTRIES: [BindGroupLayoutEntry; 7] = [
     BindGroupLayoutEntry {
         binding: 0,
         visibility: ShaderStages::FRAGMENT,
@@ -307,6 +388,26 @@ const PP_ENTRIES: [BindGroupLayoutEntry; 5] = [
         },
         count: None,
     },
+    BindGroupLayoutEntry {
+        binding: 5,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: false },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 6,
+        visibility: ShaderStages::FRAGMENT,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
 ];
 
 const PP_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 6] = [
@@ -361,7 +462,7 @@ const PP_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 6] = [
         visibility: ShaderStages::COMPUTE,
         ty: BindingType::StorageTexture {
             access: StorageTextureAccess::WriteOnly,
-            format: TextureFormat::Rgba8Unorm,
+            format: TextureFormat::Rgba16Float,
             view_dimension: TextureViewDimension::D2,
         },
         count: None,
@@ -411,6 +512,59 @@ const MESH_SKINNING_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 4] = [
     },
 ];
 
+const MESH_MORPH_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 5] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 4,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+];
+
 const PARTICLE_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 4] = [
     BindGroupLayoutEntry {
         binding: 0,
@@ -496,7 +650,7 @@ const BLOOM_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 5] = [
         visibility: ShaderStages::COMPUTE,
         ty: BindingType::StorageTexture {
             access: StorageTextureAccess::WriteOnly,
-            format: TextureFormat::Rgba8Unorm,
+            format: TextureFormat::Rgba16Float,
             view_dimension: TextureViewDimension::D2,
         },
         count: None,
@@ -580,6 +734,200 @@ const SSAO_APPLY_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 3] = [
     BindGroupLayoutEntry {
         binding: 2,
         visibility: ShaderStages::COMPUTE,
+        ty: BindingType::StorageTexture {
+            access: StorageTextureAccess::WriteOnly,
+            format: TextureFormat::Rgba16Float,
+            view_dimension: TextureViewDimension::D2,
+        },
+        count: None,
+    },
+];
+
+const VELOCITY_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 2] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Depth,
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::StorageTexture {
+            access: StorageTextureAccess::WriteOnly,
+            format: TextureFormat::Rgba16Float,
+            view_dimension: TextureViewDimension::D2,
+        },
+        count: None,
+    },
+];
+
+const TAA_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 7] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: false },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 4,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 5,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::StorageTexture {
+            access: StorageTextureAccess::WriteOnly,
+            format: TextureFormat::Rgba16Float,
+            view_dimension: TextureViewDimension::D2,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 6,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::StorageTexture {
+            access: StorageTextureAccess::WriteOnly,
+            format: TextureFormat::Rgba16Float,
+            view_dimension: TextureViewDimension::D2,
+        },
+        count: None,
+    },
+];
+
+const EXPOSURE_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 4] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+];
+
+const TONEMAP_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 6] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 4,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 5,
+        visibility: ShaderStages::COMPUTE,
         ty: BindingType::StorageTexture {
             access: StorageTextureAccess::WriteOnly,
             format: TextureFormat::Rgba8Unorm,
@@ -589,6 +937,88 @@ const SSAO_APPLY_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 3] = [
     },
 ];
 
+const LIGHT_CLUSTER_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 3] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: true },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: false },
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+];
+
+const IBL_COMPUTE_ENTRIES: [BindGroupLayoutEntry; 5] = [
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::Cube,
+            multisampled: false,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 1,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Sampler(SamplerBindingType::Filtering),
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 2,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::StorageTexture {
+            access: StorageTextureAccess::WriteOnly,
+            format: TextureFormat::Rgba16Float,
+            view_dimension: TextureViewDimension::D2Array,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 3,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: None,
+        },
+        count: None,
+    },
+    BindGroupLayoutEntry {
+        binding: 4,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2Array,
+            multisampled: false,
+        },
+        count: None,
+    },
+];
+
 impl StoreDefaults for BGL {
     fn populate(store: &mut Store<Self>) {
         store_add_checked!(
@@ -707,5 +1137,68 @@ impl StoreDefaults for BGL {
                 entries: SSAO_APPLY_COMPUTE_ENTRIES.to_vec()
             }
         );
+
+        store_add_checked!(
+            store,
+            HBGL::MESH_MORPH_COMPUTE_ID,
+            BGL {
+                label: HBGL::MESH_MORPH_COMPUTE.ident(),
+                entries: MESH_MORPH_COMPUTE_ENTRIES.to_vec()
+            }
+        );
+
+        store_add_checked!(
+            store,
+            HBGL::VELOCITY_COMPUTE_ID,
+            BGL {
+                label: HBGL::VELOCITY_COMPUTE.ident(),
+                entries: VELOCITY_COMPUTE_ENTRIES.to_vec()
+            }
+        );
+
+        store_add_checked!(
+            store,
+            HBGL::TAA_COMPUTE_ID,
+            BGL {
+                label: HBGL::TAA_COMPUTE.ident(),
+                entries: TAA_COMPUTE_ENTRIES.to_vec()
+            }
+        );
+
+        store_add_checked!(
+            store,
+            HBGL::EXPOSURE_COMPUTE_ID,
+            BGL {
+                label: HBGL::EXPOSURE_COMPUTE.ident(),
+                entries: EXPOSURE_COMPUTE_ENTRIES.to_vec()
+            }
+        );
+
+        store_add_checked!(
+            store,
+            HBGL::TONEMAP_COMPUTE_ID,
+            BGL {
+                label: HBGL::TONEMAP_COMPUTE.ident(),
+                entries: TONEMAP_COMPUTE_ENTRIES.to_vec()
+            }
+        );
+
+        store_add_checked!(
+            store,
+            HBGL::LIGHT_CLUSTER_COMPUTE_ID,
+            BGL {
+                label: HBGL::LIGHT_CLUSTER_COMPUTE.ident(),
+                entries: LIGHT_CLUSTER_COMPUTE_ENTRIES.to_vec()
+            }
+        );
+
+        store_add_checked!(
+            store,
+            HBGL::IBL_COMPUTE_ID,
+            BGL {
+                label: HBGL::IBL_COMPUTE.ident(),
+                entries: IBL_COMPUTE_ENTRIES.to_vec()
+            }
+        );
     }
 }
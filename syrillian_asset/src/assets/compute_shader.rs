@@ -4,6 +4,7 @@ use crate::{HBGL, store_add_checked};
 use bon::Builder;
 
 const COMPUTE_MESH_SKINNING: &str = include_str!("shader/shaders/compute/mesh_skinning.wgsl");
+const COMPUTE_MESH_MORPH: &str = include_str!("shader/shaders/compute/mesh_morph.wgsl");
 const COMPUTE_POST_PROCESS_SSR: &str =
     include_str!("shader/shaders/compute/ssr_post_process_compute.wgsl");
 const COMPUTE_POST_PROCESS_SSAO: &str =
@@ -20,6 +21,18 @@ const COMPUTE_POST_PROCESS_BLOOM_BLUR: &str =
     include_str!("shader/shaders/compute/bloom_blur_compute.wgsl");
 const COMPUTE_POST_PROCESS_BLOOM_COMPOSITE: &str =
     include_str!("shader/shaders/compute/bloom_composite_compute.wgsl");
+const COMPUTE_POST_PROCESS_VELOCITY: &str =
+    include_str!("shader/shaders/compute/velocity_compute.wgsl");
+const COMPUTE_POST_PROCESS_TAA: &str = include_str!("shader/shaders/compute/taa_compute.wgsl");
+const COMPUTE_POST_PROCESS_DOF: &str = include_str!("shader/shaders/compute/dof_compute.wgsl");
+const COMPUTE_POST_PROCESS_MOTION_BLUR: &str =
+    include_str!("shader/shaders/compute/motion_blur_compute.wgsl");
+const COMPUTE_POST_PROCESS_EXPOSURE: &str =
+    include_str!("shader/shaders/compute/exposure_compute.wgsl");
+const COMPUTE_POST_PROCESS_TONEMAP: &str =
+    include_str!("shader/shaders/compute/tonemap_compute.wgsl");
+const COMPUTE_LIGHT_CLUSTERS: &str = include_str!("shader/shaders/compute/light_clusters.wgsl");
+const COMPUTE_IBL: &str = include_str!("shader/shaders/compute/ibl_compute.wgsl");
 
 #[derive(Debug, Clone, Builder)]
 pub struct ComputeShader {
@@ -45,7 +58,21 @@ impl H<ComputeShader> {
     pub const POST_PROCESS_SSAO_BLUR_X_ID: u32 = 8;
     pub const POST_PROCESS_SSAO_BLUR_Y_ID: u32 = 9;
     pub const POST_PROCESS_SSAO_APPLY_ID: u32 = 10;
-    pub const MAX_BUILTIN_ID: u32 = 10;
+    pub const MESH_MORPH_ID: u32 = 11;
+    pub const POST_PROCESS_VELOCITY_ID: u32 = 12;
+    pub const POST_PROCESS_TAA_ID: u32 = 13;
+    pub const POST_PROCESS_DOF_ID: u32 = 14;
+    pub const POST_PROCESS_MOTION_BLUR_ID: u32 = 15;
+    pub const POST_PROCESS_EXPOSURE_HISTOGRAM_ID: u32 = 16;
+    pub const POST_PROCESS_EXPOSURE_AVERAGE_ID: u32 = 17;
+    pub const POST_PROCESS_TONEMAP_ID: u32 = 18;
+    pub const LIGHT_CLUSTERS_ID: u32 = 19;
+    pub const IBL_CAPTURE_ID: u32 = 20;
+    pub const IBL_DOWNSAMPLE_ID: u32 = 21;
+    pub const IBL_IRRADIANCE_ID: u32 = 22;
+    pub const IBL_PREFILTER_ID: u32 = 23;
+    pub const IBL_BRDF_LUT_ID: u32 = 24;
+    pub const MAX_BUILTIN_ID: u32 = 24;
 
     pub const FALLBACK: H<ComputeShader> = H::new(Self::FALLBACK_ID);
     pub const MESH_SKINNING: H<ComputeShader> = H::new(Self::MESH_SKINNING_ID);
@@ -62,6 +89,23 @@ impl H<ComputeShader> {
     pub const POST_PROCESS_SSAO_BLUR_Y: H<ComputeShader> =
         H::new(Self::POST_PROCESS_SSAO_BLUR_Y_ID);
     pub const POST_PROCESS_SSAO_APPLY: H<ComputeShader> = H::new(Self::POST_PROCESS_SSAO_APPLY_ID);
+    pub const MESH_MORPH: H<ComputeShader> = H::new(Self::MESH_MORPH_ID);
+    pub const POST_PROCESS_VELOCITY: H<ComputeShader> = H::new(Self::POST_PROCESS_VELOCITY_ID);
+    pub const POST_PROCESS_TAA: H<ComputeShader> = H::new(Self::POST_PROCESS_TAA_ID);
+    pub const POST_PROCESS_DOF: H<ComputeShader> = H::new(Self::POST_PROCESS_DOF_ID);
+    pub const POST_PROCESS_MOTION_BLUR: H<ComputeShader> =
+        H::new(Self::POST_PROCESS_MOTION_BLUR_ID);
+    pub const POST_PROCESS_EXPOSURE_HISTOGRAM: H<ComputeShader> =
+        H::new(Self::POST_PROCESS_EXPOSURE_HISTOGRAM_ID);
+    pub const POST_PROCESS_EXPOSURE_AVERAGE: H<ComputeShader> =
+        H::new(Self::POST_PROCESS_EXPOSURE_AVERAGE_ID);
+    pub const POST_PROCESS_TONEMAP: H<ComputeShader> = H::new(Self::POST_PROCESS_TONEMAP_ID);
+    pub const LIGHT_CLUSTERS: H<ComputeShader> = H::new(Self::LIGHT_CLUSTERS_ID);
+    pub const IBL_CAPTURE: H<ComputeShader> = H::new(Self::IBL_CAPTURE_ID);
+    pub const IBL_DOWNSAMPLE: H<ComputeShader> = H::new(Self::IBL_DOWNSAMPLE_ID);
+    pub const IBL_IRRADIANCE: H<ComputeShader> = H::new(Self::IBL_IRRADIANCE_ID);
+    pub const IBL_PREFILTER: H<ComputeShader> = H::new(Self::IBL_PREFILTER_ID);
+    pub const IBL_BRDF_LUT: H<ComputeShader> = H::new(Self::IBL_BRDF_LUT_ID);
 }
 
 impl StoreDefaults for ComputeShader {
@@ -177,6 +221,153 @@ impl StoreDefaults for ComputeShader {
                 vec![HBGL::SSAO_APPLY_COMPUTE]
             )
         );
+
+        store_add_checked!(
+            store,
+            HComputeShader::MESH_MORPH_ID,
+            ComputeShader::new(
+                "Mesh Morph Compute",
+                COMPUTE_MESH_MORPH,
+                vec![HBGL::MESH_MORPH_COMPUTE]
+            )
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::POST_PROCESS_VELOCITY_ID,
+            ComputeShader::new(
+                "Velocity Compute",
+                COMPUTE_POST_PROCESS_VELOCITY,
+                vec![HBGL::RENDER, HBGL::VELOCITY_COMPUTE]
+            )
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::POST_PROCESS_TAA_ID,
+            ComputeShader::new(
+                "TAA Resolve Compute",
+                COMPUTE_POST_PROCESS_TAA,
+                vec![HBGL::TAA_COMPUTE]
+            )
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::POST_PROCESS_DOF_ID,
+            ComputeShader::new(
+                "Depth of Field Compute",
+                COMPUTE_POST_PROCESS_DOF,
+                vec![HBGL::RENDER, HBGL::POST_PROCESS_COMPUTE]
+            )
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::POST_PROCESS_MOTION_BLUR_ID,
+            ComputeShader::new(
+                "Motion Blur Compute",
+                COMPUTE_POST_PROCESS_MOTION_BLUR,
+                vec![HBGL::RENDER, HBGL::POST_PROCESS_COMPUTE]
+            )
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::POST_PROCESS_EXPOSURE_HISTOGRAM_ID,
+            ComputeShader::builder()
+                .name("Exposure Histogram Compute")
+                .code(COMPUTE_POST_PROCESS_EXPOSURE)
+                .entry_point("cs_histogram")
+                .bind_group_layouts(vec![HBGL::RENDER, HBGL::EXPOSURE_COMPUTE])
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::POST_PROCESS_EXPOSURE_AVERAGE_ID,
+            ComputeShader::builder()
+                .name("Exposure Average Compute")
+                .code(COMPUTE_POST_PROCESS_EXPOSURE)
+                .entry_point("cs_average")
+                .bind_group_layouts(vec![HBGL::RENDER, HBGL::EXPOSURE_COMPUTE])
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::POST_PROCESS_TONEMAP_ID,
+            ComputeShader::new(
+                "Tonemap Compute",
+                COMPUTE_POST_PROCESS_TONEMAP,
+                vec![HBGL::TONEMAP_COMPUTE]
+            )
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::LIGHT_CLUSTERS_ID,
+            ComputeShader::new(
+                "Light Cluster Compute",
+                COMPUTE_LIGHT_CLUSTERS,
+                vec![HBGL::RENDER, HBGL::LIGHT_CLUSTER_COMPUTE]
+            )
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::IBL_CAPTURE_ID,
+            ComputeShader::builder()
+                .name("IBL Environment Capture Compute")
+                .code(COMPUTE_IBL)
+                .entry_point("cs_capture")
+                .bind_group_layouts(vec![HBGL::RENDER, HBGL::IBL_COMPUTE])
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::IBL_DOWNSAMPLE_ID,
+            ComputeShader::builder()
+                .name("IBL Environment Downsample Compute")
+                .code(COMPUTE_IBL)
+                .entry_point("cs_downsample")
+                .bind_group_layouts(vec![HBGL::RENDER, HBGL::IBL_COMPUTE])
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::IBL_IRRADIANCE_ID,
+            ComputeShader::builder()
+                .name("IBL Irradiance Compute")
+                .code(COMPUTE_IBL)
+                .entry_point("cs_irradiance")
+                .bind_group_layouts(vec![HBGL::RENDER, HBGL::IBL_COMPUTE])
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::IBL_PREFILTER_ID,
+            ComputeShader::builder()
+                .name("IBL Specular Prefilter Compute")
+                .code(COMPUTE_IBL)
+                .entry_point("cs_prefilter")
+                .bind_group_layouts(vec![HBGL::RENDER, HBGL::IBL_COMPUTE])
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HComputeShader::IBL_BRDF_LUT_ID,
+            ComputeShader::builder()
+                .name("IBL BRDF LUT Compute")
+                .code(COMPUTE_IBL)
+                .entry_point("cs_brdf_lut")
+                .bind_group_layouts(vec![HBGL::RENDER, HBGL::IBL_COMPUTE])
+                .build()
+        );
     }
 }
 
@@ -214,6 +405,38 @@ impl StoreType for ComputeShader {
             HComputeShader::POST_PROCESS_SSAO_APPLY_ID => {
                 HandleName::Static("SSAO Apply Compute Shader")
             }
+            HComputeShader::MESH_MORPH_ID => HandleName::Static("Mesh Morph Compute Shader"),
+            HComputeShader::POST_PROCESS_VELOCITY_ID => {
+                HandleName::Static("Velocity Compute Shader")
+            }
+            HComputeShader::POST_PROCESS_TAA_ID => HandleName::Static("TAA Resolve Compute Shader"),
+            HComputeShader::POST_PROCESS_DOF_ID => {
+                HandleName::Static("Depth of Field Compute Shader")
+            }
+            HComputeShader::POST_PROCESS_MOTION_BLUR_ID => {
+                HandleName::Static("Motion Blur Compute Shader")
+            }
+            HComputeShader::POST_PROCESS_EXPOSURE_HISTOGRAM_ID => {
+                HandleName::Static("Exposure Histogram Compute Shader")
+            }
+            HComputeShader::POST_PROCESS_EXPOSURE_AVERAGE_ID => {
+                HandleName::Static("Exposure Average Compute Shader")
+            }
+            HComputeShader::POST_PROCESS_TONEMAP_ID => HandleName::Static("Tonemap Compute Shader"),
+            HComputeShader::LIGHT_CLUSTERS_ID => HandleName::Static("Light Cluster Compute Shader"),
+            HComputeShader::IBL_CAPTURE_ID => {
+                HandleName::Static("IBL Environment Capture Compute Shader")
+            }
+            HComputeShader::IBL_DOWNSAMPLE_ID => {
+                HandleName::Static("IBL Environment Downsample Compute Shader")
+            }
+            HComputeShader::IBL_IRRADIANCE_ID => {
+                HandleName::Static("IBL Irradiance Compute Shader")
+            }
+            HComputeShader::IBL_PREFILTER_ID => {
+                HandleName::Static("IBL Specular Prefilter Compute Shader")
+            }
+            HComputeShader::IBL_BRDF_LUT_ID => HandleName::Static("IBL BRDF LUT Compute Shader"),
             _ => HandleName::Id(handle),
         }
     }
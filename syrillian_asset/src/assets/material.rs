@@ -1,8 +1,9 @@
 use crate::assets::{HMaterial, HShader, HTexture2D};
 use crate::material_inputs::{MaterialImmediateDef, MaterialInputLayout, MaterialTextureDef};
+use crate::material_instance::AlphaMode;
 use crate::store::{H, HandleName, Store, StoreDefaults, StoreType, StoreTypeFallback};
 use crate::{MaterialShaderSet, store_add_checked};
-use glamx::Vec3;
+use glamx::{Vec2, Vec3};
 use syrillian_shadergen::value::{MaterialValue, MaterialValueType};
 
 #[derive(Debug, Clone)]
@@ -161,6 +162,71 @@ impl Material {
                     ty: MaterialValueType::Bool,
                     default: MaterialValue::Bool(false),
                 },
+                MaterialImmediateDef {
+                    name: "emissive".to_string(),
+                    ty: MaterialValueType::Vec3,
+                    default: MaterialValue::Vec3(Vec3::ZERO),
+                },
+                MaterialImmediateDef {
+                    name: "emissive_strength".to_string(),
+                    ty: MaterialValueType::F32,
+                    default: MaterialValue::F32(1.0),
+                },
+                MaterialImmediateDef {
+                    name: "occlusion_strength".to_string(),
+                    ty: MaterialValueType::F32,
+                    default: MaterialValue::F32(1.0),
+                },
+                MaterialImmediateDef {
+                    name: "alpha_cutoff".to_string(),
+                    ty: MaterialValueType::F32,
+                    default: MaterialValue::F32(0.5),
+                },
+                MaterialImmediateDef {
+                    name: "alpha_mode".to_string(),
+                    ty: MaterialValueType::U32,
+                    default: MaterialValue::U32(AlphaMode::Blend as u32),
+                },
+                MaterialImmediateDef {
+                    name: "double_sided".to_string(),
+                    ty: MaterialValueType::Bool,
+                    default: MaterialValue::Bool(true),
+                },
+                MaterialImmediateDef {
+                    name: "use_emissive_texture".to_string(),
+                    ty: MaterialValueType::Bool,
+                    default: MaterialValue::Bool(false),
+                },
+                MaterialImmediateDef {
+                    name: "use_occlusion_texture".to_string(),
+                    ty: MaterialValueType::Bool,
+                    default: MaterialValue::Bool(false),
+                },
+                MaterialImmediateDef {
+                    name: "uv_offset".to_string(),
+                    ty: MaterialValueType::Vec2,
+                    default: MaterialValue::Vec2(Vec2::ZERO),
+                },
+                MaterialImmediateDef {
+                    name: "uv_scale".to_string(),
+                    ty: MaterialValueType::Vec2,
+                    default: MaterialValue::Vec2(Vec2::ONE),
+                },
+                MaterialImmediateDef {
+                    name: "uv_rotation".to_string(),
+                    ty: MaterialValueType::F32,
+                    default: MaterialValue::F32(0.0),
+                },
+                MaterialImmediateDef {
+                    name: "use_metallic_texture".to_string(),
+                    ty: MaterialValueType::Bool,
+                    default: MaterialValue::Bool(false),
+                },
+                MaterialImmediateDef {
+                    name: "uv_sets".to_string(),
+                    ty: MaterialValueType::U32,
+                    default: MaterialValue::U32(0),
+                },
             ],
             textures: vec![
                 MaterialTextureDef {
@@ -175,6 +241,18 @@ impl Material {
                     name: "roughness".to_string(),
                     default: HTexture2D::FALLBACK_ROUGHNESS,
                 },
+                MaterialTextureDef {
+                    name: "metallic".to_string(),
+                    default: HTexture2D::FALLBACK_ROUGHNESS,
+                },
+                MaterialTextureDef {
+                    name: "emissive".to_string(),
+                    default: HTexture2D::FALLBACK_ROUGHNESS,
+                },
+                MaterialTextureDef {
+                    name: "occlusion".to_string(),
+                    default: HTexture2D::FALLBACK_ROUGHNESS,
+                },
             ],
         }
     }
@@ -1,9 +1,35 @@
 use crate::material_inputs::MaterialInputLayout;
 use crate::store::{H, HandleName, Store, StoreDefaults, StoreType, StoreTypeFallback};
-use crate::{HMaterial, HMaterialInstance, HTexture2D, store_add_checked};
-use glamx::Vec3;
+use crate::{HMaterial, HMaterialInstance, HRenderTexture2D, HTexture2D, store_add_checked};
+use glamx::{Vec2, Vec3};
 use std::collections::HashMap;
+use syrillian_shadergen::function::PbrShader;
 use syrillian_shadergen::value::MaterialValue;
+use syrillian_utils::debug_panic;
+
+/// How the alpha of a material is interpreted, matching the glTF `alphaMode`.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Alpha is ignored and the surface is fully opaque.
+    Opaque = 0,
+    /// Fragments with an alpha below the `alpha_cutoff` are discarded, all others are opaque.
+    Mask = 1,
+    /// The surface is blended if its alpha or its diffuse texture has transparency.
+    #[default]
+    Blend = 2,
+}
+
+impl AlphaMode {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(AlphaMode::Opaque),
+            1 => Some(AlphaMode::Mask),
+            2 => Some(AlphaMode::Blend),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct MaterialInstance {
@@ -11,6 +37,8 @@ pub struct MaterialInstance {
     pub material: HMaterial,
     pub values: HashMap<String, MaterialValue>,
     pub textures: HashMap<String, Option<HTexture2D>>,
+    /// Render textures sampled instead of the texture of the same name
+    pub render_textures: HashMap<String, HRenderTexture2D>,
 }
 
 impl MaterialInstance {
@@ -26,6 +54,15 @@ impl MaterialInstance {
         self.textures.get(name).copied()
     }
 
+    pub fn render_texture(&self, name: &str) -> Option<HRenderTexture2D> {
+        self.render_textures.get(name).copied()
+    }
+
+    /// Samples the render `texture` in the texture slot `name`, like a camera's output.
+    pub fn set_render_texture(&mut self, name: impl Into<String>, texture: HRenderTexture2D) {
+        self.render_textures.insert(name.into(), texture);
+    }
+
     pub fn apply_defaults(&mut self, layout: &MaterialInputLayout) {
         for field in &layout.immediates {
             self.values
@@ -55,6 +92,12 @@ impl MaterialInstance {
         }
     }
 
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.value_u32("alpha_mode")
+            .and_then(AlphaMode::from_u32)
+            .unwrap_or_default()
+    }
+
     pub fn set_bool(&mut self, name: &str, value: bool, layout: &MaterialInputLayout) {
         if layout.immediates.iter().any(|field| field.name == name) {
             self.values
@@ -69,6 +112,7 @@ pub struct MaterialInstanceBuilder {
     material: HMaterial,
     values: HashMap<String, MaterialValue>,
     textures: HashMap<String, Option<HTexture2D>>,
+    render_textures: HashMap<String, HRenderTexture2D>,
 }
 
 impl Default for MaterialInstanceBuilder {
@@ -78,6 +122,7 @@ impl Default for MaterialInstanceBuilder {
             material: HMaterial::DEFAULT,
             values: HashMap::default(),
             textures: HashMap::default(),
+            render_textures: HashMap::default(),
         }
     }
 }
@@ -117,6 +162,12 @@ impl MaterialInstanceBuilder {
         self
     }
 
+    /// Samples the render `texture` in the texture slot `name`, like a camera's output.
+    pub fn render_texture(mut self, name: impl Into<String>, texture: HRenderTexture2D) -> Self {
+        self.render_textures.insert(name.into(), texture);
+        self
+    }
+
     pub fn diffuse(mut self, color: Vec3) -> Self {
         self.values
             .insert("diffuse".to_string(), MaterialValue::Vec3(color));
@@ -167,6 +218,89 @@ impl MaterialInstanceBuilder {
         self
     }
 
+    pub fn alpha_mode(mut self, mode: AlphaMode) -> Self {
+        self.values
+            .insert("alpha_mode".to_string(), MaterialValue::U32(mode as u32));
+        self
+    }
+
+    /// Alpha below which fragments are discarded in [`AlphaMode::Mask`].
+    pub fn alpha_cutoff(mut self, cutoff: f32) -> Self {
+        self.values
+            .insert("alpha_cutoff".to_string(), MaterialValue::F32(cutoff));
+        self
+    }
+
+    /// Whether back faces are rendered (with flipped normals) or discarded.
+    pub fn double_sided(mut self, double_sided: bool) -> Self {
+        self.values.insert(
+            "double_sided".to_string(),
+            MaterialValue::Bool(double_sided),
+        );
+        self
+    }
+
+    /// Linear emitted color. It's added on top of the lit surface and isn't affected by lights.
+    pub fn emissive(mut self, color: Vec3) -> Self {
+        self.values
+            .insert("emissive".to_string(), MaterialValue::Vec3(color));
+        self
+    }
+
+    pub fn emissive_strength(mut self, strength: f32) -> Self {
+        self.values.insert(
+            "emissive_strength".to_string(),
+            MaterialValue::F32(strength),
+        );
+        self
+    }
+
+    /// How much the occlusion texture darkens indirect lighting, from `0.0` (not at all) to `1.0`.
+    pub fn occlusion_strength(mut self, strength: f32) -> Self {
+        self.values.insert(
+            "occlusion_strength".to_string(),
+            MaterialValue::F32(strength),
+        );
+        self
+    }
+
+    /// Transforms the texture coordinates of all textures: scaled first, then rotated
+    /// counter-clockwise by `rotation` radians and offset last.
+    pub fn uv_transform(mut self, offset: Vec2, scale: Vec2, rotation: f32) -> Self {
+        self.values
+            .insert("uv_offset".to_string(), MaterialValue::Vec2(offset));
+        self.values
+            .insert("uv_scale".to_string(), MaterialValue::Vec2(scale));
+        self.values
+            .insert("uv_rotation".to_string(), MaterialValue::F32(rotation));
+        self
+    }
+
+    /// Samples the texture slot `name` with uv set `1` instead of the primary set `0`.
+    /// Only the slots of [`PbrShader::UV_SET_TEXTURES`] can switch their uv set.
+    pub fn texture_uv_set(mut self, name: &str, set: u32) -> Self {
+        let Some(bit) = PbrShader::UV_SET_TEXTURES
+            .iter()
+            .position(|texture| *texture == name)
+        else {
+            debug_panic!("Texture slot \"{name}\" can't switch its uv set");
+            return self;
+        };
+
+        let mask = match self.values.get("uv_sets") {
+            Some(MaterialValue::U32(mask)) => *mask,
+            _ => 0,
+        };
+        let mask = if set == 0 {
+            mask & !(1 << bit)
+        } else {
+            mask | (1 << bit)
+        };
+        self.values
+            .insert("uv_sets".to_string(), MaterialValue::U32(mask));
+        self
+    }
+
     pub fn diffuse_texture(self, texture: impl Into<Option<HTexture2D>>) -> Self {
         self.texture("diffuse", texture)
     }
@@ -179,12 +313,27 @@ impl MaterialInstanceBuilder {
         self.texture("roughness", texture)
     }
 
+    /// Metallic texture, read from the blue channel like a glTF metallic-roughness texture.
+    pub fn metallic_texture(self, texture: impl Into<Option<HTexture2D>>) -> Self {
+        self.texture("metallic", texture)
+    }
+
+    pub fn emissive_texture(self, texture: impl Into<Option<HTexture2D>>) -> Self {
+        self.texture("emissive", texture)
+    }
+
+    /// Ambient occlusion texture, read from the red channel.
+    pub fn occlusion_texture(self, texture: impl Into<Option<HTexture2D>>) -> Self {
+        self.texture("occlusion", texture)
+    }
+
     pub fn build(self) -> MaterialInstance {
         MaterialInstance {
             name: self.name.unwrap_or_else(|| "Material Instance".to_string()),
             material: self.material,
             values: self.values,
             textures: self.textures,
+            render_textures: self.render_textures,
         }
     }
 
@@ -209,6 +358,7 @@ impl StoreDefaults for MaterialInstance {
             material: HMaterial::FALLBACK,
             values: HashMap::new(),
             textures: HashMap::new(),
+            render_textures: HashMap::new(),
         };
         store_add_checked!(store, H::<MaterialInstance>::FALLBACK_ID, fallback);
 
@@ -217,6 +367,7 @@ impl StoreDefaults for MaterialInstance {
             material: HMaterial::DEFAULT,
             values: HashMap::new(),
             textures: HashMap::new(),
+            render_textures: HashMap::new(),
         };
         store_add_checked!(store, H::<MaterialInstance>::DEFAULT_ID, default);
     }
@@ -51,6 +51,52 @@ impl Bones {
     pub fn none() -> Bones {
         Bones::default()
     }
+
+    /// Bones in the given model space bind pose. Children, roots and the local and inverse bind
+    /// matrices are derived from the parents and `bind_global`.
+    pub fn from_bind_globals(
+        names: impl IntoIterator<Item = impl Into<String>>,
+        parents: Vec<Option<usize>>,
+        bind_global: Vec<Mat4>,
+    ) -> Bones {
+        let names: Vec<String> = names.into_iter().map(Into::into).collect();
+        debug_assert_eq!(
+            names.len(),
+            parents.len(),
+            "Every bone needs a parent entry"
+        );
+        debug_assert_eq!(
+            names.len(),
+            bind_global.len(),
+            "Every bone needs a bind pose"
+        );
+
+        let mut children = vec![Vec::new(); names.len()];
+        let mut roots = Vec::new();
+        for (bone, parent) in parents.iter().enumerate() {
+            match parent {
+                Some(parent) => children[*parent].push(bone),
+                None => roots.push(bone),
+            }
+        }
+
+        let bind_local = parents
+            .iter()
+            .zip(&bind_global)
+            .map(|(parent, global)| parent.map_or(*global, |p| bind_global[p].inverse() * *global))
+            .collect();
+
+        Bones {
+            index_of: names.iter().cloned().zip(0..).collect(),
+            names,
+            parents,
+            children,
+            roots,
+            inverse_bind: bind_global.iter().map(Mat4::inverse).collect(),
+            bind_global,
+            bind_local,
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -1,5 +1,6 @@
-use super::{Bones, Mesh, Vertex3D, bounding_sphere_from_vertices};
+use super::{Bones, Mesh, MorphTarget, Vertex3D, bounding_sphere_from_vertices};
 use crate::mesh::MeshVertexData;
+use glamx::{Vec2, Vec4};
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -7,8 +8,12 @@ use std::sync::Arc;
 pub struct MeshBuilder {
     vertices: Vec<Vertex3D>,
     indices: Option<Vec<u32>>,
+    colors: Option<Vec<Vec4>>,
+    uv1: Option<Vec<Vec2>>,
     material_ranges: Option<Vec<Range<u32>>>,
     bones: Option<Bones>,
+    morph_targets: Vec<MorphTarget>,
+    morph_weights: Vec<f32>,
 }
 
 impl MeshBuilder {
@@ -16,8 +21,12 @@ impl MeshBuilder {
         MeshBuilder {
             vertices,
             indices: None,
+            colors: None,
+            uv1: None,
             material_ranges: None,
             bones: None,
+            morph_targets: Vec::new(),
+            morph_weights: Vec::new(),
         }
     }
 
@@ -33,13 +42,49 @@ impl MeshBuilder {
             material_ranges.push(0u32..vert_count as u32);
         }
 
-        let bounding_sphere = bounding_sphere_from_vertices(&self.vertices);
+        let mut bounding_sphere = bounding_sphere_from_vertices(&self.vertices);
+        // grow the bounds so they still hold every target at full weight
+        bounding_sphere.radius += self
+            .morph_targets
+            .iter()
+            .map(MorphTarget::max_displacement)
+            .sum::<f32>();
+
+        debug_assert!(
+            self.colors
+                .as_ref()
+                .is_none_or(|c| c.len() == self.vertices.len()),
+            "Vertex color count must match vertex count"
+        );
+        debug_assert!(
+            self.uv1
+                .as_ref()
+                .is_none_or(|uv| uv.len() == self.vertices.len()),
+            "Secondary uv count must match vertex count"
+        );
+        debug_assert!(
+            self.morph_targets.iter().all(|t| {
+                let count = self.vertices.len();
+                t.positions.len() == count && t.normals.len() == count && t.tangents.len() == count
+            }),
+            "Morph target delta count must match vertex count"
+        );
+
+        let mut data = MeshVertexData::new(self.vertices, self.indices);
+        data.colors = self.colors;
+        data.uv1 = self.uv1;
+        data.morph_targets = self.morph_targets;
+
+        let mut morph_weights = self.morph_weights;
+        morph_weights.resize(data.morph_targets.len(), 0.0);
 
         Mesh {
-            data: Arc::new(MeshVertexData::new(self.vertices, self.indices)),
+            data: Arc::new(data),
             material_ranges,
             bones: self.bones.unwrap_or_default(),
             bounding_sphere,
+            lods: Vec::new(),
+            morph_weights,
         }
     }
 
@@ -57,6 +102,30 @@ impl MeshBuilder {
         self.indices = Some(indices);
         self
     }
+
+    /// Attaches one linear RGBA color per vertex.
+    pub fn with_vertex_colors(mut self, colors: Vec<Vec4>) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    /// Attaches a secondary uv set (e.g. for lightmaps), one coordinate per vertex.
+    pub fn with_uv1(mut self, uv1: Vec<Vec2>) -> Self {
+        self.uv1 = Some(uv1);
+        self
+    }
+
+    /// Attaches blend shapes, each with one delta per vertex.
+    pub fn with_morph_targets(mut self, targets: Vec<MorphTarget>) -> Self {
+        self.morph_targets = targets;
+        self
+    }
+
+    /// Sets the default weight of each morph target. Missing weights default to zero.
+    pub fn with_morph_weights(mut self, weights: Vec<f32>) -> Self {
+        self.morph_weights = weights;
+        self
+    }
 }
 
 impl From<MeshBuilder> for Mesh {
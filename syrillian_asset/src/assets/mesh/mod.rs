@@ -1,17 +1,21 @@
 pub mod bone;
 pub mod buffer;
 pub mod builder;
+pub mod morph;
+pub mod processing;
 pub mod vertex;
 
 pub use bone::{Bone, Bones};
 pub use builder::MeshBuilder;
-pub use vertex::Vertex3D;
+pub use morph::MorphTarget;
+pub use processing::{LodChainSettings, MeshLod, NormalMode};
+pub use vertex::{MeshInstance, SecondaryVertex3D, Vertex3D};
 
 use crate::HMesh;
 use crate::mesh::buffer::UNIT_SQUARE_VERT;
 use crate::store::{H, HandleName, Store, StoreDefaults, StoreType};
 use crate::store_add_checked;
-use glamx::{Vec2, Vec3};
+use glamx::{Vec2, Vec3, Vec4};
 use itertools::izip;
 use obj::{IndexTuple, ObjError};
 use snafu::Snafu;
@@ -42,12 +46,22 @@ pub struct Mesh {
     pub material_ranges: Vec<Range<u32>>,
     pub bones: Bones,
     pub bounding_sphere: BoundingSphere,
+    /// Simplified levels of detail, see [`Mesh::generate_lods`].
+    pub lods: Vec<MeshLod>,
+    /// Weights of the morph targets used when nothing else overrides them.
+    pub morph_weights: Vec<f32>,
 }
 
 #[derive(Debug, Clone)]
 pub struct MeshVertexData<T: Debug + Clone> {
     pub vertices: Vec<T>,
     pub indices: Option<Vec<u32>>,
+    /// Optional per-vertex colors, one per vertex in `vertices`.
+    pub colors: Option<Vec<Vec4>>,
+    /// Optional secondary uv set (e.g. lightmap coordinates), one per vertex in `vertices`.
+    pub uv1: Option<Vec<Vec2>>,
+    /// Blend shapes, each with one delta per vertex in `vertices`.
+    pub morph_targets: Vec<MorphTarget>,
 }
 
 impl Mesh {
@@ -89,6 +103,74 @@ impl Mesh {
         self.data.indices.is_some()
     }
 
+    #[inline]
+    pub fn vertex_colors(&self) -> Option<&[Vec4]> {
+        self.data.colors.as_deref()
+    }
+
+    #[inline]
+    pub fn has_vertex_colors(&self) -> bool {
+        self.data.colors.is_some()
+    }
+
+    #[inline]
+    pub fn uv1(&self) -> Option<&[Vec2]> {
+        self.data.uv1.as_deref()
+    }
+
+    #[inline]
+    pub fn has_uv1(&self) -> bool {
+        self.data.uv1.is_some()
+    }
+
+    #[inline]
+    pub fn morph_targets(&self) -> &[MorphTarget] {
+        &self.data.morph_targets
+    }
+
+    #[inline]
+    pub fn morph_target_count(&self) -> usize {
+        self.data.morph_targets.len()
+    }
+
+    #[inline]
+    pub fn has_morph_targets(&self) -> bool {
+        !self.data.morph_targets.is_empty()
+    }
+
+    /// Applies the morph targets with the given weights on the CPU. Missing weights count as zero.
+    ///
+    /// The renderer does the same on the GPU, this is meant for tools and tests.
+    pub fn morphed_vertices(&self, weights: &[f32]) -> Vec<Vertex3D> {
+        let mut vertices = self.vertices().to_vec();
+        let active = self
+            .morph_targets()
+            .iter()
+            .zip(weights)
+            .filter(|(_, w)| **w != 0.0)
+            .collect::<Vec<_>>();
+
+        if active.is_empty() {
+            return vertices;
+        }
+
+        for (i, vertex) in vertices.iter_mut().enumerate() {
+            let mut normal = vertex.normal;
+            let mut tangent = vertex.tangent.truncate();
+            for &(target, &weight) in &active {
+                vertex.position += target.positions[i] * weight;
+                normal += target.normals[i] * weight;
+                tangent += target.tangents[i] * weight;
+            }
+            vertex.normal = normal.normalize_or(vertex.normal);
+            vertex.tangent = tangent
+                .normalize_or(vertex.tangent.truncate())
+                .extend(vertex.tangent.w);
+        }
+
+        vertices
+    }
+
     pub fn load_from_obj_slice(data: &[u8]) -> Result<Mesh, MeshError> {
         let data = obj::ObjData::load_buf(data)?;
         let mut vertices: Vec<Vec3> = Vec::new();
@@ -135,6 +217,8 @@ impl Mesh {
             material_ranges,
             bones: Bones::none(),
             bounding_sphere,
+            lods: Vec::new(),
+            morph_weights: Vec::new(),
         })
     }
 
@@ -161,7 +245,32 @@ impl Mesh {
 
 impl MeshVertexData<Vertex3D> {
     pub fn new(vertices: Vec<Vertex3D>, indices: Option<Vec<u32>>) -> Self {
-        MeshVertexData { vertices, indices }
+        MeshVertexData {
+            vertices,
+            indices,
+            colors: None,
+            uv1: None,
+            morph_targets: Vec::new(),
+        }
+    }
+
+    /// Interleaves the optional vertex streams into the layout used by the secondary vertex buffer.
+    /// Missing colors default to white and a missing secondary uv set mirrors the primary one.
+    pub fn secondary_vertices(&self) -> Vec<SecondaryVertex3D> {
+        let colors = self.colors.as_deref().unwrap_or_default();
+        let uv1 = self.uv1.as_deref().unwrap_or_default();
+
+        self.vertices
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let fallback = SecondaryVertex3D::fallback(v.uv);
+                SecondaryVertex3D {
+                    color: colors.get(i).map_or(fallback.color, |c| c.to_array()),
+                    uv1: uv1.get(i).copied().unwrap_or(fallback.uv1),
+                }
+            })
+            .collect()
     }
 
     pub fn make_triangle_indices(&self) -> Vec<[u32; 3]> {
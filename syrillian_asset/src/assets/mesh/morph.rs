@@ -0,0 +1,81 @@
+use glamx::Vec3;
+
+/// Number of floats one vertex of one morph target takes up in [`pack_morph_deltas`]:
+/// position, normal and tangent deltas.
+pub const MORPH_DELTA_FLOATS: usize = 9;
+
+/// A blend shape, storing per-vertex offsets that are added to the base mesh scaled by the weight
+/// of the target.
+///
+/// Every stream holds one delta per vertex of the mesh it belongs to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MorphTarget {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub tangents: Vec<Vec3>,
+}
+
+impl MorphTarget {
+    /// Creates a target that only moves vertices, leaving normals and tangents untouched.
+    pub fn from_positions(positions: Vec<Vec3>) -> Self {
+        let count = positions.len();
+        MorphTarget {
+            positions,
+            normals: vec![Vec3::ZERO; count],
+            tangents: vec![Vec3::ZERO; count],
+        }
+    }
+
+    /// Creates a target without any offsets for `vertex_count` vertices.
+    pub fn zeroed(vertex_count: usize) -> Self {
+        Self::from_positions(vec![Vec3::ZERO; vertex_count])
+    }
+
+    pub fn with_normals(mut self, normals: Vec<Vec3>) -> Self {
+        self.normals = normals;
+        self
+    }
+
+    pub fn with_tangents(mut self, tangents: Vec<Vec3>) -> Self {
+        self.tangents = tangents;
+        self
+    }
+
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Length of the largest position offset.
+    pub fn max_displacement(&self) -> f32 {
+        self.positions
+            .iter()
+            .map(|d| d.length())
+            .fold(0.0, f32::max)
+    }
+
+    pub(crate) fn pick(&self, indices: &[u32]) -> Self {
+        let pick = |source: &[Vec3]| indices.iter().map(|&i| source[i as usize]).collect();
+        MorphTarget {
+            positions: pick(&self.positions),
+            normals: pick(&self.normals),
+            tangents: pick(&self.tangents),
+        }
+    }
+
+    pub(crate) fn deltas(&self, vertex: usize) -> [f32; MORPH_DELTA_FLOATS] {
+        let [px, py, pz] = self.positions[vertex].to_array();
+        let [nx, ny, nz] = self.normals[vertex].to_array();
+        let [tx, ty, tz] = self.tangents[vertex].to_array();
+        [px, py, pz, nx, ny, nz, tx, ty, tz]
+    }
+}
+
+/// Packs all targets target-major into the layout read by the morph compute shader, which is
+/// [`MORPH_DELTA_FLOATS`] floats per vertex per target.
+pub fn pack_morph_deltas(targets: &[MorphTarget]) -> Vec<f32> {
+    targets
+        .iter()
+        .flat_map(|target| (0..target.vertex_count()).flat_map(|v| target.deltas(v)))
+        .collect()
+}
@@ -0,0 +1,494 @@
+//! Geometry processing for [`Mesh`] data.
+//!
+//! Everything in here works on any [`Mesh`], no matter if it was loaded from an obj, imported
+//! from a glTF or assembled by a [`MeshBuilder`](super::MeshBuilder).
+
+use super::{Mesh, MeshVertexData, SecondaryVertex3D, Vertex3D};
+use bevy_mikktspace as mikktspace;
+use glamx::{Vec3, Vec4};
+use meshopt::{SimplifyOptions, VertexDataAdapter};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+use tracing::warn;
+
+/// How [`Mesh::recalculate_normals`] derives the vertex normals.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NormalMode {
+    /// Area-weighted average of all faces touching a vertex position.
+    Smooth,
+    /// Every triangle uses its face normal. Shared vertices are split up for this.
+    Flat,
+}
+
+/// A simplified level of detail, sharing the vertex data of the [`Mesh`] it was generated from.
+#[derive(Debug, Clone)]
+pub struct MeshLod {
+    pub indices: Vec<u32>,
+    /// Ranges into `indices`, one for every material range of the base mesh.
+    pub material_ranges: Vec<Range<u32>>,
+    /// Simplification error, relative to the extents of the mesh.
+    pub error: f32,
+}
+
+impl MeshLod {
+    #[inline]
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+}
+
+/// Controls how [`Mesh::generate_lods`] builds a LOD chain.
+#[derive(Debug, Copy, Clone)]
+pub struct LodChainSettings {
+    /// Target triangle ratio of every level relative to the one before it.
+    pub reduction: f32,
+    /// Maximum amount of simplified levels, not counting the base mesh.
+    pub max_levels: usize,
+    /// Largest simplification error a level may have, relative to the mesh extents.
+    pub max_error: f32,
+    /// No level with fewer triangles than this is generated.
+    pub min_triangles: usize,
+    /// Keeps the open borders of every material range in place, so ranges can't crack apart.
+    pub lock_borders: bool,
+}
+
+impl Default for LodChainSettings {
+    fn default() -> Self {
+        Self {
+            reduction: 0.5,
+            max_levels: 4,
+            max_error: 0.05,
+            min_triangles: 32,
+            lock_borders: true,
+        }
+    }
+}
+
+impl Mesh {
+    /// Returns the simplified levels of this mesh, from the most to the least detailed.
+    /// The mesh itself is the implicit level 0.
+    #[inline]
+    pub fn lods(&self) -> &[MeshLod] {
+        &self.lods
+    }
+
+    /// Recalculates all vertex normals from the triangle geometry.
+    ///
+    /// Tangents are not updated, so [`Mesh::generate_tangents`] should be called afterward
+    /// if the mesh is normal mapped.
+    pub fn recalculate_normals(&mut self, mode: NormalMode) {
+        let data = Arc::make_mut(&mut self.data);
+        match mode {
+            NormalMode::Smooth => smooth_normals(data),
+            NormalMode::Flat => {
+                unweld(data);
+                flat_normals(data);
+                self.lods.clear();
+            }
+        }
+    }
+
+    /// Generates MikkTSpace tangents, including the handedness of every tangent frame, from the
+    /// positions, normals and primary uv set.
+    ///
+    /// Shared vertices whose triangle corners end up with different tangent frames (e.g. on
+    /// mirrored uv seams) are split, so indexed meshes may gain vertices. Existing LOD levels keep
+    /// referencing the original vertices. Corners for which no tangent could be derived
+    /// (e.g. degenerate uvs) keep their current one.
+    pub fn generate_tangents(&mut self) {
+        let data = Arc::make_mut(&mut self.data);
+        let triangles = data.make_triangle_indices();
+        let mut geometry = TangentGeometry {
+            vertices: &data.vertices,
+            triangles: &triangles,
+            tangents: vec![None; triangles.len() * 3],
+        };
+
+        if let Err(e) = mikktspace::generate_tangents(&mut geometry) {
+            warn!("Failed to generate tangents: {e:?}");
+            return;
+        }
+
+        let corners = geometry.tangents;
+        apply_corner_tangents(data, &corners);
+    }
+
+    /// Merges all vertices whose attributes are equal within `epsilon` and makes the mesh indexed.
+    ///
+    /// Attributes are compared on a grid of `epsilon` sized cells. An `epsilon` of `0.0` only
+    /// merges exact duplicates. Existing LOD levels are discarded since they'd reference
+    /// vertices that don't exist anymore.
+    pub fn weld_vertices(&mut self, epsilon: f32) {
+        let data = Arc::make_mut(&mut self.data);
+        weld(data, epsilon);
+        self.lods.clear();
+    }
+
+    /// Reorders the triangles of every material range (and every LOD level) for better
+    /// post-transform vertex cache usage. Meshes without indices are welded exactly first.
+    pub fn optimize_vertex_cache(&mut self) {
+        if !self.has_indices() {
+            self.weld_vertices(0.0);
+        }
+
+        let data = Arc::make_mut(&mut self.data);
+        let vertex_count = data.vertices.len();
+        let Some(indices) = data.indices.as_mut() else {
+            return;
+        };
+
+        for range in &self.material_ranges {
+            optimize_range(indices, range, vertex_count);
+        }
+
+        for lod in &mut self.lods {
+            for range in &lod.material_ranges {
+                optimize_range(&mut lod.indices, range, vertex_count);
+            }
+        }
+    }
+
+    /// Simplifies every material range to about `target_ratio` of its triangles using
+    /// quadric error metrics, as long as the error stays below `max_error` (relative to the
+    /// mesh extents).
+    ///
+    /// The result references the vertices of this mesh. Meshes that aren't welded barely
+    /// simplify, since every triangle edge is a border then.
+    pub fn simplify(&self, target_ratio: f32, max_error: f32, lock_borders: bool) -> MeshLod {
+        let source: Cow<[u32]> = match self.indices() {
+            Some(indices) => Cow::Borrowed(indices),
+            None => Cow::Owned((0..self.vertex_count() as u32).collect()),
+        };
+
+        let adapter = VertexDataAdapter::new(
+            bytemuck::cast_slice(self.vertices()),
+            size_of::<Vertex3D>(),
+            0,
+        )
+        .expect("Vertex3D data should always be a valid position source");
+
+        let mut options = SimplifyOptions::None;
+        if lock_borders {
+            options |= SimplifyOptions::LockBorder;
+        }
+
+        let mut indices = Vec::with_capacity(source.len());
+        let mut material_ranges = Vec::with_capacity(self.material_ranges.len());
+        let mut error = 0.0f32;
+
+        for range in self.point_ranges() {
+            let slice = triangle_slice(&source, &range);
+            let target = (slice.len() as f32 * target_ratio.clamp(0.0, 1.0)) as usize / 3 * 3;
+
+            let mut range_error = 0.0;
+            let simplified = meshopt::simplify(
+                slice,
+                &adapter,
+                target,
+                max_error,
+                options,
+                Some(&mut range_error),
+            );
+
+            let start = indices.len() as u32;
+            indices.extend(simplified);
+            material_ranges.push(start..indices.len() as u32);
+            error = error.max(range_error);
+        }
+
+        MeshLod {
+            indices,
+            material_ranges,
+            error,
+        }
+    }
+
+    /// Replaces the LOD levels of this mesh with a freshly simplified chain.
+    ///
+    /// Every level is simplified from the base mesh directly, with the triangle target shrinking
+    /// by [`LodChainSettings::reduction`] per level. Generation stops early once simplification
+    /// can't make progress within the error bound. Meshes without indices are welded exactly first.
+    pub fn generate_lods(&mut self, settings: &LodChainSettings) {
+        if !self.has_indices() {
+            self.weld_vertices(0.0);
+        }
+
+        self.lods.clear();
+
+        let mut ratio = 1.0;
+        let mut previous = self.triangle_count();
+        for _ in 0..settings.max_levels {
+            ratio *= settings.reduction;
+
+            let lod = self.simplify(ratio, settings.max_error, settings.lock_borders);
+            let triangles = lod.triangle_count();
+            if triangles < settings.min_triangles || triangles >= previous {
+                break;
+            }
+
+            previous = triangles;
+            self.lods.push(lod);
+        }
+    }
+
+    fn point_ranges(&self) -> Vec<Range<u32>> {
+        if self.material_ranges.is_empty() {
+            let count = self.indices().map_or(self.vertex_count(), <[u32]>::len);
+            std::iter::once(0..count as u32).collect()
+        } else {
+            self.material_ranges.clone()
+        }
+    }
+}
+
+struct TangentGeometry<'a> {
+    vertices: &'a [Vertex3D],
+    triangles: &'a [[u32; 3]],
+    /// One tangent per triangle corner
+    tangents: Vec<Option<Vec4>>,
+}
+
+impl TangentGeometry<'_> {
+    fn vertex(&self, face: usize, vert: usize) -> &Vertex3D {
+        &self.vertices[self.triangles[face][vert] as usize]
+    }
+}
+
+impl mikktspace::Geometry for TangentGeometry<'_> {
+    fn num_faces(&self) -> usize {
+        self.triangles.len()
+    }
+
+    fn num_vertices_of_face(&self, _face: usize) -> usize {
+        3
+    }
+
+    fn position(&self, face: usize, vert: usize) -> [f32; 3] {
+        self.vertex(face, vert).position.to_array()
+    }
+
+    fn normal(&self, face: usize, vert: usize) -> [f32; 3] {
+        self.vertex(face, vert).normal.to_array()
+    }
+
+    fn tex_coord(&self, face: usize, vert: usize) -> [f32; 2] {
+        self.vertex(face, vert).uv.to_array()
+    }
+
+    fn set_tangent(
+        &mut self,
+        tangent_space: Option<mikktspace::TangentSpace>,
+        face: usize,
+        vert: usize,
+    ) {
+        let Some(space) = tangent_space else {
+            return;
+        };
+
+        let tangent = Vec4::from(space.tangent_encoded());
+        if tangent.truncate().length_squared() > f32::EPSILON {
+            let direction = tangent.truncate().normalize();
+            self.tangents[face * 3 + vert] = Some(direction.extend(tangent.w));
+        }
+    }
+}
+
+/// Assigns the tangents of every triangle corner to its vertex. Vertices shared by corners with
+/// different tangent frames are duplicated, one copy per frame.
+fn apply_corner_tangents(data: &mut MeshVertexData<Vertex3D>, corners: &[Option<Vec4>]) {
+    let vertex_count = data.vertices.len();
+    let mut tangents: Vec<Option<Vec4>> = vec![None; vertex_count];
+    let mut origin: Vec<u32> = (0..vertex_count as u32).collect();
+    let mut copies: HashMap<u32, Vec<u32>> = HashMap::new();
+
+    for (corner, tangent) in corners.iter().enumerate() {
+        let Some(tangent) = *tangent else {
+            continue;
+        };
+
+        // unindexed meshes have a vertex per corner, so nothing can disagree there
+        let Some(indices) = data.indices.as_mut() else {
+            tangents[corner] = Some(tangent);
+            continue;
+        };
+
+        let vertex = indices[corner];
+        let candidates = copies.get(&vertex).into_iter().flatten().copied();
+        let existing = std::iter::once(vertex)
+            .chain(candidates)
+            .find(|&v| tangents[v as usize].is_none_or(|t| same_tangent_frame(t, tangent)));
+
+        let target = existing.unwrap_or_else(|| {
+            let copy = origin.len() as u32;
+            origin.push(vertex);
+            tangents.push(None);
+            copies.entry(vertex).or_default().push(copy);
+            copy
+        });
+
+        tangents[target as usize].get_or_insert(tangent);
+        indices[corner] = target;
+    }
+
+    if origin.len() > vertex_count {
+        data.vertices = pick(&data.vertices, &origin);
+        data.colors = data.colors.as_ref().map(|c| pick(c, &origin));
+        data.uv1 = data.uv1.as_ref().map(|uv| pick(uv, &origin));
+        for target in &mut data.morph_targets {
+            *target = target.pick(&origin);
+        }
+    }
+
+    for (vertex, tangent) in data.vertices.iter_mut().zip(tangents) {
+        if let Some(tangent) = tangent {
+            vertex.tangent = tangent;
+        }
+    }
+}
+
+/// Corners can share a vertex if their handedness matches and their tangents are about parallel.
+fn same_tangent_frame(a: Vec4, b: Vec4) -> bool {
+    a.w == b.w && a.truncate().dot(b.truncate()) > 0.999
+}
+
+fn smooth_normals(data: &mut MeshVertexData<Vertex3D>) {
+    let mut normals: HashMap<[u32; 3], Vec3> = HashMap::new();
+
+    for [a, b, c] in data.make_triangle_indices() {
+        let pa = data.vertices[a as usize].position;
+        let pb = data.vertices[b as usize].position;
+        let pc = data.vertices[c as usize].position;
+
+        // the cross product length is twice the triangle area, which gives the area weighting
+        let face_normal = (pb - pa).cross(pc - pa);
+        for p in [pa, pb, pc] {
+            *normals.entry(position_key(p)).or_default() += face_normal;
+        }
+    }
+
+    for vertex in &mut data.vertices {
+        if let Some(normal) = normals.get(&position_key(vertex.position)) {
+            vertex.normal = normal.normalize_or(vertex.normal);
+        }
+    }
+}
+
+fn flat_normals(data: &mut MeshVertexData<Vertex3D>) {
+    debug_assert!(data.indices.is_none(), "Flat normals need an unwelded mesh");
+
+    for triangle in data.vertices.chunks_exact_mut(3) {
+        let [a, b, c] = triangle else {
+            unreachable!();
+        };
+        let normal = (b.position - a.position)
+            .cross(c.position - a.position)
+            .normalize_or(a.normal);
+
+        a.normal = normal;
+        b.normal = normal;
+        c.normal = normal;
+    }
+}
+
+/// Expands an indexed mesh so every triangle corner has its own vertex.
+fn unweld(data: &mut MeshVertexData<Vertex3D>) {
+    let Some(indices) = data.indices.take() else {
+        return;
+    };
+
+    data.vertices = pick(&data.vertices, &indices);
+    data.colors = data.colors.as_ref().map(|c| pick(c, &indices));
+    data.uv1 = data.uv1.as_ref().map(|uv| pick(uv, &indices));
+    for target in &mut data.morph_targets {
+        *target = target.pick(&indices);
+    }
+}
+
+fn weld(data: &mut MeshVertexData<Vertex3D>, epsilon: f32) {
+    let secondary = data.secondary_vertices();
+    let mut lookup: HashMap<Vec<i64>, u32> = HashMap::with_capacity(data.vertices.len());
+    let mut kept: Vec<u32> = Vec::new();
+    let mut remap = Vec::with_capacity(data.vertices.len());
+
+    for (i, (vertex, extra)) in data.vertices.iter().zip(&secondary).enumerate() {
+        let mut key = vertex_key(vertex, extra, epsilon);
+        // vertices only merge if every blend shape moves them the same way
+        for target in &data.morph_targets {
+            key.extend(target.deltas(i).map(|v| quantize(v, epsilon)));
+        }
+        let next = kept.len() as u32;
+        let id = *lookup.entry(key).or_insert_with(|| {
+            kept.push(i as u32);
+            next
+        });
+        remap.push(id);
+    }
+
+    let indices = match data.indices.take() {
+        Some(indices) => indices.iter().map(|&i| remap[i as usize]).collect(),
+        None => remap,
+    };
+
+    data.vertices = pick(&data.vertices, &kept);
+    data.colors = data.colors.as_ref().map(|c| pick(c, &kept));
+    data.uv1 = data.uv1.as_ref().map(|uv| pick(uv, &kept));
+    for target in &mut data.morph_targets {
+        *target = target.pick(&kept);
+    }
+    data.indices = Some(indices);
+}
+
+fn vertex_key(vertex: &Vertex3D, extra: &SecondaryVertex3D, epsilon: f32) -> Vec<i64> {
+    let floats = vertex
+        .position
+        .to_array()
+        .into_iter()
+        .chain(vertex.uv.to_array())
+        .chain(vertex.normal.to_array())
+        .chain(vertex.tangent.to_array())
+        .chain(vertex.bone_weights)
+        .chain(extra.color)
+        .chain(extra.uv1.to_array())
+        .map(|v| quantize(v, epsilon));
+
+    floats.chain(vertex.bone_indices.map(i64::from)).collect()
+}
+
+fn quantize(v: f32, epsilon: f32) -> i64 {
+    if epsilon > 0.0 {
+        (v / epsilon).round() as i64
+    } else {
+        float_key(v) as i64
+    }
+}
+
+fn optimize_range(indices: &mut [u32], range: &Range<u32>, vertex_count: usize) {
+    let bounds = triangle_bounds(indices.len(), range);
+    meshopt::optimize_vertex_cache_in_place(&mut indices[bounds], vertex_count);
+}
+
+fn triangle_slice<'a>(indices: &'a [u32], range: &Range<u32>) -> &'a [u32] {
+    &indices[triangle_bounds(indices.len(), range)]
+}
+
+/// Clamps a material range to the index count and cuts off incomplete triangles.
+fn triangle_bounds(len: usize, range: &Range<u32>) -> Range<usize> {
+    let start = (range.start as usize).min(len);
+    let end = (range.end as usize).clamp(start, len);
+    start..start + (end - start) / 3 * 3
+}
+
+fn pick<T: Copy>(source: &[T], indices: &[u32]) -> Vec<T> {
+    indices.iter().map(|&i| source[i as usize]).collect()
+}
+
+fn position_key(p: Vec3) -> [u32; 3] {
+    p.to_array().map(float_key)
+}
+
+/// Bit pattern of a float, with `-0.0` and `0.0` treated as equal.
+fn float_key(v: f32) -> u32 {
+    if v == 0.0 { 0 } else { v.to_bits() }
+}
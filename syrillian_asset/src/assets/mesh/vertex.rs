@@ -1,4 +1,4 @@
-use glamx::{Vec2, Vec3};
+use glamx::{Mat4, Vec2, Vec3, Vec4};
 use static_assertions::const_assert_eq;
 use syrillian_utils::sizes::{VEC2_SIZE, VEC3_SIZE, VEC4_SIZE, vertex_layout_size};
 use wgpu::{BufferAddress, VertexAttribute, VertexFormat};
@@ -34,7 +34,7 @@ impl SimpleVertex3D {
         let tx = world_up[0] - nx * dot;
         let ty = world_up[1] - ny * dot;
         let tz = world_up[2] - nz * dot;
-        let tangent = Vec3::new(tx, ty, tz);
+        let tangent = Vec4::new(tx, ty, tz, 1.0);
 
         let position = Vec3::new(px, py, pz);
         let uv = Vec2::new(u, v);
@@ -58,13 +58,15 @@ pub struct Vertex3D {
     pub position: Vec3,
     pub uv: Vec2,
     pub normal: Vec3,
-    pub tangent: Vec3,
+    /// Tangent direction in `xyz` and the handedness of the tangent frame (`1.0` or `-1.0`) in
+    /// `w`. The bitangent is `cross(normal, tangent.xyz) * tangent.w`.
+    pub tangent: Vec4,
     pub bone_indices: [u32; 4],
     pub bone_weights: [f32; 4],
 }
 
 impl Vertex3D {
-    /// Creates a new vertex from individual attributes.
+    /// Creates a new vertex from individual attributes, with a right-handed tangent frame.
     pub fn new(
         position: Vec3,
         tex_coord: Vec2,
@@ -77,7 +79,7 @@ impl Vertex3D {
             position,
             uv: tex_coord,
             normal,
-            tangent,
+            tangent: tangent.extend(1.0),
             bone_indices: pad_to_four(bone_indices, 0x0),
             bone_weights: pad_to_four(bone_weights, 0.0),
         }
@@ -105,18 +107,18 @@ impl Vertex3D {
                     shader_location: 2,
                 },
                 VertexAttribute {
-                    format: VertexFormat::Float32x3,
+                    format: VertexFormat::Float32x4,
                     offset: (VEC3_SIZE * 2 + VEC2_SIZE) as BufferAddress,
                     shader_location: 3,
                 },
                 VertexAttribute {
                     format: VertexFormat::Uint32x4,
-                    offset: (VEC3_SIZE * 3 + VEC2_SIZE) as BufferAddress,
+                    offset: (VEC4_SIZE + VEC3_SIZE * 2 + VEC2_SIZE) as BufferAddress,
                     shader_location: 4,
                 },
                 VertexAttribute {
                     format: VertexFormat::Float32x4,
-                    offset: (VEC4_SIZE + VEC3_SIZE * 3 + VEC2_SIZE) as BufferAddress,
+                    offset: (VEC4_SIZE * 2 + VEC3_SIZE * 2 + VEC2_SIZE) as BufferAddress,
                     shader_location: 5,
                 },
             ],
@@ -132,7 +134,7 @@ impl Vertex3D {
             position,
             uv,
             normal,
-            tangent: Vec3::X,
+            tangent: Vec4::new(1.0, 0.0, 0.0, 1.0),
             bone_indices: [0; 4],
             bone_weights: [0.0; 4],
         }
@@ -143,25 +145,157 @@ impl Vertex3D {
             position,
             uv: Vec2::ZERO,
             normal: Vec3::Y,
-            tangent: Vec3::X,
+            tangent: Vec4::new(1.0, 0.0, 0.0, 1.0),
             bone_indices: [0; 4],
             bone_weights: [0.0; 4],
         }
     }
 }
 
+/// Optional per-vertex attributes, stored in a second vertex stream next to [`Vertex3D`].
+///
+/// Meshes that don't provide these attributes get a default stream with white vertex colors
+/// and the primary uv set mirrored into `uv1`, so every mesh pipeline can rely on both streams.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SecondaryVertex3D {
+    /// Linear RGBA color. Kept as an array since [`Vec4`] would pad this vertex to 32 bytes.
+    pub color: [f32; 4],
+    pub uv1: Vec2,
+}
+
+impl SecondaryVertex3D {
+    /// Vertex buffer slot the secondary stream is bound to.
+    pub const SLOT: u32 = 1;
+
+    pub fn new(color: Vec4, uv1: Vec2) -> Self {
+        SecondaryVertex3D {
+            color: color.to_array(),
+            uv1,
+        }
+    }
+
+    /// Secondary attributes used when a mesh doesn't provide any of its own.
+    pub const fn fallback(uv: Vec2) -> Self {
+        SecondaryVertex3D {
+            color: [1.0; 4],
+            uv1: uv,
+        }
+    }
+
+    /// Returns a [`wgpu::VertexBufferLayout`] describing the layout of the secondary stream.
+    pub const fn continuous_descriptor<'a>() -> wgpu::VertexBufferLayout<'a> {
+        const LAYOUT: wgpu::VertexBufferLayout = wgpu::VertexBufferLayout {
+            array_stride: size_of::<SecondaryVertex3D>() as BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 6,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: VEC4_SIZE,
+                    shader_location: 7,
+                },
+            ],
+        };
+
+        const_assert_eq!(size_of::<SecondaryVertex3D>(), vertex_layout_size(&LAYOUT));
+
+        LAYOUT
+    }
+}
+
+/// Per-instance attributes of a mesh draw, stored in a third vertex stream that steps once per
+/// instance.
+///
+/// Regular draws bind a single [`MeshInstance::IDENTITY`], so the model transform is used as is.
+/// Instanced draws bind one entry per instance, placed relative to the model transform.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct MeshInstance {
+    pub transform: Mat4,
+    /// Added to the picking color of the draw. Zero unless instances should be picked as
+    /// different objects.
+    pub pick_color: [f32; 4],
+}
+
+impl MeshInstance {
+    /// Vertex buffer slot the instance stream is bound to.
+    pub const SLOT: u32 = 2;
+
+    pub const IDENTITY: MeshInstance = MeshInstance {
+        transform: Mat4::IDENTITY,
+        pick_color: [0.0; 4],
+    };
+
+    pub const fn new(transform: Mat4, pick_color: [f32; 4]) -> Self {
+        MeshInstance {
+            transform,
+            pick_color,
+        }
+    }
+
+    /// Returns a [`wgpu::VertexBufferLayout`] describing the layout of the instance stream.
+    pub const fn continuous_descriptor<'a>() -> wgpu::VertexBufferLayout<'a> {
+        const LAYOUT: wgpu::VertexBufferLayout = wgpu::VertexBufferLayout {
+            array_stride: size_of::<MeshInstance>() as BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: 0,
+                    shader_location: 8,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VEC4_SIZE,
+                    shader_location: 9,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VEC4_SIZE * 2,
+                    shader_location: 10,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VEC4_SIZE * 3,
+                    shader_location: 11,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4,
+                    offset: VEC4_SIZE * 4,
+                    shader_location: 12,
+                },
+            ],
+        };
+
+        const_assert_eq!(size_of::<MeshInstance>(), vertex_layout_size(&LAYOUT));
+
+        LAYOUT
+    }
+}
+
+/// `(position, uv, normal, tangent, bitangent, bone indices, bone weights)`. The handedness of
+/// the tangent frame is taken from the bitangent.
 pub type Vertex3DTuple<'a, IU, IF> = (Vec3, Vec2, Vec3, Vec3, Vec3, IU, IF);
 
 impl<'a, IU: AsRef<[u32]>, IF: AsRef<[f32]>> From<Vertex3DTuple<'a, IU, IF>> for Vertex3D {
     fn from(value: Vertex3DTuple<IU, IF>) -> Self {
-        Vertex3D::new(
+        let mut vertex = Vertex3D::new(
             value.0,
             value.1,
             value.2,
             value.3,
             value.5.as_ref(),
             value.6.as_ref(),
-        )
+        );
+        if value.2.cross(value.3).dot(value.4) < 0.0 {
+            vertex.tangent.w = -1.0;
+        }
+        vertex
     }
 }
 
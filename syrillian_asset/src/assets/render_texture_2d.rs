@@ -9,6 +9,29 @@ pub struct RenderTexture2D {
     pub format: TextureFormat,
 }
 
+impl RenderTexture2D {
+    /// Format of render textures that cameras can render into.
+    pub const CAMERA_FORMAT: TextureFormat = TextureFormat::Bgra8UnormSrgb;
+
+    pub fn new(width: u32, height: u32, format: TextureFormat) -> Self {
+        Self {
+            width: width.max(1),
+            height: height.max(1),
+            format,
+        }
+    }
+
+    /// A render texture a camera can render into, and which materials can then sample.
+    pub fn new_camera_target(width: u32, height: u32) -> Self {
+        Self::new(width, height, Self::CAMERA_FORMAT)
+    }
+
+    /// Whether a camera can render into this texture.
+    pub fn is_camera_target(&self) -> bool {
+        self.format.remove_srgb_suffix() == Self::CAMERA_FORMAT.remove_srgb_suffix()
+    }
+}
+
 impl StoreType for RenderTexture2D {
     const NAME: &str = "Render Texture 2D";
 
@@ -1,18 +1,24 @@
-use crate::mesh::Vertex3D;
+use crate::mesh::{MeshInstance, SecondaryVertex3D, Vertex3D};
 use wgpu::{
     BlendState, ColorTargetState, ColorWrites, TextureFormat, VertexBufferLayout, VertexStepMode,
 };
 
-pub const DEFAULT_VBL: [VertexBufferLayout; 1] = [Vertex3D::continuous_descriptor()];
-pub const DEFAULT_VBL_STEP_INSTANCE: [VertexBufferLayout; 1] = {
+pub const DEFAULT_VBL: [VertexBufferLayout; 3] = [
+    Vertex3D::continuous_descriptor(),
+    SecondaryVertex3D::continuous_descriptor(),
+    MeshInstance::continuous_descriptor(),
+];
+pub const DEFAULT_VBL_STEP_INSTANCE: [VertexBufferLayout; 2] = {
     let mut continuous = Vertex3D::continuous_descriptor();
     continuous.step_mode = VertexStepMode::Instance;
-    [continuous]
+    let mut secondary = SecondaryVertex3D::continuous_descriptor();
+    secondary.step_mode = VertexStepMode::Instance;
+    [continuous, secondary]
 };
 
 pub const DEFAULT_COLOR_TARGETS: &[Option<ColorTargetState>] = &[
     Some(ColorTargetState {
-        format: TextureFormat::Rgba8Unorm, // color
+        format: TextureFormat::Rgba16Float, // color
         blend: Some(BlendState::ALPHA_BLENDING),
         write_mask: ColorWrites::all(),
     }),
@@ -29,7 +35,7 @@ pub const DEFAULT_COLOR_TARGETS: &[Option<ColorTargetState>] = &[
 ];
 
 pub const ONLY_COLOR_TARGET: &[Option<ColorTargetState>] = &[Some(ColorTargetState {
-    format: TextureFormat::Rgba8Unorm,
+    format: TextureFormat::Rgba16Float,
     blend: Some(BlendState::ALPHA_BLENDING),
     write_mask: ColorWrites::all(),
 })];
@@ -41,6 +47,13 @@ pub const ONLY_COLOR_TARGET_SRGB: &[Option<ColorTargetState>] = &[Some(ColorTarg
 })];
 
 pub const DEFAULT_PP_COLOR_TARGETS: &[Option<ColorTargetState>] = &[Some(ColorTargetState {
+    format: TextureFormat::Rgba16Float,
+    blend: None,
+    write_mask: ColorWrites::all(),
+})];
+
+/// Post processing after tonemapping, which works on display range colors
+pub const DISPLAY_PP_COLOR_TARGETS: &[Option<ColorTargetState>] = &[Some(ColorTargetState {
     format: TextureFormat::Rgba8Unorm,
     blend: None,
     write_mask: ColorWrites::all(),
@@ -8,7 +8,7 @@ pub mod immediates;
 
 use self::defaults::{
     DEFAULT_COLOR_TARGETS, DEFAULT_PP_COLOR_TARGETS, DEFAULT_VBL, DEFAULT_VBL_STEP_INSTANCE,
-    ONLY_COLOR_TARGET, ONLY_COLOR_TARGET_SRGB, SURFACE_PP_COLOR_TARGETS,
+    DISPLAY_PP_COLOR_TARGETS, ONLY_COLOR_TARGET, ONLY_COLOR_TARGET_SRGB, SURFACE_PP_COLOR_TARGETS,
 };
 use crate::HShader;
 use crate::material_inputs::MaterialInputLayout;
@@ -20,13 +20,15 @@ use bon::Builder;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
-use syrillian_shadergen::function::{PbrShader, PostProcessPassthroughMaterial};
+use syrillian_shadergen::function::{
+    PbrShader, PostProcessMaterialExpression, PostProcessPassthroughMaterial,
+};
 use syrillian_shadergen::generator::{MaterialGroupOverrides, ShaderKind, assemble_shader};
 use syrillian_shadergen::generator::{MeshPass, PICKING_TEXTURE_FORMAT};
 use syrillian_shadergen::{MaterialCompiler, PostProcessCompiler};
 use syrillian_utils::sizes::{VEC2_SIZE, VEC3_SIZE, VEC4_SIZE, WGPU_VEC4_ALIGN};
 use wgpu::{
-    ColorTargetState, ColorWrites, PolygonMode, PrimitiveTopology, VertexAttribute,
+    ColorTargetState, ColorWrites, PolygonMode, PrimitiveTopology, TextureFormat, VertexAttribute,
     VertexBufferLayout, VertexFormat, VertexStepMode,
 };
 
@@ -135,7 +137,10 @@ impl H<Shader> {
     pub const SKYBOX_ID: u32 = 19;
     pub const SKYBOX_PROCEDURAL_ID: u32 = 20;
     pub const DIM3_GEN_LIT_ID: u32 = 21;
-    pub const MAX_BUILTIN_ID: u32 = 21;
+    pub const SPRITE_ID: u32 = 22;
+    pub const SPRITE_PICKER_ID: u32 = 23;
+    pub const DIM3_VELOCITY_ID: u32 = 24;
+    pub const MAX_BUILTIN_ID: u32 = 24;
 
     // The fallback shader if a pipeline fails
     pub const FALLBACK: H<Shader> = H::new(Self::FALLBACK_ID);
@@ -188,6 +193,15 @@ impl H<Shader> {
     pub const SKYBOX: H<Shader> = H::new(Self::SKYBOX_ID);
     pub const DIM3_GEN_LIT: H<Shader> = H::new(Self::DIM3_GEN_LIT_ID);
     pub const SKYBOX_PROCEDURAL: H<Shader> = H::new(Self::SKYBOX_PROCEDURAL_ID);
+
+    // Instanced sprite shader, one quad per sprite instance.
+    pub const SPRITE: H<Shader> = H::new(Self::SPRITE_ID);
+
+    // Sprite picking shader.
+    pub const SPRITE_PICKING: H<Shader> = H::new(Self::SPRITE_PICKER_ID);
+
+    // Writes the screen space motion of moving meshes into the velocity G-buffer.
+    pub const DIM3_VELOCITY: H<Shader> = H::new(Self::DIM3_VELOCITY_ID);
 }
 
 const SHADER_FALLBACK3D: &str = include_str!("shaders/fallback_shader3d.wgsl");
@@ -201,6 +215,9 @@ const SHADER_LINE2D: &str = include_str!("shaders/line.wgsl");
 const SHADER_POST_PROCESS_FXAA: &str = include_str!("shaders/post_process_fxaa.wgsl");
 const SHADER_SKYBOX: &str = include_str!("shaders/skybox.wgsl");
 const SHADER_SKYBOX_PROCEDURAL: &str = include_str!("shaders/skybox_procedural.wgsl");
+const SHADER_SPRITE: &str = include_str!("shaders/sprite.wgsl");
+const SHADER_SPRITE_PICKER: &str = include_str!("shaders/picking_sprite.wgsl");
+const SHADER_DIM3_VELOCITY: &str = include_str!("shaders/velocity.wgsl");
 
 const DEBUG_EDGES_SHADER: &str = include_str!("shaders/debug/edges.wgsl");
 const DEBUG_VERTEX_NORMAL_SHADER: &str = include_str!("shaders/debug/vertex_normals.wgsl");
@@ -515,7 +532,13 @@ impl StoreDefaults for Shader {
         store_add_checked!(
             store,
             HShader::POST_PROCESS_FXAA_ID,
-            Shader::new_post_process_fragment_linear("Post Process FXAA", SHADER_POST_PROCESS_FXAA)
+            Shader::builder()
+                .shader_type(ShaderType::PostProcessing)
+                .name("Post Process FXAA")
+                .code(ShaderCode::Fragment(SHADER_POST_PROCESS_FXAA.to_string()))
+                .color_target(DISPLAY_PP_COLOR_TARGETS)
+                .depth_enabled(false)
+                .build()
         );
 
         store_add_checked!(
@@ -556,6 +579,89 @@ impl StoreDefaults for Shader {
                 .material_groups(material_groups.clone())
                 .build()
         );
+
+        const SPRITE_VBL: &[VertexBufferLayout] = &[VertexBufferLayout {
+            array_stride: VEC4_SIZE * 6,
+            step_mode: VertexStepMode::Instance,
+            attributes: &[
+                VertexAttribute {
+                    format: VertexFormat::Float32x4, // origin
+                    offset: 0,
+                    shader_location: 0,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4, // x axis
+                    offset: VEC4_SIZE,
+                    shader_location: 1,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4, // y axis
+                    offset: VEC4_SIZE * 2,
+                    shader_location: 2,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4, // uv rect
+                    offset: VEC4_SIZE * 3,
+                    shader_location: 3,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4, // color
+                    offset: VEC4_SIZE * 4,
+                    shader_location: 4,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x4, // pick color
+                    offset: VEC4_SIZE * 5,
+                    shader_location: 5,
+                },
+            ],
+        }];
+
+        store_add_checked!(
+            store,
+            HShader::SPRITE_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Sprite Shader")
+                .code(ShaderCode::Full(SHADER_SPRITE.to_string()))
+                .vertex_buffers(SPRITE_VBL)
+                .material_layout(default_layout.clone())
+                .material_groups(material_groups.clone())
+                .build()
+        );
+
+        store_add_checked!(
+            store,
+            HShader::SPRITE_PICKER_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Sprite Picking Shader")
+                .code(ShaderCode::Full(SHADER_SPRITE_PICKER.to_string()))
+                .vertex_buffers(SPRITE_VBL)
+                .immediate_size(VEC4_SIZE as u32)
+                .material_layout(default_layout.clone())
+                .material_groups(material_groups.clone())
+                .color_target(PICKING_COLOR_TARGET)
+                .build()
+        );
+
+        const VELOCITY_COLOR_TARGET: &[Option<ColorTargetState>] = &[Some(ColorTargetState {
+            format: TextureFormat::Rgba16Float,
+            blend: None,
+            write_mask: ColorWrites::all(),
+        })];
+
+        store_add_checked!(
+            store,
+            HShader::DIM3_VELOCITY_ID,
+            Shader::builder()
+                .shader_type(ShaderType::Custom)
+                .name("Mesh Velocity Shader")
+                .code(ShaderCode::Full(SHADER_DIM3_VELOCITY.to_string()))
+                .immediate_size((VEC4_SIZE * 4) as u32)
+                .color_target(VELOCITY_COLOR_TARGET)
+                .build()
+        );
     }
 }
 
@@ -583,6 +689,9 @@ impl StoreType for Shader {
             HShader::SKYBOX_ID => "Skybox Background Shader",
             HShader::SKYBOX_PROCEDURAL_ID => "Skybox Procedural Shader",
             HShader::DIM3_GEN_LIT_ID => "mesh3d_gen_lit",
+            HShader::SPRITE_ID => "Sprite Shader",
+            HShader::SPRITE_PICKER_ID => "Sprite Picking Shader",
+            HShader::DIM3_VELOCITY_ID => "Mesh Velocity Shader",
 
             HShader::DEBUG_EDGES_ID => "Debug Edges Shader",
             HShader::DEBUG_VERTEX_NORMALS_ID => "Debug Vertex Normals Shader",
@@ -684,6 +793,16 @@ impl Shader {
         }
     }
 
+    /// Compiles a post process material into a shader for a custom post process pass.
+    pub fn new_post_process_material<S, M>(name: S, material: &M) -> Shader
+    where
+        S: Into<String>,
+        M: PostProcessMaterialExpression,
+    {
+        let code = PostProcessCompiler::compile_post_process_fragment(material, 0);
+        Self::new_post_process_fragment_linear(name, code)
+    }
+
     pub fn new_fragment<S, S2>(name: S, code: S2) -> Shader
     where
         S: Into<String>,
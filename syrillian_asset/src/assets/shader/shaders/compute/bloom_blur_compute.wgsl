@@ -13,7 +13,7 @@ struct BloomParams {
 @group(0) @binding(1) var bloomAuxInput: texture_2d<f32>;
 @group(0) @binding(2) var bloomSampler: sampler;
 @group(0) @binding(3) var<uniform> bloomParams: BloomParams;
-@group(0) @binding(4) var bloomOutput: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(4) var bloomOutput: texture_storage_2d<rgba16float, write>;
 
 fn sample_rgb(uv: vec2f) -> vec3f {
     return textureSampleLevel(bloomInput, bloomSampler, uv, 0.0).rgb;
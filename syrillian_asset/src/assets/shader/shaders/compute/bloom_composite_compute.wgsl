@@ -13,7 +13,7 @@ struct BloomParams {
 @group(0) @binding(1) var bloomAuxInput: texture_2d<f32>;
 @group(0) @binding(2) var bloomSampler: sampler;
 @group(0) @binding(3) var<uniform> bloomParams: BloomParams;
-@group(0) @binding(4) var bloomOutput: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(4) var bloomOutput: texture_storage_2d<rgba16float, write>;
 
 @compute @workgroup_size(8, 8, 1)
 fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
@@ -0,0 +1,78 @@
+#use render
+
+@group(1) @binding(0) var postTexture: texture_2d<f32>;
+@group(1) @binding(2) var postDepth: texture_depth_2d;
+@group(1) @binding(5) var dofOutput: texture_storage_2d<rgba16float, write>;
+
+// Height of a full frame sensor in meters, the focal length is relative to it
+const SENSOR_HEIGHT: f32 = 0.024;
+const MAX_COC_RADIUS: f32 = 16.0;
+const SAMPLE_COUNT: u32 = 48u;
+const GOLDEN_ANGLE: f32 = 2.39996323;
+
+fn view_depth(pixel: vec2i, size_f: vec2f) -> f32 {
+    let uv = (vec2f(pixel) + vec2f(0.5)) / size_f;
+    let depth = textureLoad(postDepth, pixel, 0);
+    let ndc = vec4f(uv * vec2f(2.0, -2.0) + vec2f(-1.0, 1.0), depth, 1.0);
+    let world_h = camera.inv_view_proj_mat * ndc;
+    let world = world_h.xyz / max(abs(world_h.w), 1e-6) * sign(world_h.w);
+    return -(camera.view_mat * vec4f(world, 1.0)).z;
+}
+
+// Signed radius of the circle of confusion in pixels, negative in front of the focus plane.
+// Thin lens model: the blur diameter on the sensor is f² * (z - s) / (N * z * (s - f)).
+fn coc_radius(pixel: vec2i, size_f: vec2f) -> f32 {
+    let focal_length = camera.focal_length * 0.001;
+    let focus = max(camera.focus_distance, focal_length + 1e-3);
+    let z = max(view_depth(pixel, size_f), 1e-4);
+
+    let diameter = focal_length * focal_length * (z - focus)
+        / (camera.aperture * z * (focus - focal_length));
+    let radius = diameter / SENSOR_HEIGHT * size_f.y * 0.5;
+    return clamp(radius, -MAX_COC_RADIUS, MAX_COC_RADIUS);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(dofOutput);
+    if (gid.x >= size.x || gid.y >= size.y) {
+        return;
+    }
+
+    let pixel = vec2i(gid.xy);
+    let size_f = vec2f(size);
+    let center = textureLoad(postTexture, pixel, 0);
+
+    // an aperture of zero is a pinhole camera, everything is in focus
+    if (camera.aperture <= 0.0) {
+        textureStore(dofOutput, pixel, center);
+        return;
+    }
+
+    let center_coc = coc_radius(pixel, size_f);
+    let max_pixel = vec2i(size) - vec2i(1);
+
+    // Gathers a disc of samples, every sample counts if its own blur circle reaches this pixel.
+    // Samples behind this pixel can't spread further than this pixel is blurred, so sharp
+    // foreground edges don't get smeared by the blurry background.
+    var color = center.rgb;
+    var weight = 1.0;
+    for (var i = 0u; i < SAMPLE_COUNT; i++) {
+        let distance = sqrt((f32(i) + 0.5) / f32(SAMPLE_COUNT)) * MAX_COC_RADIUS;
+        let angle = f32(i) * GOLDEN_ANGLE;
+        let offset = vec2f(cos(angle), sin(angle)) * distance;
+        let sample_pixel = clamp(pixel + vec2i(round(offset)), vec2i(0), max_pixel);
+
+        let sample_coc = coc_radius(sample_pixel, size_f);
+        var reach = abs(sample_coc);
+        if (sample_coc > center_coc) {
+            reach = min(reach, abs(center_coc));
+        }
+
+        let w = saturate(reach - distance + 1.0);
+        color += textureLoad(postTexture, sample_pixel, 0).rgb * w;
+        weight += w;
+    }
+
+    textureStore(dofOutput, pixel, vec4f(color / weight, center.a));
+}
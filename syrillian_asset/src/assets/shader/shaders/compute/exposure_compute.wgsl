@@ -0,0 +1,114 @@
+#use render
+
+struct ExposureParams {
+    // Lowest luminance of the histogram, as log2
+    min_log_luminance: f32,
+    // Luminance range covered by the histogram, as log2
+    log_luminance_range: f32,
+    // Exposure offset in EV, applied in both modes
+    compensation: f32,
+    // Adaptation speed when the scene gets brighter
+    speed_up: f32,
+    // Adaptation speed when the scene gets darker
+    speed_down: f32,
+    // 0 for manual exposure, 1 for automatic exposure
+    mode: u32,
+    // 1 if the adapted luminance should snap to the current frame
+    reset: u32,
+    _pad0: f32,
+}
+
+struct ExposureState {
+    // Adapted average scene luminance, 0 before the first frame
+    luminance: f32,
+    // Multiplier applied to the scene color before tonemapping
+    exposure: f32,
+    _pad0: f32,
+    _pad1: f32,
+}
+
+@group(1) @binding(0) var exposureColor: texture_2d<f32>;
+@group(1) @binding(1) var<storage, read_write> exposureHistogram: array<atomic<u32>, 256>;
+@group(1) @binding(2) var<storage, read_write> exposureState: ExposureState;
+@group(1) @binding(3) var<uniform> exposureParams: ExposureParams;
+
+const BIN_COUNT: u32 = 256u;
+const MIDDLE_GREY: f32 = 0.18;
+const LUMINANCE_WEIGHTS: vec3f = vec3f(0.2126, 0.7152, 0.0722);
+
+var<workgroup> localBins: array<atomic<u32>, 256>;
+var<workgroup> weightedBins: array<f32, 256>;
+
+// Bin 0 holds the black pixels, which are left out of the average
+fn luminance_bin(color: vec3f) -> u32 {
+    let luminance = dot(color, LUMINANCE_WEIGHTS);
+    if (luminance < 1e-5) {
+        return 0u;
+    }
+
+    let range = max(exposureParams.log_luminance_range, 1e-3);
+    let log_luminance = saturate((log2(luminance) - exposureParams.min_log_luminance) / range);
+    return u32(log_luminance * 254.0 + 1.0);
+}
+
+@compute @workgroup_size(16, 16, 1)
+fn cs_histogram(
+    @builtin(global_invocation_id) gid: vec3u,
+    @builtin(local_invocation_index) local_index: u32,
+) {
+    atomicStore(&localBins[local_index], 0u);
+    workgroupBarrier();
+
+    let size = textureDimensions(exposureColor);
+    if (gid.x < size.x && gid.y < size.y) {
+        let color = textureLoad(exposureColor, vec2i(gid.xy), 0).rgb;
+        atomicAdd(&localBins[luminance_bin(color)], 1u);
+    }
+    workgroupBarrier();
+
+    atomicAdd(&exposureHistogram[local_index], atomicLoad(&localBins[local_index]));
+}
+
+// Averages the histogram of this frame, adapts the luminance towards it and clears the
+// histogram for the next frame.
+@compute @workgroup_size(256, 1, 1)
+fn cs_average(@builtin(local_invocation_index) local_index: u32) {
+    let count = atomicLoad(&exposureHistogram[local_index]);
+    weightedBins[local_index] = f32(count) * f32(local_index);
+    atomicStore(&exposureHistogram[local_index], 0u);
+    workgroupBarrier();
+
+    for (var cutoff = BIN_COUNT / 2u; cutoff > 0u; cutoff = cutoff / 2u) {
+        if (local_index < cutoff) {
+            weightedBins[local_index] += weightedBins[local_index + cutoff];
+        }
+        workgroupBarrier();
+    }
+
+    if (local_index != 0u) {
+        return;
+    }
+
+    let size = textureDimensions(exposureColor);
+    let lit_pixels = max(f32(size.x * size.y) - f32(count), 1.0);
+    let average_bin = max(weightedBins[0] / lit_pixels, 1.0);
+    let average_log = (average_bin - 1.0) / 254.0 * exposureParams.log_luminance_range
+        + exposureParams.min_log_luminance;
+    let target_luminance = exp2(average_log);
+
+    var luminance = target_luminance;
+    let last = exposureState.luminance;
+    if (exposureParams.reset == 0u && last > 0.0) {
+        let speed = select(exposureParams.speed_down, exposureParams.speed_up, target_luminance > last);
+        let blend = 1.0 - exp(-max(system.delta_time, 0.0) * speed);
+        luminance = last + (target_luminance - last) * blend;
+    }
+    exposureState.luminance = luminance;
+
+    let compensation = exp2(exposureParams.compensation);
+    if (exposureParams.mode == 1u) {
+        exposureState.exposure = compensation * MIDDLE_GREY / max(luminance, 1e-4);
+    } else {
+        exposureState.exposure = compensation;
+    }
+}
@@ -0,0 +1,238 @@
+#use render
+#use light
+
+struct IblParams {
+    // Perceptual roughness the specular map is prefiltered for
+    roughness: f32,
+    sample_count: u32,
+    // Face size of the first mip of the environment capture
+    environment_size: f32,
+    _pad0: f32,
+}
+
+@group(1) @binding(0) var iblEnvironment: texture_cube<f32>;
+@group(1) @binding(1) var iblSampler: sampler;
+@group(1) @binding(2) var iblOutput: texture_storage_2d_array<rgba16float, write>;
+@group(1) @binding(3) var<uniform> params: IblParams;
+@group(1) @binding(4) var iblSource: texture_2d_array<f32>;
+
+const SKY_MODE_PROCEDURAL: u32 = 1u;
+// Largest value a half float can hold
+const MAX_RADIANCE: f32 = 65504.0;
+
+// Direction through the texel center of a cube face, in the usual +X, -X, +Y, -Y, +Z, -Z order
+fn cube_direction(face: u32, texel: vec2u, size: vec2u) -> vec3f {
+    let uv = (vec2f(texel) + vec2f(0.5)) / vec2f(size) * 2.0 - 1.0;
+
+    switch (face) {
+        case 0u: { return normalize(vec3f(1.0, -uv.y, -uv.x)); }
+        case 1u: { return normalize(vec3f(-1.0, -uv.y, uv.x)); }
+        case 2u: { return normalize(vec3f(uv.x, 1.0, uv.y)); }
+        case 3u: { return normalize(vec3f(uv.x, -1.0, -uv.y)); }
+        case 4u: { return normalize(vec3f(uv.x, -uv.y, 1.0)); }
+        default: { return normalize(vec3f(-uv.x, -uv.y, -1.0)); }
+    }
+}
+
+fn radical_inverse(bits_in: u32) -> f32 {
+    var bits = (bits_in << 16u) | (bits_in >> 16u);
+    bits = ((bits & 0x55555555u) << 1u) | ((bits & 0xAAAAAAAAu) >> 1u);
+    bits = ((bits & 0x33333333u) << 2u) | ((bits & 0xCCCCCCCCu) >> 2u);
+    bits = ((bits & 0x0F0F0F0Fu) << 4u) | ((bits & 0xF0F0F0F0u) >> 4u);
+    bits = ((bits & 0x00FF00FFu) << 8u) | ((bits & 0xFF00FF00u) >> 8u);
+    return f32(bits) * 2.3283064365386963e-10;
+}
+
+fn hammersley(i: u32, count: u32) -> vec2f {
+    return vec2f(f32(i) / f32(count), radical_inverse(i));
+}
+
+// Columns are the tangent, bitangent and the normal
+fn tangent_basis(N: vec3f) -> mat3x3<f32> {
+    let up = select(vec3f(1.0, 0.0, 0.0), vec3f(0.0, 0.0, 1.0), abs(N.z) < 0.999);
+    let T = normalize(cross(up, N));
+    let B = cross(N, T);
+    return mat3x3<f32>(T, B, N);
+}
+
+// Half vector around +Z, distributed by GGX with the given alpha
+fn importance_sample_ggx(xi: vec2f, a: f32) -> vec3f {
+    let phi = 2.0 * PI * xi.x;
+    let cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+    let sin_theta = sqrt(max(1.0 - cos_theta * cos_theta, 0.0));
+    return vec3f(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+}
+
+fn ggx_distribution(NdotH: f32, a: f32) -> f32 {
+    let a2 = a * a;
+    let d = NdotH * NdotH * (a2 - 1.0) + 1.0;
+    return a2 / max(PI * d * d, 1e-8);
+}
+
+// Environment mip that covers the solid angle of a sample, for filtered importance sampling
+fn sample_lod(pdf: f32) -> f32 {
+    let texel_solid_angle = 4.0 * PI / (6.0 * params.environment_size * params.environment_size);
+    let sample_solid_angle = 1.0 / (f32(params.sample_count) * pdf + 1e-4);
+    return max(0.5 * log2(sample_solid_angle / texel_solid_angle) + 1.0, 0.0);
+}
+
+// Captures the sky as it's drawn behind the scene, without the sun disk. The sun reaches
+// surfaces as a light of its own.
+@compute @workgroup_size(8, 8, 1)
+fn cs_capture(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(iblOutput);
+    if (gid.x >= size.x || gid.y >= size.y || gid.z >= 6u) {
+        return;
+    }
+
+    let dir = cube_direction(gid.z, gid.xy, size);
+    let atm = atmosphere_eval(dir, sky_sun_direction());
+
+    var radiance = atm.sky_radiance;
+    if (sky.mode != SKY_MODE_PROCEDURAL) {
+        let source_size = f32(textureDimensions(skybox_map).x);
+        let lod = max(log2(source_size / f32(size.x)), 0.0);
+        let env = textureSampleLevel(skybox_map, skybox_sampler, dir, lod).rgb;
+        radiance = sky_over_environment(env, dir, atm);
+    }
+
+    radiance = clamp(radiance, vec3f(0.0), vec3f(MAX_RADIANCE));
+    textureStore(iblOutput, gid.xy, gid.z, vec4f(radiance, 1.0));
+}
+
+// Averages 2x2 texels of the previous mip of every face
+@compute @workgroup_size(8, 8, 1)
+fn cs_downsample(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(iblOutput);
+    if (gid.x >= size.x || gid.y >= size.y || gid.z >= 6u) {
+        return;
+    }
+
+    let src = vec2i(gid.xy * 2u);
+    let max_src = vec2i(textureDimensions(iblSource)) - 1;
+    let layer = i32(gid.z);
+
+    var sum = vec3f(0.0);
+    for (var y = 0; y < 2; y++) {
+        for (var x = 0; x < 2; x++) {
+            sum += textureLoad(iblSource, min(src + vec2i(x, y), max_src), layer, 0).rgb;
+        }
+    }
+
+    textureStore(iblOutput, gid.xy, gid.z, vec4f(sum * 0.25, 1.0));
+}
+
+// Cosine weighted average of the environment over the hemisphere around every direction.
+// Lambertian surfaces multiply it by their albedo.
+@compute @workgroup_size(8, 8, 1)
+fn cs_irradiance(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(iblOutput);
+    if (gid.x >= size.x || gid.y >= size.y || gid.z >= 6u) {
+        return;
+    }
+
+    let N = cube_direction(gid.z, gid.xy, size);
+    let basis = tangent_basis(N);
+    let count = max(params.sample_count, 1u);
+
+    var sum = vec3f(0.0);
+    for (var i = 0u; i < count; i++) {
+        let xi = hammersley(i, count);
+        let phi = 2.0 * PI * xi.x;
+        let cos_theta = sqrt(1.0 - xi.y);
+        let sin_theta = sqrt(xi.y);
+        let L = basis * vec3f(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+        let lod = sample_lod(cos_theta / PI);
+        sum += textureSampleLevel(iblEnvironment, iblSampler, L, lod).rgb;
+    }
+
+    textureStore(iblOutput, gid.xy, gid.z, vec4f(sum / f32(count), 1.0));
+}
+
+// GGX prefiltered environment for one roughness, assuming the view is along the normal
+@compute @workgroup_size(8, 8, 1)
+fn cs_prefilter(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(iblOutput);
+    if (gid.x >= size.x || gid.y >= size.y || gid.z >= 6u) {
+        return;
+    }
+
+    let N = cube_direction(gid.z, gid.xy, size);
+    let roughness = params.roughness;
+
+    if (roughness <= 1e-3) {
+        let mirror = textureSampleLevel(iblEnvironment, iblSampler, N, 0.0).rgb;
+        textureStore(iblOutput, gid.xy, gid.z, vec4f(mirror, 1.0));
+        return;
+    }
+
+    let a = roughness * roughness;
+    let basis = tangent_basis(N);
+    let count = max(params.sample_count, 1u);
+
+    var sum = vec3f(0.0);
+    var weight = 0.0;
+    for (var i = 0u; i < count; i++) {
+        let H = basis * importance_sample_ggx(hammersley(i, count), a);
+        let L = normalize(2.0 * dot(N, H) * H - N);
+        let NdotL = dot(N, L);
+        if (NdotL <= 0.0) {
+            continue;
+        }
+
+        // with the view along the normal, the pdf of L is D / 4
+        let NdotH = saturate(dot(N, H));
+        let lod = sample_lod(ggx_distribution(NdotH, a) * 0.25);
+        sum += textureSampleLevel(iblEnvironment, iblSampler, L, lod).rgb * NdotL;
+        weight += NdotL;
+    }
+
+    textureStore(iblOutput, gid.xy, gid.z, vec4f(sum / max(weight, 1e-4), 1.0));
+}
+
+fn geometry_schlick_ggx(NdotX: f32, k: f32) -> f32 {
+    return NdotX / (NdotX * (1.0 - k) + k);
+}
+
+// Split sum scale and bias of the specular BRDF, by the view angle along x and the perceptual
+// roughness along y
+@compute @workgroup_size(8, 8, 1)
+fn cs_brdf_lut(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(iblOutput);
+    if (gid.x >= size.x || gid.y >= size.y || gid.z >= 1u) {
+        return;
+    }
+
+    let NdotV = max((f32(gid.x) + 0.5) / f32(size.x), 1e-3);
+    let roughness = (f32(gid.y) + 0.5) / f32(size.y);
+    let a = roughness * roughness;
+    let k = a * 0.5;
+
+    let V = vec3f(sqrt(1.0 - NdotV * NdotV), 0.0, NdotV);
+    let count = max(params.sample_count, 1u);
+
+    var scale = 0.0;
+    var bias = 0.0;
+    for (var i = 0u; i < count; i++) {
+        let H = importance_sample_ggx(hammersley(i, count), a);
+        let L = normalize(2.0 * dot(V, H) * H - V);
+
+        let NdotL = saturate(L.z);
+        if (NdotL <= 0.0) {
+            continue;
+        }
+
+        let NdotH = saturate(H.z);
+        let VdotH = saturate(dot(V, H));
+        let G = geometry_schlick_ggx(NdotV, k) * geometry_schlick_ggx(NdotL, k);
+        let G_vis = G * VdotH / max(NdotH * NdotV, 1e-6);
+        let Fc = pow(1.0 - VdotH, 5.0);
+
+        scale += (1.0 - Fc) * G_vis;
+        bias += Fc * G_vis;
+    }
+
+    let lut = vec2f(scale, bias) / f32(count);
+    textureStore(iblOutput, gid.xy, gid.z, vec4f(lut, 0.0, 1.0));
+}
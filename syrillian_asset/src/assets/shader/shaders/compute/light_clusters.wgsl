@@ -0,0 +1,122 @@
+#use render
+
+const LIGHT_TYPE_SUN: u32 = 1;
+
+struct Light {
+    position: vec3<f32>,
+    up: vec3<f32>,
+    radius: f32,
+    direction: vec3<f32>,
+    range: f32,
+    color: vec3<f32>,
+    intensity: f32,
+    inner_angle: f32,
+    outer_angle: f32,
+    cos_inner: f32,
+    cos_outer: f32,
+    type_id: u32,
+    shadow_map_id: u32,
+    shadow_mat_base: u32,
+    shadow_cascades: u32,
+    cascade_split_lambda: f32,
+    shadow_distance: f32,
+    cascade_blend: f32,
+}
+
+struct LightClusterParams {
+    grid: vec3<u32>,
+    // u32 per cluster, the light count followed by the light indices
+    stride: u32,
+    light_count: u32,
+    primary_sun: u32,
+    debug_view: u32,
+    _pad0: u32,
+}
+
+@group(1) @binding(0) var<uniform> params: LightClusterParams;
+@group(1) @binding(1) var<storage, read> lights: array<Light>;
+@group(1) @binding(2) var<storage, read_write> clusters: array<u32>;
+
+// View depth where the given depth slice starts, slices are spaced logarithmically
+fn slice_depth(slice: u32) -> f32 {
+    let near = max(camera.near, 1e-4);
+    let far = max(camera.far, near * 1.001);
+    return near * pow(far / near, f32(slice) / f32(params.grid.z));
+}
+
+fn view_point(ndc: vec3f) -> vec3f {
+    let world = camera.inv_view_proj_mat * vec4f(ndc, 1.0);
+    let view = camera.view_mat * vec4f(world.xyz / world.w, 1.0);
+    return view.xyz;
+}
+
+// Point on the line of sight through the NDC position at the given view depth. Works for
+// perspective and orthographic projections alike.
+fn point_at_depth(ndc: vec2f, depth: f32) -> vec3f {
+    let a = view_point(vec3f(ndc, 0.25));
+    let b = view_point(vec3f(ndc, 0.75));
+    let dz = b.z - a.z;
+    if (abs(dz) < 1e-6) {
+        return vec3f(a.xy, -depth);
+    }
+    return mix(a, b, (-depth - a.z) / dz);
+}
+
+fn sphere_hits_aabb(center: vec3f, radius: f32, aabb_min: vec3f, aabb_max: vec3f) -> bool {
+    let closest = clamp(center, aabb_min, aabb_max);
+    let d = center - closest;
+    return dot(d, d) <= radius * radius;
+}
+
+// One invocation per cluster. Point and spot lights are tested by their range, lights without
+// a range reach every cluster. Sun lights aren't binned, the primary sun is looked up directly.
+@compute @workgroup_size(4, 4, 4)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let grid = params.grid;
+    if (gid.x >= grid.x || gid.y >= grid.y || gid.z >= grid.z) {
+        return;
+    }
+
+    let tile_size = 2.0 / vec2f(grid.xy);
+    let ndc_min = vec2f(-1.0) + vec2f(gid.xy) * tile_size;
+    let ndc_max = ndc_min + tile_size;
+    let depth_near = slice_depth(gid.z);
+    let depth_far = slice_depth(gid.z + 1u);
+
+    var aabb_min = vec3f(3.4e38);
+    var aabb_max = vec3f(-3.4e38);
+    for (var corner = 0u; corner < 8u; corner++) {
+        let ndc = vec2f(
+            select(ndc_min.x, ndc_max.x, (corner & 1u) != 0u),
+            select(ndc_min.y, ndc_max.y, (corner & 2u) != 0u),
+        );
+        let depth = select(depth_near, depth_far, (corner & 4u) != 0u);
+        let p = point_at_depth(ndc, depth);
+        aabb_min = min(aabb_min, p);
+        aabb_max = max(aabb_max, p);
+    }
+
+    let cluster = (gid.z * grid.y + gid.y) * grid.x + gid.x;
+    let base = cluster * params.stride;
+    let capacity = params.stride - 1u;
+
+    var count = 0u;
+    for (var i = 0u; i < params.light_count && count < capacity; i++) {
+        let light = lights[i];
+        if (light.type_id == LIGHT_TYPE_SUN) {
+            continue;
+        }
+
+        if (light.range > 0.0) {
+            let center = (camera.view_mat * vec4f(light.position, 1.0)).xyz;
+            if (!sphere_hits_aabb(center, light.range, aabb_min, aabb_max)) {
+                continue;
+            }
+        }
+
+        clusters[base + 1u + count] = i;
+        count++;
+    }
+
+    clusters[base] = count;
+}
@@ -0,0 +1,92 @@
+const WORDS_PER_VERTEX: u32 = 20u;
+const FLOATS_PER_DELTA: u32 = 9u;
+
+struct MorphParams {
+    vertex_count: u32,
+    first_vertex: u32,
+    mesh_vertex_count: u32,
+    target_count: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: MorphParams;
+@group(0) @binding(1) var<storage, read> weights: array<f32>;
+@group(0) @binding(2) var<storage, read> deltas: array<f32>;
+@group(0) @binding(3) var<storage, read> src_words: array<u32>;
+@group(0) @binding(4) var<storage, read_write> dst_words: array<u32>;
+
+fn load_f32(word_index: u32) -> f32 {
+    return bitcast<f32>(src_words[word_index]);
+}
+
+fn load_vec3(base: u32) -> vec3<f32> {
+    return vec3<f32>(
+        load_f32(base),
+        load_f32(base + 1u),
+        load_f32(base + 2u)
+    );
+}
+
+fn load_delta(base: u32) -> vec3<f32> {
+    return vec3<f32>(
+        deltas[base],
+        deltas[base + 1u],
+        deltas[base + 2u]
+    );
+}
+
+fn store_f32(word_index: u32, value: f32) {
+    dst_words[word_index] = bitcast<u32>(value);
+}
+
+fn store_vec3(base: u32, value: vec3<f32>) {
+    store_f32(base, value.x);
+    store_f32(base + 1u, value.y);
+    store_f32(base + 2u, value.z);
+}
+
+fn normalize_or(v: vec3<f32>, fallback: vec3<f32>) -> vec3<f32> {
+    let len = length(v);
+    if (len < 1e-8) {
+        return fallback;
+    }
+    return v / len;
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let idx = gid.x;
+    if (idx >= params.vertex_count) {
+        return;
+    }
+
+    let base = idx * WORDS_PER_VERTEX;
+
+    for (var i = 0u; i < WORDS_PER_VERTEX; i = i + 1u) {
+        dst_words[base + i] = src_words[base + i];
+    }
+
+    let p_obj = load_vec3(base + 0u);
+    let n_obj = load_vec3(base + 5u);
+    let t_obj = load_vec3(base + 8u);
+
+    var p = p_obj;
+    var n = n_obj;
+    var t = t_obj;
+
+    let vertex = params.first_vertex + idx;
+    for (var morph = 0u; morph < params.target_count; morph = morph + 1u) {
+        let w = weights[morph];
+        if (w == 0.0) {
+            continue;
+        }
+
+        let d = (morph * params.mesh_vertex_count + vertex) * FLOATS_PER_DELTA;
+        p += load_delta(d) * w;
+        n += load_delta(d + 3u) * w;
+        t += load_delta(d + 6u) * w;
+    }
+
+    store_vec3(base + 0u, p);
+    store_vec3(base + 5u, normalize_or(n, n_obj));
+    store_vec3(base + 8u, normalize_or(t, t_obj));
+}
@@ -1,5 +1,5 @@
 const MAX_BONES: u32 = 256u;
-const WORDS_PER_VERTEX: u32 = 19u;
+const WORDS_PER_VERTEX: u32 = 20u;
 
 struct BoneData {
     mats: array<mat4x4<f32>, MAX_BONES>,
@@ -148,8 +148,8 @@ fn cs_main(@builtin(global_invocation_id) gid: vec3<u32>) {
     let p_obj = vec4<f32>(load_vec3(base + 0u), 1.0);
     let n_obj = load_vec3(base + 5u);
     let t_obj = load_vec3(base + 8u);
-    let bone_idx = load_uvec4(base + 11u);
-    let bone_w = load_vec4(base + 15u);
+    let bone_idx = load_uvec4(base + 12u);
+    let bone_w = load_vec4(base + 16u);
 
     let p_sk = skin_pos(p_obj, bone_idx, bone_w);
     let n_sk = skin_dir(n_obj, bone_idx, bone_w);
@@ -0,0 +1,70 @@
+#use render
+
+@group(1) @binding(0) var postTexture: texture_2d<f32>;
+@group(1) @binding(1) var postSampler: sampler;
+@group(1) @binding(2) var postDepth: texture_depth_2d;
+@group(1) @binding(3) var postVelocity: texture_2d<f32>;
+@group(1) @binding(5) var motionBlurOutput: texture_storage_2d<rgba16float, write>;
+
+// Fraction of the frame the virtual shutter is open for
+const SHUTTER: f32 = 0.5;
+const MAX_BLUR_PIXELS: f32 = 32.0;
+const SAMPLE_COUNT: u32 = 12u;
+
+fn hash12(p: vec2u) -> f32 {
+    var x = p.x * 1664525u + p.y * 1013904223u;
+    x ^= x >> 16u;
+    x *= 2246822519u;
+    x ^= x >> 13u;
+    return f32(x & 0x00FFFFFFu) / 16777216.0;
+}
+
+fn blur_vector(pixel: vec2i, size_f: vec2f) -> vec2f {
+    return textureLoad(postVelocity, pixel, 0).xy * SHUTTER * size_f;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(motionBlurOutput);
+    if (gid.x >= size.x || gid.y >= size.y) {
+        return;
+    }
+
+    let pixel = vec2i(gid.xy);
+    let size_f = vec2f(size);
+    let center = textureLoad(postTexture, pixel, 0);
+
+    var blur = blur_vector(pixel, size_f);
+    let blur_length = length(blur);
+    if (blur_length < 0.5) {
+        textureStore(motionBlurOutput, pixel, center);
+        return;
+    }
+    blur *= min(blur_length, MAX_BLUR_PIXELS) / blur_length;
+
+    let uv = (vec2f(pixel) + vec2f(0.5)) / size_f;
+    let center_depth = textureLoad(postDepth, pixel, 0);
+    let max_pixel = vec2i(size) - vec2i(1);
+    let offset = hash12(gid.xy) - 0.5;
+
+    // Samples along the motion of the surface in this pixel, centered on it. Static surfaces in
+    // front of it are left out, they stay sharp instead of being dragged along.
+    var color = center.rgb;
+    var weight = 1.0;
+    for (var i = 0u; i < SAMPLE_COUNT; i++) {
+        let t = (f32(i) + 0.5 + offset) / f32(SAMPLE_COUNT) - 0.5;
+        let sample_uv = uv + blur * t / size_f;
+        let sample_pixel = clamp(vec2i(sample_uv * size_f), vec2i(0), max_pixel);
+
+        let sample_depth = textureLoad(postDepth, sample_pixel, 0);
+        let sample_moving = length(blur_vector(sample_pixel, size_f)) >= 0.5;
+        if (sample_depth < center_depth && !sample_moving) {
+            continue;
+        }
+
+        color += textureSampleLevel(postTexture, postSampler, sample_uv, 0.0).rgb;
+        weight += 1.0;
+    }
+
+    textureStore(motionBlurOutput, pixel, vec4f(color / weight, center.a));
+}
@@ -1,6 +1,6 @@
 @group(0) @binding(0) var colorInput: texture_2d<f32>;
 @group(0) @binding(1) var aoInput: texture_2d<f32>;
-@group(0) @binding(2) var colorOutput: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2) var colorOutput: texture_storage_2d<rgba16float, write>;
 
 @compute @workgroup_size(8, 8, 1)
 fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
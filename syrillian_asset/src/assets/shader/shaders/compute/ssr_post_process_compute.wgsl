@@ -5,7 +5,7 @@
 @group(1) @binding(2) var postDepth: texture_depth_2d;
 @group(1) @binding(3) var postNormal: texture_2d<f32>;
 @group(1) @binding(4) var postMaterial: texture_2d<f32>;
-@group(1) @binding(5) var ssrOutput: texture_storage_2d<rgba8unorm, write>;
+@group(1) @binding(5) var ssrOutput: texture_storage_2d<rgba16float, write>;
 
 fn uv_to_pixel(uv: vec2f, size_f: vec2f) -> vec2i {
     let max_uv = (size_f - vec2f(1.0)) / size_f;
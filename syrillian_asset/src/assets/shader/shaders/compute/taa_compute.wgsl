@@ -0,0 +1,80 @@
+struct TaaParams {
+    // Weight of the current frame in the result
+    blend: f32,
+    // 0 if the history doesn't show this camera's last frame
+    history_valid: u32,
+    _pad0: f32,
+    _pad1: f32,
+}
+
+@group(0) @binding(0) var taaColor: texture_2d<f32>;
+@group(0) @binding(1) var taaHistory: texture_2d<f32>;
+@group(0) @binding(2) var taaSampler: sampler;
+@group(0) @binding(3) var taaVelocity: texture_2d<f32>;
+@group(0) @binding(4) var<uniform> taaParams: TaaParams;
+@group(0) @binding(5) var taaOutput: texture_storage_2d<rgba16float, write>;
+@group(0) @binding(6) var taaHistoryOutput: texture_storage_2d<rgba16float, write>;
+
+fn rgb_to_ycocg(c: vec3f) -> vec3f {
+    return vec3f(
+        0.25 * c.r + 0.5 * c.g + 0.25 * c.b,
+        0.5 * c.r - 0.5 * c.b,
+        -0.25 * c.r + 0.5 * c.g - 0.25 * c.b,
+    );
+}
+
+fn ycocg_to_rgb(c: vec3f) -> vec3f {
+    return vec3f(c.x + c.y - c.z, c.x + c.z, c.x - c.y - c.z);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(taaOutput);
+    if (gid.x >= size.x || gid.y >= size.y) {
+        return;
+    }
+
+    let pixel = vec2i(gid.xy);
+    let max_pixel = vec2i(size) - vec2i(1);
+    let uv = (vec2f(gid.xy) + vec2f(0.5)) / vec2f(size);
+    let current = textureLoad(taaColor, pixel, 0);
+
+    // color distribution of the 3x3 neighbourhood, the history is clipped to it so it can't
+    // ghost colors that aren't on screen anymore. the longest velocity around is used, so
+    // edges of moving surfaces reproject with the surface and not the background
+    var m1 = vec3f(0.0);
+    var m2 = vec3f(0.0);
+    var velocity = vec2f(0.0);
+    for (var y = -1; y <= 1; y++) {
+        for (var x = -1; x <= 1; x++) {
+            let p = clamp(pixel + vec2i(x, y), vec2i(0), max_pixel);
+            let c = rgb_to_ycocg(textureLoad(taaColor, p, 0).rgb);
+            m1 += c;
+            m2 += c * c;
+
+            let v = textureLoad(taaVelocity, p, 0).xy;
+            if (dot(v, v) > dot(velocity, velocity)) {
+                velocity = v;
+            }
+        }
+    }
+
+    let mean = m1 / 9.0;
+    let sigma = sqrt(max(m2 / 9.0 - mean * mean, vec3f(0.0)));
+    let low = mean - sigma * 1.25;
+    let high = mean + sigma * 1.25;
+
+    let prev_uv = uv - velocity;
+    let on_screen = all(prev_uv >= vec2f(0.0)) && all(prev_uv <= vec2f(1.0));
+
+    var result = current.rgb;
+    if (taaParams.history_valid != 0u && on_screen) {
+        let history = textureSampleLevel(taaHistory, taaSampler, prev_uv, 0.0).rgb;
+        let clipped = ycocg_to_rgb(clamp(rgb_to_ycocg(history), low, high));
+        result = mix(clipped, current.rgb, taaParams.blend);
+    }
+
+    let color = vec4f(result, current.a);
+    textureStore(taaOutput, pixel, color);
+    textureStore(taaHistoryOutput, pixel, color);
+}
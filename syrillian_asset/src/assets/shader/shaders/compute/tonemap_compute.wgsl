@@ -0,0 +1,201 @@
+struct GradingParams {
+    // White balance gains in LMS space
+    balance: vec3f,
+    // 0 ACES, 1 AgX, 2 Reinhard, 3 Neutral
+    tonemapper: u32,
+    contrast: f32,
+    saturation: f32,
+    // How much of the LUT result is used, 0 without a LUT
+    lut_contribution: f32,
+    // 1 if the LUT texture stores sRGB encoded values which aren't decoded when sampled
+    lut_decode: u32,
+}
+
+struct ExposureState {
+    luminance: f32,
+    exposure: f32,
+    _pad0: f32,
+    _pad1: f32,
+}
+
+@group(0) @binding(0) var tonemapColor: texture_2d<f32>;
+@group(0) @binding(1) var tonemapSampler: sampler;
+@group(0) @binding(2) var tonemapLut: texture_2d<f32>;
+@group(0) @binding(3) var<storage, read> exposureState: ExposureState;
+@group(0) @binding(4) var<uniform> grading: GradingParams;
+@group(0) @binding(5) var tonemapOutput: texture_storage_2d<rgba8unorm, write>;
+
+const MIDDLE_GREY_LOG: f32 = -2.4739312; // log2(0.18)
+const LUMINANCE_WEIGHTS: vec3f = vec3f(0.2126, 0.7152, 0.0722);
+
+// Linear Rec.709 to LMS and back, the columns are the rows of the usual matrices
+const LIN_2_LMS: mat3x3<f32> = mat3x3<f32>(
+    vec3f(3.90405e-1, 5.49941e-1, 8.92632e-3),
+    vec3f(7.08416e-2, 9.63172e-1, 1.35775e-3),
+    vec3f(2.31082e-2, 1.28021e-1, 9.36245e-1),
+);
+const LMS_2_LIN: mat3x3<f32> = mat3x3<f32>(
+    vec3f(2.85847e+0, -1.62879e+0, -2.48910e-2),
+    vec3f(-2.10182e-1, 1.15820e+0, 3.24281e-4),
+    vec3f(-4.18120e-2, -1.18169e-1, 1.06867e+0),
+);
+
+fn rrt_and_odt_fit(v: vec3f) -> vec3f {
+    let a = v * (v + 0.0245786) - 0.000090537;
+    let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+    return a / b;
+}
+
+fn tonemap_aces(color: vec3f) -> vec3f {
+    let aces_in = mat3x3<f32>(
+        vec3f(0.59719, 0.07600, 0.02840),
+        vec3f(0.35458, 0.90834, 0.13383),
+        vec3f(0.04823, 0.01566, 0.83777),
+    );
+    let aces_out = mat3x3<f32>(
+        vec3f(1.60475, -0.10208, -0.00327),
+        vec3f(-0.53108, 1.10813, -0.07276),
+        vec3f(-0.07367, -0.00605, 1.07602),
+    );
+
+    return aces_out * rrt_and_odt_fit(aces_in * color);
+}
+
+fn agx_contrast(x: vec3f) -> vec3f {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    return 15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2
+        + 0.1191 * x - 0.00232;
+}
+
+fn tonemap_agx(color: vec3f) -> vec3f {
+    let agx_in = mat3x3<f32>(
+        vec3f(0.842479062253094, 0.0423282422610123, 0.0423756549057051),
+        vec3f(0.0784335999999992, 0.878468636469772, 0.0784336),
+        vec3f(0.0792237451477643, 0.0791661274605434, 0.879142973793104),
+    );
+    let agx_out = mat3x3<f32>(
+        vec3f(1.19687900512017, -0.0528968517574562, -0.0529716355144438),
+        vec3f(-0.0980208811401368, 1.15190312990417, -0.0980434501171241),
+        vec3f(-0.0990297440797205, -0.0989611768448433, 1.15107367264116),
+    );
+    let min_ev = -12.47393;
+    let max_ev = 4.026069;
+
+    var v = agx_in * color;
+    v = clamp(log2(max(v, vec3f(1e-10))), vec3f(min_ev), vec3f(max_ev));
+    v = (v - min_ev) / (max_ev - min_ev);
+    v = agx_out * agx_contrast(v);
+    // AgX ends in display encoding, go back to linear
+    return pow(max(v, vec3f(0.0)), vec3f(2.2));
+}
+
+fn tonemap_reinhard(color: vec3f) -> vec3f {
+    return color / (vec3f(1.0) + color);
+}
+
+// Khronos PBR Neutral
+fn tonemap_neutral(color: vec3f) -> vec3f {
+    let start_compression = 0.8 - 0.04;
+    let desaturation = 0.15;
+
+    let x = min(color.r, min(color.g, color.b));
+    let offset = select(0.04, x - 6.25 * x * x, x < 0.08);
+    var c = color - offset;
+
+    let peak = max(c.r, max(c.g, c.b));
+    if (peak < start_compression) {
+        return c;
+    }
+
+    let d = 1.0 - start_compression;
+    let new_peak = 1.0 - d * d / (peak + d - start_compression);
+    c *= new_peak / peak;
+
+    let g = 1.0 - 1.0 / (desaturation * (peak - new_peak) + 1.0);
+    return mix(c, vec3f(new_peak), g);
+}
+
+fn linear_to_srgb(c: vec3f) -> vec3f {
+    let low = c * 12.92;
+    let high = 1.055 * pow(c, vec3f(1.0 / 2.4)) - 0.055;
+    return select(high, low, c <= vec3f(0.0031308));
+}
+
+fn srgb_to_linear(c: vec3f) -> vec3f {
+    let low = c / 12.92;
+    let high = pow((c + 0.055) / 1.055, vec3f(2.4));
+    return select(high, low, c <= vec3f(0.04045));
+}
+
+// The LUT is a strip of `size` slices along x, each `size` by `size` texels, indexed by the
+// sRGB encoded color with blue picking the slice.
+fn sample_lut(color: vec3f) -> vec3f {
+    let dims = vec2f(textureDimensions(tonemapLut));
+    let size = max(dims.y, 2.0);
+    let scaled = saturate(linear_to_srgb(saturate(color))) * (size - 1.0);
+
+    let slice = floor(scaled.b);
+    let next_slice = min(slice + 1.0, size - 1.0);
+    let slice_blend = scaled.b - slice;
+
+    let v = (scaled.g + 0.5) / dims.y;
+    let uv_a = vec2f((slice * size + scaled.r + 0.5) / dims.x, v);
+    let uv_b = vec2f((next_slice * size + scaled.r + 0.5) / dims.x, v);
+
+    let a = textureSampleLevel(tonemapLut, tonemapSampler, uv_a, 0.0).rgb;
+    let b = textureSampleLevel(tonemapLut, tonemapSampler, uv_b, 0.0).rgb;
+    let graded = mix(a, b, slice_blend);
+
+    if (grading.lut_decode == 1u) {
+        return srgb_to_linear(graded);
+    }
+    return graded;
+}
+
+fn grade(color_in: vec3f) -> vec3f {
+    var color = max(color_in * exposureState.exposure, vec3f(0.0));
+
+    color = (color * LIN_2_LMS) * grading.balance * LMS_2_LIN;
+    color = max(color, vec3f(0.0));
+
+    let log_color = log2(max(color, vec3f(1e-6)));
+    color = exp2((log_color - MIDDLE_GREY_LOG) * grading.contrast + MIDDLE_GREY_LOG);
+
+    let luma = dot(color, LUMINANCE_WEIGHTS);
+    color = max(mix(vec3f(luma), color, grading.saturation), vec3f(0.0));
+
+    switch (grading.tonemapper) {
+        case 1u: {
+            color = tonemap_agx(color);
+        }
+        case 2u: {
+            color = tonemap_reinhard(color);
+        }
+        case 3u: {
+            color = tonemap_neutral(color);
+        }
+        default: {
+            color = tonemap_aces(color);
+        }
+    }
+    color = saturate(color);
+
+    if (grading.lut_contribution > 0.0) {
+        color = mix(color, sample_lut(color), grading.lut_contribution);
+    }
+
+    return color;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(tonemapOutput);
+    if (gid.x >= size.x || gid.y >= size.y) {
+        return;
+    }
+
+    let pixel = vec2i(gid.xy);
+    let color = textureLoad(tonemapColor, pixel, 0);
+    textureStore(tonemapOutput, pixel, vec4f(grade(color.rgb), color.a));
+}
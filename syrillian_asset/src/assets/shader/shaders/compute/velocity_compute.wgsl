@@ -0,0 +1,45 @@
+#use render
+
+@group(1) @binding(0) var postDepth: texture_depth_2d;
+@group(1) @binding(1) var velocityOutput: texture_storage_2d<rgba16float, write>;
+
+fn uv_to_ndc(uv: vec2f) -> vec2f {
+    return uv * vec2f(2.0, -2.0) + vec2f(-1.0, 1.0);
+}
+
+fn ndc_to_uv(ndc: vec2f) -> vec2f {
+    return ndc * vec2f(0.5, -0.5) + vec2f(0.5);
+}
+
+// Screen space motion of the surface seen in each pixel, caused by the camera moving since the
+// last frame. Stored as the current uv minus the uv the surface had in the last frame.
+@compute @workgroup_size(8, 8, 1)
+fn cs_main(@builtin(global_invocation_id) gid: vec3u) {
+    let size = textureDimensions(velocityOutput);
+    if (gid.x >= size.x || gid.y >= size.y) {
+        return;
+    }
+
+    let pixel = vec2i(gid.xy);
+    let uv = (vec2f(gid.xy) + vec2f(0.5)) / vec2f(size);
+    let depth = textureLoad(postDepth, pixel, 0);
+
+    let ndc = uv_to_ndc(uv);
+    let world_h = camera.inv_view_proj_mat * vec4f(ndc, depth, 1.0);
+    if (abs(world_h.w) < 1e-6) {
+        textureStore(velocityOutput, pixel, vec4f(0.0));
+        return;
+    }
+
+    let world = world_h.xyz / world_h.w;
+    let prev_clip = camera.prev_view_proj_mat * vec4f(world, 1.0);
+    if (prev_clip.w <= 1e-6) {
+        textureStore(velocityOutput, pixel, vec4f(0.0));
+        return;
+    }
+
+    // the depth buffer was rendered with the jittered projection, remove it to only keep motion
+    let current_uv = ndc_to_uv(ndc - camera.jitter);
+    let prev_uv = ndc_to_uv(prev_clip.xy / prev_clip.w);
+    textureStore(velocityOutput, pixel, vec4f(current_uv - prev_uv, 0.0, 0.0));
+}
@@ -52,6 +52,21 @@ macro_rules! test_custom_shader {
     };
 }
 
+macro_rules! test_compute_shader {
+    ($fn_name:ident => $path:literal) => {
+        #[test]
+        fn $fn_name() {
+            use crate::shader::checks::validate_wgsl_source;
+            use syrillian_shadergen::generator::assemble_compute_shader;
+
+            let shader = assemble_compute_shader(include_str!($path));
+            validate_wgsl_source(&shader)
+                .inspect_err(|e| e.emit_to_stderr_with_path(&shader, $path))
+                .unwrap();
+        }
+    };
+}
+
 // Fundamental Shaders
 test_shader!(shader_2d, "Shader 2D" => "shader2d.wgsl");
 test_shader!(fallback_shader3d, "Fallback Shader 3D" => "fallback_shader3d.wgsl");
@@ -61,6 +76,9 @@ test_custom_shader!(picking_mesh, "Mesh Picking Shader" => "picking_mesh.wgsl");
 test_custom_shader!(picking_ui, "UI Picking Shader" => "picking_ui.wgsl");
 test_custom_shader!(text2d, "Text 2D Shader" => "text2d.wgsl");
 test_custom_shader!(text3d, "Text 3D Shader" => "text3d.wgsl");
+test_custom_shader!(sprite, "Sprite Shader" => "sprite.wgsl");
+test_custom_shader!(picking_sprite, "Sprite Picking Shader" => "picking_sprite.wgsl");
+test_custom_shader!(mesh_velocity, "Mesh Velocity Shader" => "velocity.wgsl");
 test_custom_shader!(debug_line2d, "Debug Line 2D" => "line.wgsl");
 
 // Debug shaders
@@ -71,6 +89,17 @@ test_custom_shader!(debug_text2d, "Debug Text 2D Geometry Shader" => "debug/text
 test_custom_shader!(debug_text3d, "Debug Text 3D Geometry Shader" => "debug/text3d_geometry.wgsl");
 test_custom_shader!(debug_light, "Debug Light Geometry Shader" => "debug/light.wgsl");
 
+// Compute shaders
+test_compute_shader!(compute_velocity => "compute/velocity_compute.wgsl");
+test_compute_shader!(compute_taa => "compute/taa_compute.wgsl");
+test_compute_shader!(compute_dof => "compute/dof_compute.wgsl");
+test_compute_shader!(compute_motion_blur => "compute/motion_blur_compute.wgsl");
+test_compute_shader!(compute_exposure => "compute/exposure_compute.wgsl");
+test_compute_shader!(compute_tonemap => "compute/tonemap_compute.wgsl");
+test_compute_shader!(compute_light_clusters => "compute/light_clusters.wgsl");
+test_compute_shader!(compute_ibl => "compute/ibl_compute.wgsl");
+test_compute_shader!(compute_mesh_morph => "compute/mesh_morph.wgsl");
+
 #[test]
 fn fullscreen_passthrough() {
     use crate::Shader;
@@ -87,6 +116,54 @@ fn fullscreen_passthrough() {
         .unwrap();
 }
 
+#[test]
+fn post_process_material_inputs() {
+    use crate::Shader;
+    use crate::shader::checks::validate_wgsl_source;
+    use syrillian_shadergen::PostProcessCompiler;
+    use syrillian_shadergen::chunks::NodeId;
+    use syrillian_shadergen::function::{MaterialExpressionValue, PostProcessMaterialExpression};
+    use syrillian_shadergen::value::MaterialValueType;
+
+    struct InputsMaterial;
+
+    impl PostProcessMaterialExpression for InputsMaterial {
+        fn outputs(&self) -> Vec<MaterialExpressionValue> {
+            vec![MaterialExpressionValue {
+                name: "color",
+                value_type: MaterialValueType::Vec4,
+            }]
+        }
+
+        fn compile(&self, compiler: &mut PostProcessCompiler, _output_index: u32) -> NodeId {
+            let uv = compiler.vertex_uv();
+            let (tex, sampler) = compiler.post_surface_input();
+            let color = compiler.texture_sample(tex, sampler, uv);
+            let depth = compiler.post_depth(uv);
+            let normal = compiler.post_normal(uv);
+            let material = compiler.post_material(uv);
+            let velocity = compiler.post_velocity(uv);
+            let strength = compiler.post_param(3);
+
+            let nx = compiler.swizzle(normal, "x");
+            let mx = compiler.swizzle(material, "x");
+            let vx = compiler.swizzle(velocity, "x");
+            let sum = compiler.add(depth, nx);
+            let sum = compiler.add(sum, mx);
+            let sum = compiler.add(sum, vx);
+            let factor = compiler.mul(sum, strength);
+            compiler.mul(color, factor)
+        }
+    }
+
+    let shader =
+        Shader::new_post_process_material("Post Process Inputs", &InputsMaterial).gen_code();
+
+    validate_wgsl_source(&shader)
+        .inspect_err(|e| e.emit_to_stderr_with_path(&shader, "shadergen/post_process_inputs"))
+        .unwrap();
+}
+
 #[test]
 fn shadergen_mesh3d() {
     use crate::Shader;
@@ -7,7 +7,8 @@ struct PickColor {
 
 var<immediate> pick: PickColor;
 
+// Single draws only set the immediate, instanced draws only the color of every instance
 @fragment
 fn fs_main(in: FInput) -> @location(0) vec4<f32> {
-    return pick.color;
+    return pick.color + in.pick_color;
 }
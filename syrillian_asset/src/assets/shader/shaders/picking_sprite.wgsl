@@ -0,0 +1,60 @@
+#use model
+#use material_textures
+
+struct SpriteIn {
+    // the bottom left corner and the two edges leaving it, in the space of the model transform
+    @location(0) origin: vec4<f32>,
+    @location(1) axis_x: vec4<f32>,
+    @location(2) axis_y: vec4<f32>,
+    // xy = uv of the top left corner, zw = uv of the bottom right corner
+    @location(3) uv_rect: vec4<f32>,
+    @location(4) color: vec4<f32>,
+    // added to the picking color of the draw, set for batched sprites
+    @location(5) pick_color: vec4<f32>,
+}
+
+struct VOut {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) alpha: f32,
+    @location(2) @interpolate(flat) pick_color: vec4<f32>,
+}
+
+struct PickColor {
+    color: vec4<f32>,
+};
+
+var<immediate> pick: PickColor;
+
+fn sprite_corner(index: u32) -> vec2<f32> {
+    var corners = array<vec2<f32>, 6>(
+        vec2(0.0, 0.0),
+        vec2(1.0, 0.0),
+        vec2(1.0, 1.0),
+        vec2(0.0, 0.0),
+        vec2(1.0, 1.0),
+        vec2(0.0, 1.0),
+    );
+    return corners[index % 6u];
+}
+
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32, in: SpriteIn) -> VOut {
+    var out: VOut;
+    let corner = sprite_corner(vertex_index);
+    let local_pos = in.origin + corner.x * in.axis_x + corner.y * in.axis_y;
+    out.position = camera.view_proj_mat * model.transform * local_pos;
+    out.uv = mix(in.uv_rect.xy, in.uv_rect.zw, vec2(corner.x, 1.0 - corner.y));
+    out.alpha = in.color.a;
+    out.pick_color = in.pick_color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VOut) -> @location(0) vec4<f32> {
+    let alpha = textureSample(t_diffuse, s_diffuse, in.uv).a * in.alpha;
+    if (alpha <= 0.5) {
+        discard;
+    }
+    return pick.color + in.pick_color;
+}
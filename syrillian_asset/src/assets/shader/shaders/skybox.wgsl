@@ -43,10 +43,7 @@ fn fs_main(in: SkyVertexOut) -> @location(0) vec4<f32> {
 
     let env = textureSample(skybox_map, skybox_sampler, dir).rgb;
 
-    let hemi = smoothstep(-0.02, 0.02, dir.y);
-
-    let atm_applied = env * atm.transmittance_view + atm.sky_radiance;
-    let sky_color = mix(env, atm_applied, hemi);
+    let sky_color = sky_over_environment(env, dir, atm);
 
     let sun = sky_sun_disk_add(dir, sun_dir, sky_color, atm);
 
@@ -0,0 +1,36 @@
+#use default_vertex
+#use model
+
+struct PreviousModel {
+    transform: mat4x4<f32>,
+};
+
+var<immediate> previous: PreviousModel;
+
+struct VelocityInput {
+    @builtin(position) clip: vec4<f32>,
+    @location(0) current_clip: vec4<f32>,
+    @location(1) previous_clip: vec4<f32>,
+}
+
+@vertex
+fn vs_main(in: VInput, instance: InstanceInput) -> VelocityInput {
+    var out: VelocityInput;
+
+    let p_obj = instance_transform(instance) * vec4(in.position, 1.0);
+    let ws_pos = model.transform * p_obj;
+    out.clip = camera.view_proj_mat * ws_pos;
+    out.current_clip = out.clip;
+    out.previous_clip = camera.prev_view_proj_mat * (previous.transform * p_obj);
+
+    return out;
+}
+
+// Same encoding as the camera velocity, the current uv minus the uv of the last frame.
+@fragment
+fn fs_main(in: VelocityInput) -> @location(0) vec4<f32> {
+    let current_ndc = in.current_clip.xy / in.current_clip.w - camera.jitter;
+    let previous_ndc = in.previous_clip.xy / in.previous_clip.w;
+    let velocity = (current_ndc - previous_ndc) * vec2f(0.5, -0.5);
+    return vec4f(velocity, 0.0, 0.0);
+}
@@ -1,79 +1,28 @@
 // TODO: refactor
 
-use crate::SkeletalComponent;
+use crate::animation_graph::{AnimationGraph, AnimationGraphPlayer, AnimationSample};
+use crate::animation_layer::{ActiveClip, AnimationLayer, LayerBlend};
+use crate::root_motion::{RootMotion, RootMotionDelta};
+use crate::{MeshRenderer, RigidBodyComponent, SkeletalComponent};
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 use syrillian::Reflect;
 use syrillian::World;
-use syrillian::components::Component;
+use syrillian::components::{CRef, Component};
 use syrillian::core::GameObjectId;
-use syrillian::math::{Quat, Vec3};
+use syrillian::math::{Affine3A, Quat, Vec3};
 use syrillian::tracing::warn;
 use syrillian::utils::animation::{
-    AnimationClip, Binding, ChannelBinding, ClipIndex, sample_rotation, sample_scale,
-    sample_translation,
+    AnimationClip, AnimationEvent, Binding, ChannelBinding, ClipIndex, TransformKeys,
+    sample_morph_weights, sample_motion, sample_rotation, sample_scale, sample_translation,
+    twist_angle,
 };
+use syrillian::utils::retarget::Retargeting;
 
 const DEFAULT_CROSSFADE_DURATION: f32 = 0.2;
 const LAYER_REMOVE_EPSILON: f32 = 1e-3;
-
-#[derive(Debug, Clone)]
-struct ActiveLayer {
-    clip_index: usize,
-    time: f32,
-    speed: f32,
-    looping: bool,
-    weight: f32,
-    target_weight: f32,
-    fade_rate: f32,
-}
-
-impl ActiveLayer {
-    fn new_immediate(
-        clip_index: usize,
-        looping: bool,
-        speed: f32,
-        start_weight: f32,
-        target_weight: f32,
-    ) -> Self {
-        Self {
-            clip_index,
-            time: 0.0,
-            speed,
-            looping,
-            weight: start_weight.clamp(0.0, 1.0),
-            target_weight: target_weight.clamp(0.0, 1.0),
-            fade_rate: 0.0,
-        }
-    }
-
-    fn set_fade_target(&mut self, target_weight: f32, duration: f32) {
-        self.target_weight = target_weight.clamp(0.0, 1.0);
-
-        if duration <= 0.0 {
-            self.weight = self.target_weight;
-            self.fade_rate = 0.0;
-            return;
-        }
-
-        self.fade_rate = (self.target_weight - self.weight) / duration;
-    }
-
-    fn step_weight(&mut self, dt: f32) {
-        if self.fade_rate == 0.0 {
-            return;
-        }
-
-        self.weight = (self.weight + self.fade_rate * dt).clamp(0.0, 1.0);
-
-        let reached_target = (self.fade_rate > 0.0 && self.weight >= self.target_weight)
-            || (self.fade_rate < 0.0 && self.weight <= self.target_weight);
-        if reached_target {
-            self.weight = self.target_weight;
-            self.fade_rate = 0.0;
-        }
-    }
-}
+/// Clips blended in with less weight than this don't fire their events
+const EVENT_WEIGHT_THRESHOLD: f32 = 0.05;
 
 #[derive(Default, Reflect)]
 pub struct AnimationComponent {
@@ -81,9 +30,23 @@ pub struct AnimationComponent {
     clips: Vec<AnimationClip>,
     clip_indices: Vec<ClipIndex>,
 
-    // Active layered playback stack
+    // Crossfade stack of the base layer
     #[dont_reflect]
-    layers: Vec<ActiveLayer>,
+    base: Vec<ActiveClip>,
+
+    // State machine driving the base layer instead of the crossfade stack
+    #[dont_reflect]
+    graph: Option<AnimationGraphPlayer>,
+
+    // Named layers blended on top of the base layer, in order
+    #[dont_reflect]
+    layers: Vec<AnimationLayer>,
+
+    // Moves the object by the motion of the root instead of the root itself
+    #[dont_reflect]
+    root_motion: Option<RootMotion>,
+    #[dont_reflect]
+    pending_root_motion: RootMotionDelta,
 
     bindings: Vec<Vec<ChannelBinding>>,
 }
@@ -91,6 +54,36 @@ pub struct AnimationComponent {
 /// Position, Rotation, Scale
 type SkeletonLocals = (Vec3, Quat, Vec3);
 
+/// Something a channel binding writes a pose to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum PoseTarget {
+    Transform(GameObjectId),
+    Bone { skel: GameObjectId, idx: usize },
+}
+
+/// Space the root motion channel is animated in.
+#[derive(Debug, Copy, Clone)]
+struct RootFrame {
+    to_world: Affine3A,
+    /// World up, in the space of the root channel
+    up: Vec3,
+}
+
+/// Blended channels of a single layer, along with the mask weight of each target.
+#[derive(Debug, Default)]
+struct LayerPose {
+    targets: HashMap<PoseTarget, (PoseAccumulator, f32)>,
+    morphs: HashMap<GameObjectId, (WeightsAccumulator, f32)>,
+}
+
+/// Resulting local poses of all animated targets, before they're written back.
+#[derive(Debug, Default)]
+struct ResolvedPose {
+    targets: HashMap<PoseTarget, SkeletonLocals>,
+    morphs: HashMap<GameObjectId, Vec<f32>>,
+    bind_poses: HashMap<GameObjectId, Vec<SkeletonLocals>>,
+}
+
 #[derive(Debug, Copy, Clone, Default)]
 struct Vec3Accumulator {
     sum: Vec3,
@@ -172,6 +165,36 @@ impl QuatAccumulator {
     }
 }
 
+#[derive(Debug, Clone, Default)]
+struct WeightsAccumulator {
+    sum: Vec<f32>,
+    weight_sum: f32,
+}
+
+impl WeightsAccumulator {
+    fn add(&mut self, values: &[f32], weight: f32) {
+        if weight <= 0.0 {
+            return;
+        }
+        if self.sum.len() < values.len() {
+            self.sum.resize(values.len(), 0.0);
+        }
+        for (sum, value) in self.sum.iter_mut().zip(values) {
+            *sum += value * weight;
+        }
+        self.weight_sum += weight;
+    }
+
+    fn mixed(&self) -> Option<Vec<f32>> {
+        (self.weight_sum > LAYER_REMOVE_EPSILON)
+            .then(|| self.sum.iter().map(|w| w / self.weight_sum).collect())
+    }
+
+    fn blend_weight(&self) -> f32 {
+        self.weight_sum.clamp(0.0, 1.0)
+    }
+}
+
 #[derive(Debug, Copy, Clone, Default)]
 struct PoseAccumulator {
     translation: Vec3Accumulator,
@@ -181,28 +204,224 @@ struct PoseAccumulator {
 
 impl Component for AnimationComponent {
     fn update(&mut self, world: &mut World) {
-        if self.layers.is_empty() || self.clips.is_empty() {
+        let dt = world.delta_time().as_secs_f32();
+        self.advance(world, dt);
+    }
+}
+
+impl AnimationComponent {
+    /// Advances playback by `dt` seconds and poses the animated objects. This is done every frame
+    /// with the frame time, call it yourself to step the animation by a fixed amount.
+    pub fn advance(&mut self, world: &mut World, dt: f32) {
+        if self.clips.is_empty() {
             return;
         }
 
-        let dt = world.delta_time().as_secs_f32();
-        self.advance_layers(dt);
-        self.prune_layers();
-        if self.layers.is_empty() {
+        let base_samples = match &mut self.graph {
+            Some(graph) => {
+                graph.advance(dt);
+                graph.samples()
+            }
+            None => {
+                advance_clips(&self.clips, &mut self.base, dt);
+                prune_clips(&mut self.base);
+                clip_samples(&self.base)
+            }
+        };
+
+        for layer in &mut self.layers {
+            advance_clips(&self.clips, &mut layer.clips, dt);
+            prune_clips(&mut layer.clips);
+        }
+
+        if base_samples.is_empty() && !self.layers.iter().any(AnimationLayer::is_playing) {
             return;
         }
 
-        self.evaluate_and_apply_layers();
+        let events = self.passed_events(&base_samples);
+        let root_frame = self.root_frame();
+        let root_motion = root_frame.map(|frame| self.root_motion_delta(frame, &base_samples));
+        self.evaluate_and_apply(&base_samples, root_frame);
+        if let Some(motion) = root_motion {
+            self.apply_root_motion(motion, dt);
+        }
+        self.send_events(world, &events);
     }
-}
 
-impl AnimationComponent {
     pub fn set_clips(&mut self, clips: Vec<AnimationClip>) {
         let clip_indices = clips.iter().map(ClipIndex::new).collect();
         self.clips = clips;
         self.clip_indices = clip_indices;
         self.resolve_bindings();
-        self.layers.clear();
+        self.base.clear();
+        for layer in &mut self.layers {
+            layer.clips.clear();
+        }
+        if let Some(graph) = &mut self.graph {
+            graph.resolve(&self.clips);
+        }
+    }
+
+    /// Sets clips that were made for another skeleton, after retargeting them onto the first
+    /// skeleton below this object.
+    pub fn set_retargeted_clips(&mut self, clips: Vec<AnimationClip>, retargeting: &Retargeting) {
+        let Some(skel) = self.find_skeleton() else {
+            warn!(
+                "No skeleton found to retarget clips onto in {}",
+                self.parent().name
+            );
+            self.set_clips(clips);
+            return;
+        };
+
+        let clips = clips
+            .iter()
+            .map(|clip| retargeting.retarget(clip, skel.bones()))
+            .collect();
+        self.set_clips(clips);
+    }
+
+    /// Hands playback over to an animation state machine. The clips referenced by the graph are
+    /// looked up by name in the clips of this component.
+    ///
+    /// Playing clips directly with one of the `play_*` or `crossfade_*` functions removes the
+    /// graph again.
+    pub fn set_graph(&mut self, graph: AnimationGraph) {
+        self.base.clear();
+        self.graph = Some(AnimationGraphPlayer::new(graph, &self.clips));
+    }
+
+    pub fn clear_graph(&mut self) {
+        self.graph = None;
+    }
+
+    pub fn graph(&self) -> Option<&AnimationGraphPlayer> {
+        self.graph.as_ref()
+    }
+
+    pub fn graph_mut(&mut self) -> Option<&mut AnimationGraphPlayer> {
+        self.graph.as_mut()
+    }
+
+    /// Sets a float parameter of the animation graph, if there is one.
+    pub fn set_float_parameter(&mut self, name: &str, value: f32) {
+        if let Some(graph) = &mut self.graph {
+            graph.set_float(name, value);
+        }
+    }
+
+    /// Sets a bool parameter of the animation graph, if there is one.
+    pub fn set_bool_parameter(&mut self, name: &str, value: bool) {
+        if let Some(graph) = &mut self.graph {
+            graph.set_bool(name, value);
+        }
+    }
+
+    /// Sets a trigger of the animation graph, if there is one.
+    pub fn set_trigger(&mut self, name: &str) {
+        if let Some(graph) = &mut self.graph {
+            graph.set_trigger(name);
+        }
+    }
+
+    /// Adds a layer on top of all existing ones, replacing a layer with the same name.
+    pub fn add_layer(&mut self, layer: AnimationLayer) {
+        match self.layers.iter_mut().find(|l| l.name == layer.name) {
+            Some(existing) => *existing = layer,
+            None => self.layers.push(layer),
+        }
+    }
+
+    pub fn remove_layer(&mut self, name: &str) -> Option<AnimationLayer> {
+        let index = self.layers.iter().position(|l| l.name == name)?;
+        Some(self.layers.remove(index))
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&AnimationLayer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut AnimationLayer> {
+        self.layers.iter_mut().find(|l| l.name == name)
+    }
+
+    pub fn layers(&self) -> &[AnimationLayer] {
+        &self.layers
+    }
+
+    pub fn set_layer_weight(&mut self, name: &str, weight: f32) {
+        match self.layer_mut(name) {
+            Some(layer) => layer.set_weight(weight),
+            None => warn!("No animation layer \"{name}\" found"),
+        }
+    }
+
+    /// Plays the clip called `clip` on the layer called `layer`, replacing what it played before.
+    pub fn play_on_layer(&mut self, layer: &str, clip: &str, looping: bool, speed: f32) -> bool {
+        self.crossfade_on_layer(layer, clip, 0.0, looping, speed)
+    }
+
+    /// Fades the clip called `clip` in on the layer called `layer`, while everything else that
+    /// plays on the layer fades out.
+    pub fn crossfade_on_layer(
+        &mut self,
+        layer: &str,
+        clip: &str,
+        duration: f32,
+        looping: bool,
+        speed: f32,
+    ) -> bool {
+        let Some(index) = self.find_clip_index_by_name(clip) else {
+            warn!("No clip \"{clip}\" found in {}", self.parent().name);
+            return false;
+        };
+        let Some(layer) = self.layers.iter_mut().find(|l| l.name == layer) else {
+            warn!("No animation layer \"{layer}\" found");
+            return false;
+        };
+
+        crossfade_into(&mut layer.clips, index, duration, looping, speed, 1.0);
+        true
+    }
+
+    /// Fades out everything that plays on the layer called `layer`.
+    pub fn stop_layer(&mut self, layer: &str, duration: f32) {
+        let Some(layer) = self.layer_mut(layer) else {
+            return;
+        };
+
+        for clip in &mut layer.clips {
+            clip.set_fade_target(0.0, duration);
+        }
+        prune_clips(&mut layer.clips);
+    }
+
+    /// Extracts the motion of a root bone or object from the base animation and moves the
+    /// animated object with it. `None` leaves the root animated as it is.
+    pub fn set_root_motion(&mut self, root_motion: Option<RootMotion>) {
+        self.root_motion = root_motion;
+        self.pending_root_motion = RootMotionDelta::default();
+    }
+
+    pub fn root_motion(&self) -> Option<&RootMotion> {
+        self.root_motion.as_ref()
+    }
+
+    /// Takes the root motion extracted since the last call, in world space. Only collects motion
+    /// when [`RootMotion::apply_to_object`] is off.
+    pub fn take_root_motion(&mut self) -> RootMotionDelta {
+        std::mem::take(&mut self.pending_root_motion)
+    }
+
+    fn find_skeleton(&self) -> Option<CRef<SkeletalComponent>> {
+        let mut stack = vec![self.parent()];
+        while let Some(go) = stack.pop() {
+            if let Some(skel) = go.get_component::<SkeletalComponent>() {
+                return Some(skel);
+            }
+            stack.extend(go.children().iter().copied());
+        }
+        None
     }
 
     pub fn resolve_bindings(&mut self) {
@@ -235,6 +454,22 @@ impl AnimationComponent {
         for clip in self.clips.iter() {
             let mut binds = Vec::<ChannelBinding>::with_capacity(clip.channels.len());
             for (ch_index, ch) in clip.channels.iter().enumerate() {
+                if ch.keys.has_morph_weights() {
+                    match map_nodes.get(&ch.target_name) {
+                        Some(&go) => binds.push(ChannelBinding {
+                            ch_index,
+                            target: Binding::MorphWeights(go),
+                        }),
+                        None => warn!(
+                            "No object found for morph weight channel {}",
+                            ch.target_name
+                        ),
+                    }
+                    if !ch.keys.has_transform() {
+                        continue;
+                    }
+                }
+
                 if let Some(bones) = bone_map.get(&ch.target_name) {
                     for (skel_go, i) in bones.iter().copied() {
                         binds.push(ChannelBinding {
@@ -276,17 +511,8 @@ impl AnimationComponent {
             return;
         }
 
-        let target_weight = weight.clamp(0.0, 1.0);
-        self.layers.clear();
-        if target_weight > 0.0 {
-            self.layers.push(ActiveLayer::new_immediate(
-                index,
-                looping,
-                speed,
-                target_weight,
-                target_weight,
-            ));
-        }
+        self.graph = None;
+        crossfade_into(&mut self.base, index, 0.0, looping, speed, weight);
     }
 
     pub fn crossfade_by_name(
@@ -318,32 +544,15 @@ impl AnimationComponent {
             return false;
         }
 
-        let target_weight = target_weight.clamp(0.0, 1.0);
-        if duration <= 0.0 {
-            self.layers.clear();
-            if target_weight > 0.0 {
-                self.layers.push(ActiveLayer::new_immediate(
-                    index,
-                    looping,
-                    speed,
-                    target_weight,
-                    target_weight,
-                ));
-            }
-            return true;
-        }
-
-        for layer in &mut self.layers {
-            layer.set_fade_target(0.0, duration);
-        }
-
-        if target_weight > 0.0 {
-            let mut incoming = ActiveLayer::new_immediate(index, looping, speed, 0.0, 0.0);
-            incoming.set_fade_target(target_weight, duration);
-            self.layers.push(incoming);
-        }
-
-        self.prune_layers();
+        self.graph = None;
+        crossfade_into(
+            &mut self.base,
+            index,
+            duration,
+            looping,
+            speed,
+            target_weight,
+        );
         true
     }
 
@@ -383,159 +592,497 @@ impl AnimationComponent {
         self.clips.iter().position(|c| c.name == name)
     }
 
-    fn advance_layers(&mut self, dt: f32) {
-        for layer in &mut self.layers {
-            layer.time += dt * layer.speed;
-            let clip = &self.clips[layer.clip_index];
-            if clip.duration > 0.0 {
-                if layer.looping {
-                    layer.time = layer.time.rem_euclid(clip.duration);
-                } else if layer.time > clip.duration {
-                    layer.time = clip.duration;
-                } else if layer.time < 0.0 {
-                    layer.time = 0.0;
-                }
-            } else {
-                layer.time = 0.0;
+    /// Space the root channel is animated in, if root motion is set up and the root is animated.
+    fn root_frame(&self) -> Option<RootFrame> {
+        let root = &self.root_motion.as_ref()?.root;
+        let binding = self.bindings.iter().enumerate().find_map(|(clip, binds)| {
+            binds
+                .iter()
+                .find(|b| self.clips[clip].channels[b.ch_index].target_name == *root)
+        })?;
+
+        let to_world = match binding.target {
+            Binding::Transform(go) => go.transform.affine_ext(false),
+            Binding::Bone { skel, idx } => {
+                let skeleton = skel.get_component::<SkeletalComponent>()?;
+                let bones = skeleton.bones();
+                let parent_bind = bones
+                    .parents
+                    .get(idx)
+                    .copied()
+                    .flatten()
+                    .map_or(Affine3A::IDENTITY, |parent| {
+                        Affine3A::from_mat4(bones.bind_global[parent])
+                    });
+                skel.transform.affine() * parent_bind
             }
+            Binding::MorphWeights(_) => return None,
+        };
 
-            layer.step_weight(dt);
-        }
+        let up = to_world
+            .inverse()
+            .transform_vector3(Vec3::Y)
+            .try_normalize()?;
+        Some(RootFrame { to_world, up })
     }
 
-    fn prune_layers(&mut self) {
-        self.layers.retain(|layer| {
-            layer.weight > LAYER_REMOVE_EPSILON || layer.target_weight > LAYER_REMOVE_EPSILON
-        });
+    /// Blended motion of the root channel over the last update, in world space.
+    fn root_motion_delta(&self, frame: RootFrame, samples: &[AnimationSample]) -> RootMotionDelta {
+        let Some(settings) = &self.root_motion else {
+            return RootMotionDelta::default();
+        };
+
+        let mut translation = Vec3::ZERO;
+        let mut angle = 0.0;
+        let mut weight_sum = 0.0;
+        for sample in samples
+            .iter()
+            .filter(|sample| sample.weight > LAYER_REMOVE_EPSILON)
+        {
+            let clip = &self.clips[sample.clip_index];
+            let Some(&channel) = self.clip_indices[sample.clip_index]
+                .by_name
+                .get(&settings.root)
+            else {
+                continue;
+            };
+
+            let keys = &clip.channels[channel].keys;
+            let (mut t, a) = sample_motion(
+                keys,
+                clip.duration,
+                sample.time,
+                sample.delta,
+                sample.looping,
+                frame.up,
+            );
+            if settings.extract_rotation {
+                // the object already took over the turn of the clip up to here, which the
+                // pinned pose no longer shows
+                let start = sample.time - sample.delta;
+                let start = match sample.looping && clip.duration > 0.0 {
+                    true => start.rem_euclid(clip.duration),
+                    false => start.clamp(0.0, clip.duration.max(0.0)),
+                };
+                let twist =
+                    |t: f32| sample_rotation(keys, t).map_or(0.0, |r| twist_angle(r, frame.up));
+                t = Quat::from_axis_angle(frame.up, twist(0.0) - twist(start)) * t;
+            }
+            translation += t * sample.weight;
+            angle += a * sample.weight;
+            weight_sum += sample.weight;
+        }
+
+        if weight_sum <= LAYER_REMOVE_EPSILON {
+            return RootMotionDelta::default();
+        }
+
+        translation /= weight_sum;
+        angle /= weight_sum;
+        if !settings.extract_vertical {
+            translation -= frame.up * translation.dot(frame.up);
+        }
+        if !settings.extract_rotation {
+            angle = 0.0;
+        }
+
+        let axis = frame.to_world.transform_vector3(frame.up).normalize();
+        RootMotionDelta {
+            translation: frame.to_world.transform_vector3(translation),
+            rotation: Quat::from_axis_angle(axis, angle),
+        }
     }
 
-    fn ensure_pose_accumulator(
-        skel_go: GameObjectId,
-        locals: &mut HashMap<GameObjectId, Vec<PoseAccumulator>>,
-    ) -> Option<&mut Vec<PoseAccumulator>> {
-        if !skel_go.exists() {
-            return None;
+    /// Moves the rigid body or transform of the animated object by the extracted root motion.
+    fn apply_root_motion(&mut self, motion: RootMotionDelta, dt: f32) {
+        if !self
+            .root_motion
+            .as_ref()
+            .is_some_and(|rm| rm.apply_to_object)
+        {
+            self.pending_root_motion = self.pending_root_motion.then(motion);
+            return;
         }
 
-        match locals.entry(skel_go) {
-            Entry::Occupied(o) => Some(o.into_mut()),
-            Entry::Vacant(e) => {
-                let skel = skel_go.get_component::<SkeletalComponent>()?;
-                Some(e.insert(vec![PoseAccumulator::default(); skel.bone_count()]))
+        let mut obj = self.parent();
+        if let Some(mut rigid) = obj.get_component::<RigidBodyComponent>() {
+            let kinematic = rigid.is_kinematic();
+            if let Some(body) = rigid.body_mut() {
+                if kinematic {
+                    let mut pose = *body.position();
+                    pose.translation += motion.translation;
+                    pose.rotation = motion.rotation * pose.rotation;
+                    body.set_next_kinematic_position(pose);
+                } else if dt > 0.0 {
+                    let (axis, angle) = motion.rotation.to_axis_angle();
+                    let mut linvel = motion.translation / dt;
+                    if !self
+                        .root_motion
+                        .as_ref()
+                        .is_some_and(|rm| rm.extract_vertical)
+                    {
+                        linvel.y = body.linvel().y;
+                    }
+                    body.set_linvel(linvel, true);
+                    body.set_angvel(axis * angle / dt, true);
+                }
+                return;
             }
         }
-    }
 
-    fn evaluate_and_apply_layers(&mut self) {
-        let mut skel_locals: HashMap<GameObjectId, Vec<PoseAccumulator>> = HashMap::new();
-        let mut transform_locals: HashMap<GameObjectId, PoseAccumulator> = HashMap::new();
+        let parent = obj.transform.affine_ext(false);
+        let parent_rotation = obj.transform.global_rotation_ext(false);
+        let local_rotation = *obj.transform.local_rotation();
+        obj.transform
+            .translate(parent.inverse().transform_vector3(motion.translation));
+        obj.transform.set_local_rotation(
+            (parent_rotation.inverse() * motion.rotation * parent_rotation * local_rotation)
+                .normalize(),
+        );
+    }
 
-        for layer in self
+    /// Events that playback went past in this update. Every clip blended in with more than
+    /// [`EVENT_WEIGHT_THRESHOLD`] fires its events, so a clip that is fading out still reports
+    /// them. An event name is only reported once per update, which keeps blended clips sharing
+    /// events like walk and run in a blend space from sending the same footstep twice.
+    fn passed_events(&self, base_samples: &[AnimationSample]) -> Vec<AnimationEvent> {
+        let layer_samples = self
             .layers
             .iter()
             .filter(|layer| layer.weight > LAYER_REMOVE_EPSILON)
+            .flat_map(|layer| {
+                clip_samples(&layer.clips)
+                    .into_iter()
+                    .map(move |sample| AnimationSample {
+                        weight: sample.weight * layer.weight,
+                        ..sample
+                    })
+            });
+
+        let mut events: Vec<AnimationEvent> = Vec::new();
+        for sample in base_samples.iter().copied().chain(layer_samples) {
+            if sample.weight <= EVENT_WEIGHT_THRESHOLD {
+                continue;
+            }
+            let passed = self.clips[sample.clip_index].events_between(
+                sample.time,
+                sample.delta,
+                sample.looping,
+            );
+            for event in passed {
+                if !events.iter().any(|known| known.name == event.name) {
+                    events.push(event.clone());
+                }
+            }
+        }
+        events
+    }
+
+    /// Hands events to all other components of the animated object.
+    fn send_events(&self, world: &mut World, events: &[AnimationEvent]) {
+        if events.is_empty() {
+            return;
+        }
+
+        let components: Vec<_> = self.parent().iter_dyn_components().cloned().collect();
+        for mut component in components {
+            if component.is_a::<AnimationComponent>() {
+                continue;
+            }
+            for event in events {
+                component.on_animation_event(world, event);
+            }
+        }
+    }
+
+    fn evaluate_and_apply(&self, base_samples: &[AnimationSample], root_frame: Option<RootFrame>) {
+        let mut resolved = ResolvedPose::default();
+
+        let base = self.accumulate(base_samples, None, root_frame);
+        Self::blend_layer(&mut resolved, base, LayerBlend::Override, 1.0);
+
+        for layer in &self.layers {
+            if layer.weight <= LAYER_REMOVE_EPSILON || !layer.is_playing() {
+                continue;
+            }
+
+            let samples = clip_samples(&layer.clips);
+            let pose = self.accumulate(&samples, Some(layer), None);
+            Self::blend_layer(&mut resolved, pose, layer.blend, layer.weight);
+        }
+
+        Self::apply(resolved);
+    }
+
+    /// Keys the first frame of an additive channel is subtracted from.
+    fn reference_keys<'a>(
+        &'a self,
+        layer: &AnimationLayer,
+        own: &'a TransformKeys,
+        target_name: &str,
+    ) -> &'a TransformKeys {
+        let reference = layer
+            .reference_clip
+            .as_deref()
+            .and_then(|name| self.find_clip_index_by_name(name))
+            .and_then(|clip| {
+                let channel = *self.clip_indices[clip].by_name.get(target_name)?;
+                Some(&self.clips[clip].channels[channel].keys)
+            });
+        reference.unwrap_or(own)
+    }
+
+    /// Samples and blends all clips of one layer. Additive layers accumulate the offsets of
+    /// their clips from the reference pose. With a `root_frame`, the root motion channel is kept
+    /// where it started.
+    fn accumulate(
+        &self,
+        samples: &[AnimationSample],
+        layer: Option<&AnimationLayer>,
+        root_frame: Option<RootFrame>,
+    ) -> LayerPose {
+        let mut pose = LayerPose::default();
+
+        for sample in samples
+            .iter()
+            .filter(|sample| sample.weight > LAYER_REMOVE_EPSILON)
         {
-            let clip = &self.clips[layer.clip_index];
-            let binds = &self.bindings[layer.clip_index];
-            let weight = layer.weight;
-            let time = layer.time;
+            let clip = &self.clips[sample.clip_index];
+            let binds = &self.bindings[sample.clip_index];
+            let weight = sample.weight;
+            let time = sample.time;
 
             for b in binds {
                 let ch = &clip.channels[b.ch_index];
-                let t = sample_translation(&ch.keys, time);
-                let r = sample_rotation(&ch.keys, time);
-                let s = sample_scale(&ch.keys, time);
-
-                match b.target {
-                    Binding::Transform(go) => {
-                        if !go.exists() {
-                            warn!("Animation game object was not found");
-                            continue;
-                        }
+                let mask = layer.map_or(1.0, |l| l.mask_weight(&ch.target_name));
+                if mask <= 0.0 {
+                    continue;
+                }
 
-                        let pose = transform_locals.entry(go).or_default();
-                        if let Some(t) = t {
-                            pose.translation.add(t, weight);
-                        }
-                        if let Some(r) = r {
-                            pose.rotation.add(r, weight);
-                        }
-                        if let Some(s) = s {
-                            pose.scale.add(s, weight);
-                        }
+                let reference = layer
+                    .filter(|l| l.blend == LayerBlend::Additive)
+                    .map(|l| self.reference_keys(l, &ch.keys, &ch.target_name));
+
+                if let Binding::MorphWeights(go) = b.target {
+                    let mut weights = sample_morph_weights(&ch.keys, time);
+                    if let Some(reference) = reference {
+                        weights = weights
+                            .zip(sample_morph_weights(reference, 0.0))
+                            .map(|(w, r)| w.iter().zip(r).map(|(w, r)| w - r).collect());
+                    }
+                    if let Some(weights) = weights {
+                        let (accum, mask_weight) = pose.morphs.entry(go).or_default();
+                        accum.add(&weights, weight);
+                        *mask_weight = mask;
                     }
-                    Binding::Bone { skel, idx } => {
-                        if let Some(locals) = Self::ensure_pose_accumulator(skel, &mut skel_locals)
-                        {
-                            if idx >= locals.len() {
-                                warn!("Binding bone index {idx} is out of range");
-                                continue;
-                            }
-
-                            let pose = &mut locals[idx];
-                            if let Some(t) = t {
-                                pose.translation.add(t, weight);
-                            }
-                            if let Some(r) = r {
-                                pose.rotation.add(r, weight);
-                            }
-                            if let Some(s) = s {
-                                pose.scale.add(s, weight);
-                            }
-                        } else {
+                    continue;
+                }
+
+                let mut t = sample_translation(&ch.keys, time);
+                let mut r = sample_rotation(&ch.keys, time);
+                let mut s = sample_scale(&ch.keys, time);
+                if let Some(reference) = reference {
+                    t = t
+                        .zip(sample_translation(reference, 0.0))
+                        .map(|(t, r)| t - r);
+                    r = r
+                        .zip(sample_rotation(reference, 0.0))
+                        .map(|(q, r)| r.inverse() * q);
+                    s = s.zip(sample_scale(reference, 0.0)).map(|(s, r)| {
+                        Vec3::select(r.abs().cmpgt(Vec3::splat(f32::EPSILON)), s / r, Vec3::ONE)
+                    });
+                }
+                if let Some(frame) = root_frame
+                    && let Some(settings) = &self.root_motion
+                    && ch.target_name == settings.root
+                {
+                    (t, r) = pin_root(settings, frame.up, &ch.keys, t, r);
+                }
+
+                let target = match b.target {
+                    Binding::Transform(go) => PoseTarget::Transform(go),
+                    Binding::Bone { skel, idx } => PoseTarget::Bone { skel, idx },
+                    Binding::MorphWeights(_) => unreachable!(),
+                };
+
+                let (accum, mask_weight) = pose.targets.entry(target).or_default();
+                *mask_weight = mask;
+                if let Some(t) = t {
+                    accum.translation.add(t, weight);
+                }
+                if let Some(r) = r {
+                    accum.rotation.add(r, weight);
+                }
+                if let Some(s) = s {
+                    accum.scale.add(s, weight);
+                }
+            }
+        }
+
+        pose
+    }
+
+    /// Pose a target has before any layer is applied: the current local transform of objects and
+    /// the bind pose of bones.
+    fn rest_pose(
+        target: PoseTarget,
+        bind_poses: &mut HashMap<GameObjectId, Vec<SkeletonLocals>>,
+    ) -> Option<SkeletonLocals> {
+        match target {
+            PoseTarget::Transform(go) => {
+                if !go.exists() {
+                    warn!("Animation game object was not found");
+                    return None;
+                }
+
+                let tr = &go.transform;
+                Some((
+                    *tr.local_position(),
+                    *tr.local_rotation(),
+                    *tr.local_scale(),
+                ))
+            }
+            PoseTarget::Bone { skel, idx } => {
+                let bind = match bind_poses.entry(skel) {
+                    Entry::Occupied(o) => o.into_mut(),
+                    Entry::Vacant(e) => {
+                        if !skel.exists() {
                             warn!("Binding bone not found");
+                            return None;
                         }
+                        let Some(skel) = skel.get_component::<SkeletalComponent>() else {
+                            warn!("Skeleton not found on supposed Bone Channel Binding");
+                            return None;
+                        };
+                        let locals = skel
+                            .bones()
+                            .bind_local
+                            .iter()
+                            .map(|bind_local| {
+                                let (scale, rotation, translation) =
+                                    bind_local.to_scale_rotation_translation();
+                                (translation, rotation, scale)
+                            })
+                            .collect();
+                        e.insert(locals)
                     }
+                };
+
+                let pose = bind.get(idx).copied();
+                if pose.is_none() {
+                    warn!("Binding bone index {idx} is out of range");
                 }
+                pose
             }
         }
+    }
+
+    fn blend_layer(resolved: &mut ResolvedPose, layer: LayerPose, blend: LayerBlend, weight: f32) {
+        for (target, (accum, mask)) in layer.targets {
+            let current = match resolved.targets.entry(target) {
+                Entry::Occupied(o) => o.into_mut(),
+                Entry::Vacant(e) => match Self::rest_pose(target, &mut resolved.bind_poses) {
+                    Some(rest) => e.insert(rest),
+                    None => continue,
+                },
+            };
 
-        for (mut go, pose) in transform_locals {
-            if !go.exists() {
-                warn!("Animation game object was not found");
+            let factor = weight * mask;
+            let (translation, rotation, scale) = current;
+            match blend {
+                LayerBlend::Override => {
+                    if let Some(t) = accum.translation.mixed() {
+                        *translation =
+                            translation.lerp(t, accum.translation.blend_weight() * factor);
+                    }
+                    if let Some(r) = accum.rotation.mixed() {
+                        *rotation = rotation.slerp(r, accum.rotation.blend_weight() * factor);
+                    }
+                    if let Some(s) = accum.scale.mixed() {
+                        *scale = scale.lerp(s, accum.scale.blend_weight() * factor);
+                    }
+                }
+                LayerBlend::Additive => {
+                    if let Some(t) = accum.translation.mixed() {
+                        *translation += t * accum.translation.blend_weight() * factor;
+                    }
+                    if let Some(r) = accum.rotation.mixed() {
+                        *rotation *=
+                            Quat::IDENTITY.slerp(r, accum.rotation.blend_weight() * factor);
+                    }
+                    if let Some(s) = accum.scale.mixed() {
+                        *scale *= Vec3::ONE.lerp(s, accum.scale.blend_weight() * factor);
+                    }
+                }
+            }
+        }
+
+        for (go, (accum, mask)) in layer.morphs {
+            let Some(mixed) = accum.mixed() else {
                 continue;
+            };
+
+            let current = match resolved.morphs.entry(go) {
+                Entry::Occupied(o) => o.into_mut(),
+                Entry::Vacant(e) => {
+                    let Some(renderer) = go.get_component::<MeshRenderer>() else {
+                        warn!("Mesh renderer not found on morph weight channel binding");
+                        continue;
+                    };
+                    e.insert(renderer.morph_weights().to_vec())
+                }
+            };
+
+            if current.len() < mixed.len() {
+                current.resize(mixed.len(), 0.0);
             }
 
-            let tr = &mut go.transform;
-            if let Some(t) = pose.translation.mixed() {
-                tr.set_local_position_vec(
-                    tr.local_position().lerp(t, pose.translation.blend_weight()),
-                );
+            let factor = accum.blend_weight() * weight * mask;
+            for (from, w) in current.iter_mut().zip(mixed) {
+                match blend {
+                    LayerBlend::Override => *from += (w - *from) * factor,
+                    LayerBlend::Additive => *from += w * factor,
+                }
             }
-            if let Some(r) = pose.rotation.mixed() {
-                tr.set_local_rotation(tr.local_rotation().slerp(r, pose.rotation.blend_weight()));
+        }
+    }
+
+    fn apply(resolved: ResolvedPose) {
+        let mut skeletons = resolved.bind_poses;
+
+        for (target, (t, r, s)) in resolved.targets {
+            match target {
+                PoseTarget::Transform(mut go) => {
+                    if !go.exists() {
+                        warn!("Animation game object was not found");
+                        continue;
+                    }
+
+                    let tr = &mut go.transform;
+                    tr.set_local_position_vec(t);
+                    tr.set_local_rotation(r);
+                    tr.set_nonuniform_local_scale(s);
+                }
+                PoseTarget::Bone { skel, idx } => {
+                    if let Some(local) = skeletons.get_mut(&skel).and_then(|l| l.get_mut(idx)) {
+                        *local = (t, r, s);
+                    }
+                }
             }
-            if let Some(s) = pose.scale.mixed() {
-                tr.set_nonuniform_local_scale(tr.local_scale().lerp(s, pose.scale.blend_weight()));
+        }
+
+        for (go, weights) in resolved.morphs {
+            if let Some(mut renderer) = go.get_component::<MeshRenderer>() {
+                renderer.set_morph_weights(&weights);
             }
         }
 
-        for (skel_go, accum) in skel_locals {
+        for (skel_go, locals) in skeletons {
             let Some(mut skel) = skel_go.get_component::<SkeletalComponent>() else {
                 warn!("Skeleton not found on supposed Bone Channel Binding");
                 continue;
             };
 
-            let bones = skel.bones();
-            let mut locals: Vec<SkeletonLocals> = Vec::with_capacity(bones.len());
-            for (i, bind_local) in bones.bind_local.iter().enumerate() {
-                let (bind_scale, bind_rotation, bind_translation) =
-                    bind_local.to_scale_rotation_translation();
-                let pose = accum.get(i).copied().unwrap_or_default();
-
-                let translation = pose.translation.mixed().map_or(bind_translation, |t| {
-                    bind_translation.lerp(t, pose.translation.blend_weight())
-                });
-                let rotation = pose.rotation.mixed().map_or(bind_rotation, |r| {
-                    bind_rotation.slerp(r, pose.rotation.blend_weight())
-                });
-                let scale = pose.scale.mixed().map_or(bind_scale, |s| {
-                    bind_scale.lerp(s, pose.scale.blend_weight())
-                });
-
-                locals.push((translation, rotation, scale));
-            }
-
             skel.set_local_pose_trs(&locals);
         }
     }
@@ -545,6 +1092,118 @@ impl AnimationComponent {
     }
 }
 
+/// Keeps the root channel at its first frame along the extracted directions: the offset
+/// perpendicular to `up` (and along it, if vertical motion is extracted) and the rotation around
+/// `up` are taken out of the pose.
+fn pin_root(
+    settings: &RootMotion,
+    up: Vec3,
+    keys: &TransformKeys,
+    t: Option<Vec3>,
+    r: Option<Quat>,
+) -> (Option<Vec3>, Option<Quat>) {
+    let t = t.map(|t| {
+        let offset = t - sample_translation(keys, 0.0).unwrap_or(t);
+        match settings.extract_vertical {
+            true => t - offset,
+            false => t - (offset - up * offset.dot(up)),
+        }
+    });
+
+    let r = r.map(|r| {
+        if !settings.extract_rotation {
+            return r;
+        }
+        let start = sample_rotation(keys, 0.0).map_or(0.0, |r0| twist_angle(r0, up));
+        Quat::from_axis_angle(up, start - twist_angle(r, up)) * r
+    });
+
+    (t, r)
+}
+
+fn clip_samples(clips: &[ActiveClip]) -> Vec<AnimationSample> {
+    clips
+        .iter()
+        .map(|clip| AnimationSample {
+            clip_index: clip.clip_index,
+            time: clip.time,
+            weight: clip.weight,
+            delta: clip.delta,
+            looping: clip.looping,
+        })
+        .collect()
+}
+
+fn advance_clips(clips: &[AnimationClip], playing: &mut [ActiveClip], dt: f32) {
+    for active in playing {
+        let previous = active.time;
+        active.time += dt * active.speed;
+        let clip = &clips[active.clip_index];
+        if clip.duration > 0.0 {
+            if active.looping {
+                active.time = active.time.rem_euclid(clip.duration);
+            } else if active.time > clip.duration {
+                active.time = clip.duration;
+            } else if active.time < 0.0 {
+                active.time = 0.0;
+            }
+        } else {
+            active.time = 0.0;
+        }
+
+        active.delta = if active.looping && clip.duration > 0.0 {
+            dt * active.speed
+        } else {
+            active.time - previous
+        };
+        active.step_weight(dt);
+    }
+}
+
+fn prune_clips(playing: &mut Vec<ActiveClip>) {
+    playing.retain(|clip| {
+        clip.weight > LAYER_REMOVE_EPSILON || clip.target_weight > LAYER_REMOVE_EPSILON
+    });
+}
+
+/// Fades `index` in over `duration` while everything else on the stack fades out. Without a
+/// duration, the clip replaces the stack right away.
+fn crossfade_into(
+    playing: &mut Vec<ActiveClip>,
+    index: usize,
+    duration: f32,
+    looping: bool,
+    speed: f32,
+    target_weight: f32,
+) {
+    let target_weight = target_weight.clamp(0.0, 1.0);
+    if duration <= 0.0 {
+        playing.clear();
+        if target_weight > 0.0 {
+            playing.push(ActiveClip::new_immediate(
+                index,
+                looping,
+                speed,
+                target_weight,
+                target_weight,
+            ));
+        }
+        return;
+    }
+
+    for clip in playing.iter_mut() {
+        clip.set_fade_target(0.0, duration);
+    }
+
+    if target_weight > 0.0 {
+        let mut incoming = ActiveClip::new_immediate(index, looping, speed, 0.0, 0.0);
+        incoming.set_fade_target(target_weight, duration);
+        playing.push(incoming);
+    }
+
+    prune_clips(playing);
+}
+
 fn collect_subtree_by_name(root: GameObjectId, out: &mut HashMap<String, GameObjectId>) {
     out.insert(root.name.clone(), root);
     for child in root.children().iter().copied() {
@@ -0,0 +1,688 @@
+//! Data-driven animation state machines.
+//!
+//! An [`AnimationGraph`] describes named states that play a [`Motion`], and the transitions
+//! between them. Transitions fire when all of their [`Condition`]s hold for the graph's named
+//! parameters, and optionally once the current state has played up to an exit time. The graph
+//! is run by an [`AnimationGraphPlayer`], usually through
+//! [`AnimationComponent::set_graph`](crate::AnimationComponent::set_graph), which turns it into
+//! weighted clip samples every frame.
+//!
+//! ```rust
+//! use syrillian_components::animation_graph::*;
+//!
+//! let graph = AnimationGraph::new()
+//!     .with_state(AnimationState::blend_1d(
+//!         "Locomotion",
+//!         "speed",
+//!         [(0.0, "Idle"), (1.5, "Walk"), (5.5, "Run")],
+//!     ))
+//!     .with_state(AnimationState::clip("Jump", "Jump").with_looping(false))
+//!     .with_transition(AnimationTransition::from_any("Jump").when(Condition::Trigger("jump".into())))
+//!     .with_transition(
+//!         AnimationTransition::new("Jump", "Locomotion")
+//!             .when(Condition::IsTrue("grounded".into()))
+//!             .with_exit_time(0.8),
+//!     );
+//! ```
+
+use std::collections::HashMap;
+use syrillian::math::Vec2;
+use syrillian::tracing::warn;
+use syrillian::utils::animation::AnimationClip;
+
+const DEFAULT_TRANSITION_DURATION: f32 = 0.2;
+const STATE_REMOVE_EPSILON: f32 = 1e-3;
+
+/// Value of a named graph parameter.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParameterValue {
+    Float(f32),
+    Bool(bool),
+    /// A flag that is reset as soon as a transition consumes it.
+    Trigger(bool),
+}
+
+/// A requirement on a named parameter for a transition to fire.
+///
+/// Parameters that were never set count as `0.0` or `false`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Greater(String, f32),
+    Less(String, f32),
+    IsTrue(String),
+    IsFalse(String),
+    /// Holds while the trigger is set, and consumes it when the transition fires.
+    Trigger(String),
+}
+
+/// A sample point of a one-dimensional blend space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlendPoint1D {
+    pub position: f32,
+    pub clip: String,
+}
+
+/// A sample point of a two-dimensional blend space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlendPoint2D {
+    pub position: Vec2,
+    pub clip: String,
+}
+
+/// What a state plays.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Motion {
+    Clip(String),
+    /// Blends the two points closest to the parameter value linearly.
+    BlendSpace1D {
+        parameter: String,
+        points: Vec<BlendPoint1D>,
+    },
+    /// Blends the points around the parameter values with gradient band interpolation, points
+    /// that aren't neighbours of the parameter values don't contribute.
+    BlendSpace2D {
+        x: String,
+        y: String,
+        points: Vec<BlendPoint2D>,
+    },
+}
+
+impl Motion {
+    fn clips(&self) -> Vec<&str> {
+        match self {
+            Motion::Clip(clip) => vec![clip],
+            Motion::BlendSpace1D { points, .. } => points.iter().map(|p| p.clip.as_str()).collect(),
+            Motion::BlendSpace2D { points, .. } => points.iter().map(|p| p.clip.as_str()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationState {
+    pub name: String,
+    pub motion: Motion,
+    pub speed: f32,
+    pub looping: bool,
+}
+
+impl AnimationState {
+    pub fn new(name: impl Into<String>, motion: Motion) -> Self {
+        Self {
+            name: name.into(),
+            motion,
+            speed: 1.0,
+            looping: true,
+        }
+    }
+
+    /// A state playing a single clip.
+    pub fn clip(name: impl Into<String>, clip: impl Into<String>) -> Self {
+        Self::new(name, Motion::Clip(clip.into()))
+    }
+
+    /// A state blending clips placed along a single float parameter.
+    pub fn blend_1d<C: Into<String>>(
+        name: impl Into<String>,
+        parameter: impl Into<String>,
+        points: impl IntoIterator<Item = (f32, C)>,
+    ) -> Self {
+        let points = points
+            .into_iter()
+            .map(|(position, clip)| BlendPoint1D {
+                position,
+                clip: clip.into(),
+            })
+            .collect();
+        Self::new(
+            name,
+            Motion::BlendSpace1D {
+                parameter: parameter.into(),
+                points,
+            },
+        )
+    }
+
+    /// A state blending clips placed on the plane spanned by two float parameters.
+    pub fn blend_2d<C: Into<String>>(
+        name: impl Into<String>,
+        x: impl Into<String>,
+        y: impl Into<String>,
+        points: impl IntoIterator<Item = (Vec2, C)>,
+    ) -> Self {
+        let points = points
+            .into_iter()
+            .map(|(position, clip)| BlendPoint2D {
+                position,
+                clip: clip.into(),
+            })
+            .collect();
+        Self::new(
+            name,
+            Motion::BlendSpace2D {
+                x: x.into(),
+                y: y.into(),
+                points,
+            },
+        )
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationTransition {
+    /// The state this transition leaves, or `None` to allow it from any state.
+    pub from: Option<String>,
+    pub to: String,
+    pub conditions: Vec<Condition>,
+    /// Crossfade duration in seconds.
+    pub duration: f32,
+    /// Normalized time the source state has to reach before the transition may fire.
+    ///
+    /// For looping states, values below `1.0` only let the transition fire in the update that
+    /// playback crosses this point of a loop.
+    pub exit_time: Option<f32>,
+}
+
+impl AnimationTransition {
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: Some(from.into()),
+            ..Self::from_any(to)
+        }
+    }
+
+    /// A transition that can fire from every state but its target.
+    pub fn from_any(to: impl Into<String>) -> Self {
+        Self {
+            from: None,
+            to: to.into(),
+            conditions: Vec::new(),
+            duration: DEFAULT_TRANSITION_DURATION,
+            exit_time: None,
+        }
+    }
+
+    pub fn when(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_exit_time(mut self, exit_time: f32) -> Self {
+        self.exit_time = Some(exit_time);
+        self
+    }
+}
+
+/// The description of an animation state machine. The first state added is the entry state,
+/// unless another one is picked with [`AnimationGraph::with_entry`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnimationGraph {
+    pub states: Vec<AnimationState>,
+    pub transitions: Vec<AnimationTransition>,
+    pub entry: Option<String>,
+    pub parameters: HashMap<String, ParameterValue>,
+}
+
+impl AnimationGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_state(mut self, state: AnimationState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    pub fn with_transition(mut self, transition: AnimationTransition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    pub fn with_entry(mut self, state: impl Into<String>) -> Self {
+        self.entry = Some(state.into());
+        self
+    }
+
+    /// Sets the initial value of a parameter.
+    pub fn with_parameter(mut self, name: impl Into<String>, value: ParameterValue) -> Self {
+        self.parameters.insert(name.into(), value);
+        self
+    }
+
+    pub fn state_index(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|s| s.name == name)
+    }
+}
+
+/// One clip to sample, as produced by an [`AnimationGraphPlayer`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnimationSample {
+    pub clip_index: usize,
+    /// Time in seconds
+    pub time: f32,
+    pub weight: f32,
+    /// Seconds of the clip played since the last frame, negative when playing backwards
+    pub delta: f32,
+    pub looping: bool,
+}
+
+/// A clip of a state's motion, resolved against the clips of the animation component.
+#[derive(Debug, Copy, Clone)]
+struct ResolvedClip {
+    /// Index of the blend point in the motion
+    point: usize,
+    clip_index: usize,
+    duration: f32,
+}
+
+#[derive(Debug, Clone)]
+struct StateInstance {
+    state: usize,
+    /// Played cycles since entering the state
+    phase: f32,
+    previous_phase: f32,
+    weight: f32,
+    fade_rate: f32,
+}
+
+impl StateInstance {
+    fn new(state: usize, weight: f32, fade_rate: f32) -> Self {
+        Self {
+            state,
+            phase: 0.0,
+            previous_phase: 0.0,
+            weight,
+            fade_rate,
+        }
+    }
+
+    fn step_weight(&mut self, dt: f32) {
+        self.weight = (self.weight + self.fade_rate * dt).clamp(0.0, 1.0);
+        if self.weight >= 1.0 || self.weight <= 0.0 {
+            self.fade_rate = 0.0;
+        }
+    }
+}
+
+/// Runs an [`AnimationGraph`]: keeps the parameters, the current state and the states that are
+/// still fading out.
+#[derive(Debug, Clone)]
+pub struct AnimationGraphPlayer {
+    graph: AnimationGraph,
+    clips: Vec<Vec<ResolvedClip>>,
+    parameters: HashMap<String, ParameterValue>,
+    current: Option<StateInstance>,
+    fading: Vec<StateInstance>,
+}
+
+impl AnimationGraphPlayer {
+    /// Resolves the clip names of the graph against `clips` and enters the entry state.
+    pub fn new(graph: AnimationGraph, clips: &[AnimationClip]) -> Self {
+        let mut player = Self {
+            parameters: graph.parameters.clone(),
+            graph,
+            clips: Vec::new(),
+            current: None,
+            fading: Vec::new(),
+        };
+        player.resolve(clips);
+        player
+    }
+
+    /// Looks up the clips of every state again and restarts at the entry state. Parameters keep
+    /// their values.
+    pub fn resolve(&mut self, clips: &[AnimationClip]) {
+        self.clips = self
+            .graph
+            .states
+            .iter()
+            .map(|state| {
+                state
+                    .motion
+                    .clips()
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(point, name)| {
+                        let Some(clip_index) = clips.iter().position(|c| c.name == name) else {
+                            warn!(
+                                "No clip \"{name}\" found for animation state {}",
+                                state.name
+                            );
+                            return None;
+                        };
+                        Some(ResolvedClip {
+                            point,
+                            clip_index,
+                            duration: clips[clip_index].duration,
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let entry = match &self.graph.entry {
+            Some(name) => self.graph.state_index(name).or_else(|| {
+                warn!("Entry animation state \"{name}\" does not exist");
+                None
+            }),
+            None => (!self.graph.states.is_empty()).then_some(0),
+        };
+
+        self.fading.clear();
+        self.current = entry.map(|state| StateInstance::new(state, 1.0, 0.0));
+    }
+
+    pub fn graph(&self) -> &AnimationGraph {
+        &self.graph
+    }
+
+    /// Name of the state that is playing or being faded into.
+    pub fn current_state(&self) -> Option<&str> {
+        let current = self.current.as_ref()?;
+        Some(&self.graph.states[current.state].name)
+    }
+
+    /// Normalized time of the current state. Looping states count up by one every cycle.
+    pub fn current_time(&self) -> f32 {
+        self.current.as_ref().map_or(0.0, |c| c.phase)
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        !self.fading.is_empty()
+    }
+
+    pub fn parameter(&self, name: &str) -> Option<ParameterValue> {
+        self.parameters.get(name).copied()
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.set_parameter(name, ParameterValue::Float(value));
+    }
+
+    pub fn set_bool(&mut self, name: &str, value: bool) {
+        self.set_parameter(name, ParameterValue::Bool(value));
+    }
+
+    pub fn set_trigger(&mut self, name: &str) {
+        self.set_parameter(name, ParameterValue::Trigger(true));
+    }
+
+    pub fn reset_trigger(&mut self, name: &str) {
+        self.set_parameter(name, ParameterValue::Trigger(false));
+    }
+
+    pub fn set_parameter(&mut self, name: &str, value: ParameterValue) {
+        match self.parameters.get_mut(name) {
+            Some(current) => *current = value,
+            None => {
+                self.parameters.insert(name.to_string(), value);
+            }
+        }
+    }
+
+    /// Switches to `state` immediately or with a crossfade, ignoring transitions.
+    pub fn jump_to(&mut self, state: &str, duration: f32) -> bool {
+        let Some(index) = self.graph.state_index(state) else {
+            warn!("No animation state \"{state}\" found");
+            return false;
+        };
+        self.enter(index, duration);
+        true
+    }
+
+    /// Advances all playing states by `dt` seconds, then fires at most one transition.
+    pub fn advance(&mut self, dt: f32) {
+        let Some(mut current) = self.current.take() else {
+            return;
+        };
+
+        let previous_phase = current.phase;
+        self.advance_instance(&mut current, dt);
+        let mut fading = std::mem::take(&mut self.fading);
+        for instance in &mut fading {
+            self.advance_instance(instance, dt);
+        }
+        fading.retain(|i| i.weight > STATE_REMOVE_EPSILON);
+
+        self.fading = fading;
+        let state = current.state;
+        let phase = current.phase;
+        self.current = Some(current);
+
+        if let Some(transition) = self.find_transition(state, previous_phase, phase) {
+            let transition = self.graph.transitions[transition].clone();
+            self.consume_triggers(&transition.conditions);
+            if let Some(to) = self.graph.state_index(&transition.to) {
+                self.enter(to, transition.duration);
+            }
+        }
+    }
+
+    /// The clips to blend for the current frame. Weights of a crossfade add up to one.
+    pub fn samples(&self) -> Vec<AnimationSample> {
+        let mut samples = Vec::new();
+        for instance in self.fading.iter().chain(&self.current) {
+            if instance.weight <= STATE_REMOVE_EPSILON {
+                continue;
+            }
+
+            let state = &self.graph.states[instance.state];
+            let clips = &self.clips[instance.state];
+            let weights = self.motion_weights(&state.motion);
+            let cycle = if state.looping {
+                instance.phase.rem_euclid(1.0)
+            } else {
+                instance.phase.clamp(0.0, 1.0)
+            };
+
+            for clip in clips {
+                let weight = weights.get(clip.point).copied().unwrap_or(0.0) * instance.weight;
+                if weight <= STATE_REMOVE_EPSILON {
+                    continue;
+                }
+
+                samples.push(AnimationSample {
+                    clip_index: clip.clip_index,
+                    time: cycle * clip.duration,
+                    weight,
+                    delta: (instance.phase - instance.previous_phase) * clip.duration,
+                    looping: state.looping,
+                });
+            }
+        }
+        samples
+    }
+
+    fn enter(&mut self, state: usize, duration: f32) {
+        let previous = self.current.take();
+        if duration <= 0.0 {
+            self.fading.clear();
+            self.current = Some(StateInstance::new(state, 1.0, 0.0));
+            return;
+        }
+
+        self.fading.extend(previous);
+        for instance in &mut self.fading {
+            instance.fade_rate = -instance.weight / duration;
+        }
+        self.current = Some(StateInstance::new(state, 0.0, 1.0 / duration));
+    }
+
+    fn advance_instance(&self, instance: &mut StateInstance, dt: f32) {
+        let state = &self.graph.states[instance.state];
+        let duration = self.motion_duration(instance.state);
+        instance.previous_phase = instance.phase;
+        if duration > 0.0 {
+            instance.phase += dt * state.speed / duration;
+            if !state.looping {
+                instance.phase = instance.phase.clamp(0.0, 1.0);
+            }
+        }
+        instance.step_weight(dt);
+    }
+
+    /// Duration of one cycle of the state, which is the weighted average of all blended clips,
+    /// so their cycles stay in sync.
+    fn motion_duration(&self, state: usize) -> f32 {
+        let weights = self.motion_weights(&self.graph.states[state].motion);
+        let (sum, weight_sum) = self.clips[state]
+            .iter()
+            .map(|clip| {
+                (
+                    clip.duration,
+                    weights.get(clip.point).copied().unwrap_or(0.0),
+                )
+            })
+            .fold((0.0, 0.0), |(sum, ws), (d, w)| (sum + d * w, ws + w));
+
+        if weight_sum <= f32::EPSILON {
+            return 0.0;
+        }
+        sum / weight_sum
+    }
+
+    fn motion_weights(&self, motion: &Motion) -> Vec<f32> {
+        match motion {
+            Motion::Clip(_) => vec![1.0],
+            Motion::BlendSpace1D { parameter, points } => {
+                let positions: Vec<f32> = points.iter().map(|p| p.position).collect();
+                blend_weights_1d(&positions, self.float(parameter))
+            }
+            Motion::BlendSpace2D { x, y, points } => {
+                let positions: Vec<Vec2> = points.iter().map(|p| p.position).collect();
+                blend_weights_2d(&positions, Vec2::new(self.float(x), self.float(y)))
+            }
+        }
+    }
+
+    fn float(&self, name: &str) -> f32 {
+        match self.parameters.get(name) {
+            Some(ParameterValue::Float(v)) => *v,
+            Some(ParameterValue::Bool(b) | ParameterValue::Trigger(b)) => *b as u8 as f32,
+            None => 0.0,
+        }
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        match self.parameters.get(name) {
+            Some(ParameterValue::Float(v)) => *v != 0.0,
+            Some(ParameterValue::Bool(b) | ParameterValue::Trigger(b)) => *b,
+            None => false,
+        }
+    }
+
+    fn condition_holds(&self, condition: &Condition) -> bool {
+        match condition {
+            Condition::Greater(name, value) => self.float(name) > *value,
+            Condition::Less(name, value) => self.float(name) < *value,
+            Condition::IsTrue(name) | Condition::Trigger(name) => self.flag(name),
+            Condition::IsFalse(name) => !self.flag(name),
+        }
+    }
+
+    fn consume_triggers(&mut self, conditions: &[Condition]) {
+        for condition in conditions {
+            if let Condition::Trigger(name) = condition {
+                self.reset_trigger(name);
+            }
+        }
+    }
+
+    fn find_transition(&self, state: usize, previous_phase: f32, phase: f32) -> Option<usize> {
+        let name = &self.graph.states[state].name;
+        let looping = self.graph.states[state].looping;
+
+        let any_state = self
+            .graph
+            .transitions
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.from.is_none() && t.to != *name);
+        let from_state = self
+            .graph
+            .transitions
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.from.as_ref() == Some(name));
+
+        any_state
+            .chain(from_state)
+            .find(|(_, t)| {
+                t.exit_time
+                    .is_none_or(|exit| exit_reached(exit, looping, previous_phase, phase))
+                    && t.conditions.iter().all(|c| self.condition_holds(c))
+            })
+            .map(|(i, _)| i)
+    }
+}
+
+fn exit_reached(exit_time: f32, looping: bool, previous_phase: f32, phase: f32) -> bool {
+    if !looping || exit_time >= 1.0 {
+        return phase >= exit_time;
+    }
+
+    (previous_phase - exit_time).floor() < (phase - exit_time).floor()
+}
+
+/// Weights of the points of a one-dimensional blend space at `value`. Only the two points around
+/// the value contribute, values outside of the range use the closest point.
+pub fn blend_weights_1d(positions: &[f32], value: f32) -> Vec<f32> {
+    let mut weights = vec![0.0; positions.len()];
+
+    let below = (0..positions.len())
+        .filter(|&i| positions[i] <= value)
+        .max_by(|&a, &b| positions[a].total_cmp(&positions[b]));
+    let above = (0..positions.len())
+        .filter(|&i| positions[i] > value)
+        .min_by(|&a, &b| positions[a].total_cmp(&positions[b]));
+
+    match (below, above) {
+        (Some(lo), Some(hi)) => {
+            let alpha = (value - positions[lo]) / (positions[hi] - positions[lo]);
+            weights[lo] = 1.0 - alpha;
+            weights[hi] = alpha;
+        }
+        (Some(i), None) | (None, Some(i)) => weights[i] = 1.0,
+        (None, None) => {}
+    }
+
+    weights
+}
+
+/// Weights of the points of a two-dimensional blend space at `value`, using gradient band
+/// interpolation. Every point fades out towards each of the other points, so only the points
+/// neighbouring `value` contribute. A point exactly at `value` takes the full weight.
+pub fn blend_weights_2d(positions: &[Vec2], value: Vec2) -> Vec<f32> {
+    let mut weights: Vec<f32> = positions
+        .iter()
+        .map(|&p| {
+            positions
+                .iter()
+                .map(|&other| other - p)
+                .filter(|band| band.length_squared() > f32::EPSILON)
+                .map(|band| 1.0 - (value - p).dot(band) / band.length_squared())
+                .fold(1.0f32, f32::min)
+                .max(0.0)
+        })
+        .collect();
+
+    let sum: f32 = weights.iter().sum();
+    if sum > 0.0 {
+        weights.iter_mut().for_each(|w| *w /= sum);
+    }
+    weights
+}
@@ -0,0 +1,199 @@
+//! Named animation layers that are blended on top of the base animation of an
+//! [`AnimationComponent`](crate::AnimationComponent).
+//!
+//! Every layer plays its own clips. A [`BoneMask`] limits which bones and objects it affects, so
+//! an upper body clip can play while the base animation keeps moving the legs. Additive layers
+//! add the difference between their clip and its reference pose instead of replacing the pose.
+
+use std::collections::HashMap;
+use syrillian::core::{Bones, GameObjectId};
+
+/// How a layer combines with the layers below it.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum LayerBlend {
+    /// Blends towards the pose of the layer by its weight.
+    #[default]
+    Override,
+    /// Adds the offset of the clip from its reference pose, scaled by the weight.
+    Additive,
+}
+
+/// Per-bone weights of a layer, matched by the name of the animated bone or object.
+///
+/// Names that aren't part of the mask aren't affected by the layer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoneMask {
+    weights: HashMap<String, f32>,
+}
+
+impl BoneMask {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a single bone or object with the given weight.
+    pub fn with(mut self, name: impl Into<String>, weight: f32) -> Self {
+        self.weights.insert(name.into(), weight.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Adds the bone `root` and all bones below it in the skeleton.
+    pub fn with_bone_subtree(mut self, bones: &Bones, root: &str) -> Self {
+        let Some(&root) = bones.index_of.get(root) else {
+            return self;
+        };
+
+        let mut stack = vec![root];
+        while let Some(bone) = stack.pop() {
+            self.weights.insert(bones.names[bone].clone(), 1.0);
+            stack.extend(bones.children[bone].iter().copied());
+        }
+        self
+    }
+
+    /// Adds the object `root` and all of its descendants.
+    pub fn with_object_subtree(mut self, root: GameObjectId) -> Self {
+        let mut stack = vec![root];
+        while let Some(obj) = stack.pop() {
+            self.weights.insert(obj.name.clone(), 1.0);
+            stack.extend(obj.children().iter().copied());
+        }
+        self
+    }
+
+    /// Removes a single bone or object again.
+    pub fn without(mut self, name: &str) -> Self {
+        self.weights.remove(name);
+        self
+    }
+
+    pub fn weight(&self, name: &str) -> f32 {
+        self.weights.get(name).copied().unwrap_or(0.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+}
+
+/// A clip playing in a layer, with the weight it's faded in with.
+#[derive(Debug, Clone)]
+pub(crate) struct ActiveClip {
+    pub clip_index: usize,
+    pub time: f32,
+    /// Seconds played in the last update
+    pub delta: f32,
+    pub speed: f32,
+    pub looping: bool,
+    pub weight: f32,
+    pub target_weight: f32,
+    pub fade_rate: f32,
+}
+
+impl ActiveClip {
+    pub fn new_immediate(
+        clip_index: usize,
+        looping: bool,
+        speed: f32,
+        start_weight: f32,
+        target_weight: f32,
+    ) -> Self {
+        Self {
+            clip_index,
+            time: 0.0,
+            delta: 0.0,
+            speed,
+            looping,
+            weight: start_weight.clamp(0.0, 1.0),
+            target_weight: target_weight.clamp(0.0, 1.0),
+            fade_rate: 0.0,
+        }
+    }
+
+    pub fn set_fade_target(&mut self, target_weight: f32, duration: f32) {
+        self.target_weight = target_weight.clamp(0.0, 1.0);
+
+        if duration <= 0.0 {
+            self.weight = self.target_weight;
+            self.fade_rate = 0.0;
+            return;
+        }
+
+        self.fade_rate = (self.target_weight - self.weight) / duration;
+    }
+
+    pub fn step_weight(&mut self, dt: f32) {
+        if self.fade_rate == 0.0 {
+            return;
+        }
+
+        self.weight = (self.weight + self.fade_rate * dt).clamp(0.0, 1.0);
+
+        let reached_target = (self.fade_rate > 0.0 && self.weight >= self.target_weight)
+            || (self.fade_rate < 0.0 && self.weight <= self.target_weight);
+        if reached_target {
+            self.weight = self.target_weight;
+            self.fade_rate = 0.0;
+        }
+    }
+}
+
+/// A named layer of an [`AnimationComponent`](crate::AnimationComponent).
+///
+/// Clips are played on a layer with
+/// [`AnimationComponent::play_on_layer`](crate::AnimationComponent::play_on_layer) and
+/// [`AnimationComponent::crossfade_on_layer`](crate::AnimationComponent::crossfade_on_layer).
+#[derive(Debug, Clone)]
+pub struct AnimationLayer {
+    pub name: String,
+    pub weight: f32,
+    pub blend: LayerBlend,
+    /// Limits the layer to some bones and objects. Without a mask, the layer affects everything.
+    pub mask: Option<BoneMask>,
+    /// Clip whose first frame is subtracted from additive clips. Without one, every additive clip
+    /// is relative to its own first frame.
+    pub reference_clip: Option<String>,
+    pub(crate) clips: Vec<ActiveClip>,
+}
+
+impl AnimationLayer {
+    pub fn new(name: impl Into<String>, blend: LayerBlend) -> Self {
+        Self {
+            name: name.into(),
+            weight: 1.0,
+            blend,
+            mask: None,
+            reference_clip: None,
+            clips: Vec::new(),
+        }
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_mask(mut self, mask: BoneMask) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    pub fn with_reference_clip(mut self, clip: impl Into<String>) -> Self {
+        self.reference_clip = Some(clip.into());
+        self
+    }
+
+    pub fn set_weight(&mut self, weight: f32) {
+        self.weight = weight.clamp(0.0, 1.0);
+    }
+
+    /// How much the layer affects the bone or object called `name`.
+    pub fn mask_weight(&self, name: &str) -> f32 {
+        self.mask.as_ref().map_or(1.0, |mask| mask.weight(name))
+    }
+
+    /// Whether any clip is playing or fading on this layer.
+    pub fn is_playing(&self) -> bool {
+        !self.clips.is_empty()
+    }
+}
@@ -7,6 +7,7 @@ use syrillian::components::Component;
 use syrillian::core::GameObjectId;
 use syrillian::math::Vec3;
 use syrillian::physics::rapier3d::prelude::*;
+use syrillian::physics::{CollisionLayer, PhysicsMaterial};
 use syrillian::tracing::{trace, warn};
 
 use crate::{MeshRenderer, RigidBodyComponent};
@@ -30,6 +31,14 @@ pub struct Collider3D {
     linked_to_body: Option<RigidBodyHandle>,
     shape_kind: ColliderShapeKind,
     last_scale: Vec3,
+    material: PhysicsMaterial,
+    /// Name of the registered material the collider shares
+    material_name: Option<String>,
+    /// Revision of the registered materials the material was taken from
+    materials_revision: Option<u64>,
+    layer: CollisionLayer,
+    /// Revision of the collision layers the collider's groups were made from
+    layers_revision: Option<u64>,
 
     #[cfg(debug_assertions)]
     enable_debug_render: bool, // TODO: Sync with GPU
@@ -70,6 +79,11 @@ impl Default for Collider3D {
             linked_to_body: None,
             shape_kind: ColliderShapeKind::Cuboid,
             last_scale: Vec3::ONE,
+            material: PhysicsMaterial::DEFAULT,
+            material_name: None,
+            materials_revision: None,
+            layer: CollisionLayer::DEFAULT,
+            layers_revision: None,
 
             #[cfg(debug_assertions)]
             enable_debug_render: true,
@@ -90,6 +104,9 @@ impl Component for Collider3D {
         let phys_handle = world.physics.collider_set.insert(collider.clone());
 
         self.phys_handle = Some(phys_handle);
+        self.apply_properties(world);
+        self.sync_material(world);
+        self.sync_layer(world);
         self.sync_with_transform_world(world, true);
     }
 
@@ -106,6 +123,8 @@ impl Component for Collider3D {
             }
         }
 
+        self.sync_material(world);
+        self.sync_layer(world);
         self.sync_with_transform_world(world, false);
     }
 
@@ -225,13 +244,166 @@ impl Collider3D {
     }
 
     fn default_collider(parent: GameObjectId, shape: SharedShape) -> Collider {
+        let material = PhysicsMaterial::DEFAULT;
         ColliderBuilder::new(shape)
-            .density(1.0)
-            .friction(0.999)
+            .density(material.density)
+            .friction(material.friction)
+            .restitution(material.restitution)
             .user_data(parent.as_ffi() as u128)
             .build()
     }
 
+    pub fn material(&self) -> PhysicsMaterial {
+        self.material
+    }
+
+    /// Gives the collider its own copy of `material`, no longer sharing a registered material.
+    pub fn set_material(&mut self, material: PhysicsMaterial) {
+        self.material = material;
+        self.material_name = None;
+        if let Some(collider) = self.collider_mut() {
+            material.apply(collider);
+        }
+    }
+
+    /// Shares the material registered as `name` on the physics simulation. When the material is
+    /// registered again, the collider follows. Returns `false` if there is no such material.
+    pub fn use_material(&mut self, name: &str) -> bool {
+        if self.world().physics.material(name).is_none() {
+            warn!("No physics material \"{name}\" was registered");
+            return false;
+        }
+
+        self.material_name = Some(name.to_string());
+        self.materials_revision = None;
+        self.sync_material(self.world());
+        true
+    }
+
+    /// Name of the registered material the collider shares, if any.
+    pub fn material_name(&self) -> Option<&str> {
+        self.material_name.as_deref()
+    }
+
+    pub fn layer(&self) -> CollisionLayer {
+        self.layer
+    }
+
+    pub fn set_layer(&mut self, layer: CollisionLayer) {
+        self.layer = layer;
+        self.layers_revision = None;
+        self.sync_layer(self.world());
+    }
+
+    /// Moves the collider to the layer called `name`, adding the layer if it doesn't exist yet.
+    /// Returns the layer, or `None` if all layers are used already.
+    pub fn set_layer_name(&mut self, name: &str) -> Option<CollisionLayer> {
+        let Some(layer) = self.world().physics.layers.add(name) else {
+            warn!("Collision layer \"{name}\" can't be added, all layers are used");
+            return None;
+        };
+
+        self.set_layer(layer);
+        Some(layer)
+    }
+
+    fn sync_material(&mut self, world: &mut World) {
+        let Some(name) = &self.material_name else {
+            return;
+        };
+        let materials = &world.physics.materials;
+        if self.materials_revision == Some(materials.revision()) {
+            return;
+        }
+
+        self.materials_revision = Some(materials.revision());
+        let Some(material) = materials.get(name) else {
+            return;
+        };
+        self.material = material;
+
+        if let Some(collider) = world.physics.collider_set.get_mut(self.handle()) {
+            material.apply(collider);
+        }
+    }
+
+    fn sync_layer(&mut self, world: &mut World) {
+        let layers = &world.physics.layers;
+        if self.layers_revision == Some(layers.revision()) {
+            return;
+        }
+
+        let groups = layers.groups(self.layer);
+        self.layers_revision = Some(layers.revision());
+
+        let Some(collider) = world.physics.collider_set.get_mut(self.handle()) else {
+            return;
+        };
+        collider.set_collision_groups(groups);
+        collider.set_solver_groups(groups);
+    }
+
+    /// Reads the material and layer from the properties of the object, which are filled from
+    /// the extras of glTF nodes:
+    ///
+    /// - `physics_material`: name of a registered material, shared unless a property overrides it
+    /// - `friction`, `restitution`, `density`: numbers overriding the material
+    /// - `friction_combine`, `restitution_combine`: `"average"`, `"min"`, `"multiply"` or `"max"`
+    /// - `collision_layer`: name of the layer, added if it doesn't exist yet
+    fn apply_properties(&mut self, world: &mut World) {
+        let parent = self.parent();
+        let number = |key: &str| {
+            parent
+                .property(key)
+                .and_then(|v| v.as_f64())
+                .map(|v| v as f32)
+        };
+        let text = |key: &str| parent.property(key).and_then(|v| v.as_str());
+
+        let registered = text("physics_material").and_then(|name| {
+            let material = world.physics.material(name);
+            if material.is_none() {
+                warn!("No physics material \"{name}\" was registered");
+            }
+            Some((name, material?))
+        });
+
+        let mut material = registered.map_or(self.material, |(_, registered)| registered);
+        if let Some(friction) = number("friction") {
+            material.friction = friction;
+        }
+        if let Some(restitution) = number("restitution") {
+            material.restitution = restitution;
+        }
+        if let Some(density) = number("density") {
+            material.density = density;
+        }
+        if let Some(rule) = text("friction_combine").and_then(PhysicsMaterial::parse_combine_rule) {
+            material.friction_combine = rule;
+        }
+        if let Some(rule) =
+            text("restitution_combine").and_then(PhysicsMaterial::parse_combine_rule)
+        {
+            material.restitution_combine = rule;
+        }
+
+        match registered {
+            Some((name, registered)) if material == registered => {
+                self.material_name = Some(name.to_string());
+                self.materials_revision = None;
+            }
+            _ if material != self.material => self.set_material(material),
+            _ => {}
+        }
+
+        if let Some(name) = text("collision_layer") {
+            match world.physics.layers.add(name) {
+                Some(layer) => self.layer = layer,
+                None => warn!("Collision layer \"{name}\" can't be added, all layers are used"),
+            }
+        }
+    }
+
     pub fn link_to_rigid_body(&mut self, world: &mut World, h_body: Option<RigidBodyHandle>) {
         world.physics.collider_set.set_parent(
             self.handle(),
@@ -0,0 +1,655 @@
+//! Inverse kinematics for skeletal meshes.
+//!
+//! An [`IkComponent`] next to a [`SkeletalComponent`] adjusts the pose after the
+//! [`AnimationComponent`](crate::AnimationComponent) evaluated it: two-bone chains for arms and
+//! legs, aim constraints for heads and weapons, and longer chains solved with FABRIK or CCD.
+//! Constraints are solved in the order they were added, each blended in by its own weight.
+//!
+//! Weights blend from the pose the bones had before the solve. Bones that no animation wrote
+//! since the last frame are put back into that pose first, so a partial weight doesn't pile up
+//! on last frame's result.
+//!
+//! ```no_run
+//! use syrillian::core::GameObjectId;
+//! use syrillian_components::ik::{AimConstraint, FootPlacement, IkComponent, IkTarget, TwoBoneIk};
+//!
+//! fn setup(mut character: GameObjectId, enemy: GameObjectId) {
+//!     let mut ik = character.add_component::<IkComponent>();
+//!     ik.add_constraint(TwoBoneIk::new(
+//!         "LeftUpLeg",
+//!         "LeftLeg",
+//!         "LeftFoot",
+//!         IkTarget::Ground(FootPlacement::default()),
+//!     ));
+//!     ik.add_constraint(AimConstraint::new("Head", enemy).with_max_angle(1.2));
+//! }
+//! ```
+
+use crate::{RigidBodyComponent, SkeletalComponent};
+use syrillian::Reflect;
+use syrillian::World;
+use syrillian::components::Component;
+use syrillian::core::{Bones, GameObjectId};
+use syrillian::math::{Mat4, Quat, Vec3};
+use syrillian::physics::QueryFilter;
+use syrillian::physics::rapier3d::geometry::Ray;
+use syrillian::physics::rapier3d::prelude::RigidBodyHandle;
+
+/// Where a constraint wants its bone to go.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IkTarget {
+    /// A fixed point in world space.
+    Position(Vec3),
+    /// Follows an object.
+    Object(GameObjectId),
+    /// The ground below where the bone is animated, found by a raycast.
+    Ground(FootPlacement),
+}
+
+/// Puts a foot on the ground below it.
+///
+/// The ground is searched straight down below the animated foot. The foot keeps the height it is
+/// animated at above the origin of the object, so lifted feet stay lifted on slopes and stairs.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FootPlacement {
+    /// How far above the origin of the object the ground is searched from.
+    pub ray_height: f32,
+    /// How far below the origin of the object a foot may reach down.
+    pub max_drop: f32,
+}
+
+impl Default for FootPlacement {
+    fn default() -> Self {
+        Self {
+            ray_height: 0.5,
+            max_drop: 0.5,
+        }
+    }
+}
+
+impl From<Vec3> for IkTarget {
+    fn from(position: Vec3) -> Self {
+        IkTarget::Position(position)
+    }
+}
+
+impl From<GameObjectId> for IkTarget {
+    fn from(object: GameObjectId) -> Self {
+        IkTarget::Object(object)
+    }
+}
+
+impl IkTarget {
+    /// World position of the target for a bone animated at `effector`. `None` if the target
+    /// object is gone or no ground was found.
+    fn resolve(&self, world: &World, owner: GameObjectId, effector: Vec3) -> Option<Vec3> {
+        match self {
+            IkTarget::Position(position) => Some(*position),
+            IkTarget::Object(object) => object.exists().then(|| object.transform.position()),
+            IkTarget::Ground(placement) => {
+                let base = owner.transform.position();
+                let lift = effector.y - base.y;
+                let origin = Vec3::new(effector.x, base.y + placement.ray_height, effector.z);
+
+                let mut filter = QueryFilter::new().exclude_sensors();
+                if let Some(handle) = character_body(owner) {
+                    filter = filter.exclude_rigid_body(handle);
+                }
+
+                let ray = Ray::new(origin, Vec3::NEG_Y);
+                let max_toi = placement.ray_height + placement.max_drop;
+                let (toi, _) = world.physics.cast_ray(&ray, max_toi, true, filter)?;
+                Some(Vec3::new(effector.x, origin.y - toi + lift, effector.z))
+            }
+        }
+    }
+}
+
+/// Solves a chain of two bones, like an arm or a leg, so the end of the chain reaches the target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TwoBoneIk {
+    /// Upper bone, e.g. the thigh
+    pub root: String,
+    /// Middle bone, e.g. the shin
+    pub mid: String,
+    /// Bone that reaches for the target, e.g. the foot
+    pub end: String,
+    pub target: IkTarget,
+    /// Point the middle joint bends towards. Without one, the chain keeps bending the way it's
+    /// animated.
+    pub pole: Option<IkTarget>,
+    pub weight: f32,
+}
+
+impl TwoBoneIk {
+    pub fn new(
+        root: impl Into<String>,
+        mid: impl Into<String>,
+        end: impl Into<String>,
+        target: impl Into<IkTarget>,
+    ) -> Self {
+        Self {
+            root: root.into(),
+            mid: mid.into(),
+            end: end.into(),
+            target: target.into(),
+            pole: None,
+            weight: 1.0,
+        }
+    }
+
+    pub fn with_pole(mut self, pole: impl Into<IkTarget>) -> Self {
+        self.pole = Some(pole.into());
+        self
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Turns a bone to look at a target, like a head following an enemy or a hand aiming a weapon.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AimConstraint {
+    pub bone: String,
+    pub target: IkTarget,
+    /// Axis of the bone that should point at the target, in the space of the bone
+    pub forward: Vec3,
+    /// Largest angle in radians the bone is turned away from its animated direction
+    pub max_angle: f32,
+    pub weight: f32,
+}
+
+impl AimConstraint {
+    pub fn new(bone: impl Into<String>, target: impl Into<IkTarget>) -> Self {
+        Self {
+            bone: bone.into(),
+            target: target.into(),
+            forward: Vec3::Z,
+            max_angle: std::f32::consts::PI,
+            weight: 1.0,
+        }
+    }
+
+    pub fn with_forward(mut self, forward: Vec3) -> Self {
+        self.forward = forward.normalize_or(Vec3::Z);
+        self
+    }
+
+    pub fn with_max_angle(mut self, max_angle: f32) -> Self {
+        self.max_angle = max_angle.max(0.0);
+        self
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Algorithm used to solve a [`ChainIk`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ChainSolver {
+    /// Forward and backward reaching. Converges fast and bends the chain evenly.
+    #[default]
+    Fabrik,
+    /// Cyclic coordinate descent. Bends the bones close to the end more, like a tail or tentacle.
+    Ccd,
+}
+
+/// Solves a chain of any length, so its last bone reaches the target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainIk {
+    /// Bones from the root of the chain to its tip
+    pub bones: Vec<String>,
+    pub target: IkTarget,
+    pub solver: ChainSolver,
+    pub iterations: usize,
+    /// Distance to the target at which the chain counts as solved
+    pub tolerance: f32,
+    pub weight: f32,
+}
+
+impl ChainIk {
+    pub fn new(
+        bones: impl IntoIterator<Item = impl Into<String>>,
+        target: impl Into<IkTarget>,
+        solver: ChainSolver,
+    ) -> Self {
+        Self {
+            bones: bones.into_iter().map(Into::into).collect(),
+            target: target.into(),
+            solver,
+            iterations: 10,
+            tolerance: 1e-3,
+            weight: 1.0,
+        }
+    }
+
+    /// Chain of all bones from `root` down to `tip`. `None` if `tip` isn't below `root`.
+    pub fn between(
+        bones: &Bones,
+        root: &str,
+        tip: &str,
+        target: impl Into<IkTarget>,
+        solver: ChainSolver,
+    ) -> Option<Self> {
+        let root = *bones.index_of.get(root)?;
+        let mut bone = *bones.index_of.get(tip)?;
+
+        let mut chain = vec![bone];
+        while bone != root {
+            bone = bones.parents[bone]?;
+            chain.push(bone);
+        }
+        chain.reverse();
+
+        let names = chain.into_iter().map(|bone| bones.names[bone].clone());
+        Some(Self::new(names, target, solver))
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance.max(0.0);
+        self
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IkConstraint {
+    TwoBone(TwoBoneIk),
+    Aim(AimConstraint),
+    Chain(ChainIk),
+}
+
+impl From<TwoBoneIk> for IkConstraint {
+    fn from(constraint: TwoBoneIk) -> Self {
+        IkConstraint::TwoBone(constraint)
+    }
+}
+
+impl From<AimConstraint> for IkConstraint {
+    fn from(constraint: AimConstraint) -> Self {
+        IkConstraint::Aim(constraint)
+    }
+}
+
+impl From<ChainIk> for IkConstraint {
+    fn from(constraint: ChainIk) -> Self {
+        IkConstraint::Chain(constraint)
+    }
+}
+
+impl IkConstraint {
+    pub fn weight(&self) -> f32 {
+        match self {
+            IkConstraint::TwoBone(c) => c.weight,
+            IkConstraint::Aim(c) => c.weight,
+            IkConstraint::Chain(c) => c.weight,
+        }
+    }
+
+    pub fn set_weight(&mut self, weight: f32) {
+        let weight = weight.clamp(0.0, 1.0);
+        match self {
+            IkConstraint::TwoBone(c) => c.weight = weight,
+            IkConstraint::Aim(c) => c.weight = weight,
+            IkConstraint::Chain(c) => c.weight = weight,
+        }
+    }
+
+    fn solve(&self, world: &World, owner: GameObjectId, skel: &mut SkeletalComponent) {
+        let weight = self.weight();
+        if weight <= 0.0 {
+            return;
+        }
+
+        let to_world = owner.transform.affine();
+        let to_model = to_world.inverse();
+        let target = |target: &IkTarget, effector: Vec3| {
+            let effector = to_world.transform_point3(effector);
+            let target = target.resolve(world, owner, effector)?;
+            Some(to_model.transform_point3(target))
+        };
+
+        match self {
+            IkConstraint::TwoBone(c) => {
+                let Some(chain) = bone_indices(skel.bones(), [&c.root, &c.mid, &c.end]) else {
+                    return;
+                };
+                let globals = skel.global_pose();
+                let current = chain.map(|bone| bone_position(&globals[bone]));
+                let Some(goal) = target(&c.target, current[2]) else {
+                    return;
+                };
+                let pole = c.pole.as_ref().and_then(|pole| target(pole, current[1]));
+
+                let solved = solve_two_bone(current, goal, pole);
+                apply_chain(skel, &chain, &blend_positions(&current, &solved, weight));
+            }
+            IkConstraint::Aim(c) => {
+                let Some([bone]) = bone_indices(skel.bones(), [&c.bone]) else {
+                    return;
+                };
+                let globals = skel.global_pose();
+                let position = bone_position(&globals[bone]);
+                let Some(goal) = target(&c.target, position) else {
+                    return;
+                };
+
+                let forward = bone_rotation(&globals[bone]) * c.forward;
+                let Some(desired) = (goal - position).try_normalize() else {
+                    return;
+                };
+                let rotation = Quat::from_rotation_arc(forward.normalize(), desired);
+                let (axis, angle) = rotation.to_axis_angle();
+                let rotation = Quat::from_axis_angle(axis, angle.min(c.max_angle));
+                rotate_bone(skel, &globals, bone, rotation, weight);
+            }
+            IkConstraint::Chain(c) => {
+                let Some(chain) = c
+                    .bones
+                    .iter()
+                    .map(|name| skel.bones().index_of.get(name).copied())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return;
+                };
+                if chain.len() < 2 {
+                    return;
+                }
+
+                let globals = skel.global_pose();
+                let current: Vec<Vec3> = chain
+                    .iter()
+                    .map(|&bone| bone_position(&globals[bone]))
+                    .collect();
+                let Some(goal) = target(&c.target, current[current.len() - 1]) else {
+                    return;
+                };
+
+                let mut solved = current.clone();
+                match c.solver {
+                    ChainSolver::Fabrik => {
+                        solve_fabrik(&mut solved, goal, c.iterations, c.tolerance)
+                    }
+                    ChainSolver::Ccd => solve_ccd(&mut solved, goal, c.iterations, c.tolerance),
+                }
+                apply_chain(skel, &chain, &blend_positions(&current, &solved, weight));
+            }
+        }
+    }
+}
+
+/// Adjusts the pose of the [`SkeletalComponent`] on the same object after it was animated.
+#[derive(Debug, Default, Reflect)]
+pub struct IkComponent {
+    #[dont_reflect]
+    constraints: Vec<IkConstraint>,
+    // Local rotation of every bone before and after the last solve
+    #[dont_reflect]
+    last_solve: Vec<(Quat, Quat)>,
+}
+
+impl Component for IkComponent {
+    // after the animation component wrote the pose in update
+    fn post_update(&mut self, world: &mut World) {
+        if self.constraints.is_empty() && self.last_solve.is_empty() {
+            return;
+        }
+
+        let owner = self.parent();
+        let Some(mut skel) = owner.get_component::<SkeletalComponent>() else {
+            return;
+        };
+        if skel.bone_count() == 0 {
+            return;
+        }
+
+        self.restore_unanimated(&mut skel);
+        if self.constraints.is_empty() {
+            self.last_solve.clear();
+            return;
+        }
+
+        let animated: Vec<Quat> = (0..skel.bone_count())
+            .map(|bone| skel.local_rotation(bone))
+            .collect();
+        for constraint in &self.constraints {
+            constraint.solve(world, owner, &mut skel);
+        }
+        self.last_solve = animated
+            .into_iter()
+            .enumerate()
+            .map(|(bone, animated)| (animated, skel.local_rotation(bone)))
+            .collect();
+    }
+}
+
+impl IkComponent {
+    /// Adds a constraint that's solved after all existing ones and returns its index.
+    pub fn add_constraint(&mut self, constraint: impl Into<IkConstraint>) -> usize {
+        self.constraints.push(constraint.into());
+        self.constraints.len() - 1
+    }
+
+    pub fn remove_constraint(&mut self, index: usize) -> Option<IkConstraint> {
+        (index < self.constraints.len()).then(|| self.constraints.remove(index))
+    }
+
+    pub fn constraints(&self) -> &[IkConstraint] {
+        &self.constraints
+    }
+
+    pub fn constraint_mut(&mut self, index: usize) -> Option<&mut IkConstraint> {
+        self.constraints.get_mut(index)
+    }
+
+    pub fn set_weight(&mut self, index: usize, weight: f32) {
+        if let Some(constraint) = self.constraints.get_mut(index) {
+            constraint.set_weight(weight);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.constraints.clear();
+    }
+
+    /// Puts bones that still hold the result of the last solve back into the pose they had
+    /// before it. Bones an animation wrote since then are left alone.
+    fn restore_unanimated(&self, skel: &mut SkeletalComponent) {
+        if self.last_solve.len() != skel.bone_count() {
+            return;
+        }
+
+        for (bone, &(animated, solved)) in self.last_solve.iter().enumerate() {
+            if animated != solved && skel.local_rotation(bone) == solved {
+                skel.set_local_rotation(bone, animated);
+            }
+        }
+    }
+}
+
+/// Positions of a two-bone chain (e.g. shoulder, elbow and hand) that put its end on `target`,
+/// with the middle joint bending towards `pole`. Without a pole, the chain keeps bending in its
+/// current plane. Targets out of reach stretch the chain towards them.
+pub fn solve_two_bone(chain: [Vec3; 3], target: Vec3, pole: Option<Vec3>) -> [Vec3; 3] {
+    let [root, mid, end] = chain;
+    let upper = root.distance(mid);
+    let lower = mid.distance(end);
+    let Some(dir) = (target - root).try_normalize() else {
+        return chain;
+    };
+    if upper <= f32::EPSILON || lower <= f32::EPSILON {
+        return chain;
+    }
+
+    let reach = root
+        .distance(target)
+        .min(upper + lower)
+        .max((upper - lower).abs());
+
+    let bend_towards = pole.unwrap_or(mid) - root;
+    let bend = (bend_towards - dir * bend_towards.dot(dir))
+        .try_normalize()
+        .unwrap_or_else(|| dir.any_orthonormal_vector());
+
+    let cos =
+        ((upper * upper + reach * reach - lower * lower) / (2.0 * upper * reach)).clamp(-1.0, 1.0);
+    let sin = (1.0 - cos * cos).max(0.0).sqrt();
+
+    [
+        root,
+        root + (dir * cos + bend * sin) * upper,
+        root + dir * reach,
+    ]
+}
+
+/// Moves the joints of a chain so its last joint reaches `target`, keeping the first joint in
+/// place and the bone lengths intact (FABRIK).
+pub fn solve_fabrik(positions: &mut [Vec3], target: Vec3, iterations: usize, tolerance: f32) {
+    let n = positions.len();
+    if n < 2 {
+        return;
+    }
+
+    let lengths: Vec<f32> = positions.windows(2).map(|w| w[0].distance(w[1])).collect();
+    let root = positions[0];
+
+    if root.distance(target) >= lengths.iter().sum() {
+        let dir = (target - root).normalize_or_zero();
+        for i in 1..n {
+            positions[i] = positions[i - 1] + dir * lengths[i - 1];
+        }
+        return;
+    }
+
+    for _ in 0..iterations {
+        if positions[n - 1].distance(target) <= tolerance {
+            break;
+        }
+
+        positions[n - 1] = target;
+        for i in (0..n - 1).rev() {
+            let dir = (positions[i] - positions[i + 1]).normalize_or_zero();
+            positions[i] = positions[i + 1] + dir * lengths[i];
+        }
+
+        positions[0] = root;
+        for i in 1..n {
+            let dir = (positions[i] - positions[i - 1]).normalize_or_zero();
+            positions[i] = positions[i - 1] + dir * lengths[i - 1];
+        }
+    }
+}
+
+/// Rotates the joints of a chain, starting at the end, so its last joint reaches `target`
+/// (cyclic coordinate descent).
+pub fn solve_ccd(positions: &mut [Vec3], target: Vec3, iterations: usize, tolerance: f32) {
+    let n = positions.len();
+    if n < 2 {
+        return;
+    }
+
+    for _ in 0..iterations {
+        if positions[n - 1].distance(target) <= tolerance {
+            break;
+        }
+
+        for joint in (0..n - 1).rev() {
+            let pivot = positions[joint];
+            let (Some(from), Some(to)) = (
+                (positions[n - 1] - pivot).try_normalize(),
+                (target - pivot).try_normalize(),
+            ) else {
+                continue;
+            };
+
+            let rotation = Quat::from_rotation_arc(from, to);
+            for position in &mut positions[joint + 1..] {
+                *position = pivot + rotation * (*position - pivot);
+            }
+        }
+    }
+}
+
+fn bone_indices<const N: usize>(bones: &Bones, names: [&String; N]) -> Option<[usize; N]> {
+    let mut indices = [0; N];
+    for (index, name) in indices.iter_mut().zip(names) {
+        *index = *bones.index_of.get(name)?;
+    }
+    Some(indices)
+}
+
+fn bone_position(global: &Mat4) -> Vec3 {
+    global.w_axis.truncate()
+}
+
+fn bone_rotation(global: &Mat4) -> Quat {
+    global.to_scale_rotation_translation().1
+}
+
+fn blend_positions(current: &[Vec3], solved: &[Vec3], weight: f32) -> Vec<Vec3> {
+    current
+        .iter()
+        .zip(solved)
+        .map(|(current, solved)| current.lerp(*solved, weight))
+        .collect()
+}
+
+/// Rotates every bone of a chain so it points at the solved position of the next one.
+fn apply_chain(skel: &mut SkeletalComponent, chain: &[usize], solved: &[Vec3]) {
+    for (i, pair) in chain.windows(2).enumerate() {
+        let globals = skel.global_pose();
+        let from = bone_position(&globals[pair[0]]);
+        let to = bone_position(&globals[pair[1]]);
+
+        let (Some(current), Some(desired)) = (
+            (to - from).try_normalize(),
+            (solved[i + 1] - from).try_normalize(),
+        ) else {
+            continue;
+        };
+        let rotation = Quat::from_rotation_arc(current, desired);
+        rotate_bone(skel, &globals, pair[0], rotation, 1.0);
+    }
+}
+
+/// Applies a model space rotation to a bone, blended in by `weight`.
+fn rotate_bone(
+    skel: &mut SkeletalComponent,
+    globals: &[Mat4],
+    bone: usize,
+    rotation: Quat,
+    weight: f32,
+) {
+    let parent = skel.bones().parents[bone].map_or(Quat::IDENTITY, |p| bone_rotation(&globals[p]));
+    let rotated = (parent.inverse() * rotation * bone_rotation(&globals[bone])).normalize();
+    let local = skel.local_rotation(bone);
+    skel.set_local_rotation(bone, local.slerp(rotated, weight));
+}
+
+/// The rigid body of the character, which shouldn't be hit by its own foot placement rays.
+fn character_body(owner: GameObjectId) -> Option<RigidBodyHandle> {
+    let mut current = Some(owner);
+    while let Some(obj) = current {
+        if let Some(handle) = obj
+            .get_component::<RigidBodyComponent>()
+            .and_then(|rigid| rigid.handle_opt())
+        {
+            return Some(handle);
+        }
+        current = *obj.parent();
+    }
+    None
+}
@@ -0,0 +1,148 @@
+use syrillian::assets::{HMaterialInstance, HMesh};
+use syrillian::components::Component;
+use syrillian::math::Mat4;
+use syrillian::tracing::warn;
+use syrillian::{Reflect, World};
+use syrillian_render::proxies::{InstancedMeshProxy, SceneProxy};
+use syrillian_render::proxy_data_mut;
+use syrillian_render::rendering::CPUDrawCtx;
+
+/// Renders one mesh at many transforms relative to its object, e.g. for scattering grass or
+/// rocks. All instances are drawn together in one instanced draw per material slot.
+///
+/// The instances are culled and picked as a whole, together with their object.
+#[derive(Debug, Reflect)]
+pub struct InstancedMeshRenderer {
+    mesh: HMesh,
+    materials: Vec<HMaterialInstance>,
+    instances: Vec<Mat4>,
+    dirty_mesh: bool,
+    dirty_materials: bool,
+    dirty_instances: bool,
+}
+
+impl Default for InstancedMeshRenderer {
+    fn default() -> Self {
+        InstancedMeshRenderer {
+            mesh: HMesh::UNIT_CUBE,
+            materials: vec![],
+            instances: vec![],
+            dirty_mesh: false,
+            dirty_materials: false,
+            dirty_instances: false,
+        }
+    }
+}
+
+impl Component for InstancedMeshRenderer {
+    fn create_render_proxy(&mut self, world: &World) -> Option<Box<dyn SceneProxy>> {
+        let Some(mesh) = world.assets.meshes.try_get(self.mesh) else {
+            warn!(
+                "Instanced Mesh Renderer couldn't create its proxy because the mesh wasn't found in the asset store"
+            );
+            return None;
+        };
+
+        self.dirty_mesh = false;
+        self.dirty_materials = false;
+        self.dirty_instances = false;
+
+        let mut proxy =
+            InstancedMeshProxy::new(self.mesh, self.materials.clone(), self.instances.clone());
+        proxy.material_ranges = mesh.material_ranges.clone();
+        proxy.mesh_bounds = Some(mesh.bounding_sphere);
+
+        Some(Box::new(proxy))
+    }
+
+    fn update_proxy(&mut self, world: &World, mut ctx: CPUDrawCtx) {
+        if self.dirty_instances {
+            let instances = self.instances.clone();
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut InstancedMeshProxy = proxy_data_mut!(sc);
+                data.set_instances(instances);
+            });
+            self.dirty_instances = false;
+        }
+
+        if !self.dirty_mesh && !self.dirty_materials {
+            return;
+        }
+
+        let Some(mesh) = world.assets.meshes.try_get(self.mesh) else {
+            warn!(
+                "Instanced Mesh Renderer couldn't update its proxy because the mesh wasn't found in the asset store"
+            );
+            return;
+        };
+
+        let h_mesh = self.mesh;
+        let bounds = mesh.bounding_sphere;
+        let materials = self.materials.clone();
+        let material_ranges = mesh.material_ranges.clone();
+        ctx.send_proxy_update(move |sc| {
+            let data: &mut InstancedMeshProxy = proxy_data_mut!(sc);
+            data.mesh = h_mesh;
+            data.mesh_bounds = Some(bounds);
+            data.materials = materials;
+            data.material_ranges = material_ranges;
+        });
+
+        self.dirty_mesh = false;
+        self.dirty_materials = false;
+    }
+}
+
+impl InstancedMeshRenderer {
+    pub fn set_mesh(&mut self, mesh: HMesh) {
+        self.mesh = mesh;
+        self.dirty_mesh = true;
+    }
+
+    pub fn mesh(&self) -> HMesh {
+        self.mesh
+    }
+
+    pub fn set_materials(&mut self, materials: Vec<HMaterialInstance>) {
+        self.materials = materials;
+        self.dirty_materials = true;
+    }
+
+    pub fn set_material_slot(&mut self, idx: usize, material: HMaterialInstance) {
+        let size = idx + 1;
+        if self.materials.len() < size {
+            self.materials.resize(size, HMaterialInstance::FALLBACK);
+        }
+        self.materials[idx] = material;
+        self.dirty_materials = true;
+    }
+
+    /// Replaces all instances. Transforms are relative to the object of this renderer.
+    pub fn set_instances(&mut self, instances: impl IntoIterator<Item = Mat4>) {
+        self.instances.clear();
+        self.instances.extend(instances);
+        self.dirty_instances = true;
+    }
+
+    /// Adds an instance, relative to the object of this renderer.
+    pub fn add_instance(&mut self, transform: Mat4) {
+        self.instances.push(transform);
+        self.dirty_instances = true;
+    }
+
+    pub fn clear_instances(&mut self) {
+        if self.instances.is_empty() {
+            return;
+        }
+        self.instances.clear();
+        self.dirty_instances = true;
+    }
+
+    pub fn instances(&self) -> &[Mat4] {
+        &self.instances
+    }
+
+    pub fn instance_count(&self) -> usize {
+        self.instances.len()
+    }
+}
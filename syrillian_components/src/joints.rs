@@ -71,7 +71,12 @@ pub struct PrismaticConfig {
 
 #[derive(Clone, Default, Reflect)]
 #[reflect_all]
-pub struct SphericalConfig;
+pub struct SphericalConfig {
+    /// Limits of the rotation around the X axis of the joint frames
+    pub twist_limits: Option<[f32; 2]>,
+    /// Limits of the rotation around the Y and Z axes of the joint frames
+    pub swing_limits: Option<[f32; 2]>,
+}
 
 #[derive(Clone, Reflect)]
 #[reflect_all]
@@ -182,12 +187,19 @@ impl JointTypeTrait for Spherical {
     const NAME: &str = "SphericalJoint";
     const FULL_NAME: &str = concat!(module_path!(), "::", "SphericalJoint");
 
-    fn build(_: &Self::Config, anchor1: Vec3, anchor2: Vec3) -> GenericJoint {
-        SphericalJointBuilder::new()
+    fn build(config: &Self::Config, anchor1: Vec3, anchor2: Vec3) -> GenericJoint {
+        let mut b = SphericalJointBuilder::new()
             .local_anchor1(anchor1)
-            .local_anchor2(anchor2)
-            .build()
-            .into()
+            .local_anchor2(anchor2);
+
+        if let Some(lim) = config.twist_limits {
+            b = b.limits(JointAxis::AngX, lim);
+        }
+        if let Some(lim) = config.swing_limits {
+            b = b.limits(JointAxis::AngY, lim).limits(JointAxis::AngZ, lim);
+        }
+
+        b.build().into()
     }
 }
 
@@ -617,6 +629,23 @@ impl JointComponent<Revolute> {
     }
 }
 
+impl JointComponent<Spherical> {
+    pub fn set_twist_limits(&mut self, min: f32, max: f32) {
+        self.config.twist_limits = Some([min, max]);
+        if let Some(s) = self.joint_data_mut().and_then(|j| j.as_spherical_mut()) {
+            s.set_limits(JointAxis::AngX, [min, max]);
+        }
+    }
+
+    pub fn set_swing_limits(&mut self, min: f32, max: f32) {
+        self.config.swing_limits = Some([min, max]);
+        if let Some(s) = self.joint_data_mut().and_then(|j| j.as_spherical_mut()) {
+            s.set_limits(JointAxis::AngY, [min, max]);
+            s.set_limits(JointAxis::AngZ, [min, max]);
+        }
+    }
+}
+
 impl JointComponent<Prismatic> {
     pub fn set_axis(&mut self, axis: Vec3) {
         self.config.axis = axis;
@@ -35,6 +35,8 @@
 //! ```
 
 pub mod animation;
+pub mod animation_graph;
+pub mod animation_layer;
 pub mod audio;
 pub mod button;
 pub mod collider;
@@ -43,15 +45,22 @@ pub mod fp_camera;
 pub mod fp_movement;
 pub mod freecam;
 pub mod gravity;
+pub mod ik;
+pub mod instanced_mesh_renderer;
 pub mod joints;
 pub mod light;
 pub mod mesh_renderer;
 pub mod particle_system;
+pub mod ragdoll;
 pub mod rigid_body;
+pub mod root_motion;
 pub mod rotate;
 pub mod skeletal;
+pub mod sprite;
+pub mod sprite_animator;
 pub mod text;
 pub mod third_person_controller;
+pub mod vehicle;
 
 pub mod extensions;
 pub mod prefabs;
@@ -67,6 +76,8 @@ pub use fp_camera::FirstPersonCameraController;
 pub use fp_movement::FirstPersonMovementController;
 pub use freecam::FreecamController;
 pub use gravity::GravityComponent;
+pub use ik::IkComponent;
+pub use instanced_mesh_renderer::InstancedMeshRenderer;
 pub use joints::{
     FixedJoint, PrismaticJoint, RevoluteJoint, RopeJoint, SphericalJoint, SpringJoint,
 };
@@ -74,8 +85,12 @@ pub use light::{PointLightComponent, SpotLightComponent, SunLightComponent};
 pub use mesh_renderer::MeshRenderer;
 pub use particle_system::ParticleSystemComponent;
 pub use profiler::Profiler;
+pub use ragdoll::RagdollComponent;
 pub use rigid_body::RigidBodyComponent;
 pub use rotate::RotateComponent;
 pub use skeletal::SkeletalComponent;
+pub use sprite::{SortingLayer, Sprite, SpriteFrame, SpriteRenderer, SpriteSheet};
+pub use sprite_animator::{SpriteAnimation, SpriteAnimator};
 pub use text::Text3D;
 pub use third_person_controller::ThirdPersonCharacterController;
+pub use vehicle::Vehicle;
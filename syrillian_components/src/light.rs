@@ -9,6 +9,7 @@ use syrillian::core::reflection::{
 };
 use syrillian::math::{Vec3, vec3};
 use syrillian::utils::FloatMathExt;
+use syrillian_render::lighting::cascades::MAX_SHADOW_CASCADES;
 use syrillian_render::lighting::proxy::{LightProxy, LightType};
 use syrillian_render::rendering::CPUDrawCtx;
 
@@ -151,6 +152,7 @@ impl LightTypeTrait for Sun {
         *outer_angle = 1.0;
         light.intensity = 1.0;
         light.color = vec3(1.0, 0.95, 0.72);
+        light.shadow_cascades = 4;
         light.inner_angle = *inner_angle;
         light.outer_angle = *outer_angle;
         light.cos_inner = light.inner_angle.min(light.outer_angle).cos();
@@ -324,3 +326,34 @@ impl<L: LightTypeTrait + 'static> LightComponent<L> {
         self.target_inner_angle = rad;
     }
 }
+
+impl LightComponent<Sun> {
+    /// Sets the number of shadow cascades, up to 4. 0 turns the sun's shadows off.
+    pub fn set_shadow_cascades(&mut self, cascades: u32) {
+        self.data_mut(true).shadow_cascades = cascades.min(MAX_SHADOW_CASCADES);
+    }
+
+    /// Sets how the view is split into cascades, from evenly (0) to logarithmically (1).
+    /// Logarithmic splits give shadows close to the camera more detail.
+    pub fn set_cascade_split_lambda(&mut self, lambda: f32) {
+        self.data_mut(true).cascade_split_lambda = lambda.clamp(0.0, 1.0);
+    }
+
+    /// Sets the view distance up to which the sun casts shadows.
+    pub fn set_shadow_distance(&mut self, distance: f32) {
+        self.data_mut(true).shadow_distance = distance.max(0.1);
+    }
+
+    /// Sets how much of a cascade's border is blended into the next cascade, from 0 to 1.
+    pub fn set_cascade_blend(&mut self, blend: f32) {
+        self.data_mut(true).cascade_blend = blend.clamp(0.0, 1.0);
+    }
+
+    pub fn shadow_cascades(&self) -> u32 {
+        self.data().shadow_cascades
+    }
+
+    pub fn shadow_distance(&self) -> f32 {
+        self.data().shadow_distance
+    }
+}
@@ -5,7 +5,7 @@ use syrillian::core::{BoneData, Vertex3D};
 use syrillian::math::Vec3;
 use syrillian::tracing::warn;
 use syrillian::{Reflect, World};
-use syrillian_render::proxies::{MeshSceneProxy, SceneProxy};
+use syrillian_render::proxies::{MeshLodState, MeshSceneProxy, SceneProxy};
 use syrillian_render::proxy_data_mut;
 use syrillian_render::rendering::CPUDrawCtx;
 
@@ -20,8 +20,12 @@ pub struct DebugVertexNormal {
 pub struct MeshRenderer {
     mesh: HMesh,
     materials: Vec<HMaterialInstance>,
+    lod_bias: f32,
+    morph_weights: Vec<f32>,
     dirty_mesh: bool,
     dirty_materials: bool,
+    dirty_lod: bool,
+    dirty_morph_weights: bool,
 }
 
 impl Default for MeshRenderer {
@@ -30,8 +34,12 @@ impl Default for MeshRenderer {
         MeshRenderer {
             mesh: HMesh::UNIT_CUBE,
             materials: vec![],
+            lod_bias: 1.0,
+            morph_weights: vec![],
             dirty_mesh: false,
             dirty_materials: false,
+            dirty_lod: false,
+            dirty_morph_weights: false,
         }
     }
 }
@@ -45,6 +53,11 @@ impl Component for MeshRenderer {
             return None;
         };
 
+        if self.morph_weights.is_empty() {
+            self.morph_weights = mesh.morph_weights.clone();
+        }
+        self.dirty_morph_weights = false;
+
         Some(Box::new(MeshSceneProxy {
             mesh: self.mesh,
             materials: self.materials.clone(),
@@ -52,7 +65,10 @@ impl Component for MeshRenderer {
             bone_data: BoneData::new_full_identity(),
             bones_dirty: false,
             skinned: !mesh.bones.is_empty(),
+            morph_weights: self.morph_weights.clone(),
+            morph_dirty: true,
             bounding: mesh.bones.is_empty().then_some(mesh.bounding_sphere),
+            lod: MeshLodState::with_bias(self.lod_bias),
         }))
     }
 
@@ -70,6 +86,25 @@ impl Component for MeshRenderer {
             });
         }
 
+        if self.dirty_lod {
+            let bias = self.lod_bias;
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut MeshSceneProxy = proxy_data_mut!(sc);
+                data.lod.bias = bias;
+            });
+            self.dirty_lod = false;
+        }
+
+        if self.dirty_morph_weights {
+            let weights = self.morph_weights.clone();
+            ctx.send_proxy_update(move |sc| {
+                let data: &mut MeshSceneProxy = proxy_data_mut!(sc);
+                data.morph_weights = weights;
+                data.morph_dirty = true;
+            });
+            self.dirty_morph_weights = false;
+        }
+
         if !self.dirty_mesh && !self.dirty_materials {
             return;
         }
@@ -85,12 +120,19 @@ impl Component for MeshRenderer {
             let h_mesh = self.mesh;
             let bounds = mesh.bounding_sphere;
             let skinned = !mesh.bones.is_empty();
+            if self.morph_weights.is_empty() {
+                self.morph_weights = mesh.morph_weights.clone();
+            }
+            let weights = self.morph_weights.clone();
             ctx.send_proxy_update(move |sc| {
                 let data: &mut MeshSceneProxy = proxy_data_mut!(sc);
                 data.mesh = h_mesh;
                 data.bounding = (!skinned).then_some(bounds);
                 data.skinned = skinned;
                 data.bones_dirty = skinned;
+                data.morph_weights = weights;
+                data.morph_dirty = true;
+                data.lod.reset();
             })
         }
 
@@ -113,8 +155,11 @@ impl MeshRenderer {
         self.set_materials(materials);
     }
 
+    /// Swaps the mesh. Morph weights fall back to the defaults of the new mesh unless they're
+    /// set again afterwards.
     pub fn set_mesh(&mut self, mesh: HMesh) {
         self.mesh = mesh;
+        self.morph_weights.clear();
         self.dirty_mesh = true;
     }
 
@@ -135,6 +180,45 @@ impl MeshRenderer {
     pub fn mesh(&self) -> HMesh {
         self.mesh
     }
+
+    /// Scales how early this renderer switches to simplified mesh levels, on top of the global
+    /// LOD bias. `0.0` always renders the full detail mesh.
+    pub fn set_lod_bias(&mut self, bias: f32) {
+        self.lod_bias = bias.max(0.0);
+        self.dirty_lod = true;
+    }
+
+    pub fn lod_bias(&self) -> f32 {
+        self.lod_bias
+    }
+
+    /// Sets the weight of every morph target of the mesh, in target order.
+    /// Targets without a weight don't deform the mesh.
+    pub fn set_morph_weights(&mut self, weights: &[f32]) {
+        if self.morph_weights == weights {
+            return;
+        }
+        self.morph_weights.clear();
+        self.morph_weights.extend_from_slice(weights);
+        self.dirty_morph_weights = true;
+    }
+
+    pub fn set_morph_weight(&mut self, index: usize, weight: f32) {
+        if self.morph_weights.get(index) == Some(&weight) {
+            return;
+        }
+        if self.morph_weights.len() <= index {
+            self.morph_weights.resize(index + 1, 0.0);
+        }
+        self.morph_weights[index] = weight;
+        self.dirty_morph_weights = true;
+    }
+
+    /// Current morph target weights. These start out as the default weights of the mesh once
+    /// the renderer has been added to the world.
+    pub fn morph_weights(&self) -> &[f32] {
+        &self.morph_weights
+    }
 }
 
 impl From<&Vertex3D> for DebugVertexNormal {
@@ -9,6 +9,11 @@ struct CameraData {
     far: f32,
     fov_target: f32,
     zoom_speed: f32,
+    prev_view_proj_mat: mat4x4<f32>,
+    jitter: vec2<f32>,
+    focus_distance: f32,
+    aperture: f32,
+    focal_length: f32,
 }
 
 struct SystemData {
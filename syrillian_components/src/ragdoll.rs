@@ -0,0 +1,523 @@
+//! Ragdolls built from the skeleton of a [`SkeletalComponent`].
+//!
+//! A [`RagdollComponent`] creates one capsule [`Collider3D`] and [`RigidBodyComponent`] per major
+//! bone and connects them with limited joints. While the character is animated, the bodies are
+//! kinematic and follow the animated pose. Once the simulation starts, the bodies drive the pose
+//! instead, until [`RagdollComponent::blend_to_animation`] hands it back to the animation.
+//!
+//! Ragdoll colliders are sensors while the character is animated, so they don't push its own
+//! character controller around. Disable the controller while the ragdoll is simulated.
+
+use crate::joints::{RevoluteJoint, SphericalJoint};
+use crate::{Collider3D, RigidBodyComponent, SkeletalComponent};
+use syrillian::Reflect;
+use syrillian::World;
+use syrillian::components::Component;
+use syrillian::core::{Bones, GameObjectId};
+use syrillian::math::{Affine3A, Pose, Quat, Vec3};
+use syrillian::physics::rapier3d::prelude::{GenericJoint, SharedShape};
+use syrillian::tracing::warn;
+
+/// How a ragdoll body is connected to the body of the closest bone above it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RagdollJoint {
+    /// Ball joint, for hips, shoulders and the neck. Limits are in radians.
+    Ball { swing: f32, twist: f32 },
+    /// Hinge, for elbows and knees. The axis is in the space of the bone, limits are in radians.
+    Hinge { axis: Vec3, limits: [f32; 2] },
+}
+
+impl Default for RagdollJoint {
+    fn default() -> Self {
+        RagdollJoint::Ball {
+            swing: 45f32.to_radians(),
+            twist: 30f32.to_radians(),
+        }
+    }
+}
+
+/// A bone that gets its own body in the ragdoll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RagdollBone {
+    pub bone: String,
+    /// Bone the capsule reaches to. Without one, it reaches to the first child of the bone.
+    pub tip: Option<String>,
+    pub radius: f32,
+    pub joint: RagdollJoint,
+}
+
+impl RagdollBone {
+    pub fn new(bone: impl Into<String>, radius: f32) -> Self {
+        Self {
+            bone: bone.into(),
+            tip: None,
+            radius,
+            joint: RagdollJoint::default(),
+        }
+    }
+
+    pub fn with_tip(mut self, tip: impl Into<String>) -> Self {
+        self.tip = Some(tip.into());
+        self
+    }
+
+    pub fn with_joint(mut self, joint: RagdollJoint) -> Self {
+        self.joint = joint;
+        self
+    }
+}
+
+/// Describes which bones of a skeleton become bodies of a ragdoll.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RagdollBuilder {
+    pub bones: Vec<RagdollBone>,
+}
+
+impl RagdollBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Picks every bone that is at least `min_length` long in the bind pose, with capsules as
+    /// thick as `radius_ratio` times their length.
+    pub fn from_skeleton(bones: &Bones, min_length: f32, radius_ratio: f32) -> Self {
+        let position = |bone: usize| bones.bind_global[bone].w_axis.truncate();
+
+        let bones = (0..bones.len())
+            .filter_map(|bone| {
+                let (tip, length) = bones.children[bone]
+                    .iter()
+                    .map(|&child| (child, position(bone).distance(position(child))))
+                    .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+                (length >= min_length).then(|| {
+                    RagdollBone::new(bones.names[bone].clone(), length * radius_ratio)
+                        .with_tip(bones.names[tip].clone())
+                })
+            })
+            .collect();
+
+        Self { bones }
+    }
+
+    pub fn with_bone(mut self, bone: RagdollBone) -> Self {
+        self.bones.push(bone);
+        self
+    }
+}
+
+/// Whether the pose comes from the animation or the ragdoll.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum RagdollMode {
+    /// Bodies are kinematic and follow the animated pose.
+    #[default]
+    Animated,
+    /// Bodies are simulated and drive the pose.
+    Simulated,
+    /// The pose blends from where the ragdoll came to rest back to the animation.
+    Blending,
+}
+
+#[derive(Debug, Clone)]
+struct RagdollBody {
+    bone: usize,
+    object: GameObjectId,
+    /// Pose of the bone relative to its body
+    offset: Affine3A,
+    /// Whether a bone above this one has a body too. Only the topmost bodies move their bone,
+    /// all others only rotate it.
+    has_parent_body: bool,
+}
+
+#[derive(Debug, Clone)]
+struct RagdollBlend {
+    /// Local pose of every body's bone when the blend started
+    from: Vec<(Vec3, Quat)>,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Ragdoll of the [`SkeletalComponent`] on the same object. Build it with
+/// [`RagdollComponent::build`] once the skeleton is set up.
+#[derive(Debug, Default, Reflect)]
+pub struct RagdollComponent {
+    #[dont_reflect]
+    bodies: Vec<RagdollBody>,
+    #[dont_reflect]
+    mode: RagdollMode,
+    #[dont_reflect]
+    blend: Option<RagdollBlend>,
+}
+
+impl Component for RagdollComponent {
+    // after the animation and the rigid bodies are done
+    fn post_update(&mut self, world: &mut World) {
+        if self.bodies.is_empty() {
+            return;
+        }
+
+        let Some(mut skel) = self.parent().get_component::<SkeletalComponent>() else {
+            return;
+        };
+
+        match self.mode {
+            RagdollMode::Animated => {}
+            RagdollMode::Simulated => self.pose_from_bodies(&mut skel),
+            RagdollMode::Blending => self.blend_pose(&mut skel, world.delta_time().as_secs_f32()),
+        }
+
+        if self.mode != RagdollMode::Simulated {
+            self.drive_bodies(&skel);
+        }
+    }
+
+    fn delete(&mut self, _world: &mut World) {
+        self.clear();
+    }
+}
+
+impl RagdollComponent {
+    /// Creates the bodies and joints of the ragdoll in the current pose, replacing a ragdoll
+    /// that was built before. Returns the number of bodies.
+    pub fn build(&mut self, builder: &RagdollBuilder) -> usize {
+        self.clear();
+
+        let owner = self.parent();
+        let Some(skel) = owner.get_component::<SkeletalComponent>() else {
+            warn!("RagdollComponent requires a SkeletalComponent");
+            return 0;
+        };
+
+        let bones = skel.bones();
+        let globals = skel.global_pose();
+        let to_world = owner.transform.affine();
+        let bone_world = |bone: usize| to_world * Affine3A::from_mat4(globals[bone]);
+
+        let mut specs: Vec<(usize, &RagdollBone)> = builder
+            .bones
+            .iter()
+            .filter_map(|spec| match bones.index_of.get(&spec.bone) {
+                Some(&bone) => Some((bone, spec)),
+                None => {
+                    warn!("No bone \"{}\" found for the ragdoll", spec.bone);
+                    None
+                }
+            })
+            .collect();
+        specs.sort_by_key(|&(bone, _)| bone_depth(bones, bone));
+
+        for (bone, spec) in specs {
+            let world_pose = bone_world(bone);
+            let (_, bone_rotation, start) = world_pose.to_scale_rotation_translation();
+
+            let tip = spec
+                .tip
+                .as_ref()
+                .and_then(|tip| bones.index_of.get(tip).copied())
+                .or_else(|| bones.children[bone].first().copied())
+                .map(|tip| bone_world(tip).translation.into())
+                .filter(|tip: &Vec3| tip.distance(start) > f32::EPSILON)
+                .unwrap_or(start + bone_rotation * Vec3::Y * spec.radius * 2.0);
+
+            // capsules lie along the Y axis of their body
+            let direction = (tip - start).normalize();
+            let align = Quat::from_rotation_arc(Vec3::Y, bone_rotation.inverse() * direction);
+            let body_rotation = (bone_rotation * align).normalize();
+            let center = (start + tip) * 0.5;
+            let half_height = (start.distance(tip) * 0.5 - spec.radius).max(0.01);
+
+            let mut object = self
+                .world()
+                .new_object(format!("{} Ragdoll {}", owner.name, spec.bone));
+            object.transform.set_local_position_vec(center);
+            object.transform.set_local_rotation(body_rotation);
+
+            let mut rigid = object.add_component::<RigidBodyComponent>();
+            rigid.set_kinematic(true);
+            let collider = object.add_component::<Collider3D>();
+            if let Some(collider) = collider.collider_mut() {
+                collider.set_shape(SharedShape::capsule_y(half_height, spec.radius.max(0.001)));
+                collider.set_sensor(true);
+            }
+
+            let body_pose = Affine3A::from_rotation_translation(body_rotation, center);
+            let parent_body = self.parent_body(bones, bone);
+            if let Some(parent_body) = parent_body {
+                connect(object, parent_body, spec.joint, start, body_rotation, align);
+            }
+
+            self.bodies.push(RagdollBody {
+                bone,
+                object,
+                offset: body_pose.inverse() * world_pose,
+                has_parent_body: parent_body.is_some(),
+            });
+        }
+
+        self.mode = RagdollMode::Animated;
+        self.bodies.len()
+    }
+
+    /// Removes all bodies and joints of the ragdoll.
+    pub fn clear(&mut self) {
+        for mut body in self.bodies.drain(..) {
+            if body.object.exists() {
+                body.object.delete();
+            }
+        }
+        self.mode = RagdollMode::Animated;
+        self.blend = None;
+    }
+
+    pub fn mode(&self) -> RagdollMode {
+        self.mode
+    }
+
+    pub fn is_simulated(&self) -> bool {
+        self.mode == RagdollMode::Simulated
+    }
+
+    /// Objects holding the bodies of the ragdoll.
+    pub fn bodies(&self) -> impl Iterator<Item = GameObjectId> + '_ {
+        self.bodies.iter().map(|body| body.object)
+    }
+
+    /// Object holding the body of the bone called `bone`.
+    pub fn body_of(&self, bone: &str) -> Option<GameObjectId> {
+        let skel = self.parent().get_component::<SkeletalComponent>()?;
+        let bone = skel.bones().index(bone)?;
+        self.bodies
+            .iter()
+            .find(|body| body.bone == bone)
+            .map(|body| body.object)
+    }
+
+    /// World position of the topmost body, usually the hips. Useful to move the character to
+    /// where the ragdoll came to rest before blending back to the animation.
+    pub fn root_position(&self) -> Option<Vec3> {
+        self.bodies
+            .first()
+            .filter(|body| body.object.exists())
+            .map(|body| body.object.transform.position())
+    }
+
+    /// Hands the pose over to the physics simulation. The bodies keep the velocity they had
+    /// while following the animation.
+    pub fn start_simulation(&mut self) {
+        for body in &self.bodies {
+            set_simulated(body.object, true);
+        }
+        self.mode = RagdollMode::Simulated;
+        self.blend = None;
+    }
+
+    /// Blends the pose from the ragdoll back to the animation over `duration` seconds.
+    pub fn blend_to_animation(&mut self, duration: f32) {
+        if self.mode != RagdollMode::Simulated {
+            return;
+        }
+
+        for body in &self.bodies {
+            set_simulated(body.object, false);
+        }
+
+        let Some(skel) = self.parent().get_component::<SkeletalComponent>() else {
+            self.mode = RagdollMode::Animated;
+            return;
+        };
+
+        let from = self
+            .bodies
+            .iter()
+            .map(|body| {
+                (
+                    skel.local_position(body.bone),
+                    skel.local_rotation(body.bone),
+                )
+            })
+            .collect();
+        self.blend = Some(RagdollBlend {
+            from,
+            elapsed: 0.0,
+            duration,
+        });
+        self.mode = RagdollMode::Blending;
+    }
+
+    /// Applies an impulse to the body of the bone called `bone`, e.g. where a hit landed.
+    pub fn apply_impulse(&mut self, bone: &str, impulse: Vec3) {
+        let Some(object) = self.body_of(bone) else {
+            return;
+        };
+        if let Some(mut rigid) = object.get_component::<RigidBodyComponent>()
+            && let Some(body) = rigid.body_mut()
+        {
+            body.apply_impulse(impulse, true);
+        }
+    }
+
+    /// Closest body above `bone`, if there is one.
+    fn parent_body(&self, bones: &Bones, bone: usize) -> Option<GameObjectId> {
+        let mut current = bones.parents[bone];
+        while let Some(bone) = current {
+            if let Some(body) = self.bodies.iter().find(|body| body.bone == bone) {
+                return Some(body.object);
+            }
+            current = bones.parents[bone];
+        }
+        None
+    }
+
+    /// Moves the kinematic bodies to where their bones are animated.
+    fn drive_bodies(&self, skel: &SkeletalComponent) {
+        let globals = skel.global_pose();
+        let to_world = self.parent().transform.affine();
+
+        for body in &self.bodies {
+            let bone_world = to_world * Affine3A::from_mat4(globals[body.bone]);
+            let (_, rotation, translation) =
+                (bone_world * body.offset.inverse()).to_scale_rotation_translation();
+
+            if let Some(mut rigid) = body.object.get_component::<RigidBodyComponent>()
+                && let Some(rb) = rigid.body_mut()
+            {
+                rb.set_next_kinematic_position(Pose::from_parts(translation, rotation));
+            }
+        }
+    }
+
+    /// Writes the poses of the simulated bodies into the skeleton.
+    fn pose_from_bodies(&self, skel: &mut SkeletalComponent) {
+        let to_model = self.parent().transform.affine().inverse();
+
+        for body in &self.bodies {
+            let Some(rigid) = body.object.get_component::<RigidBodyComponent>() else {
+                continue;
+            };
+            let pose = rigid.world_render_isometry();
+            let body_world = Affine3A::from_rotation_translation(pose.rotation, pose.translation);
+            let model = to_model * body_world * body.offset;
+
+            let globals = skel.global_pose();
+            let parent = skel.bones().parents[body.bone]
+                .map_or(Affine3A::IDENTITY, |p| Affine3A::from_mat4(globals[p]));
+            let (_, rotation, translation) =
+                (parent.inverse() * model).to_scale_rotation_translation();
+
+            skel.set_local_rotation(body.bone, rotation);
+            if !body.has_parent_body {
+                skel.set_local_transform(body.bone, translation);
+            }
+        }
+    }
+
+    /// Blends from the pose the ragdoll rested in to the animated pose.
+    fn blend_pose(&mut self, skel: &mut SkeletalComponent, dt: f32) {
+        let Some(blend) = &mut self.blend else {
+            self.mode = RagdollMode::Animated;
+            return;
+        };
+
+        blend.elapsed += dt;
+        let t = if blend.duration <= 0.0 {
+            1.0
+        } else {
+            (blend.elapsed / blend.duration).clamp(0.0, 1.0)
+        };
+
+        for (body, (position, rotation)) in self.bodies.iter().zip(&blend.from) {
+            let animated = skel.local_rotation(body.bone);
+            skel.set_local_rotation(body.bone, rotation.slerp(animated, t));
+            if !body.has_parent_body {
+                let animated = skel.local_position(body.bone);
+                skel.set_local_transform(body.bone, position.lerp(animated, t));
+            }
+        }
+
+        if t >= 1.0 {
+            self.blend = None;
+            self.mode = RagdollMode::Animated;
+        }
+    }
+}
+
+fn bone_depth(bones: &Bones, bone: usize) -> usize {
+    let mut depth = 0;
+    let mut current = bones.parents[bone];
+    while let Some(parent) = current {
+        depth += 1;
+        current = bones.parents[parent];
+    }
+    depth
+}
+
+/// Switches a ragdoll body between kinematic and simulated.
+fn set_simulated(object: GameObjectId, simulated: bool) {
+    if let Some(mut rigid) = object.get_component::<RigidBodyComponent>() {
+        rigid.set_kinematic(!simulated);
+        if let Some(body) = rigid.body_mut() {
+            body.wake_up(true);
+        }
+    }
+    if let Some(collider) = object.get_component::<Collider3D>()
+        && let Some(collider) = collider.collider_mut()
+    {
+        collider.set_sensor(!simulated);
+    }
+}
+
+/// Connects a body to the body above it with a limited joint at the start of its bone.
+///
+/// The joint frames are set up so they line up in the current pose, with the X axis along the
+/// bone for ball joints and along the hinge axis for hinges.
+fn connect(
+    mut object: GameObjectId,
+    parent: GameObjectId,
+    joint: RagdollJoint,
+    anchor: Vec3,
+    body_rotation: Quat,
+    align: Quat,
+) {
+    let parent_rotation = parent.transform.rotation();
+    let parent_position = parent.transform.position();
+    let body_position = object.transform.position();
+
+    let anchor1 = body_rotation.inverse() * (anchor - body_position);
+    let anchor2 = parent_rotation.inverse() * (anchor - parent_position);
+
+    let axis = match joint {
+        RagdollJoint::Ball { .. } => Vec3::Y,
+        RagdollJoint::Hinge { axis, .. } => (align.inverse() * axis).normalize_or(Vec3::X),
+    };
+    let frame1 = Quat::from_rotation_arc(Vec3::X, axis);
+    let frame2 = (parent_rotation.inverse() * body_rotation * frame1).normalize();
+
+    let configure = |data: Option<&mut GenericJoint>| {
+        if let Some(data) = data {
+            data.set_local_frame1(Pose::from_parts(anchor1, frame1));
+            data.set_local_frame2(Pose::from_parts(anchor2, frame2));
+            data.set_contacts_enabled(false);
+        }
+    };
+
+    match joint {
+        RagdollJoint::Ball { swing, twist } => {
+            let mut j = object.add_component::<SphericalJoint>();
+            j.config.twist_limits = Some([-twist, twist]);
+            j.config.swing_limits = Some([-swing, swing]);
+            j.set_anchor1(anchor1);
+            j.set_anchor2(anchor2);
+            j.connect_to(parent);
+            configure(j.joint_data_mut());
+        }
+        RagdollJoint::Hinge { limits, .. } => {
+            let mut j = object.add_component::<RevoluteJoint>();
+            j.config.axis = Vec3::X;
+            j.config.limits = Some(limits);
+            j.set_anchor1(anchor1);
+            j.set_anchor2(anchor2);
+            j.connect_to(parent);
+            configure(j.joint_data_mut());
+        }
+    }
+}
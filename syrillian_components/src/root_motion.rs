@@ -0,0 +1,76 @@
+//! Root motion settings of an [`AnimationComponent`](crate::AnimationComponent).
+//!
+//! With root motion, the movement a clip applies to its root bone is taken out of the pose and
+//! moves the animated object instead. The root bone stays where the clip started, so the mesh
+//! doesn't drift away from the object's collider and doesn't snap back when a clip loops.
+
+use syrillian::math::{Quat, Vec3};
+
+/// Which parts of the root's movement are extracted, and where they go.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RootMotion {
+    /// Name of the animated root bone or object.
+    pub root: String,
+    /// Extracts the rotation around the up axis, leaving tilting and rolling in the pose.
+    pub extract_rotation: bool,
+    /// Extracts vertical movement as well. Off by default, so jumps and crouches stay in the pose.
+    pub extract_vertical: bool,
+    /// Moves the animated object by the extracted motion. A kinematic rigid body on the object is
+    /// moved to its next kinematic position, a dynamic one gets the matching velocity.
+    ///
+    /// Turn this off to apply the motion yourself, e.g. from a character controller, by taking it
+    /// with [`AnimationComponent::take_root_motion`](crate::AnimationComponent::take_root_motion).
+    pub apply_to_object: bool,
+}
+
+impl RootMotion {
+    pub fn new(root: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            extract_rotation: true,
+            extract_vertical: false,
+            apply_to_object: true,
+        }
+    }
+
+    pub fn with_rotation(mut self, extract: bool) -> Self {
+        self.extract_rotation = extract;
+        self
+    }
+
+    pub fn with_vertical(mut self, extract: bool) -> Self {
+        self.extract_vertical = extract;
+        self
+    }
+
+    pub fn with_apply_to_object(mut self, apply: bool) -> Self {
+        self.apply_to_object = apply;
+        self
+    }
+}
+
+/// Motion extracted from the root, in world space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RootMotionDelta {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+impl Default for RootMotionDelta {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+        }
+    }
+}
+
+impl RootMotionDelta {
+    /// Appends `other`, which happened after this motion.
+    pub fn then(self, other: RootMotionDelta) -> Self {
+        Self {
+            translation: self.translation + other.translation,
+            rotation: (other.rotation * self.rotation).normalize(),
+        }
+    }
+}
@@ -118,6 +118,38 @@ impl SkeletalComponent {
         self.dirty = true;
     }
 
+    pub fn local_position(&self, index: usize) -> Vec3 {
+        self.skin_transform[index].w_axis.truncate()
+    }
+
+    pub fn local_rotation(&self, index: usize) -> Quat {
+        Quat::from_mat4(&self.skin_rotation[index])
+    }
+
+    /// Model space transforms of all bones in the current pose. Unlike the palette, this doesn't
+    /// wait for the next palette update.
+    pub fn global_pose(&self) -> Vec<Mat4> {
+        let mut globals = vec![Mat4::IDENTITY; self.bones_static.len()];
+        let mut stack: Vec<(usize, Mat4)> = self
+            .bones_static
+            .roots
+            .iter()
+            .map(|&root| (root, Mat4::IDENTITY))
+            .collect();
+
+        while let Some((i, parent_global)) = stack.pop() {
+            let local = self.skin_transform[i] * self.skin_rotation[i] * self.skin_scale[i];
+            globals[i] = parent_global * local;
+            stack.extend(
+                self.bones_static.children[i]
+                    .iter()
+                    .map(|&c| (c, globals[i])),
+            );
+        }
+
+        globals
+    }
+
     pub fn palette(&self) -> &[Mat4] {
         &self.palette
     }
@@ -0,0 +1,406 @@
+use syrillian::assets::{HMaterialInstance, HTexture2D, MaterialInstance};
+use syrillian::components::Component;
+use syrillian::math::{UVec2, Vec2, Vec4};
+use syrillian::{Reflect, World};
+pub use syrillian_render::proxies::SortingLayer;
+use syrillian_render::proxies::{SceneProxy, SpriteInstance, SpriteProxy};
+use syrillian_render::proxy_data_mut;
+use syrillian_render::rendering::CPUDrawCtx;
+
+/// A rectangle of a sprite sheet texture, in pixels from the top left corner.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SpriteFrame {
+    pub position: UVec2,
+    pub size: UVec2,
+}
+
+impl SpriteFrame {
+    pub fn new(position: UVec2, size: UVec2) -> Self {
+        Self { position, size }
+    }
+}
+
+/// A texture, cut into the frames a [`SpriteRenderer`] can show.
+///
+/// Works for a single image, a texture atlas with frames of any size, or a sprite sheet with a
+/// grid of equally sized frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteSheet {
+    texture: HTexture2D,
+    texture_size: UVec2,
+    frames: Vec<SpriteFrame>,
+}
+
+impl SpriteSheet {
+    /// The whole texture as a single frame.
+    pub fn single(texture: HTexture2D, texture_size: UVec2) -> Self {
+        Self::from_frames(
+            texture,
+            texture_size,
+            vec![SpriteFrame::new(UVec2::ZERO, texture_size)],
+        )
+    }
+
+    /// Cuts the texture into a grid of `frame_size` frames, row by row from the top left.
+    /// Frames that don't fit into the texture completely are left out.
+    pub fn grid(texture: HTexture2D, texture_size: UVec2, frame_size: UVec2) -> Self {
+        let frame_size = frame_size.max(UVec2::ONE);
+        let cells = texture_size / frame_size;
+        let frames = (0..cells.y)
+            .flat_map(|row| {
+                (0..cells.x).map(move |column| {
+                    SpriteFrame::new(UVec2::new(column, row) * frame_size, frame_size)
+                })
+            })
+            .collect();
+
+        Self::from_frames(texture, texture_size, frames)
+    }
+
+    /// A texture atlas with the given frames.
+    pub fn from_frames(texture: HTexture2D, texture_size: UVec2, frames: Vec<SpriteFrame>) -> Self {
+        Self {
+            texture,
+            texture_size: texture_size.max(UVec2::ONE),
+            frames,
+        }
+    }
+
+    /// The whole texture as a single frame, with the size of the texture in the asset store.
+    pub fn from_texture(world: &World, texture: HTexture2D) -> Option<Self> {
+        let size = {
+            let texture = world.assets.textures.try_get(texture)?;
+            UVec2::new(texture.width, texture.height)
+        };
+        Some(Self::single(texture, size))
+    }
+
+    pub fn texture(&self) -> HTexture2D {
+        self.texture
+    }
+
+    pub fn texture_size(&self) -> UVec2 {
+        self.texture_size
+    }
+
+    pub fn frames(&self) -> &[SpriteFrame] {
+        &self.frames
+    }
+
+    pub fn frame(&self, index: usize) -> Option<SpriteFrame> {
+        self.frames.get(index).copied()
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Texture coordinates of a frame. xy is the top left corner, zw the bottom right corner.
+    pub fn uv_rect(&self, index: usize) -> Option<Vec4> {
+        let frame = self.frame(index)?;
+        let size = self.texture_size.as_vec2();
+        let min = frame.position.as_vec2() / size;
+        let max = (frame.position + frame.size).as_vec2() / size;
+        Some(Vec4::new(min.x, min.y, max.x, max.y))
+    }
+}
+
+/// One sprite drawn by a [`SpriteRenderer`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Sprite {
+    /// Frame of the sprite sheet
+    pub frame: usize,
+    /// Offset from the object, in world units
+    pub offset: Vec2,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    /// Color multiplied with the texture
+    pub tint: Vec4,
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            offset: Vec2::ZERO,
+            flip_x: false,
+            flip_y: false,
+            tint: Vec4::ONE,
+        }
+    }
+}
+
+/// Draws sprites from a [`SpriteSheet`] in the XY plane of its object.
+///
+/// All sprites of a renderer are drawn with a single draw call. Renderers with the same sheet
+/// texture or material, sorting layer and order in layer that are drawn right after each other
+/// share one draw call as well, but tile maps or crowds are still cheapest as one renderer. The
+/// first sprite is the one changed by [`set_frame`](Self::set_frame),
+/// [`set_flip`](Self::set_flip) and [`set_tint`](Self::set_tint), and animated by a
+/// [`SpriteAnimator`](crate::SpriteAnimator).
+#[derive(Debug, Reflect)]
+pub struct SpriteRenderer {
+    sheet: Option<SpriteSheet>,
+    /// The material set with [`set_material`](Self::set_material)
+    material: Option<HMaterialInstance>,
+    /// The material the renderer made for the sheet, and the texture it samples
+    sheet_material: Option<(HTexture2D, HMaterialInstance)>,
+    sprites: Vec<Sprite>,
+    #[reflect]
+    pivot: Vec2,
+    #[reflect]
+    pixels_per_unit: f32,
+    layer: SortingLayer,
+    #[reflect]
+    order: i16,
+    dirty_sprites: bool,
+    dirty_material: bool,
+    dirty_sorting: bool,
+}
+
+impl Default for SpriteRenderer {
+    fn default() -> Self {
+        Self {
+            sheet: None,
+            material: None,
+            sheet_material: None,
+            sprites: vec![Sprite::default()],
+            pivot: Vec2::splat(0.5),
+            pixels_per_unit: 100.0,
+            layer: SortingLayer::DEFAULT,
+            order: 0,
+            dirty_sprites: false,
+            dirty_material: false,
+            dirty_sorting: false,
+        }
+    }
+}
+
+impl SpriteRenderer {
+    pub fn sheet(&self) -> Option<&SpriteSheet> {
+        self.sheet.as_ref()
+    }
+
+    pub fn set_sheet(&mut self, sheet: SpriteSheet) {
+        self.sheet = Some(sheet);
+        self.dirty_sprites = true;
+        self.dirty_material = true;
+    }
+
+    /// The material the sprites are drawn with. Its diffuse texture is sampled with the frames
+    /// of the sprite sheet.
+    pub fn material(&self) -> Option<HMaterialInstance> {
+        self.material
+            .or(self.sheet_material.map(|(_, material)| material))
+    }
+
+    /// Draws the sprites with `material` instead of a material made for the sheet's texture,
+    /// for example one sampling a render texture.
+    pub fn set_material(&mut self, material: HMaterialInstance) {
+        self.material = Some(material);
+        self.dirty_material = true;
+    }
+
+    pub fn frame(&self) -> usize {
+        self.sprites.first().map_or(0, |sprite| sprite.frame)
+    }
+
+    pub fn set_frame(&mut self, frame: usize) {
+        self.main_sprite().frame = frame;
+    }
+
+    pub fn flip_x(&self) -> bool {
+        self.sprites.first().is_some_and(|sprite| sprite.flip_x)
+    }
+
+    pub fn flip_y(&self) -> bool {
+        self.sprites.first().is_some_and(|sprite| sprite.flip_y)
+    }
+
+    pub fn set_flip(&mut self, x: bool, y: bool) {
+        let sprite = self.main_sprite();
+        sprite.flip_x = x;
+        sprite.flip_y = y;
+    }
+
+    pub fn tint(&self) -> Vec4 {
+        self.sprites.first().map_or(Vec4::ONE, |sprite| sprite.tint)
+    }
+
+    pub fn set_tint(&mut self, tint: Vec4) {
+        self.main_sprite().tint = tint;
+    }
+
+    pub fn sprites(&self) -> &[Sprite] {
+        &self.sprites
+    }
+
+    pub fn sprites_mut(&mut self) -> &mut Vec<Sprite> {
+        self.dirty_sprites = true;
+        &mut self.sprites
+    }
+
+    /// Adds a sprite to the batch and returns its index.
+    pub fn push_sprite(&mut self, sprite: Sprite) -> usize {
+        self.sprites_mut().push(sprite);
+        self.sprites.len() - 1
+    }
+
+    /// Point of a frame placed at the object, from `(0, 0)` (bottom left) to `(1, 1)` (top right).
+    pub fn pivot(&self) -> Vec2 {
+        self.pivot
+    }
+
+    pub fn set_pivot(&mut self, pivot: Vec2) {
+        self.pivot = pivot;
+        self.dirty_sprites = true;
+    }
+
+    /// Texture pixels per world unit. Use the same value for a pixel perfect camera.
+    pub fn pixels_per_unit(&self) -> f32 {
+        self.pixels_per_unit
+    }
+
+    pub fn set_pixels_per_unit(&mut self, pixels_per_unit: f32) {
+        self.pixels_per_unit = pixels_per_unit.max(f32::EPSILON);
+        self.dirty_sprites = true;
+    }
+
+    pub fn sorting_layer(&self) -> SortingLayer {
+        self.layer
+    }
+
+    pub fn order_in_layer(&self) -> i16 {
+        self.order
+    }
+
+    pub fn set_sorting(&mut self, layer: SortingLayer, order: i16) {
+        self.layer = layer;
+        self.order = order;
+        self.dirty_sorting = true;
+    }
+
+    /// The sprite quads as they're drawn, without sprites showing frames the sheet doesn't have.
+    pub fn instances(&self) -> Vec<SpriteInstance> {
+        let Some(sheet) = &self.sheet else {
+            return Vec::new();
+        };
+
+        self.sprites
+            .iter()
+            .filter_map(|sprite| {
+                let frame = sheet.frame(sprite.frame)?;
+                let mut uv = sheet.uv_rect(sprite.frame)?;
+                if sprite.flip_x {
+                    (uv.x, uv.z) = (uv.z, uv.x);
+                }
+                if sprite.flip_y {
+                    (uv.y, uv.w) = (uv.w, uv.y);
+                }
+
+                let size = frame.size.as_vec2() / self.pixels_per_unit;
+                let min = sprite.offset - self.pivot * size;
+                Some(SpriteInstance {
+                    rect: Vec4::new(min.x, min.y, size.x, size.y),
+                    uv_rect: uv,
+                    color: sprite.tint,
+                })
+            })
+            .collect()
+    }
+
+    fn main_sprite(&mut self) -> &mut Sprite {
+        let sprites = self.sprites_mut();
+        if sprites.is_empty() {
+            sprites.push(Sprite::default());
+        }
+        &mut sprites[0]
+    }
+
+    /// Makes sure the sheet material samples the sheet's texture, and returns the material the
+    /// sprites are drawn with together with the texture of the sheet material
+    fn ensure_material(&mut self, world: &World) -> (HMaterialInstance, Option<HTexture2D>) {
+        let texture = match (&self.sheet, self.material) {
+            (Some(sheet), None) => Some(sheet.texture),
+            _ => None,
+        };
+
+        if self.sheet_material.map(|(own, _)| own) != texture {
+            self.release_sheet_material(world);
+        }
+
+        if let Some(material) = self.material {
+            return (material, None);
+        }
+        let Some(texture) = texture else {
+            return (HMaterialInstance::DEFAULT, None);
+        };
+
+        let (_, material) = *self.sheet_material.get_or_insert_with(|| {
+            let material = MaterialInstance::builder()
+                .name("Sprite")
+                .diffuse_texture(texture)
+                .build();
+            (texture, world.assets.material_instances.add(material))
+        });
+        (material, Some(texture))
+    }
+
+    fn release_sheet_material(&mut self, world: &World) {
+        if let Some((_, material)) = self.sheet_material.take() {
+            world.assets.material_instances.remove(material);
+        }
+    }
+}
+
+impl Component for SpriteRenderer {
+    fn create_render_proxy(&mut self, world: &World) -> Option<Box<dyn SceneProxy>> {
+        let (material, sheet_texture) = self.ensure_material(world);
+        let mut proxy = SpriteProxy::new(material, self.instances());
+        proxy.sheet_texture = sheet_texture;
+        proxy.layer = self.layer;
+        proxy.order = self.order;
+
+        self.dirty_sprites = false;
+        self.dirty_material = false;
+        self.dirty_sorting = false;
+
+        Some(Box::new(proxy))
+    }
+
+    fn update_proxy(&mut self, world: &World, mut ctx: CPUDrawCtx) {
+        if self.dirty_material {
+            let (material, sheet_texture) = self.ensure_material(world);
+            ctx.send_proxy_update(move |proxy| {
+                let proxy: &mut SpriteProxy = proxy_data_mut!(proxy);
+                proxy.material = material;
+                proxy.sheet_texture = sheet_texture;
+            });
+            self.dirty_material = false;
+        }
+
+        if self.dirty_sprites {
+            let instances = self.instances();
+            ctx.send_proxy_update(move |proxy| {
+                let proxy: &mut SpriteProxy = proxy_data_mut!(proxy);
+                proxy.set_instances(instances);
+            });
+            self.dirty_sprites = false;
+        }
+
+        if self.dirty_sorting {
+            let layer = self.layer;
+            let order = self.order;
+            ctx.send_proxy_update(move |proxy| {
+                let proxy: &mut SpriteProxy = proxy_data_mut!(proxy);
+                proxy.layer = layer;
+                proxy.order = order;
+            });
+            self.dirty_sorting = false;
+        }
+    }
+
+    fn delete(&mut self, world: &mut World) {
+        self.release_sheet_material(world);
+    }
+}
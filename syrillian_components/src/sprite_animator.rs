@@ -0,0 +1,168 @@
+use crate::SpriteRenderer;
+use std::collections::HashMap;
+use std::ops::Range;
+use syrillian::components::Component;
+use syrillian::{Reflect, World};
+
+/// A frame based animation, stepping through frames of a sprite sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteAnimation {
+    /// Frames of the sprite sheet, in the order they're shown
+    pub frames: Vec<usize>,
+    /// Frames shown per second
+    pub fps: f32,
+    pub looping: bool,
+}
+
+impl SpriteAnimation {
+    /// A looping animation of `frames`.
+    pub fn new(frames: Vec<usize>, fps: f32) -> Self {
+        Self {
+            frames,
+            fps,
+            looping: true,
+        }
+    }
+
+    /// A looping animation of consecutive frames, like a row of a sprite sheet.
+    pub fn range(frames: Range<usize>, fps: f32) -> Self {
+        Self::new(frames.collect(), fps)
+    }
+
+    /// Plays the animation once and stops on its last frame.
+    pub fn once(mut self) -> Self {
+        self.looping = false;
+        self
+    }
+
+    /// Length of one run through the animation in seconds.
+    pub fn duration(&self) -> f32 {
+        if self.fps <= 0.0 {
+            return 0.0;
+        }
+        self.frames.len() as f32 / self.fps
+    }
+
+    /// The frame shown `time` seconds into the animation, and whether a non-looping animation
+    /// has finished by then.
+    pub fn sample(&self, time: f32) -> Option<(usize, bool)> {
+        let last = self.frames.len().checked_sub(1)?;
+        if self.fps <= 0.0 {
+            return Some((self.frames[0], false));
+        }
+
+        let step = (time.max(0.0) * self.fps).floor() as usize;
+        if self.looping {
+            Some((self.frames[step % self.frames.len()], false))
+        } else {
+            Some((self.frames[step.min(last)], step > last))
+        }
+    }
+}
+
+/// Plays named [`SpriteAnimation`]s on the [`SpriteRenderer`] of its object.
+#[derive(Debug, Reflect)]
+pub struct SpriteAnimator {
+    animations: HashMap<String, SpriteAnimation>,
+    current: Option<String>,
+    #[reflect]
+    time: f32,
+    /// Playback speed multiplier
+    #[reflect]
+    pub speed: f32,
+    finished: bool,
+}
+
+impl Default for SpriteAnimator {
+    fn default() -> Self {
+        Self {
+            animations: HashMap::new(),
+            current: None,
+            time: 0.0,
+            speed: 1.0,
+            finished: false,
+        }
+    }
+}
+
+impl SpriteAnimator {
+    pub fn add_animation(&mut self, name: impl Into<String>, animation: SpriteAnimation) {
+        self.animations.insert(name.into(), animation);
+    }
+
+    pub fn animation(&self, name: &str) -> Option<&SpriteAnimation> {
+        self.animations.get(name)
+    }
+
+    /// Plays the animation called `name`. Nothing changes if it's already playing, so this can be
+    /// called every frame. Returns `false` if there is no such animation.
+    pub fn play(&mut self, name: &str) -> bool {
+        if !self.animations.contains_key(name) {
+            return false;
+        }
+        if self.current.as_deref() != Some(name) || self.finished {
+            self.current = Some(name.to_string());
+            self.time = 0.0;
+            self.finished = false;
+        }
+        true
+    }
+
+    /// Plays the animation called `name` from its first frame, even if it's already playing.
+    pub fn restart(&mut self, name: &str) -> bool {
+        self.finished = true;
+        self.play(name)
+    }
+
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.time = 0.0;
+        self.finished = false;
+    }
+
+    /// Name of the animation that was played last. It stays set after a non-looping animation
+    /// finished, until another one is played or the animator is stopped.
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.current.is_some() && !self.finished
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The sprite sheet frame the current animation shows.
+    pub fn current_frame(&self) -> Option<usize> {
+        let animation = self.animations.get(self.current.as_deref()?)?;
+        animation.sample(self.time).map(|(frame, _)| frame)
+    }
+
+    /// Moves the current animation `seconds` ahead, scaled by the speed, and returns the frame
+    /// it shows afterward.
+    pub fn advance(&mut self, seconds: f32) -> Option<usize> {
+        let animation = self.animations.get(self.current.as_deref()?)?;
+        if !self.finished {
+            self.time += seconds * self.speed;
+        }
+        let (frame, finished) = animation.sample(self.time)?;
+        self.finished = finished;
+        Some(frame)
+    }
+}
+
+impl Component for SpriteAnimator {
+    fn update(&mut self, world: &mut World) {
+        let Some(frame) = self.advance(world.delta_time().as_secs_f32()) else {
+            return;
+        };
+
+        if let Some(mut renderer) = self.parent().get_component::<SpriteRenderer>()
+            && renderer.frame() != frame
+        {
+            renderer.set_frame(frame);
+        }
+    }
+}
@@ -1,6 +1,6 @@
 // TODO: Refactor & Merge First Person Controller
 
-use crate::{Collider3D, RigidBodyComponent};
+use crate::{AnimationComponent, Collider3D, RigidBodyComponent};
 use syrillian::Reflect;
 use syrillian::World;
 use syrillian::components::{CRef, CWeak, CameraComponent, Component};
@@ -19,6 +19,18 @@ use syrillian::physics::rapier3d::pipeline::QueryFilter;
 use syrillian::tracing::warn;
 use syrillian_render::rendering::viewport::ViewportId;
 
+/// Horizontal speed of the character, fed into an animation graph on the character.
+pub const SPEED_PARAMETER: &str = "speed";
+/// Whether the character stands on the ground, fed into an animation graph on the character.
+pub const GROUNDED_PARAMETER: &str = "grounded";
+/// Trigger that is set on the animation graph of the character when it jumps.
+pub const JUMP_TRIGGER: &str = "jump";
+
+/// Moves a kinematic character with an orbiting camera.
+///
+/// If the character or one of its children has an [`AnimationComponent`] with an animation
+/// graph, the controller drives the [`SPEED_PARAMETER`], [`GROUNDED_PARAMETER`] and
+/// [`JUMP_TRIGGER`] parameters of it.
 #[derive(Debug, Reflect)]
 #[reflect_all]
 pub struct ThirdPersonCharacterController {
@@ -61,6 +73,8 @@ pub struct ThirdPersonCharacterController {
     #[dont_reflect]
     camera: CWeak<CameraComponent>,
     #[dont_reflect]
+    animation: CWeak<AnimationComponent>,
+    #[dont_reflect]
     self_collider_handle: Option<ColliderHandle>,
     #[dont_reflect]
     warned_missing_rigidbody: bool,
@@ -112,6 +126,7 @@ impl Default for ThirdPersonCharacterController {
 
             rigid_body: CWeak::null(),
             camera: CWeak::null(),
+            animation: CWeak::null(),
             self_collider_handle: None,
             warned_missing_rigidbody: false,
             warned_missing_camera: false,
@@ -225,7 +240,8 @@ impl Component for ThirdPersonCharacterController {
             self.velocity.y -= self.gravity * dt;
         }
 
-        if self.is_grounded && world.input.is_jump_down() {
+        let jumped = self.is_grounded && world.input.is_jump_down();
+        if jumped {
             self.velocity.y = self.jump_speed;
             self.is_grounded = false;
         }
@@ -275,6 +291,9 @@ impl Component for ThirdPersonCharacterController {
             body.set_linvel(movement.translation / dt, true);
             body.set_angvel(Vec3::ZERO, true);
         }
+
+        let horizontal = Vec3::new(movement.translation.x, 0.0, movement.translation.z);
+        self.update_animation_parameters(world, horizontal.length() / dt, jumped);
     }
 
     fn post_update(&mut self, world: &mut World) {
@@ -532,6 +551,27 @@ impl ThirdPersonCharacterController {
             .set_local_rotation(local_look_rotation);
     }
 
+    fn update_animation_parameters(&mut self, world: &World, speed: f32, jumped: bool) {
+        if !self.animation.exists(world) {
+            let mut parent = self.parent();
+            self.animation = parent
+                .get_component::<AnimationComponent>()
+                .or_else(|| parent.get_child_component::<AnimationComponent>())
+                .map(CRef::downgrade)
+                .unwrap_or_default();
+        }
+
+        let Some(mut animation) = self.animation.upgrade(world) else {
+            return;
+        };
+
+        animation.set_float_parameter(SPEED_PARAMETER, speed);
+        animation.set_bool_parameter(GROUNDED_PARAMETER, self.is_grounded);
+        if jumped {
+            animation.set_trigger(JUMP_TRIGGER);
+        }
+    }
+
     fn current_pose(&self, world: &World) -> Option<(RigidBodyHandle, Pose)> {
         let rigid = self.rigid_body.upgrade(world)?;
         let body = rigid.body()?;
@@ -0,0 +1,494 @@
+//! Raycast vehicles.
+//!
+//! A [`Vehicle`] turns the dynamic [`RigidBodyComponent`] on its object into the chassis of a car.
+//! Wheels aren't bodies of their own: every wheel casts a ray down from where it's mounted, and
+//! the suspension, engine, brakes and tire friction push the chassis at the contact points.
+//!
+//! The chassis faces -Z like every other object. Wheels are mounted in the local space of the
+//! chassis and roll around its X axis. Objects set as [`VehicleWheel::visual`] follow the pose of
+//! their wheel, so wheel meshes only need to be oriented the same way.
+
+use crate::RigidBodyComponent;
+use syrillian::Reflect;
+use syrillian::World;
+use syrillian::components::Component;
+use syrillian::core::GameObjectId;
+use syrillian::gilrs::{Axis, Button};
+use syrillian::input::{InputManager, KeyCode};
+use syrillian::math::{Pose, Quat, Vec3};
+use syrillian::physics::rapier3d::control::{DynamicRayCastVehicleController, WheelTuning};
+use syrillian::physics::rapier3d::parry::query::DefaultQueryDispatcher;
+use syrillian::physics::rapier3d::pipeline::QueryFilter;
+use syrillian::tracing::warn;
+
+/// Below this forward speed in m/s, pulling back on the throttle reverses instead of braking.
+const REVERSE_SPEED: f32 = 1.0;
+
+/// A wheel of a [`Vehicle`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VehicleWheel {
+    /// Where the suspension is mounted, in the local space of the chassis
+    pub position: Vec3,
+    pub radius: f32,
+    /// Length of the suspension when it's neither compressed nor stretched
+    pub suspension_length: f32,
+    /// Turned by the steering input
+    pub steered: bool,
+    /// Driven by the engine
+    pub driven: bool,
+    /// Locked by the handbrake
+    pub handbrake: bool,
+    /// Object that is moved and rotated with this wheel, like a wheel mesh
+    pub visual: Option<GameObjectId>,
+}
+
+impl VehicleWheel {
+    pub fn new(position: Vec3, radius: f32) -> Self {
+        Self {
+            position,
+            radius,
+            suspension_length: radius,
+            steered: false,
+            driven: false,
+            handbrake: false,
+            visual: None,
+        }
+    }
+
+    /// A steered front wheel.
+    pub fn front(position: Vec3, radius: f32) -> Self {
+        Self::new(position, radius).with_steering(true)
+    }
+
+    /// A driven rear wheel with a handbrake.
+    pub fn rear(position: Vec3, radius: f32) -> Self {
+        Self::new(position, radius)
+            .with_drive(true)
+            .with_handbrake(true)
+    }
+
+    pub fn with_suspension_length(mut self, length: f32) -> Self {
+        self.suspension_length = length;
+        self
+    }
+
+    pub fn with_steering(mut self, steered: bool) -> Self {
+        self.steered = steered;
+        self
+    }
+
+    pub fn with_drive(mut self, driven: bool) -> Self {
+        self.driven = driven;
+        self
+    }
+
+    pub fn with_handbrake(mut self, handbrake: bool) -> Self {
+        self.handbrake = handbrake;
+        self
+    }
+
+    pub fn with_visual(mut self, visual: GameObjectId) -> Self {
+        self.visual = Some(visual);
+        self
+    }
+}
+
+/// What the driver asks the vehicle to do.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct VehicleInput {
+    /// -1 (full reverse) to 1 (full throttle). Pulling back while driving forward brakes.
+    pub throttle: f32,
+    /// 0 to 1
+    pub brake: f32,
+    /// -1 (full left) to 1 (full right)
+    pub steering: f32,
+    pub handbrake: bool,
+}
+
+impl VehicleInput {
+    /// Reads the input from the keyboard (WASD or the arrow keys and space) or, if no keys are
+    /// held, from the gamepad (triggers, left stick and the south button).
+    pub fn from_player(input: &InputManager, stick_deadzone: f32) -> Self {
+        let key_axis = |negative: [KeyCode; 2], positive: [KeyCode; 2]| {
+            let pressed = |keys: [KeyCode; 2]| keys.iter().any(|&key| input.is_key_pressed(key));
+            pressed(positive) as i32 as f32 - pressed(negative) as i32 as f32
+        };
+
+        let mut throttle = key_axis(
+            [KeyCode::KeyS, KeyCode::ArrowDown],
+            [KeyCode::KeyW, KeyCode::ArrowUp],
+        );
+        if throttle == 0.0 {
+            throttle = input.gamepad.button(Button::RightTrigger2)
+                - input.gamepad.button(Button::LeftTrigger2);
+        }
+
+        let mut steering = key_axis(
+            [KeyCode::KeyA, KeyCode::ArrowLeft],
+            [KeyCode::KeyD, KeyCode::ArrowRight],
+        );
+        if steering == 0.0 {
+            steering = input.gamepad.axis(Axis::LeftStickX);
+            if steering.abs() < stick_deadzone {
+                steering = 0.0;
+            }
+        }
+
+        let handbrake =
+            input.is_key_pressed(KeyCode::Space) || input.gamepad.is_button_pressed(Button::South);
+
+        Self {
+            throttle: throttle.clamp(-1.0, 1.0),
+            brake: 0.0,
+            steering: steering.clamp(-1.0, 1.0),
+            handbrake,
+        }
+    }
+}
+
+/// Where a [`Vehicle`] gets its [`VehicleInput`] from.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum VehicleInputSource {
+    /// Keyboard and gamepad, see [`VehicleInput::from_player`].
+    #[default]
+    Player,
+    /// Only what is passed to [`Vehicle::set_input`], e.g. by an AI driver or a custom binding.
+    Manual,
+}
+
+/// Raycast vehicle on top of the [`RigidBodyComponent`] of its object.
+///
+/// Add the rigid body and a collider for the chassis first, then the wheels with
+/// [`Vehicle::add_wheel`].
+#[derive(Debug, Reflect)]
+#[reflect_all]
+pub struct Vehicle {
+    /// How hard the suspension pushes back per meter it's compressed, relative to the chassis mass
+    pub suspension_stiffness: f32,
+    /// Damping while the suspension is compressed
+    pub suspension_compression: f32,
+    /// Damping while the suspension stretches back out
+    pub suspension_damping: f32,
+    /// How far the suspension can move from its rest length
+    pub max_suspension_travel: f32,
+    pub max_suspension_force: f32,
+    /// Grip of the tires, higher values make them slip later
+    pub friction_slip: f32,
+    /// Scales the grip of the tires against sliding sideways
+    pub side_friction_stiffness: f32,
+
+    /// Torque of the engine, split across all driven wheels
+    pub engine_torque: f32,
+    /// Braking torque per wheel at full brake
+    pub brake_torque: f32,
+    /// Braking torque per wheel of the handbrake
+    pub handbrake_torque: f32,
+    /// Steering angle of the steered wheels at full lock, in degrees
+    pub max_steering_angle: f32,
+    /// How fast the wheels turn towards the requested steering angle, in degrees per second
+    pub steering_speed: f32,
+    pub stick_deadzone: f32,
+
+    #[dont_reflect]
+    pub input_source: VehicleInputSource,
+    #[dont_reflect]
+    input: VehicleInput,
+    #[dont_reflect]
+    wheels: Vec<VehicleWheel>,
+    #[dont_reflect]
+    controller: Option<DynamicRayCastVehicleController>,
+    #[dont_reflect]
+    steering_angle: f32,
+    #[dont_reflect]
+    warned_missing_rigidbody: bool,
+}
+
+impl Default for Vehicle {
+    fn default() -> Self {
+        Self {
+            suspension_stiffness: 20.0,
+            suspension_compression: 2.3,
+            suspension_damping: 4.4,
+            max_suspension_travel: 0.3,
+            max_suspension_force: 6000.0,
+            friction_slip: 10.5,
+            side_friction_stiffness: 1.0,
+
+            engine_torque: 400.0,
+            brake_torque: 60.0,
+            handbrake_torque: 120.0,
+            max_steering_angle: 35.0,
+            steering_speed: 120.0,
+            stick_deadzone: 0.15,
+
+            input_source: VehicleInputSource::Player,
+            input: VehicleInput::default(),
+            wheels: Vec::new(),
+            controller: None,
+            steering_angle: 0.0,
+            warned_missing_rigidbody: false,
+        }
+    }
+}
+
+impl Component for Vehicle {
+    fn init(&mut self, _world: &mut World) {
+        self.ensure_controller();
+    }
+
+    fn update(&mut self, world: &mut World) {
+        if self.input_source != VehicleInputSource::Player {
+            return;
+        }
+
+        self.input = if world.input.is_window_focused() {
+            VehicleInput::from_player(&world.input, self.stick_deadzone)
+        } else {
+            VehicleInput::default()
+        };
+    }
+
+    fn fixed_update(&mut self, world: &mut World) {
+        if !self.ensure_controller() {
+            return;
+        }
+
+        let dt = world.physics.integration_parameters.dt;
+        self.update_steering(dt);
+        self.apply_input(dt);
+
+        let Some(controller) = &mut self.controller else {
+            return;
+        };
+        let physics = &mut world.physics;
+        let Some(chassis) = physics.rigid_body_set.get_mut(controller.chassis) else {
+            return;
+        };
+        if self.input.throttle != 0.0 {
+            chassis.wake_up(true);
+        }
+
+        let filter = QueryFilter::default()
+            .exclude_rigid_body(controller.chassis)
+            .exclude_sensors();
+        let queries = physics.broad_phase.as_query_pipeline_mut(
+            &DefaultQueryDispatcher,
+            &mut physics.rigid_body_set,
+            &mut physics.collider_set,
+            filter,
+        );
+        controller.update_vehicle(dt, queries);
+    }
+
+    // after the chassis got its interpolated pose
+    fn post_update(&mut self, _world: &mut World) {
+        for (index, wheel) in self.wheels.iter().enumerate() {
+            let Some(mut visual) = wheel.visual.filter(|visual| visual.exists()) else {
+                continue;
+            };
+            let Some(pose) = self.wheel_transform(index) else {
+                continue;
+            };
+
+            visual.transform.set_position_vec(pose.translation);
+            visual.transform.set_rotation(pose.rotation);
+        }
+    }
+}
+
+impl Vehicle {
+    /// Adds a wheel and returns its index.
+    pub fn add_wheel(&mut self, wheel: VehicleWheel) -> usize {
+        let tuning = self.tuning();
+        if let Some(controller) = &mut self.controller {
+            add_controller_wheel(controller, &wheel, &tuning);
+        }
+
+        self.wheels.push(wheel);
+        self.wheels.len() - 1
+    }
+
+    pub fn wheels(&self) -> &[VehicleWheel] {
+        &self.wheels
+    }
+
+    pub fn input(&self) -> VehicleInput {
+        self.input
+    }
+
+    /// Sets the input of the vehicle. With [`VehicleInputSource::Player`], it's replaced by the
+    /// player's input on the next frame.
+    pub fn set_input(&mut self, input: VehicleInput) {
+        self.input = input;
+    }
+
+    /// Speed along the forward axis of the chassis in m/s, negative while reversing.
+    pub fn speed(&self) -> f32 {
+        // the controller measures along +Z, vehicles face -Z
+        self.controller
+            .as_ref()
+            .map_or(0.0, |controller| -controller.current_vehicle_speed)
+    }
+
+    /// Whether the wheel with the given index touches the ground.
+    pub fn is_wheel_grounded(&self, index: usize) -> bool {
+        self.controller
+            .as_ref()
+            .and_then(|controller| controller.wheels().get(index))
+            .is_some_and(|wheel| wheel.raycast_info().is_in_contact)
+    }
+
+    /// Whether any wheel touches the ground.
+    pub fn is_grounded(&self) -> bool {
+        (0..self.wheels.len()).any(|index| self.is_wheel_grounded(index))
+    }
+
+    /// Pose of the wheel with the given index in the local space of the chassis, including the
+    /// suspension, steering and rolling of the wheel.
+    pub fn wheel_local_transform(&self, index: usize) -> Option<Pose> {
+        let wheel = self.wheels.get(index)?;
+        let Some(state) = self
+            .controller
+            .as_ref()
+            .and_then(|controller| controller.wheels().get(index))
+        else {
+            return Some(Pose::from_parts(
+                wheel.position - Vec3::Y * wheel.suspension_length,
+                Quat::IDENTITY,
+            ));
+        };
+
+        let center = wheel.position - Vec3::Y * state.raycast_info().suspension_length;
+        let rotation =
+            Quat::from_rotation_y(state.steering) * Quat::from_rotation_x(state.rotation);
+        Some(Pose::from_parts(center, rotation))
+    }
+
+    /// Pose of the wheel with the given index in world space.
+    pub fn wheel_transform(&self, index: usize) -> Option<Pose> {
+        let local = self.wheel_local_transform(index)?;
+        let chassis = self
+            .parent()
+            .get_component::<RigidBodyComponent>()?
+            .world_render_isometry();
+
+        Some(Pose::from_parts(
+            chassis.translation + chassis.rotation * local.translation,
+            chassis.rotation * local.rotation,
+        ))
+    }
+
+    fn tuning(&self) -> WheelTuning {
+        WheelTuning {
+            suspension_stiffness: self.suspension_stiffness,
+            suspension_compression: self.suspension_compression,
+            suspension_damping: self.suspension_damping,
+            max_suspension_travel: self.max_suspension_travel,
+            side_friction_stiffness: self.side_friction_stiffness,
+            friction_slip: self.friction_slip,
+            max_suspension_force: self.max_suspension_force,
+        }
+    }
+
+    fn ensure_controller(&mut self) -> bool {
+        if self.controller.is_some() {
+            return true;
+        }
+
+        let Some(chassis) = self
+            .parent()
+            .get_component::<RigidBodyComponent>()
+            .and_then(|rigid| rigid.handle_opt())
+        else {
+            if !self.warned_missing_rigidbody {
+                self.warned_missing_rigidbody = true;
+                warn!("Vehicle requires a RigidBodyComponent");
+            }
+            return false;
+        };
+
+        let mut controller = DynamicRayCastVehicleController::new(chassis);
+        controller.index_up_axis = 1;
+        controller.index_forward_axis = 2;
+
+        let tuning = self.tuning();
+        for wheel in &self.wheels {
+            add_controller_wheel(&mut controller, wheel, &tuning);
+        }
+
+        self.controller = Some(controller);
+        true
+    }
+
+    fn update_steering(&mut self, dt: f32) {
+        let target = self.input.steering.clamp(-1.0, 1.0) * self.max_steering_angle;
+        let max_step = self.steering_speed * dt;
+        self.steering_angle += (target - self.steering_angle).clamp(-max_step, max_step);
+    }
+
+    fn apply_input(&mut self, dt: f32) {
+        let speed = self.speed();
+        let tuning = self.tuning();
+        let Some(controller) = &mut self.controller else {
+            return;
+        };
+
+        let mut throttle = self.input.throttle.clamp(-1.0, 1.0);
+        let mut brake = self.input.brake.clamp(0.0, 1.0);
+        if throttle * speed < 0.0 && speed.abs() > REVERSE_SPEED {
+            brake = brake.max(throttle.abs());
+            throttle = 0.0;
+        }
+
+        let driven = self
+            .wheels
+            .iter()
+            .filter(|wheel| wheel.driven)
+            .count()
+            .max(1) as f32;
+        // positive steering turns the wheels to the left
+        let steering = -self.steering_angle.to_radians();
+
+        for (wheel, state) in self.wheels.iter().zip(controller.wheels_mut()) {
+            state.suspension_stiffness = tuning.suspension_stiffness;
+            state.damping_compression = tuning.suspension_compression;
+            state.damping_relaxation = tuning.suspension_damping;
+            state.max_suspension_travel = tuning.max_suspension_travel;
+            state.max_suspension_force = tuning.max_suspension_force;
+            state.friction_slip = tuning.friction_slip;
+            state.side_friction_stiffness = tuning.side_friction_stiffness;
+
+            state.steering = if wheel.steered { steering } else { 0.0 };
+            state.engine_force = if wheel.driven {
+                throttle * self.engine_torque / driven / wheel.radius
+            } else {
+                0.0
+            };
+
+            let mut brake_torque = brake * self.brake_torque;
+            if wheel.handbrake && self.input.handbrake {
+                brake_torque = brake_torque.max(self.handbrake_torque);
+            }
+            // the controller takes the braking impulse of one step
+            state.brake = brake_torque / wheel.radius * dt;
+            if state.brake > 0.0 {
+                state.engine_force = 0.0;
+            }
+        }
+    }
+}
+
+fn add_controller_wheel(
+    controller: &mut DynamicRayCastVehicleController,
+    wheel: &VehicleWheel,
+    tuning: &WheelTuning,
+) {
+    controller.add_wheel(
+        wheel.position,
+        Vec3::NEG_Y,
+        Vec3::X,
+        wheel.suspension_length,
+        wheel.radius,
+        tuning,
+    );
+}
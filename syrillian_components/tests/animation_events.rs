@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use syrillian::World;
+use syrillian::components::Component;
+use syrillian::utils::animation::{AnimationClip, AnimationEvent};
+use syrillian_components::AnimationComponent;
+
+thread_local! {
+    static RECEIVED_EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Default)]
+struct EventRecorder;
+
+impl Component for EventRecorder {
+    fn on_animation_event(&mut self, _world: &mut World, event: &AnimationEvent) {
+        RECEIVED_EVENTS.with(|events| events.borrow_mut().push(event.name.clone()));
+    }
+}
+
+fn take_events() -> Vec<String> {
+    RECEIVED_EVENTS.with(|events| events.take())
+}
+
+fn clip(name: &str, events: &[(&str, f32)]) -> AnimationClip {
+    AnimationClip {
+        name: name.to_string(),
+        duration: 1.0,
+        channels: Vec::new(),
+        events: events
+            .iter()
+            .map(|&(event, time)| AnimationEvent::new(event, time))
+            .collect(),
+    }
+}
+
+#[test]
+fn blended_clips_fire_shared_events_once() {
+    let (mut world, ..) = World::fresh();
+    let mut character = world.new_object("Character");
+    character.add_component::<EventRecorder>();
+
+    let mut anim = character.add_component::<AnimationComponent>();
+    anim.set_clips(vec![
+        clip("Walk", &[("Footstep", 0.5)]),
+        clip("Run", &[("Footstep", 0.5)]),
+    ]);
+    anim.play_by_name("Walk", true, 1.0, 1.0);
+    anim.crossfade_by_name("Run", 2.0, true, 1.0, 1.0);
+    take_events();
+
+    anim.advance(&mut world, 0.6);
+    assert_eq!(take_events(), ["Footstep"]);
+
+    anim.advance(&mut world, 1.0);
+    assert_eq!(take_events(), ["Footstep"]);
+}
+
+#[test]
+fn fading_out_clips_still_fire_their_events() {
+    let (mut world, ..) = World::fresh();
+    let mut character = world.new_object("Character");
+    character.add_component::<EventRecorder>();
+
+    let mut anim = character.add_component::<AnimationComponent>();
+    anim.set_clips(vec![
+        clip("Attack", &[("Hit", 0.8)]),
+        clip("Idle", &[("Breath", 0.5)]),
+    ]);
+    anim.play_by_name("Attack", false, 1.0, 1.0);
+    anim.advance(&mut world, 0.5);
+    anim.crossfade_by_name("Idle", 0.5, true, 1.0, 1.0);
+    take_events();
+
+    // idle outweighs the attack by the time it reaches its hit
+    anim.advance(&mut world, 0.4);
+    assert_eq!(take_events(), ["Hit"]);
+
+    anim.advance(&mut world, 0.4);
+    assert_eq!(take_events(), ["Breath"]);
+}
@@ -0,0 +1,193 @@
+use syrillian::math::Vec2;
+use syrillian::utils::animation::AnimationClip;
+use syrillian_components::animation_graph::{
+    AnimationGraph, AnimationGraphPlayer, AnimationState, AnimationTransition, Condition,
+    ParameterValue, blend_weights_1d, blend_weights_2d,
+};
+
+fn clip(name: &str, duration: f32) -> AnimationClip {
+    AnimationClip {
+        name: name.to_string(),
+        duration,
+        channels: Vec::new(),
+        events: Vec::new(),
+    }
+}
+
+fn clips() -> Vec<AnimationClip> {
+    vec![
+        clip("Idle", 2.0),
+        clip("Walk", 1.0),
+        clip("Run", 0.5),
+        clip("Jump", 1.0),
+    ]
+}
+
+fn locomotion() -> AnimationGraph {
+    AnimationGraph::new()
+        .with_state(AnimationState::blend_1d(
+            "Locomotion",
+            "speed",
+            [(0.0, "Idle"), (2.0, "Walk"), (6.0, "Run")],
+        ))
+        .with_state(AnimationState::clip("Jump", "Jump").with_looping(false))
+        .with_transition(
+            AnimationTransition::from_any("Jump")
+                .when(Condition::Trigger("jump".into()))
+                .with_duration(0.5),
+        )
+        .with_transition(
+            AnimationTransition::new("Jump", "Locomotion")
+                .when(Condition::IsTrue("grounded".into()))
+                .with_exit_time(1.0)
+                .with_duration(0.0),
+        )
+        .with_parameter("grounded", ParameterValue::Bool(true))
+}
+
+#[test]
+fn blend_space_weights() {
+    let positions = [0.0, 2.0, 6.0];
+    assert_eq!(blend_weights_1d(&positions, -1.0), vec![1.0, 0.0, 0.0]);
+    assert_eq!(blend_weights_1d(&positions, 1.0), vec![0.5, 0.5, 0.0]);
+    assert_eq!(blend_weights_1d(&positions, 5.0), vec![0.0, 0.25, 0.75]);
+    assert_eq!(blend_weights_1d(&positions, 10.0), vec![0.0, 0.0, 1.0]);
+
+    let positions = [Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::NEG_X];
+    assert_eq!(
+        blend_weights_2d(&positions, Vec2::X),
+        vec![0.0, 1.0, 0.0, 0.0]
+    );
+    let weights = blend_weights_2d(&positions, Vec2::new(0.5, 0.0));
+    assert!((weights.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    assert!((weights[0] - weights[1]).abs() < 1e-6);
+    // halfway between idle and walk, neither strafing nor walking backwards bleeds in
+    assert_eq!(weights[2], 0.0);
+    assert_eq!(weights[3], 0.0);
+
+    let weights = blend_weights_2d(&positions, Vec2::new(0.25, 0.25));
+    assert!((weights.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+    assert!(weights[0] > 0.0 && weights[1] > 0.0 && weights[2] > 0.0);
+    assert_eq!(weights[3], 0.0);
+}
+
+#[test]
+fn blend_space_syncs_clips_by_normalized_time() {
+    let mut player = AnimationGraphPlayer::new(locomotion(), &clips());
+    assert_eq!(player.current_state(), Some("Locomotion"));
+
+    player.set_float("speed", 4.0);
+    player.advance(0.375);
+
+    // walk and run are blended equally, so one cycle takes 0.75s
+    let samples = player.samples();
+    assert_eq!(samples.len(), 2);
+    for sample in &samples {
+        assert!((sample.weight - 0.5).abs() < 1e-6);
+    }
+    assert!((samples[0].time - 0.5).abs() < 1e-5);
+    assert!((samples[1].time - 0.25).abs() < 1e-5);
+}
+
+#[test]
+fn trigger_crossfades_and_is_consumed() {
+    let mut player = AnimationGraphPlayer::new(locomotion(), &clips());
+    player.set_trigger("jump");
+    player.advance(0.1);
+
+    assert_eq!(player.current_state(), Some("Jump"));
+    assert_eq!(
+        player.parameter("jump"),
+        Some(ParameterValue::Trigger(false))
+    );
+    assert!(player.is_transitioning());
+
+    player.advance(0.25);
+    let samples = player.samples();
+    let total: f32 = samples.iter().map(|s| s.weight).sum();
+    assert!((total - 1.0).abs() < 1e-5);
+    let jump = samples.iter().find(|s| s.clip_index == 3).unwrap();
+    assert!((jump.weight - 0.5).abs() < 1e-5);
+
+    player.advance(0.3);
+    assert!(!player.is_transitioning());
+    assert_eq!(player.samples().len(), 1);
+}
+
+#[test]
+fn exit_time_and_conditions_gate_transitions() {
+    let mut player = AnimationGraphPlayer::new(locomotion(), &clips());
+    player.set_bool("grounded", false);
+    assert!(player.jump_to("Jump", 0.0));
+
+    player.advance(0.6);
+    assert_eq!(player.current_state(), Some("Jump"));
+
+    // the clip has finished, but the character is still airborne
+    player.advance(0.6);
+    assert_eq!(player.current_state(), Some("Jump"));
+    assert_eq!(player.current_time(), 1.0);
+
+    player.set_bool("grounded", true);
+    player.advance(0.1);
+    assert_eq!(player.current_state(), Some("Locomotion"));
+    assert!(!player.is_transitioning());
+}
+
+#[test]
+fn looping_exit_time_fires_every_cycle() {
+    let graph = AnimationGraph::new()
+        .with_state(AnimationState::clip("A", "Walk"))
+        .with_state(AnimationState::clip("B", "Idle"))
+        .with_transition(
+            AnimationTransition::new("A", "B")
+                .when(Condition::Greater("tired".into(), 0.5))
+                .with_exit_time(0.9),
+        );
+    let mut player = AnimationGraphPlayer::new(graph, &clips());
+
+    player.advance(0.5);
+    player.set_float("tired", 1.0);
+    player.advance(0.3);
+    assert_eq!(player.current_state(), Some("A"));
+
+    // crossing the exit time, even when wrapping into the next loop
+    player.advance(0.25);
+    assert_eq!(player.current_state(), Some("B"));
+}
+
+#[test]
+fn looping_exit_time_needs_a_crossing() {
+    let graph = AnimationGraph::new()
+        .with_state(AnimationState::clip("A", "Walk"))
+        .with_state(AnimationState::clip("B", "Idle"))
+        .with_transition(
+            AnimationTransition::new("A", "B")
+                .when(Condition::Greater("tired".into(), 0.5))
+                .with_exit_time(0.5),
+        );
+    let mut player = AnimationGraphPlayer::new(graph, &clips());
+
+    // the exit time was passed before the condition held, so the rest of the loop doesn't count
+    player.advance(0.7);
+    player.set_float("tired", 1.0);
+    player.advance(0.1);
+    assert_eq!(player.current_state(), Some("A"));
+
+    player.advance(0.5);
+    assert_eq!(player.current_state(), Some("A"));
+
+    player.advance(0.25);
+    assert_eq!(player.current_state(), Some("B"));
+}
+
+#[test]
+fn missing_clips_are_skipped() {
+    let graph = AnimationGraph::new()
+        .with_state(AnimationState::clip("Dance", "Dance"))
+        .with_entry("Dance");
+    let mut player = AnimationGraphPlayer::new(graph, &clips());
+    player.advance(0.1);
+    assert_eq!(player.current_state(), Some("Dance"));
+    assert!(player.samples().is_empty());
+}
@@ -0,0 +1,72 @@
+use syrillian::World;
+use syrillian::components::Component;
+use syrillian::math::Vec3;
+use syrillian::utils::animation::{AnimationClip, Channel, TransformKeys};
+use syrillian_components::AnimationComponent;
+use syrillian_components::animation_layer::{AnimationLayer, BoneMask, LayerBlend};
+
+fn hold(name: &str, targets: &[(&str, Vec3)]) -> AnimationClip {
+    let channels = targets
+        .iter()
+        .map(|&(target, position)| Channel {
+            target_name: target.to_string(),
+            keys: TransformKeys {
+                t_times: vec![0.0],
+                t_values: vec![position],
+                ..TransformKeys::default()
+            },
+        })
+        .collect();
+
+    AnimationClip {
+        name: name.to_string(),
+        duration: 1.0,
+        channels,
+        events: Vec::new(),
+    }
+}
+
+#[test]
+fn masked_and_additive_layers() {
+    let (mut world, ..) = World::fresh();
+    let mut root = world.new_object("Character");
+    let arm = world.new_object("Arm");
+    let leg = world.new_object("Leg");
+    root.add_child(arm);
+    root.add_child(leg);
+
+    let mut anim = root.add_component::<AnimationComponent>();
+    anim.set_clips(vec![
+        hold("Run", &[("Arm", Vec3::X), ("Leg", Vec3::X)]),
+        hold("Shoot", &[("Arm", Vec3::Y), ("Leg", Vec3::Y)]),
+        hold("Breathe", &[("Arm", Vec3::new(0.0, 0.0, 1.5))]),
+        hold("Reference", &[("Arm", Vec3::new(0.0, 0.0, 1.0))]),
+    ]);
+    anim.play_by_name("Run", true, 1.0, 1.0);
+    anim.add_layer(
+        AnimationLayer::new("Upper Body", LayerBlend::Override)
+            .with_mask(BoneMask::new().with_object_subtree(arm)),
+    );
+    anim.add_layer(
+        AnimationLayer::new("Breathing", LayerBlend::Additive)
+            .with_weight(0.5)
+            .with_reference_clip("Reference"),
+    );
+
+    assert!(anim.play_on_layer("Upper Body", "Shoot", true, 1.0));
+    assert!(anim.play_on_layer("Breathing", "Breathe", true, 1.0));
+    assert!(!anim.play_on_layer("Legs", "Shoot", true, 1.0));
+    anim.update(&mut world);
+
+    assert_eq!(*leg.transform.local_position(), Vec3::X);
+    assert_eq!(*arm.transform.local_position(), Vec3::new(0.0, 1.0, 0.25));
+
+    anim.set_layer_weight("Upper Body", 0.5);
+    anim.stop_layer("Breathing", 0.0);
+    assert!(!anim.layer("Breathing").unwrap().is_playing());
+    anim.update(&mut world);
+
+    assert_eq!(*arm.transform.local_position(), Vec3::new(0.5, 0.5, 0.0));
+    assert!(anim.remove_layer("Breathing").is_some());
+    assert_eq!(anim.layers().len(), 1);
+}
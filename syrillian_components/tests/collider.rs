@@ -0,0 +1,150 @@
+use syrillian::World;
+use syrillian::core::GameObjectId;
+use syrillian::core::reflection::Value;
+use syrillian::math::Vec3;
+use syrillian::physics::rapier3d::geometry::Ray;
+use syrillian::physics::rapier3d::math::Pose;
+use syrillian::physics::{CollisionLayer, PhysicsMaterial, QueryFilter};
+use syrillian_components::Collider3D;
+
+fn step(world: &mut World) {
+    world.physics.current_timepoint -= world.physics.timestep;
+    world.fixed_update();
+}
+
+#[test]
+fn material_and_layer_from_properties() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world.physics.register_material("ice", PhysicsMaterial::ICE);
+
+    let mut obj = world.new_object("Floor");
+    obj.add_property("physics_material", Value::String("ice".to_string()));
+    obj.add_property("restitution", Value::Double(0.5));
+    obj.add_property("collision_layer", Value::String("Terrain".to_string()));
+    let collider = obj.add_component::<Collider3D>();
+
+    let terrain = world.physics.layers.layer("Terrain").unwrap();
+    assert_eq!(collider.layer(), terrain);
+    assert_eq!(collider.material().friction, PhysicsMaterial::ICE.friction);
+
+    let rapier = collider.collider().unwrap();
+    assert_eq!(rapier.friction(), PhysicsMaterial::ICE.friction);
+    assert_eq!(rapier.restitution(), 0.5);
+    assert_eq!(
+        rapier.collision_groups(),
+        world.physics.layers.groups(terrain)
+    );
+}
+
+#[test]
+fn colliders_share_registered_materials() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    world
+        .physics
+        .register_material("floor", PhysicsMaterial::ICE);
+
+    let mut shared = world.new_object("Shared Floor");
+    let mut shared_collider = shared.add_component::<Collider3D>();
+    assert!(shared_collider.use_material("floor"));
+    assert_eq!(shared_collider.material_name(), Some("floor"));
+
+    let mut copied = world.new_object("Copied Floor");
+    let mut copied_collider = copied.add_component::<Collider3D>();
+    assert!(copied_collider.use_material("floor"));
+    copied_collider.set_material(PhysicsMaterial::ICE);
+    assert_eq!(copied_collider.material_name(), None);
+
+    world
+        .physics
+        .register_material("floor", PhysicsMaterial::RUBBER);
+    step(&mut world);
+
+    assert_eq!(shared_collider.material(), PhysicsMaterial::RUBBER);
+    let rapier = shared_collider.collider().unwrap();
+    assert_eq!(rapier.friction(), PhysicsMaterial::RUBBER.friction);
+    assert_eq!(rapier.restitution(), PhysicsMaterial::RUBBER.restitution);
+
+    assert_eq!(copied_collider.material(), PhysicsMaterial::ICE);
+    let rapier = copied_collider.collider().unwrap();
+    assert_eq!(rapier.friction(), PhysicsMaterial::ICE.friction);
+}
+
+/// A collider on the debris layer in front of one on the default layer, down the negative Z axis.
+fn layered_colliders() -> (
+    Box<World>,
+    CollisionLayer,
+    CollisionLayer,
+    GameObjectId,
+    GameObjectId,
+) {
+    let (mut world, ..) = World::fresh();
+    let debris = world.physics.layers.add("Debris").unwrap();
+    let player = world.physics.layers.add("Player").unwrap();
+
+    let mut near = world.new_object("Near");
+    near.transform.set_position(0.0, 0.0, -5.0);
+    let mut near_collider = near.add_component::<Collider3D>();
+    near_collider.set_layer(debris);
+
+    let mut far = world.new_object("Far");
+    far.transform.set_position(0.0, 0.0, -10.0);
+    far.add_component::<Collider3D>();
+
+    step(&mut world);
+    (world, debris, player, near, far)
+}
+
+#[test]
+fn queries_respect_layer_interactions() {
+    let (mut world, debris, player, near, far) = layered_colliders();
+
+    let ray = Ray::new(Vec3::ZERO, Vec3::NEG_Z);
+    let filter = world.physics.layer_filter(player);
+    let (_, hit) = world.physics.cast_ray(&ray, 100.0, true, filter).unwrap();
+    assert_eq!(hit, near);
+
+    world.physics.layers.set_interaction(player, debris, false);
+    step(&mut world);
+
+    let filter = world.physics.layer_filter(player);
+    let (_, hit) = world.physics.cast_ray(&ray, 100.0, true, filter).unwrap();
+    assert_eq!(hit, far);
+    assert_eq!(
+        near.get_component::<Collider3D>()
+            .unwrap()
+            .collider()
+            .unwrap()
+            .collision_groups(),
+        world.physics.layers.groups(debris)
+    );
+    assert!(
+        world
+            .physics
+            .layers
+            .interacts(player, CollisionLayer::DEFAULT)
+    );
+}
+
+#[test]
+fn layer_queries_ignore_non_interacting_layers() {
+    let (mut world, debris, player, near, far) = layered_colliders();
+    world.physics.layers.set_interaction(player, debris, false);
+    step(&mut world);
+
+    let ray = Ray::new(Vec3::ZERO, Vec3::NEG_Z);
+    let filter = QueryFilter::new();
+    let (_, hit) = world.physics.cast_ray(&ray, 100.0, true, filter).unwrap();
+    assert_eq!(hit, near);
+
+    let (_, hit) = world
+        .physics
+        .cast_ray_on_layer(&ray, 100.0, true, player, filter)
+        .unwrap();
+    assert_eq!(hit, far);
+
+    let (_, hit) = world
+        .physics
+        .cast_sphere_on_layer(0.1, 100.0, Pose::IDENTITY, Vec3::NEG_Z, player, filter)
+        .unwrap();
+    assert_eq!(hit, far);
+}
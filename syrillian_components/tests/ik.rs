@@ -0,0 +1,144 @@
+use std::f32::consts::FRAC_PI_2;
+use syrillian::World;
+use syrillian::assets::Mesh;
+use syrillian::core::{Bones, GameObjectId};
+use syrillian::math::{Mat4, Quat, Vec3};
+use syrillian_components::ik::{
+    AimConstraint, FootPlacement, IkComponent, IkTarget, TwoBoneIk, solve_ccd, solve_fabrik,
+    solve_two_bone,
+};
+use syrillian_components::{Collider3D, MeshRenderer, SkeletalComponent};
+
+fn lengths(positions: &[Vec3]) -> Vec<f32> {
+    positions.windows(2).map(|w| w[0].distance(w[1])).collect()
+}
+
+fn skeletal(world: &mut World, bones: Bones) -> GameObjectId {
+    let mesh = world
+        .assets
+        .meshes
+        .add(Mesh::builder(Vec::new()).with_bones(bones).build());
+
+    let mut character = world.new_object("Character");
+    character.add_component::<MeshRenderer>().set_mesh(mesh);
+    character.add_component::<SkeletalComponent>();
+    character
+}
+
+fn step(world: &mut World) {
+    world.physics.current_timepoint -= world.physics.timestep;
+    world.fixed_update();
+    world.update();
+    world.post_update();
+}
+
+#[test]
+fn two_bone_reaches_target_and_bends_towards_pole() {
+    let chain = [Vec3::ZERO, Vec3::Y, Vec3::new(0.0, 2.0, 0.0)];
+    let target = Vec3::new(0.0, 1.0, 1.0);
+
+    let solved = solve_two_bone(chain, target, Some(Vec3::new(0.0, 2.0, 0.0)));
+    assert!(solved[2].abs_diff_eq(target, 1e-5));
+    for (a, b) in lengths(&chain).iter().zip(lengths(&solved)) {
+        assert!((a - b).abs() < 1e-5);
+    }
+    assert!(solved[1].y > 0.5);
+
+    let flipped = solve_two_bone(chain, target, Some(Vec3::new(0.0, -2.0, 0.0)));
+    assert!(flipped[1].y < 0.5);
+
+    // out of reach, the chain stretches towards the target
+    let far = solve_two_bone(chain, Vec3::new(5.0, 0.0, 0.0), None);
+    assert!(far[1].abs_diff_eq(Vec3::X, 1e-5));
+    assert!(far[2].abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), 1e-5));
+}
+
+#[test]
+fn chain_solvers_reach_target() {
+    let chain: Vec<Vec3> = (0..4).map(|i| Vec3::new(0.0, i as f32, 0.0)).collect();
+    let target = Vec3::new(1.5, 1.5, 0.0);
+
+    let mut fabrik = chain.clone();
+    solve_fabrik(&mut fabrik, target, 20, 1e-4);
+    let mut ccd = chain.clone();
+    solve_ccd(&mut ccd, target, 20, 1e-4);
+
+    for solved in [&fabrik, &ccd] {
+        assert_eq!(solved[0], Vec3::ZERO);
+        assert!(solved[3].distance(target) < 1e-2);
+        for length in lengths(solved) {
+            assert!((length - 1.0).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn partial_weights_hold_without_animation() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let arm = Bones::from_bind_globals(
+        ["Shoulder", "Hand"],
+        vec![None, Some(0)],
+        vec![Mat4::IDENTITY, Mat4::from_translation(Vec3::X)],
+    );
+    let mut character = skeletal(&mut world, arm);
+
+    let mut ik = character.add_component::<IkComponent>();
+    let aim = ik.add_constraint(
+        AimConstraint::new("Shoulder", Vec3::new(0.0, 5.0, 0.0))
+            .with_forward(Vec3::X)
+            .with_weight(0.3),
+    );
+
+    // nothing animates the shoulder, so every frame has to start from the same pose
+    let skel = character.get_component::<SkeletalComponent>().unwrap();
+    let expected = Quat::from_rotation_z(FRAC_PI_2 * 0.3);
+    for _ in 0..5 {
+        world.post_update();
+        assert!(skel.local_rotation(0).abs_diff_eq(expected, 1e-4));
+    }
+
+    ik.set_weight(aim, 0.0);
+    world.post_update();
+    assert!(skel.local_rotation(0).abs_diff_eq(Quat::IDENTITY, 1e-4));
+}
+
+#[test]
+fn feet_are_placed_on_the_ground() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let leg = Bones::from_bind_globals(
+        ["Thigh", "Shin", "Foot"],
+        vec![None, Some(0), Some(1)],
+        [
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.55, 0.1),
+            Vec3::new(0.0, 0.1, 0.0),
+        ]
+        .into_iter()
+        .map(Mat4::from_translation)
+        .collect(),
+    );
+    let mut character = skeletal(&mut world, leg);
+
+    // a step whose top is 0.3 above the feet of the character
+    let mut ground = world.new_object("Ground");
+    ground.transform.set_position(0.0, -0.2, 0.0);
+    ground.transform.set_nonuniform_scale(4.0, 1.0, 4.0);
+    ground.add_component::<Collider3D>();
+
+    let mut ik = character.add_component::<IkComponent>();
+    ik.add_constraint(TwoBoneIk::new(
+        "Thigh",
+        "Shin",
+        "Foot",
+        IkTarget::Ground(FootPlacement::default()),
+    ));
+    step(&mut world);
+
+    // the foot keeps being 0.1 above the ground, like it's animated above the origin
+    let skel = character.get_component::<SkeletalComponent>().unwrap();
+    let globals = skel.global_pose();
+    let foot = globals[2].w_axis.truncate();
+    assert!(foot.abs_diff_eq(Vec3::new(0.0, 0.4, 0.0), 1e-3));
+    let thigh = globals[0].w_axis.truncate();
+    assert!(thigh.abs_diff_eq(Vec3::Y, 1e-5));
+}
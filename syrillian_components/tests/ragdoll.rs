@@ -0,0 +1,146 @@
+use std::thread;
+use std::time::Duration;
+use syrillian::World;
+use syrillian::assets::Mesh;
+use syrillian::core::{Bones, GameObjectId};
+use syrillian::math::{Mat4, Quat, Vec3};
+use syrillian_components::ragdoll::{RagdollBuilder, RagdollComponent, RagdollMode};
+use syrillian_components::{Collider3D, MeshRenderer, RigidBodyComponent, SkeletalComponent};
+
+fn arm() -> Bones {
+    let positions = [
+        Vec3::ZERO,
+        Vec3::new(0.3, 0.0, 0.0),
+        Vec3::new(0.55, 0.0, 0.0),
+        Vec3::new(0.6, 0.0, 0.0),
+    ];
+    Bones::from_bind_globals(
+        ["Shoulder", "Elbow", "Hand", "Finger"],
+        vec![None, Some(0), Some(1), Some(2)],
+        positions.into_iter().map(Mat4::from_translation).collect(),
+    )
+}
+
+#[test]
+fn picks_major_bones_from_skeleton() {
+    let builder = RagdollBuilder::from_skeleton(&arm(), 0.1, 0.25);
+
+    let picked: Vec<_> = builder.bones.iter().map(|b| b.bone.as_str()).collect();
+    assert_eq!(picked, ["Shoulder", "Elbow"]);
+    assert_eq!(builder.bones[0].tip.as_deref(), Some("Elbow"));
+    assert!((builder.bones[0].radius - 0.075).abs() < 1e-6);
+    assert!((builder.bones[1].radius - 0.0625).abs() < 1e-6);
+}
+
+fn skeletal_arm(world: &mut World) -> GameObjectId {
+    let mesh = world
+        .assets
+        .meshes
+        .add(Mesh::builder(Vec::new()).with_bones(arm()).build());
+
+    let mut character = world.new_object("Character");
+    character.add_component::<MeshRenderer>().set_mesh(mesh);
+    character.add_component::<SkeletalComponent>();
+    character
+}
+
+/// Puts every bone back into its bind pose, like an animation would each frame.
+fn animate_rest_pose(character: GameObjectId) {
+    let mut skel = character.get_component::<SkeletalComponent>().unwrap();
+    let rest: Vec<_> = skel
+        .bones()
+        .bind_local
+        .iter()
+        .map(|local| (local.w_axis.truncate(), Quat::IDENTITY, Vec3::ONE))
+        .collect();
+    skel.set_local_pose_trs(&rest);
+}
+
+fn step(world: &mut World) {
+    world.physics.current_timepoint -= world.physics.timestep;
+    world.fixed_update();
+    world.update();
+    world.post_update();
+}
+
+fn assert_kinematic(ragdoll: &RagdollComponent, kinematic: bool) {
+    for body in ragdoll.bodies() {
+        let rigid = body.get_component::<RigidBodyComponent>().unwrap();
+        assert_eq!(rigid.is_kinematic(), kinematic);
+        let collider = body.get_component::<Collider3D>().unwrap();
+        assert_eq!(collider.collider().unwrap().is_sensor(), kinematic);
+    }
+}
+
+#[test]
+fn builds_bodies_and_joints_on_the_skeleton() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut character = skeletal_arm(&mut world);
+    let mut ragdoll = character.add_component::<RagdollComponent>();
+
+    let builder = RagdollBuilder::from_skeleton(&arm(), 0.1, 0.25);
+    assert_eq!(ragdoll.build(&builder), 2);
+    assert_eq!(ragdoll.bodies().count(), 2);
+    assert_eq!(world.physics.rigid_body_set.len(), 2);
+    assert_eq!(world.physics.collider_set.len(), 2);
+    assert_eq!(world.physics.impulse_joint_set.len(), 1);
+    assert_eq!(ragdoll.mode(), RagdollMode::Animated);
+    assert_kinematic(&ragdoll, true);
+
+    // the upper arm capsule lies between the shoulder and the elbow
+    let upper = ragdoll.body_of("Shoulder").unwrap();
+    assert!(
+        upper
+            .transform
+            .position()
+            .abs_diff_eq(Vec3::new(0.15, 0.0, 0.0), 1e-4)
+    );
+
+    // building again replaces the ragdoll instead of adding to it
+    assert_eq!(ragdoll.build(&builder), 2);
+    assert_eq!(world.physics.rigid_body_set.len(), 2);
+    assert_eq!(world.physics.impulse_joint_set.len(), 1);
+}
+
+#[test]
+fn simulates_and_blends_back_to_the_animation() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut character = skeletal_arm(&mut world);
+    let mut ragdoll = character.add_component::<RagdollComponent>();
+    ragdoll.build(&RagdollBuilder::from_skeleton(&arm(), 0.1, 0.25));
+
+    ragdoll.start_simulation();
+    assert_eq!(ragdoll.mode(), RagdollMode::Simulated);
+    assert_kinematic(&ragdoll, false);
+
+    for _ in 0..30 {
+        step(&mut world);
+    }
+    let skel = character.get_component::<SkeletalComponent>().unwrap();
+    assert!(ragdoll.root_position().unwrap().y < -0.1);
+    assert!(skel.local_position(0).y < -0.1);
+
+    ragdoll.blend_to_animation(0.1);
+    assert_eq!(ragdoll.mode(), RagdollMode::Blending);
+    assert_kinematic(&ragdoll, true);
+
+    world.next_frame();
+    for _ in 0..100 {
+        if ragdoll.mode() == RagdollMode::Animated {
+            break;
+        }
+        thread::sleep(Duration::from_millis(5));
+        world.next_frame();
+        world.update();
+        animate_rest_pose(character);
+        world.post_update();
+    }
+    assert_eq!(ragdoll.mode(), RagdollMode::Animated);
+
+    let skel = character.get_component::<SkeletalComponent>().unwrap();
+    for bone in 0..skel.bone_count() {
+        let rest = skel.bones().bind_local[bone].w_axis.truncate();
+        assert!(skel.local_position(bone).abs_diff_eq(rest, 1e-5));
+        assert!(skel.local_rotation(bone).abs_diff_eq(Quat::IDENTITY, 1e-5));
+    }
+}
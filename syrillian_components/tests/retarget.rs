@@ -0,0 +1,84 @@
+use syrillian::World;
+use syrillian::assets::Mesh;
+use syrillian::core::Bones;
+use syrillian::math::{Mat4, Quat, Vec3};
+use syrillian::utils::animation::{AnimationClip, Channel, TransformKeys};
+use syrillian::utils::retarget::{BoneMap, Retargeting};
+use syrillian_components::{AnimationComponent, MeshRenderer, SkeletalComponent};
+
+fn spine(names: [&str; 2], hips_height: f32) -> Bones {
+    let hips = Mat4::from_translation(Vec3::Y * hips_height);
+    let spine = hips * Mat4::from_translation(Vec3::new(0.0, 0.5, 0.0));
+    Bones::from_bind_globals(names, vec![None, Some(0)], vec![hips, spine])
+}
+
+fn lean(turn: Quat) -> AnimationClip {
+    let key = |target: &str, translation: Option<Vec3>, rotation: Quat| Channel {
+        target_name: target.to_string(),
+        keys: TransformKeys {
+            t_times: translation.map(|_| vec![0.0]).unwrap_or_default(),
+            t_values: translation.into_iter().collect(),
+            r_times: vec![0.0],
+            r_values: vec![rotation],
+            ..TransformKeys::default()
+        },
+    };
+
+    AnimationClip {
+        name: "Lean".to_string(),
+        duration: 1.0,
+        channels: vec![
+            key(
+                "mixamorig:Hips",
+                Some(Vec3::new(0.5, 1.0, 0.0)),
+                Quat::IDENTITY,
+            ),
+            key("mixamorig:Spine", None, turn),
+        ],
+        events: Vec::new(),
+    }
+}
+
+#[test]
+fn retargeted_clips_animate_the_skeleton_below() {
+    let (mut world, ..) = World::fresh();
+    let mesh = world.assets.meshes.add(
+        Mesh::builder(Vec::new())
+            .with_bones(spine(["Hips", "Chest"], 2.0))
+            .build(),
+    );
+
+    let mut character = world.new_object("Character");
+    let mut rig = world.new_object("Rig");
+    rig.add_component::<MeshRenderer>().set_mesh(mesh);
+    rig.add_component::<SkeletalComponent>();
+    character.add_child(rig);
+
+    let turn = Quat::from_rotation_x(0.4);
+    let map = BoneMap::new()
+        .with_prefix("mixamorig:", "")
+        .with("mixamorig:Spine", "Chest");
+    let retargeting = Retargeting::new(spine(["mixamorig:Hips", "mixamorig:Spine"], 1.0), map)
+        .with_root("mixamorig:Hips");
+
+    let mut anim = character.add_component::<AnimationComponent>();
+    anim.set_retargeted_clips(vec![lean(turn)], &retargeting);
+
+    let names: Vec<_> = anim.clips()[0]
+        .channels
+        .iter()
+        .map(|channel| channel.target_name.as_str())
+        .collect();
+    assert_eq!(names, ["Hips", "Chest"]);
+
+    anim.play_by_name("Lean", true, 1.0, 1.0);
+    anim.advance(&mut world, 0.1);
+
+    // the hips of the target stand twice as high, so they move twice as far
+    let skel = rig.get_component::<SkeletalComponent>().unwrap();
+    assert!(
+        skel.local_position(0)
+            .abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1e-4)
+    );
+    assert!(skel.local_rotation(1).abs_diff_eq(turn, 1e-4));
+}
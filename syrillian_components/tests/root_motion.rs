@@ -0,0 +1,158 @@
+use std::f32::consts::FRAC_PI_2;
+use syrillian::World;
+use syrillian::components::Component;
+use syrillian::math::{Quat, Vec3};
+use syrillian::utils::animation::{AnimationClip, Channel, TransformKeys};
+use syrillian_components::AnimationComponent;
+use syrillian_components::animation_graph::{AnimationGraph, AnimationState};
+use syrillian_components::root_motion::{RootMotion, RootMotionDelta};
+
+fn walk() -> AnimationClip {
+    AnimationClip {
+        name: "Walk".to_string(),
+        duration: 1.0,
+        channels: vec![Channel {
+            target_name: "Hips".to_string(),
+            keys: TransformKeys {
+                t_times: vec![0.0, 1.0],
+                t_values: vec![Vec3::Y, Vec3::new(0.0, 2.0, 4.0)],
+                r_times: vec![0.0, 1.0],
+                r_values: vec![Quat::IDENTITY, Quat::from_rotation_y(1.0)],
+                ..TransformKeys::default()
+            },
+        }],
+        events: Vec::new(),
+    }
+}
+
+#[test]
+fn root_stays_in_place() {
+    let (mut world, ..) = World::fresh();
+    let mut character = world.new_object("Character");
+    let hips = world.new_object("Hips");
+    character.add_child(hips);
+
+    let mut anim = character.add_component::<AnimationComponent>();
+    anim.set_clips(vec![walk()]);
+    anim.set_graph(AnimationGraph::new().with_state(AnimationState::clip("Walk", "Walk")));
+    anim.set_root_motion(Some(RootMotion::new("Hips").with_apply_to_object(false)));
+
+    anim.graph_mut().unwrap().advance(0.5);
+    anim.update(&mut world);
+
+    // only the vertical movement and tilting stay in the pose
+    assert!(
+        hips.transform
+            .local_position()
+            .abs_diff_eq(Vec3::new(0.0, 1.5, 0.0), 1e-5)
+    );
+    assert!(
+        hips.transform
+            .local_rotation()
+            .abs_diff_eq(Quat::IDENTITY, 1e-5)
+    );
+    assert_eq!(anim.take_root_motion(), RootMotionDelta::default());
+
+    anim.set_root_motion(Some(RootMotion::new("Hips").with_rotation(false)));
+    anim.update(&mut world);
+    assert!(
+        hips.transform
+            .local_position()
+            .abs_diff_eq(Vec3::new(0.0, 1.5, 0.0), 1e-5)
+    );
+    assert!(
+        hips.transform
+            .local_rotation()
+            .abs_diff_eq(Quat::from_rotation_y(0.5), 1e-5)
+    );
+    assert_eq!(*character.transform.local_position(), Vec3::ZERO);
+}
+
+/// Turns a quarter to the left in the first half, then walks forward along the new heading.
+fn turn_and_walk() -> AnimationClip {
+    let turned = Quat::from_rotation_y(FRAC_PI_2);
+    AnimationClip {
+        name: "TurnAndWalk".to_string(),
+        duration: 1.0,
+        channels: vec![Channel {
+            target_name: "Hips".to_string(),
+            keys: TransformKeys {
+                t_times: vec![0.0, 0.5, 1.0],
+                t_values: vec![Vec3::ZERO, Vec3::ZERO, turned * Vec3::new(0.0, 0.0, 2.0)],
+                r_times: vec![0.0, 0.5, 1.0],
+                r_values: vec![Quat::IDENTITY, turned, turned],
+                ..TransformKeys::default()
+            },
+        }],
+        events: Vec::new(),
+    }
+}
+
+#[test]
+fn root_motion_moves_the_object() {
+    let (mut world, ..) = World::fresh();
+    let mut character = world.new_object("Character");
+    let hips = world.new_object("Hips");
+    character.add_child(hips);
+
+    let mut anim = character.add_component::<AnimationComponent>();
+    anim.set_clips(vec![walk(), turn_and_walk()]);
+    anim.set_graph(
+        AnimationGraph::new()
+            .with_state(AnimationState::clip("Walk", "Walk"))
+            .with_state(AnimationState::clip("TurnAndWalk", "TurnAndWalk"))
+            .with_entry("Walk"),
+    );
+    anim.set_root_motion(Some(RootMotion::new("Hips").with_rotation(false)));
+
+    anim.advance(&mut world, 0.5);
+    assert!(
+        character
+            .transform
+            .local_position()
+            .abs_diff_eq(Vec3::new(0.0, 0.0, 2.0), 1e-5)
+    );
+    assert!(
+        character
+            .transform
+            .local_rotation()
+            .abs_diff_eq(Quat::IDENTITY, 1e-5)
+    );
+
+    // the object takes over the turn, the walk after it follows the new heading once
+    character.transform.set_local_position_vec(Vec3::ZERO);
+    anim.set_root_motion(Some(RootMotion::new("Hips")));
+    anim.graph_mut().unwrap().jump_to("TurnAndWalk", 0.0);
+    anim.advance(&mut world, 0.5);
+    assert!(
+        character
+            .transform
+            .local_position()
+            .abs_diff_eq(Vec3::ZERO, 1e-5)
+    );
+    assert!(
+        character
+            .transform
+            .local_rotation()
+            .abs_diff_eq(Quat::from_rotation_y(FRAC_PI_2), 1e-5)
+    );
+
+    anim.advance(&mut world, 0.4);
+    assert!(
+        character
+            .transform
+            .local_position()
+            .abs_diff_eq(Vec3::new(1.6, 0.0, 0.0), 1e-5)
+    );
+    assert!(
+        character
+            .transform
+            .local_rotation()
+            .abs_diff_eq(Quat::from_rotation_y(FRAC_PI_2), 1e-5)
+    );
+    assert!(
+        hips.transform
+            .local_rotation()
+            .abs_diff_eq(Quat::IDENTITY, 1e-5)
+    );
+}
@@ -0,0 +1,143 @@
+use syrillian::World;
+use syrillian::assets::HTexture2D;
+use syrillian::components::Component;
+use syrillian::math::{UVec2, Vec2, Vec4};
+use syrillian_components::{Sprite, SpriteAnimation, SpriteAnimator, SpriteRenderer, SpriteSheet};
+
+#[test]
+fn grid_sheets_cut_frames_row_by_row() {
+    let sheet = SpriteSheet::grid(
+        HTexture2D::FALLBACK_DIFFUSE,
+        UVec2::new(64, 40),
+        UVec2::new(16, 16),
+    );
+
+    // 4 columns, 2 full rows, the last 8 pixels don't fit a frame
+    assert_eq!(sheet.frame_count(), 8);
+    assert_eq!(sheet.frame(5).unwrap().position, UVec2::new(16, 16));
+    assert_eq!(
+        sheet.uv_rect(5).unwrap(),
+        Vec4::new(0.25, 16.0 / 40.0, 0.5, 32.0 / 40.0)
+    );
+    assert!(sheet.uv_rect(8).is_none());
+}
+
+#[test]
+fn renderer_places_frames_by_pivot_and_pixels_per_unit() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Sprite");
+    let mut sprite = obj.add_component::<SpriteRenderer>();
+    sprite.set_sheet(SpriteSheet::grid(
+        HTexture2D::FALLBACK_DIFFUSE,
+        UVec2::new(64, 32),
+        UVec2::new(32, 32),
+    ));
+    sprite.set_pixels_per_unit(16.0);
+    sprite.set_pivot(Vec2::new(0.5, 0.0));
+    sprite.set_frame(1);
+    sprite.set_flip(true, false);
+    sprite.set_tint(Vec4::new(1.0, 0.0, 0.0, 0.5));
+
+    let instances = sprite.instances();
+    assert_eq!(instances.len(), 1);
+    assert_eq!(instances[0].rect, Vec4::new(-1.0, 0.0, 2.0, 2.0));
+    assert_eq!(instances[0].uv_rect, Vec4::new(1.0, 0.0, 0.5, 1.0));
+    assert_eq!(instances[0].color, Vec4::new(1.0, 0.0, 0.0, 0.5));
+
+    let index = sprite.push_sprite(Sprite {
+        frame: 0,
+        offset: Vec2::new(4.0, 0.0),
+        ..Sprite::default()
+    });
+    assert_eq!(index, 1);
+    sprite.push_sprite(Sprite {
+        frame: 7,
+        ..Sprite::default()
+    });
+
+    // sprites showing frames the sheet doesn't have are skipped
+    let instances = sprite.instances();
+    assert_eq!(instances.len(), 2);
+    assert_eq!(instances[1].rect, Vec4::new(3.0, 0.0, 2.0, 2.0));
+}
+
+#[test]
+fn renderers_on_the_same_sheet_share_a_batch() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let sheet = SpriteSheet::grid(
+        HTexture2D::FALLBACK_DIFFUSE,
+        UVec2::new(64, 32),
+        UVec2::new(32, 32),
+    );
+
+    let mut first_obj = world.new_object("First");
+    let mut first = first_obj.add_component::<SpriteRenderer>();
+    first.set_sheet(sheet.clone());
+    let mut second_obj = world.new_object("Second");
+    let mut second = second_obj.add_component::<SpriteRenderer>();
+    second.set_sheet(sheet);
+    second.set_frame(1);
+
+    let first_proxy = first.create_render_proxy(&world).unwrap();
+    let second_proxy = second.create_render_proxy(&world).unwrap();
+    let first_key = first_proxy.sprite_batch().unwrap().key;
+    assert_ne!(first.material(), second.material());
+    assert_eq!(first_key, second_proxy.sprite_batch().unwrap().key);
+
+    // a material set by hand only batches with itself
+    second.set_material(first.material().unwrap());
+    let custom_proxy = second.create_render_proxy(&world).unwrap();
+    assert_ne!(first_key, custom_proxy.sprite_batch().unwrap().key);
+
+    // the sheet material is released with the renderer
+    let material = first.material().unwrap();
+    assert!(world.assets.material_instances.try_get(material).is_some());
+    world.delete_object(first_obj);
+    assert!(world.assets.material_instances.try_get(material).is_none());
+}
+
+#[test]
+fn animations_step_through_frames() {
+    let walk = SpriteAnimation::range(4..8, 10.0);
+    assert_eq!(walk.duration(), 0.4);
+    assert_eq!(walk.sample(0.05), Some((4, false)));
+    assert_eq!(walk.sample(0.25), Some((6, false)));
+    assert_eq!(walk.sample(0.45), Some((4, false)));
+
+    let attack = SpriteAnimation::new(vec![9, 3], 4.0).once();
+    assert_eq!(attack.sample(0.3), Some((3, false)));
+    assert_eq!(attack.sample(0.6), Some((3, true)));
+}
+
+#[test]
+fn animator_drives_the_sprite_renderer() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+    let mut obj = world.new_object("Player");
+    let sprite = obj.add_component::<SpriteRenderer>();
+    let mut animator = obj.add_component::<SpriteAnimator>();
+    animator.add_animation("walk", SpriteAnimation::range(4..8, 10.0));
+    animator.add_animation("jump", SpriteAnimation::range(8..10, 10.0).once());
+
+    assert!(!animator.play("swim"));
+    assert!(animator.play("walk"));
+    assert_eq!(animator.advance(0.25), Some(6));
+    assert!(animator.play("walk"));
+    assert_eq!(animator.current_frame(), Some(6));
+
+    assert!(animator.play("jump"));
+    assert_eq!(animator.advance(0.05), Some(8));
+    world.update();
+    assert_eq!(sprite.frame(), animator.current_frame().unwrap());
+
+    assert_eq!(animator.advance(1.0), Some(9));
+    assert!(animator.is_finished());
+    assert!(!animator.is_playing());
+    assert_eq!(animator.current(), Some("jump"));
+
+    animator.speed = 2.0;
+    assert!(animator.restart("walk"));
+    assert_eq!(animator.advance(0.1), Some(6));
+
+    animator.stop();
+    assert_eq!(animator.current_frame(), None);
+}
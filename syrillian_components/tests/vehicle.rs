@@ -0,0 +1,81 @@
+use syrillian::World;
+use syrillian::math::Vec3;
+use syrillian_components::vehicle::{VehicleInput, VehicleInputSource, VehicleWheel};
+use syrillian_components::{Collider3D, RigidBodyComponent, Vehicle};
+
+fn step(world: &mut World, steps: usize) {
+    for _ in 0..steps {
+        world.physics.current_timepoint -= world.physics.timestep;
+        world.fixed_update();
+        world.update();
+        world.post_update();
+        world.next_frame();
+    }
+}
+
+#[test]
+fn drives_forward_on_its_wheels() {
+    let (mut world, _rx1, _rx2, _pick_tx) = World::fresh();
+
+    let mut ground = world.new_object("Ground");
+    ground.transform.set_position(0.0, -0.5, 0.0);
+    ground.transform.set_nonuniform_scale(200.0, 1.0, 200.0);
+    ground.add_component::<Collider3D>();
+
+    let mut car = world.new_object("Car");
+    car.transform.set_position(0.0, 1.0, 0.0);
+    car.transform.set_nonuniform_scale(1.6, 0.5, 3.6);
+    let mut rigid = car.add_component::<RigidBodyComponent>();
+    rigid.body_mut().unwrap().set_additional_mass(800.0, true);
+    car.add_component::<Collider3D>();
+
+    let wheel_mesh = world.new_object("Wheel");
+    let mut vehicle = car.add_component::<Vehicle>();
+    vehicle.input_source = VehicleInputSource::Manual;
+    for x in [-0.9, 0.9] {
+        let front = VehicleWheel::front(Vec3::new(x, -0.1, -1.3), 0.35).with_suspension_length(0.4);
+        let front = if x < 0.0 {
+            front.with_visual(wheel_mesh)
+        } else {
+            front
+        };
+        vehicle.add_wheel(front);
+        vehicle.add_wheel(
+            VehicleWheel::rear(Vec3::new(x, -0.1, 1.3), 0.35).with_suspension_length(0.4),
+        );
+    }
+
+    step(&mut world, 90);
+    assert!(vehicle.is_grounded());
+    assert!(vehicle.speed().abs() < 0.5);
+
+    let start = car.transform.position();
+    vehicle.set_input(VehicleInput {
+        throttle: 1.0,
+        ..VehicleInput::default()
+    });
+    step(&mut world, 120);
+
+    let moved = car.transform.position() - start;
+    assert!(
+        moved.z < -1.0,
+        "car should drive towards -Z, moved {moved:?}"
+    );
+    assert!(moved.x.abs() < 0.5);
+    assert!(vehicle.speed() > 1.0);
+
+    let wheel = vehicle.wheel_transform(0).unwrap();
+    assert!(wheel.translation.distance(wheel_mesh.transform.position()) < 1e-4);
+    assert!((wheel.translation.y - 0.35).abs() < 0.1);
+
+    let start = car.transform.position();
+    vehicle.set_input(VehicleInput {
+        throttle: 1.0,
+        steering: 1.0,
+        ..VehicleInput::default()
+    });
+    step(&mut world, 120);
+
+    let moved = car.transform.position() - start;
+    assert!(moved.x > 0.5, "car should turn right, moved {moved:?}");
+}
@@ -53,7 +53,7 @@ impl MaterialExpression for WavyBlendMaterial {
     }
 
     fn compile(&self, compiler: &mut MaterialCompiler, _output_index: u32) -> NodeId {
-        let uv = compiler.vertex_uv();
+        let uv = compiler.vertex_uv(0);
 
         let zero = compiler.constant_f32(0.0);
         let half = compiler.constant_f32(0.5);
@@ -85,7 +85,7 @@ impl MaterialExpression for TextureDownsizeMaterial {
 
     fn compile(&self, compiler: &mut MaterialCompiler, _output_index: u32) -> NodeId {
         let scale = compiler.constant_f32(10.0);
-        let uv = compiler.vertex_uv();
+        let uv = compiler.vertex_uv(0);
         let uv_scaled = compiler.mul(uv, scale);
 
         let diffuse = compiler.base_color(
@@ -220,6 +220,12 @@ impl AssetCache {
             .expect("Mesh Skinning Compute is a default layout")
     }
 
+    pub fn bgl_mesh_morph_compute(&self) -> BindGroupLayout {
+        self.bgls
+            .try_get(HBGL::MESH_MORPH_COMPUTE, self)
+            .expect("Mesh Morph Compute is a default layout")
+    }
+
     pub fn bgl_particle_compute(&self) -> BindGroupLayout {
         self.bgls
             .try_get(HBGL::PARTICLE_COMPUTE, self)
@@ -244,6 +250,42 @@ impl AssetCache {
             .expect("SSAO Apply Compute is a default layout")
     }
 
+    pub fn bgl_velocity_compute(&self) -> BindGroupLayout {
+        self.bgls
+            .try_get(HBGL::VELOCITY_COMPUTE, self)
+            .expect("Velocity Compute is a default layout")
+    }
+
+    pub fn bgl_taa_compute(&self) -> BindGroupLayout {
+        self.bgls
+            .try_get(HBGL::TAA_COMPUTE, self)
+            .expect("TAA Compute is a default layout")
+    }
+
+    pub fn bgl_exposure_compute(&self) -> BindGroupLayout {
+        self.bgls
+            .try_get(HBGL::EXPOSURE_COMPUTE, self)
+            .expect("Exposure Compute is a default layout")
+    }
+
+    pub fn bgl_tonemap_compute(&self) -> BindGroupLayout {
+        self.bgls
+            .try_get(HBGL::TONEMAP_COMPUTE, self)
+            .expect("Tonemap Compute is a default layout")
+    }
+
+    pub fn bgl_light_cluster_compute(&self) -> BindGroupLayout {
+        self.bgls
+            .try_get(HBGL::LIGHT_CLUSTER_COMPUTE, self)
+            .expect("Light Cluster Compute is a default layout")
+    }
+
+    pub fn bgl_ibl_compute(&self) -> BindGroupLayout {
+        self.bgls
+            .try_get(HBGL::IBL_COMPUTE, self)
+            .expect("IBL Compute is a default layout")
+    }
+
     pub fn material_layout(&self, layout: &MaterialInputLayout) -> BindGroupLayout {
         let key = layout.layout_key();
         if let Some(existing) = self.material_layouts.get(&key) {
@@ -1,9 +1,9 @@
 use crate::cache::{AssetCache, CacheType, GpuTexture};
 use std::collections::HashMap;
 use std::sync::Arc;
-use syrillian_asset::MaterialInstance;
 use syrillian_asset::MaterialShaderSet;
 use syrillian_asset::material_inputs::MaterialInputLayout;
+use syrillian_asset::{AlphaMode, MaterialInstance};
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindingResource, Device, Queue, TextureFormat,
 };
@@ -33,12 +33,17 @@ fn material_textures(
     let mut by_name = HashMap::new();
 
     for tex in &layout.textures {
-        let handle = instance
-            .textures
-            .get(&tex.name)
-            .and_then(|v| *v)
-            .unwrap_or(tex.default);
-        let gpu = cache.texture(handle);
+        let render_texture = instance
+            .render_texture(&tex.name)
+            .and_then(|handle| cache.render_texture(handle));
+        let gpu = render_texture.unwrap_or_else(|| {
+            let handle = instance
+                .textures
+                .get(&tex.name)
+                .and_then(|v| *v)
+                .unwrap_or(tex.default);
+            cache.texture(handle)
+        });
         ordered.push(gpu.clone());
         by_name.insert(tex.name.clone(), gpu);
     }
@@ -60,7 +65,8 @@ impl CacheType for MaterialInstance {
 
         for tex in &layout.textures {
             let flag = format!("use_{}_texture", tex.name);
-            let use_tex = self.textures.get(&tex.name).and_then(|v| *v).is_some();
+            let use_tex = self.textures.get(&tex.name).and_then(|v| *v).is_some()
+                || self.render_textures.contains_key(&tex.name);
             self.set_bool(&flag, use_tex, &layout);
         }
 
@@ -76,7 +82,8 @@ impl CacheType for MaterialInstance {
         let diffuse_has_transparency = texture_map
             .get("diffuse")
             .is_some_and(|t| t.has_transparency);
-        let transparent = alpha < 1.0 || has_transparency_flag || diffuse_has_transparency;
+        let transparent = self.alpha_mode() == AlphaMode::Blend
+            && (alpha < 1.0 || has_transparency_flag || diffuse_has_transparency);
 
         let immediates = layout.pack_immediates(&self.values);
 
@@ -4,7 +4,8 @@ use more_asserts::debug_assert_le;
 use std::ops::Range;
 use std::sync::Arc;
 use syrillian_asset::Mesh;
-use syrillian_asset::mesh::Vertex3D;
+use syrillian_asset::mesh::morph::pack_morph_deltas;
+use syrillian_asset::mesh::{MeshInstance, SecondaryVertex3D, Vertex3D};
 use syrillian_utils::debug_panic;
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 use wgpu::{BufferUsages, Device, IndexFormat, Queue};
@@ -12,15 +13,36 @@ use wgpu::{BufferUsages, Device, IndexFormat, Queue};
 #[derive(Debug)]
 pub struct Meshlet {
     pub vertex_buffer: wgpu::Buffer,
+    pub secondary_buffer: wgpu::Buffer,
     pub vertex_count: u32,
     pub index_buffer: Option<wgpu::Buffer>,
     pub index_count: u32,
     pub offset: u32,
 }
 
+/// A simplified index buffer, drawn with the vertex buffer of the first [`Meshlet`].
+#[derive(Debug)]
+pub struct RuntimeMeshLod {
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+    pub material_ranges: Vec<Range<u32>>,
+}
+
+/// Morph target deltas of a mesh, packed by [`pack_morph_deltas`] for the morph compute shader.
+#[derive(Debug)]
+pub struct RuntimeMorphTargets {
+    pub deltas: wgpu::Buffer,
+    pub target_count: u32,
+}
+
 #[derive(Debug)]
 pub struct RuntimeMesh {
     meshlets: Vec<Meshlet>,
+    /// A single [`MeshInstance::IDENTITY`], bound for draws that aren't instanced
+    identity_instance: wgpu::Buffer,
+    lods: Vec<RuntimeMeshLod>,
+    morph_targets: Option<RuntimeMorphTargets>,
+    lod_errors: Vec<f32>,
     total_vertex_count: u32,
     total_index_count: u32,
 }
@@ -36,12 +58,24 @@ impl Meshlet {
 
     pub fn bind(&self, pass: &mut wgpu::RenderPass<'_>) {
         pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(SecondaryVertex3D::SLOT, self.secondary_buffer.slice(..));
         if let Some(i_buffer) = &self.index_buffer {
             pass.set_index_buffer(i_buffer.slice(..), IndexFormat::Uint32);
         }
     }
 
     pub fn draw(&self, range: Range<u32>, pass: &mut wgpu::RenderPass<'_>) {
+        self.draw_instanced(range, 0..1, pass);
+    }
+
+    /// Draws `range` once for every instance in `instances`, taken from the instance stream
+    /// bound at [`MeshInstance::SLOT`].
+    pub fn draw_instanced(
+        &self,
+        range: Range<u32>,
+        instances: Range<u32>,
+        pass: &mut wgpu::RenderPass<'_>,
+    ) {
         let Some(inner_range) = self.clamp_range(range) else {
             debug_panic!("Meshlet received invalid draw command");
             return;
@@ -50,9 +84,9 @@ impl Meshlet {
         self.bind(pass);
 
         if self.has_indices() {
-            pass.draw_indexed(inner_range, 0, 0..1);
+            pass.draw_indexed(inner_range, 0, instances);
         } else {
-            pass.draw(inner_range, 0..1);
+            pass.draw(inner_range, instances);
         }
     }
 
@@ -68,6 +102,7 @@ impl Meshlet {
         };
 
         pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        pass.set_vertex_buffer(SecondaryVertex3D::SLOT, self.secondary_buffer.slice(..));
         if let Some(i_buffer) = &self.index_buffer {
             pass.set_index_buffer(i_buffer.slice(..), IndexFormat::Uint32);
             pass.draw_indexed(inner_range, 0, 0..1);
@@ -122,12 +157,28 @@ impl Meshlet {
     pub fn has_indices(&self) -> bool {
         self.index_buffer.is_some()
     }
+
+    /// Index of the first vertex of this meshlet in the vertex data of the whole mesh.
+    /// Indexed meshlets share one buffer holding all vertices.
+    pub fn first_vertex(&self) -> u32 {
+        if self.has_indices() { 0 } else { self.offset }
+    }
 }
 
 impl RuntimeMesh {
-    pub fn new(meshlets: Vec<Meshlet>) -> Self {
+    pub fn new(device: &Device, meshlets: Vec<Meshlet>) -> Self {
+        let identity_instance = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Mesh Identity Instance Buffer"),
+            contents: bytemuck::bytes_of(&MeshInstance::IDENTITY),
+            usage: BufferUsages::VERTEX,
+        });
+
         let mut mesh = Self {
             meshlets,
+            identity_instance,
+            lods: Vec::new(),
+            morph_targets: None,
+            lod_errors: vec![0.0],
             total_vertex_count: 0,
             total_index_count: 0,
         };
@@ -139,6 +190,27 @@ impl RuntimeMesh {
         self.meshlets = meshlets;
     }
 
+    /// Attaches simplified levels with their relative simplification errors.
+    /// Levels are only used on indexed meshes that fit into a single meshlet.
+    pub fn with_lods(mut self, lods: Vec<(RuntimeMeshLod, f32)>) -> Self {
+        if self.meshlets.len() != 1 || !self.has_indices() {
+            return self;
+        }
+
+        self.lod_errors.truncate(1);
+        self.lods.clear();
+        for (lod, error) in lods {
+            self.lods.push(lod);
+            self.lod_errors.push(error);
+        }
+        self
+    }
+
+    pub fn with_morph_targets(mut self, morph_targets: Option<RuntimeMorphTargets>) -> Self {
+        self.morph_targets = morph_targets;
+        self
+    }
+
     fn update_counts(&mut self) {
         self.total_index_count = 0;
         self.total_index_count = 0;
@@ -166,16 +238,63 @@ impl RuntimeMesh {
     }
 
     pub fn draw(&self, range: Range<u32>, pass: &mut wgpu::RenderPass<'_>) {
+        self.draw_instanced(range, &self.identity_instance, 0..1, pass);
+    }
+
+    /// Draws `range` once for every instance in `instances`, read from `instance_buffer`
+    /// holding [`MeshInstance`]s.
+    pub fn draw_instanced(
+        &self,
+        range: Range<u32>,
+        instance_buffer: &wgpu::Buffer,
+        instances: Range<u32>,
+        pass: &mut wgpu::RenderPass<'_>,
+    ) {
+        pass.set_vertex_buffer(MeshInstance::SLOT, instance_buffer.slice(..));
+
         // TODO: Check that meshlets are ordered so that iteration can end when the range passes a meshlet
         for mesh in &self.meshlets {
             if range.end < mesh.offset || range.start > mesh.offset + mesh.point_count() {
                 continue;
             }
 
-            mesh.draw(range.clone(), pass);
+            mesh.draw_instanced(range.clone(), instances.clone(), pass);
         }
     }
 
+    /// Draws `range` of the given level, where level 0 is the base mesh.
+    /// Ranges are relative to the index buffer of that level.
+    pub fn draw_lod(&self, level: usize, range: Range<u32>, pass: &mut wgpu::RenderPass<'_>) {
+        self.draw_lod_instanced(level, range, &self.identity_instance, 0..1, pass);
+    }
+
+    /// Instanced version of [`RuntimeMesh::draw_lod`], see [`RuntimeMesh::draw_instanced`].
+    pub fn draw_lod_instanced(
+        &self,
+        level: usize,
+        range: Range<u32>,
+        instance_buffer: &wgpu::Buffer,
+        instances: Range<u32>,
+        pass: &mut wgpu::RenderPass<'_>,
+    ) {
+        let (Some(lod), Some(meshlet)) = (
+            level.checked_sub(1).and_then(|i| self.lods.get(i)),
+            self.meshlets.first(),
+        ) else {
+            self.draw_instanced(range, instance_buffer, instances, pass);
+            return;
+        };
+
+        let start = range.start.min(lod.index_count);
+        let end = range.end.clamp(start, lod.index_count);
+
+        pass.set_vertex_buffer(0, meshlet.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(SecondaryVertex3D::SLOT, meshlet.secondary_buffer.slice(..));
+        pass.set_vertex_buffer(MeshInstance::SLOT, instance_buffer.slice(..));
+        pass.set_index_buffer(lod.index_buffer.slice(..), IndexFormat::Uint32);
+        pass.draw_indexed(start..end, 0, instances);
+    }
+
     pub fn draw_with_vertex_buffers(
         &self,
         range: Range<u32>,
@@ -188,6 +307,8 @@ impl RuntimeMesh {
             "Skinned vertex buffers should match meshlet count"
         );
 
+        pass.set_vertex_buffer(MeshInstance::SLOT, self.identity_instance.slice(..));
+
         for (meshlet, vertex_buffer) in self.meshlets.iter().zip(vertex_buffers) {
             if !meshlet.applies_to(range.clone()) {
                 continue;
@@ -216,6 +337,26 @@ impl RuntimeMesh {
         &self.meshlets
     }
 
+    pub fn morph_targets(&self) -> Option<&RuntimeMorphTargets> {
+        self.morph_targets.as_ref()
+    }
+
+    /// Simplified levels, not including the base mesh.
+    pub fn lods(&self) -> &[RuntimeMeshLod] {
+        &self.lods
+    }
+
+    /// Relative simplification error of every level, starting with `0.0` for the base mesh.
+    pub fn lod_errors(&self) -> &[f32] {
+        &self.lod_errors
+    }
+
+    /// Material ranges of a level, or `None` for the base mesh and unknown levels.
+    pub fn lod_material_ranges(&self, level: usize) -> Option<&[Range<u32>]> {
+        let lod = self.lods.get(level.checked_sub(1)?)?;
+        Some(&lod.material_ranges)
+    }
+
     pub fn total_point_count(&self) -> u32 {
         if self.has_indices() {
             self.total_index_count
@@ -251,6 +392,7 @@ impl CacheType for Mesh {
         let indices_num = self.indices_count();
 
         let mut meshlets = Vec::new();
+        let secondary = self.data.secondary_vertices();
 
         // TODO: Chunk indexed meshes properly
         if let Some(indices) = self.indices() {
@@ -265,6 +407,11 @@ impl CacheType for Mesh {
                 contents: bytemuck::cast_slice(self.vertices()),
                 usage: BufferUsages::VERTEX | BufferUsages::STORAGE | BufferUsages::COPY_DST,
             });
+            let secondary_buf = device.create_buffer_init(&BufferInitDescriptor {
+                label: Some("Mesh Secondary Vertex Buffer"),
+                contents: bytemuck::cast_slice(&secondary),
+                usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+            });
 
             for i in 0..=(indices_num / MAX_BUFFER_INDICES) {
                 let start = i * MAX_BUFFER_INDICES;
@@ -276,6 +423,7 @@ impl CacheType for Mesh {
                 });
                 meshlets.push(Meshlet {
                     vertex_buffer: vertex_buf.clone(),
+                    secondary_buffer: secondary_buf.clone(),
                     vertex_count: vertices_num as u32,
                     index_buffer: Some(indices_buf),
                     index_count: (start..end).len() as u32,
@@ -292,8 +440,14 @@ impl CacheType for Mesh {
                     contents: bytemuck::cast_slice(&vertices[start..end]),
                     usage: BufferUsages::VERTEX | BufferUsages::STORAGE | BufferUsages::COPY_DST,
                 });
+                let secondary_buffer = device.create_buffer_init(&BufferInitDescriptor {
+                    label: Some("Mesh Secondary Vertex Buffer"),
+                    contents: bytemuck::cast_slice(&secondary[start..end]),
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                });
                 meshlets.push(Meshlet {
                     vertex_buffer,
+                    secondary_buffer,
                     vertex_count: (start..end).len() as u32,
                     index_buffer: None,
                     index_count: 0,
@@ -302,6 +456,40 @@ impl CacheType for Mesh {
             }
         }
 
-        Arc::new(RuntimeMesh::new(meshlets))
+        let lods = self
+            .lods()
+            .iter()
+            .map(|lod| {
+                let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
+                    label: Some("Mesh LOD Index Buffer"),
+                    contents: bytemuck::cast_slice(&lod.indices),
+                    usage: BufferUsages::INDEX,
+                });
+                let runtime = RuntimeMeshLod {
+                    index_buffer,
+                    index_count: lod.indices.len() as u32,
+                    material_ranges: lod.material_ranges.clone(),
+                };
+                (runtime, lod.error)
+            })
+            .collect();
+
+        let morph_targets = self.has_morph_targets().then(|| {
+            let deltas = pack_morph_deltas(self.morph_targets());
+            RuntimeMorphTargets {
+                deltas: device.create_buffer_init(&BufferInitDescriptor {
+                    label: Some("Mesh Morph Target Buffer"),
+                    contents: bytemuck::cast_slice(&deltas),
+                    usage: BufferUsages::STORAGE,
+                }),
+                target_count: self.morph_target_count() as u32,
+            }
+        });
+
+        Arc::new(
+            RuntimeMesh::new(device, meshlets)
+                .with_lods(lods)
+                .with_morph_targets(morph_targets),
+        )
     }
 }
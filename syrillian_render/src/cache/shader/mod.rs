@@ -81,7 +81,8 @@ impl RuntimeShader {
             RenderPassType::Color
             | RenderPassType::Color2D
             | RenderPassType::Picking
-            | RenderPassType::PickingUi => Some(&self.pipeline),
+            | RenderPassType::PickingUi
+            | RenderPassType::Velocity => Some(&self.pipeline),
             RenderPassType::Shadow => self.shadow_pipeline.as_ref(),
         }
     }
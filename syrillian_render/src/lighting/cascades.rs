@@ -0,0 +1,217 @@
+//! Cascaded shadow maps for sun lights.
+//!
+//! The view frustum of the camera is cut into slices along its depth, and every slice gets its
+//! own orthographic shadow map. Close slices are small, so shadows close to the camera get most
+//! of the resolution. Each cascade is fit around the bounding sphere of its slice, which keeps
+//! its size constant while the camera turns, and its position is snapped to whole shadow map
+//! texels, so shadow edges don't shimmer while the camera moves.
+
+use crate::lighting::proxy::LightProxy;
+use crate::rendering::render_data::CameraUniform;
+use glamx::{Mat4, Vec3, Vec4};
+
+/// Maximum number of cascades of a sun light.
+pub const MAX_SHADOW_CASCADES: u32 = 4;
+
+/// Shadow casters up to this far behind a cascade, towards the sun, still cast into it.
+const MIN_CASTER_DISTANCE: f32 = 50.0;
+
+/// View and projection of one shadow cascade.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ShadowCascade {
+    pub view: Mat4,
+    pub projection: Mat4,
+    /// Position of the orthographic shadow camera
+    pub position: Vec3,
+    /// View distance at which the cascade's slice of the camera frustum ends
+    pub split_far: f32,
+}
+
+/// Distances where the slices of the frustum between `near` and `far` end.
+///
+/// `lambda` blends between splitting evenly (0) and logarithmically (1). Logarithmic splits give
+/// close cascades more resolution.
+pub fn cascade_splits(near: f32, far: f32, count: u32, lambda: f32) -> Vec<f32> {
+    let near = near.max(1e-4);
+    let far = far.max(near + 1e-3);
+    let lambda = lambda.clamp(0.0, 1.0);
+
+    (1..=count)
+        .map(|i| {
+            let t = i as f32 / count as f32;
+            let log = near * (far / near).powf(t);
+            let linear = near + (far - near) * t;
+            lambda * log + (1.0 - lambda) * linear
+        })
+        .collect()
+}
+
+/// Fits the cascades of the sun `light` to the frustum of `camera`, for shadow maps that are
+/// `resolution` texels wide.
+pub fn fit_cascades(
+    camera: &CameraUniform,
+    light: &LightProxy,
+    resolution: u32,
+) -> Vec<ShadowCascade> {
+    let count = light.shadow_cascades.min(MAX_SHADOW_CASCADES);
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let near = camera.near;
+    let far = camera.far.min(light.shadow_distance.max(near + 1e-3));
+    let corners = frustum_corners(camera);
+
+    let direction = light.direction.normalize_or(Vec3::NEG_Y);
+    let up = if direction.dot(Vec3::Y).abs() > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    };
+    // rotation into the light's space, used to snap cascades to texels
+    let light_rotation = Mat4::look_at_rh(Vec3::ZERO, direction, up);
+    let light_rotation_inv = light_rotation.inverse();
+
+    let mut slice_near = near;
+    cascade_splits(near, far, count, light.cascade_split_lambda)
+        .into_iter()
+        .map(|slice_far| {
+            let slice = slice_corners(&corners, camera.near, camera.far, slice_near, slice_far);
+            slice_near = slice_far;
+
+            let center = slice.iter().copied().sum::<Vec3>() / slice.len() as f32;
+            let radius = slice
+                .iter()
+                .map(|corner| corner.distance(center))
+                .fold(0.0, f32::max);
+            // quantized, so the cascade keeps its exact size while the camera turns
+            let radius = (radius * 16.0).ceil() / 16.0;
+
+            let texel = 2.0 * radius / resolution.max(1) as f32;
+            let snapped = light_rotation.transform_point3(center);
+            let snapped = Vec3::new(
+                (snapped.x / texel).floor() * texel,
+                (snapped.y / texel).floor() * texel,
+                snapped.z,
+            );
+            let center = light_rotation_inv.transform_point3(snapped);
+
+            let caster_distance = (radius * 2.0).max(MIN_CASTER_DISTANCE);
+            let position = center - direction * (radius + caster_distance);
+            let view = Mat4::look_at_rh(position, center, up);
+            let projection = Mat4::orthographic_rh(
+                -radius,
+                radius,
+                -radius,
+                radius,
+                0.0,
+                2.0 * radius + caster_distance,
+            );
+
+            ShadowCascade {
+                view,
+                projection,
+                position,
+                split_far: slice_far,
+            }
+        })
+        .collect()
+}
+
+/// Corners of the camera frustum, near plane first.
+fn frustum_corners(camera: &CameraUniform) -> [Vec3; 8] {
+    let mut corners = [Vec3::ZERO; 8];
+    for (i, corner) in corners.iter_mut().enumerate() {
+        let x = if i & 1 == 0 { -1.0 } else { 1.0 };
+        let y = if i & 2 == 0 { -1.0 } else { 1.0 };
+        let z = if i < 4 { 0.0 } else { 1.0 };
+        let world = camera.inv_proj_view_mat * Vec4::new(x, y, z, 1.0);
+        *corner = world.truncate() / world.w;
+    }
+    corners
+}
+
+/// Corners of the part of the frustum between the view distances `from` and `to`.
+fn slice_corners(corners: &[Vec3; 8], near: f32, far: f32, from: f32, to: f32) -> [Vec3; 8] {
+    let depth = (far - near).max(1e-6);
+    let t_from = ((from - near) / depth).clamp(0.0, 1.0);
+    let t_to = ((to - near) / depth).clamp(0.0, 1.0);
+
+    let mut slice = [Vec3::ZERO; 8];
+    for i in 0..4 {
+        let (near_corner, far_corner) = (corners[i], corners[i + 4]);
+        slice[i] = near_corner.lerp(far_corner, t_from);
+        slice[i + 4] = near_corner.lerp(far_corner, t_to);
+    }
+    slice
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera(position: Vec3) -> CameraUniform {
+        let mut camera = CameraUniform::empty();
+        let projection = Mat4::perspective_rh(60f32.to_radians(), 16.0 / 9.0, 0.1, 500.0);
+        let view = Mat4::look_at_rh(position, position + Vec3::NEG_Z, Vec3::Y);
+        camera.update(&projection, &position, &view);
+        camera.near = 0.1;
+        camera.far = 500.0;
+        camera
+    }
+
+    fn sun() -> LightProxy {
+        let mut light = LightProxy::dummy();
+        light.direction = Vec3::new(0.3, -1.0, 0.2).normalize();
+        light.shadow_cascades = 4;
+        light.shadow_distance = 100.0;
+        light.cascade_split_lambda = 0.75;
+        light
+    }
+
+    #[test]
+    fn splits_grow_towards_the_far_plane() {
+        let splits = cascade_splits(0.1, 100.0, 4, 0.75);
+        assert_eq!(splits.len(), 4);
+        assert!(splits.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!((splits[3] - 100.0).abs() < 1e-3);
+
+        let linear = cascade_splits(0.0, 100.0, 4, 0.0);
+        assert!((linear[0] - 25.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn cascades_cover_their_slice() {
+        let camera = camera(Vec3::new(3.0, 2.0, 1.0));
+        let cascades = fit_cascades(&camera, &sun(), 1024);
+        assert_eq!(cascades.len(), 4);
+
+        // a point in the middle of each slice lands inside its cascade
+        let mut slice_near = camera.near;
+        for cascade in &cascades {
+            let distance = (slice_near + cascade.split_far) * 0.5;
+            let point = camera.pos + Vec3::NEG_Z * distance;
+            let clip = cascade.projection * cascade.view * point.extend(1.0);
+            let ndc = clip.truncate() / clip.w;
+            assert!(ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0, "{ndc:?}");
+            assert!((0.0..=1.0).contains(&ndc.z), "{ndc:?}");
+            slice_near = cascade.split_far;
+        }
+    }
+
+    #[test]
+    fn cascades_only_move_in_whole_texels() {
+        let a = fit_cascades(&camera(Vec3::ZERO), &sun(), 1024);
+        let b = fit_cascades(&camera(Vec3::new(0.013, 0.0, 0.0)), &sun(), 1024);
+
+        for (a, b) in a.iter().zip(&b) {
+            let texel = 2.0 / a.projection.x_axis.x / 1024.0;
+            let offset = b.view.transform_point3(Vec3::ZERO) - a.view.transform_point3(Vec3::ZERO);
+            let in_texels = offset.truncate() / texel;
+            assert!(
+                (in_texels - in_texels.round()).abs().max_element() < 1e-2,
+                "{in_texels:?}"
+            );
+        }
+    }
+}
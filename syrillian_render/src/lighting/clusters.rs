@@ -0,0 +1,191 @@
+//! Clustered light culling.
+//!
+//! The view frustum is split into [`CLUSTER_GRID`] clusters: screen tiles that are cut into
+//! depth slices, spaced logarithmically between the near and far plane of the camera. Before the
+//! scene is drawn, a compute pass lists the point and spot lights that reach each cluster, so
+//! fragments only evaluate the lights of the cluster they're in.
+
+use crate::cache::AssetCache;
+use crate::rendering::render_data::RenderUniformData;
+use crate::rendering::uniform::ShaderUniform;
+use glamx::UVec3;
+use syrillian_asset::{HComputeShader, ensure_aligned};
+use syrillian_macros::UniformIndex;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{Buffer, BufferDescriptor, BufferUsages, CommandEncoder, ComputePassDescriptor, Device};
+
+/// Number of clusters along the screen width, the screen height and the view depth
+pub const CLUSTER_GRID: UVec3 = UVec3::new(16, 9, 24);
+/// Lights a cluster can list, more lights reaching a cluster are left out
+pub const MAX_LIGHTS_PER_CLUSTER: u32 = 63;
+
+const CLUSTER_COUNT: u32 = CLUSTER_GRID.x * CLUSTER_GRID.y * CLUSTER_GRID.z;
+/// Every cluster stores its light count followed by the light indices
+const CLUSTER_STRIDE: u32 = MAX_LIGHTS_PER_CLUSTER + 1;
+const CLUSTER_WORKGROUP_SIZE: u32 = 4;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightClusterParams {
+    pub grid: UVec3,
+    pub stride: u32,
+    pub light_count: u32,
+    /// Index of the sun that lights the sky, `u32::MAX` without one
+    pub primary_sun: u32,
+    /// Non-zero tints surfaces by the light count of their cluster
+    pub debug_view: u32,
+    pub _pad0: u32,
+}
+
+ensure_aligned!(LightClusterParams { grid }, align <= 16 * 2 => size);
+
+impl LightClusterParams {
+    pub fn new(light_count: u32, primary_sun: Option<usize>, debug_view: bool) -> Self {
+        Self {
+            grid: CLUSTER_GRID,
+            stride: CLUSTER_STRIDE,
+            light_count,
+            primary_sun: primary_sun.map_or(u32::MAX, |idx| idx as u32),
+            debug_view: debug_view as u32,
+            _pad0: 0,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, UniformIndex)]
+pub enum LightClusterComputeUniformIndex {
+    Params = 0,
+    Lights = 1,
+    Clusters = 2,
+}
+
+/// The depth slice of the cluster grid that a view depth falls into.
+pub fn cluster_slice(depth: f32, near: f32, far: f32) -> u32 {
+    let near = near.max(1e-4);
+    let far = far.max(near * 1.001);
+    let slice = (depth.max(near) / near).ln() / (far / near).ln() * CLUSTER_GRID.z as f32;
+    (slice.max(0.0) as u32).min(CLUSTER_GRID.z - 1)
+}
+
+/// The view depth where a depth slice of the cluster grid starts.
+pub fn cluster_slice_depth(slice: u32, near: f32, far: f32) -> f32 {
+    let near = near.max(1e-4);
+    let far = far.max(near * 1.001);
+    near * (far / near).powf(slice as f32 / CLUSTER_GRID.z as f32)
+}
+
+/// The parameter and light list buffers of the cluster grid, bound to the light group.
+pub struct LightClusters {
+    params: Buffer,
+    clusters: Buffer,
+}
+
+impl LightClusters {
+    pub fn new(device: &Device) -> Self {
+        let params = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Light Cluster Params"),
+            contents: bytemuck::bytes_of(&LightClusterParams::new(0, None, false)),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let clusters = device.create_buffer(&BufferDescriptor {
+            label: Some("Light Clusters"),
+            size: (CLUSTER_COUNT * CLUSTER_STRIDE) as u64 * size_of::<u32>() as u64,
+            usage: BufferUsages::STORAGE,
+            mapped_at_creation: false,
+        });
+
+        Self { params, clusters }
+    }
+
+    pub fn params(&self) -> &Buffer {
+        &self.params
+    }
+
+    pub fn clusters(&self) -> &Buffer {
+        &self.clusters
+    }
+
+    /// Binds the culling compute pass to the given light buffer.
+    pub fn compute_uniform(
+        &self,
+        cache: &AssetCache,
+        device: &Device,
+        lights: &Buffer,
+    ) -> ShaderUniform<LightClusterComputeUniformIndex> {
+        ShaderUniform::<LightClusterComputeUniformIndex>::builder(cache.bgl_light_cluster_compute())
+            .with_buffer(self.params.clone())
+            .with_storage_buffer(lights.clone())
+            .with_storage_buffer(self.clusters.clone())
+            .build(device)
+    }
+
+    /// Lists the lights of every cluster in the frustum of the camera.
+    pub fn cull(
+        &self,
+        encoder: &mut CommandEncoder,
+        cache: &AssetCache,
+        render_data: &RenderUniformData,
+        uniform: &ShaderUniform<LightClusterComputeUniformIndex>,
+    ) {
+        let shader = cache.compute_shader(HComputeShader::LIGHT_CLUSTERS);
+
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("Light Cluster Compute Pass"),
+            ..ComputePassDescriptor::default()
+        });
+        pass.set_pipeline(shader.pipeline());
+        pass.set_bind_group(0, render_data.uniform.bind_group(), &[]);
+        pass.set_bind_group(1, uniform.bind_group(), &[]);
+        pass.dispatch_workgroups(
+            CLUSTER_GRID.x.div_ceil(CLUSTER_WORKGROUP_SIZE),
+            CLUSTER_GRID.y.div_ceil(CLUSTER_WORKGROUP_SIZE),
+            CLUSTER_GRID.z.div_ceil(CLUSTER_WORKGROUP_SIZE),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slices_cover_near_to_far() {
+        let (near, far) = (0.1, 1000.0);
+
+        assert!((cluster_slice_depth(0, near, far) - near).abs() < 1e-5);
+        assert!((cluster_slice_depth(CLUSTER_GRID.z, near, far) - far).abs() < 1e-1);
+
+        assert_eq!(cluster_slice(0.0, near, far), 0);
+        assert_eq!(cluster_slice(near, near, far), 0);
+        assert_eq!(cluster_slice(far * 2.0, near, far), CLUSTER_GRID.z - 1);
+    }
+
+    #[test]
+    fn depths_fall_into_their_slice() {
+        let (near, far) = (0.1, 500.0);
+
+        let mut last = 0;
+        for slice in 0..CLUSTER_GRID.z {
+            let start = cluster_slice_depth(slice, near, far);
+            let end = cluster_slice_depth(slice + 1, near, far);
+            let mid = (start + end) * 0.5;
+
+            let found = cluster_slice(mid, near, far);
+            assert_eq!(found, slice);
+            assert!(found >= last);
+            last = found;
+        }
+    }
+
+    #[test]
+    fn params_without_sun_mark_it_missing() {
+        let params = LightClusterParams::new(12, None, false);
+        assert_eq!(params.primary_sun, u32::MAX);
+        assert_eq!(params.stride, MAX_LIGHTS_PER_CLUSTER + 1);
+
+        let params = LightClusterParams::new(12, Some(3), true);
+        assert_eq!(params.primary_sun, 3);
+        assert_eq!(params.debug_view, 1);
+    }
+}
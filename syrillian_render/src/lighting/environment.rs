@@ -0,0 +1,422 @@
+//! Image based lighting.
+//!
+//! The sky a viewport draws is captured into a small environment cubemap and preprocessed on the
+//! GPU into the maps that lit surfaces read their ambient light from: a cosine convolved
+//! irradiance map for diffuse light, a GGX prefiltered mip chain for specular reflections of
+//! increasing roughness, and a BRDF lookup table for the split sum approximation. The maps are
+//! regenerated when the skybox, the sky mode or the atmosphere changes, so a moving sun relights
+//! the scene.
+
+use crate::cache::{AssetCache, GpuTexture};
+use crate::rendering::render_data::{RenderUniformData, RenderUniformIndex, SkyUniform};
+use crate::rendering::uniform::ShaderUniform;
+use syrillian_asset::{HComputeShader, HCubemap, ensure_aligned};
+use syrillian_macros::UniformIndex;
+use wgpu::{
+    AddressMode, CommandEncoder, ComputePassDescriptor, Device, Extent3d, FilterMode,
+    MipmapFilterMode, Sampler, SamplerDescriptor, Texture, TextureDescriptor, TextureDimension,
+    TextureFormat, TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
+};
+
+/// Face size of the captured environment, which is mipmapped down to a single texel
+pub const ENVIRONMENT_SIZE: u32 = 128;
+/// Face size of the irradiance map
+pub const IRRADIANCE_SIZE: u32 = 32;
+/// Face size of the first mip of the specular map
+pub const SPECULAR_SIZE: u32 = 128;
+/// Mips of the specular map, spread evenly from perceptual roughness 0 to 1
+pub const SPECULAR_MIPS: u32 = 6;
+/// Size of the BRDF lookup table along the view angle and the roughness
+pub const BRDF_LUT_SIZE: u32 = 128;
+
+const ENVIRONMENT_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+const IBL_WORKGROUP_SIZE: u32 = 8;
+
+const IRRADIANCE_SAMPLES: u32 = 256;
+const SPECULAR_SAMPLES: u32 = 128;
+const BRDF_LUT_SAMPLES: u32 = 512;
+
+/// Cosine of the angle the sun can move before the maps are regenerated, about half a degree
+const SUN_DIRECTION_TOLERANCE: f32 = 0.99996;
+/// Relative change of the atmosphere settings that the maps are regenerated for
+const SKY_SETTING_TOLERANCE: f32 = 1e-3;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct IblParams {
+    /// Perceptual roughness the specular map is prefiltered for
+    pub roughness: f32,
+    pub sample_count: u32,
+    /// Face size of the first mip of the environment capture
+    pub environment_size: f32,
+    pub _pad0: f32,
+}
+
+ensure_aligned!(IblParams { roughness }, align <= 4 * 4 => size);
+
+impl IblParams {
+    pub fn new(roughness: f32, sample_count: u32) -> Self {
+        Self {
+            roughness,
+            sample_count,
+            environment_size: ENVIRONMENT_SIZE as f32,
+            _pad0: 0.0,
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, UniformIndex)]
+pub enum IblComputeUniformIndex {
+    Environment = 0,
+    Sampler = 1,
+    Output = 2,
+    Params = 3,
+    Source = 4,
+}
+
+/// The maps that lit shaders read image based lighting from.
+#[derive(Debug, Clone)]
+pub struct EnvironmentViews {
+    pub irradiance: TextureView,
+    pub specular: TextureView,
+    pub brdf_lut: TextureView,
+    pub sampler: Sampler,
+}
+
+impl EnvironmentViews {
+    /// Placeholder maps for passes that don't light anything with the environment.
+    pub fn fallback(cache: &AssetCache) -> Self {
+        let cubemap = cache.cubemap_fallback();
+        let texture = cache.texture_fallback();
+
+        Self {
+            irradiance: cubemap.view.clone(),
+            specular: cubemap.view.clone(),
+            brdf_lut: texture.view.clone(),
+            sampler: cubemap.sampler.clone(),
+        }
+    }
+}
+
+/// What the environment maps were generated from.
+#[derive(Debug, Copy, Clone)]
+pub struct EnvironmentSource {
+    pub skybox: Option<HCubemap>,
+    pub sky: SkyUniform,
+}
+
+impl EnvironmentSource {
+    /// Whether maps generated from `other` no longer fit this source. Tiny changes of the
+    /// atmosphere, like a sun that barely moved, keep the maps.
+    pub fn differs_from(&self, other: &EnvironmentSource) -> bool {
+        let (a, b) = (&self.sky, &other.sky);
+
+        if self.skybox != other.skybox || a.mode != b.mode {
+            return true;
+        }
+
+        let sun_a = a.sun_direction.normalize_or_zero();
+        let sun_b = b.sun_direction.normalize_or_zero();
+        if sun_a.dot(sun_b) < SUN_DIRECTION_TOLERANCE {
+            return true;
+        }
+
+        let changed =
+            |x: f32, y: f32| (x - y).abs() > SKY_SETTING_TOLERANCE * x.abs().max(y.abs()).max(1.0);
+        changed(a.sun_intensity, b.sun_intensity)
+            || changed(a.sun_strength, b.sun_strength)
+            || changed(a.altitude, b.altitude)
+            || changed(a.air_density, b.air_density)
+            || changed(a.aerosols, b.aerosols)
+    }
+}
+
+struct IblDispatch {
+    shader: HComputeShader,
+    uniform: ShaderUniform<IblComputeUniformIndex>,
+    size: u32,
+    layers: u32,
+}
+
+/// The image based lighting maps of one viewport and the compute passes that generate them.
+pub struct EnvironmentLighting {
+    views: EnvironmentViews,
+    environment_passes: Vec<IblDispatch>,
+    brdf_lut_pass: IblDispatch,
+    brdf_lut_ready: bool,
+    source: Option<EnvironmentSource>,
+    _textures: [Texture; 4],
+}
+
+impl EnvironmentLighting {
+    pub fn new(device: &Device, cache: &AssetCache) -> Self {
+        let environment_mips = ENVIRONMENT_SIZE.ilog2() + 1;
+        let environment = create_map(
+            device,
+            "IBL Environment",
+            ENVIRONMENT_SIZE,
+            environment_mips,
+            6,
+        );
+        let irradiance = create_map(device, "IBL Irradiance", IRRADIANCE_SIZE, 1, 6);
+        let specular = create_map(device, "IBL Specular", SPECULAR_SIZE, SPECULAR_MIPS, 6);
+        let brdf_lut = create_map(device, "IBL BRDF LUT", BRDF_LUT_SIZE, 1, 1);
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("IBL Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: MipmapFilterMode::Linear,
+            ..SamplerDescriptor::default()
+        });
+
+        let views = EnvironmentViews {
+            irradiance: cube_view(&irradiance),
+            specular: cube_view(&specular),
+            brdf_lut: brdf_lut.create_view(&TextureViewDescriptor {
+                label: Some("IBL BRDF LUT View"),
+                dimension: Some(TextureViewDimension::D2),
+                ..TextureViewDescriptor::default()
+            }),
+            sampler: sampler.clone(),
+        };
+
+        let environment_cube = cube_view(&environment);
+        let environment_source = mip_view(&environment, 0);
+
+        // every pass binds all inputs, the ones it doesn't read point at maps it isn't writing
+        let dispatch = |shader: HComputeShader,
+                        environment: &TextureView,
+                        output: TextureView,
+                        params: IblParams,
+                        source: TextureView,
+                        size: u32,
+                        layers: u32| IblDispatch {
+            shader,
+            uniform: ShaderUniform::<IblComputeUniformIndex>::builder(cache.bgl_ibl_compute())
+                .with_texture(environment.clone())
+                .with_sampler(sampler.clone())
+                .with_texture(output)
+                .with_buffer_data(&params)
+                .with_texture(source)
+                .build(device),
+            size,
+            layers,
+        };
+
+        let mut environment_passes = vec![dispatch(
+            HComputeShader::IBL_CAPTURE,
+            &views.irradiance,
+            mip_view(&environment, 0),
+            IblParams::new(0.0, 0),
+            mip_view(&irradiance, 0),
+            ENVIRONMENT_SIZE,
+            6,
+        )];
+
+        for mip in 1..environment_mips {
+            environment_passes.push(dispatch(
+                HComputeShader::IBL_DOWNSAMPLE,
+                &views.irradiance,
+                mip_view(&environment, mip),
+                IblParams::new(0.0, 0),
+                mip_view(&environment, mip - 1),
+                (ENVIRONMENT_SIZE >> mip).max(1),
+                6,
+            ));
+        }
+
+        environment_passes.push(dispatch(
+            HComputeShader::IBL_IRRADIANCE,
+            &environment_cube,
+            mip_view(&irradiance, 0),
+            IblParams::new(0.0, IRRADIANCE_SAMPLES),
+            environment_source.clone(),
+            IRRADIANCE_SIZE,
+            6,
+        ));
+
+        for mip in 0..SPECULAR_MIPS {
+            let roughness = mip as f32 / (SPECULAR_MIPS - 1) as f32;
+            environment_passes.push(dispatch(
+                HComputeShader::IBL_PREFILTER,
+                &environment_cube,
+                mip_view(&specular, mip),
+                IblParams::new(roughness, SPECULAR_SAMPLES),
+                environment_source.clone(),
+                (SPECULAR_SIZE >> mip).max(1),
+                6,
+            ));
+        }
+
+        let brdf_lut_pass = dispatch(
+            HComputeShader::IBL_BRDF_LUT,
+            &views.irradiance,
+            mip_view(&brdf_lut, 0),
+            IblParams::new(0.0, BRDF_LUT_SAMPLES),
+            environment_source,
+            BRDF_LUT_SIZE,
+            1,
+        );
+
+        Self {
+            views,
+            environment_passes,
+            brdf_lut_pass,
+            brdf_lut_ready: false,
+            source: None,
+            _textures: [environment, irradiance, specular, brdf_lut],
+        }
+    }
+
+    pub fn views(&self) -> &EnvironmentViews {
+        &self.views
+    }
+
+    /// Whether the maps have to be regenerated to match the given source.
+    pub fn needs_refresh(&self, source: &EnvironmentSource) -> bool {
+        self.source
+            .as_ref()
+            .is_none_or(|current| current.differs_from(source))
+    }
+
+    /// Captures the sky and regenerates the maps from it. `skybox` is the cubemap that's bound
+    /// to the viewport for `source`.
+    pub fn generate(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        device: &Device,
+        cache: &AssetCache,
+        render_data: &RenderUniformData,
+        skybox: &GpuTexture,
+        source: EnvironmentSource,
+    ) {
+        // the viewport's own render group samples the maps written here, so the passes get a
+        // render group with the same buffers and placeholder maps instead
+        let fallback = EnvironmentViews::fallback(cache);
+        let render_uniform = ShaderUniform::<RenderUniformIndex>::builder(cache.bgl_render())
+            .with_buffer(
+                render_data
+                    .uniform
+                    .buffer(RenderUniformIndex::Camera)
+                    .clone(),
+            )
+            .with_buffer(
+                render_data
+                    .uniform
+                    .buffer(RenderUniformIndex::System)
+                    .clone(),
+            )
+            .with_texture(skybox.view.clone())
+            .with_sampler(skybox.sampler.clone())
+            .with_buffer(render_data.uniform.buffer(RenderUniformIndex::Sky).clone())
+            .with_texture(fallback.irradiance)
+            .with_texture(fallback.specular)
+            .with_texture(fallback.brdf_lut)
+            .with_sampler(fallback.sampler)
+            .build(device);
+
+        let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("IBL Compute Pass"),
+            ..ComputePassDescriptor::default()
+        });
+        pass.set_bind_group(0, render_uniform.bind_group(), &[]);
+
+        let lut = (!self.brdf_lut_ready).then_some(&self.brdf_lut_pass);
+        for step in self.environment_passes.iter().chain(lut) {
+            let shader = cache.compute_shader(step.shader);
+            let groups = step.size.div_ceil(IBL_WORKGROUP_SIZE);
+
+            pass.set_pipeline(shader.pipeline());
+            pass.set_bind_group(1, step.uniform.bind_group(), &[]);
+            pass.dispatch_workgroups(groups, groups, step.layers);
+        }
+
+        self.brdf_lut_ready = true;
+        self.source = Some(source);
+    }
+}
+
+fn create_map(device: &Device, label: &str, size: u32, mips: u32, layers: u32) -> Texture {
+    device.create_texture(&TextureDescriptor {
+        label: Some(label),
+        size: Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: layers,
+        },
+        mip_level_count: mips,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: ENVIRONMENT_FORMAT,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::STORAGE_BINDING,
+        view_formats: &[],
+    })
+}
+
+fn cube_view(texture: &Texture) -> TextureView {
+    texture.create_view(&TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..TextureViewDescriptor::default()
+    })
+}
+
+/// All layers of a single mip, as written by the compute passes
+fn mip_view(texture: &Texture, mip: u32) -> TextureView {
+    texture.create_view(&TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::D2Array),
+        base_mip_level: mip,
+        mip_level_count: Some(1),
+        ..TextureViewDescriptor::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::render_data::{SkyAtmosphereSettings, SkyboxMode};
+
+    fn procedural(settings: SkyAtmosphereSettings) -> EnvironmentSource {
+        EnvironmentSource {
+            skybox: None,
+            sky: SkyUniform::from_mode_and_settings(SkyboxMode::Procedural, settings),
+        }
+    }
+
+    #[test]
+    fn identical_sources_keep_the_maps() {
+        let source = procedural(SkyAtmosphereSettings::default());
+        assert!(!source.differs_from(&source));
+    }
+
+    #[test]
+    fn moving_sun_regenerates_the_maps() {
+        let settings = SkyAtmosphereSettings::default();
+        let source = procedural(settings);
+
+        let mut nudged = settings;
+        nudged.sun_rotation += 0.001;
+        assert!(!source.differs_from(&procedural(nudged)));
+
+        let mut moved = settings;
+        moved.sun_rotation += 0.1;
+        assert!(source.differs_from(&procedural(moved)));
+    }
+
+    #[test]
+    fn skybox_and_mode_changes_regenerate_the_maps() {
+        let source = procedural(SkyAtmosphereSettings::default());
+
+        let mut cubemap = source;
+        cubemap.sky.mode = SkyboxMode::Cubemap.as_raw();
+        assert!(source.differs_from(&cubemap));
+
+        let mut skybox = source;
+        skybox.skybox = Some(HCubemap::FALLBACK);
+        assert!(source.differs_from(&skybox));
+    }
+}
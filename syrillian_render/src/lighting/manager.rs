@@ -1,7 +1,13 @@
 use crate::cache::AssetCache;
+use crate::lighting::cascades::{MAX_SHADOW_CASCADES, ShadowCascade, fit_cascades};
+use crate::lighting::clusters::{
+    LightClusterComputeUniformIndex, LightClusterParams, LightClusters,
+};
+use crate::lighting::environment::EnvironmentViews;
 use crate::lighting::proxy::{LightProxy, LightType, LightUniformIndex, ShadowUniformIndex};
+use crate::rendering::debug_renderer::DebugRenderer;
 use crate::rendering::message::LightProxyCommand;
-use crate::rendering::render_data::RenderUniformData;
+use crate::rendering::render_data::{CameraUniform, RenderUniformData};
 #[cfg(debug_assertions)]
 use crate::rendering::renderer::Renderer;
 use crate::rendering::uniform::ShaderUniform;
@@ -14,8 +20,8 @@ use syrillian_asset::{HRenderTexture2DArray, RenderTexture2DArray};
 use syrillian_utils::{TypedComponentId, debug_panic};
 use tracing::{trace, warn};
 use wgpu::{
-    AddressMode, Device, FilterMode, MipmapFilterMode, Queue, Sampler, SamplerDescriptor,
-    TextureUsages, TextureView, TextureViewDescriptor,
+    AddressMode, CommandEncoder, Device, FilterMode, MipmapFilterMode, Queue, Sampler,
+    SamplerDescriptor, TextureUsages, TextureView, TextureViewDescriptor,
 };
 
 const DUMMY_POINT_LIGHT: LightProxy = LightProxy::dummy();
@@ -27,11 +33,16 @@ pub struct LightManager {
     render_data: Vec<RenderUniformData>,
 
     uniform: ShaderUniform<LightUniformIndex>,
+    clusters: LightClusters,
+    cluster_uniform: ShaderUniform<LightClusterComputeUniformIndex>,
     shadow_uniform: ShaderUniform<ShadowUniformIndex>,
     empty_shadow_uniform: ShaderUniform<ShadowUniformIndex>,
     pub shadow_texture: HRenderTexture2DArray,
     pub _shadow_sampler: Sampler,
     shadow_matrices: Vec<Mat4>,
+    shadow_resolution: u32,
+    /// Camera that sun light cascades are fit to
+    view_camera: CameraUniform,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -53,6 +64,10 @@ impl LightManager {
 
         let render_bgl = cache.bgl_render();
         let fallback_skybox = cache.cubemap_fallback();
+        let fallback_environment = EnvironmentViews::fallback(cache);
+
+        // only the primary sun lights the scene, so only it gets cascades
+        let primary_sun = self.primary_sun_index();
 
         let mut next_layer = 0;
         for (idx, light) in self.proxies.iter_mut().enumerate() {
@@ -60,6 +75,9 @@ impl LightManager {
             let required_layers = match light_type {
                 LightType::Point => 6,
                 LightType::Spot => 1,
+                LightType::Sun if primary_sun == Some(idx) => {
+                    light.shadow_cascades.min(MAX_SHADOW_CASCADES)
+                }
                 LightType::Sun => 0,
             };
 
@@ -93,6 +111,7 @@ impl LightManager {
                     &render_bgl,
                     fallback_skybox.view.clone(),
                     fallback_skybox.sampler.clone(),
+                    &fallback_environment,
                 ));
             }
 
@@ -193,15 +212,25 @@ impl LightManager {
     }
 
     pub fn primary_sun(&self) -> Option<LightProxy> {
+        self.primary_sun_index().map(|idx| self.proxies[idx])
+    }
+
+    fn primary_sun_index(&self) -> Option<usize> {
         self.proxies
             .iter()
-            .filter(|proxy| LightType::try_from(proxy.type_id).ok() == Some(LightType::Sun))
-            .max_by(|a, b| {
+            .enumerate()
+            .filter(|(_, proxy)| LightType::try_from(proxy.type_id).ok() == Some(LightType::Sun))
+            .max_by(|(_, a), (_, b)| {
                 a.intensity
                     .partial_cmp(&b.intensity)
                     .unwrap_or(std::cmp::Ordering::Equal)
             })
-            .copied()
+            .map(|(idx, _)| idx)
+    }
+
+    /// Sets the camera that the cascades of the sun are fit to.
+    pub fn set_view_camera(&mut self, camera: &CameraUniform) {
+        self.view_camera = *camera;
     }
 
     #[profiling::function]
@@ -220,6 +249,7 @@ impl LightManager {
             .unwrap();
         let shadow_matrices =
             vec![Mat4::IDENTITY; texture.size.depth_or_array_layers.max(1) as usize];
+        let shadow_resolution = texture.size.width.max(1);
         let shadow_texel = [
             1.0 / texture.size.width.max(1) as f32,
             1.0 / texture.size.height.max(1) as f32,
@@ -231,10 +261,15 @@ impl LightManager {
 
         let bgl = cache.bgl_light();
         let count: u32 = 0;
+        let clusters = LightClusters::new(device);
         let uniform = ShaderUniform::builder(bgl)
             .with_buffer_data(&count)
             .with_storage_buffer_data(&[DUMMY_POINT_LIGHT])
+            .with_buffer(clusters.params().clone())
+            .with_storage_buffer(clusters.clusters().clone())
             .build(device);
+        let cluster_uniform =
+            clusters.compute_uniform(cache, device, uniform.buffer(LightUniformIndex::Lights));
 
         let shadow_sampler = device.create_sampler(&SamplerDescriptor {
             label: None,
@@ -272,11 +307,15 @@ impl LightManager {
             shadow_assignments: vec![],
             render_data: vec![],
             uniform,
+            clusters,
+            cluster_uniform,
             shadow_uniform,
             empty_shadow_uniform,
             shadow_texture,
             _shadow_sampler: shadow_sampler,
             shadow_matrices,
+            shadow_resolution,
+            view_camera: CameraUniform::empty(),
         }
     }
 
@@ -291,6 +330,17 @@ impl LightManager {
 
         self.shadow_matrices.fill(Mat4::IDENTITY);
 
+        let cascades: Vec<ShadowCascade> = self
+            .primary_sun_index()
+            .map(|idx| {
+                fit_cascades(
+                    &self.view_camera,
+                    &self.proxies[idx],
+                    self.shadow_resolution,
+                )
+            })
+            .unwrap_or_default();
+
         for (render_data, assignment) in self
             .render_data
             .iter_mut()
@@ -311,7 +361,10 @@ impl LightManager {
                     render_data.update_shadow_camera_for_point(light, assignment.face, queue)
                 }
                 LightType::Spot => render_data.update_shadow_camera_for_spot(light, queue),
-                LightType::Sun => (),
+                LightType::Sun => match cascades.get(assignment.face as usize) {
+                    Some(cascade) => render_data.update_shadow_camera_for_cascade(cascade, queue),
+                    None => debug_panic!("Sun light shadow cascade was not fit"),
+                },
             }
 
             if let Some(shadow_mat) = self.shadow_matrices.get_mut(assignment.layer as usize) {
@@ -338,12 +391,40 @@ impl LightManager {
             self.uniform = ShaderUniform::builder(bgl)
                 .with_buffer(count.clone())
                 .with_storage_buffer_data(proxies)
+                .with_buffer(self.clusters.params().clone())
+                .with_storage_buffer(self.clusters.clusters().clone())
                 .build(device);
+            self.cluster_uniform = self.clusters.compute_uniform(
+                cache,
+                device,
+                self.uniform.buffer(LightUniformIndex::Lights),
+            );
         } else {
             queue.write_buffer(data, 0, bytemuck::cast_slice(proxies));
         }
     }
 
+    /// Bins the point and spot lights into the clusters of the camera's view frustum, which
+    /// the lit shaders read their lights from.
+    #[profiling::function]
+    pub fn cull_lights(
+        &self,
+        encoder: &mut CommandEncoder,
+        cache: &AssetCache,
+        queue: &Queue,
+        render_data: &RenderUniformData,
+    ) {
+        let params = LightClusterParams::new(
+            self.proxies.len() as u32,
+            self.primary_sun_index(),
+            DebugRenderer::light_clusters(),
+        );
+        queue.write_buffer(self.clusters.params(), 0, bytemuck::bytes_of(&params));
+
+        self.clusters
+            .cull(encoder, cache, render_data, &self.cluster_uniform);
+    }
+
     #[cfg(debug_assertions)]
     pub fn render_debug_lights(&self, renderer: &Renderer, ctx: &crate::rendering::GPUDrawCtx) {
         use syrillian_asset::HShader;
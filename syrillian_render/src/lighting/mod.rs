@@ -1,2 +1,5 @@
+pub mod cascades;
+pub mod clusters;
+pub mod environment;
 pub mod manager;
 pub mod proxy;
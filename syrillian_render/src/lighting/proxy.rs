@@ -21,6 +21,14 @@ pub struct LightProxy {
     pub type_id: u32, // LightType
     pub shadow_map_id: u32,
     pub shadow_mat_base: u32,
+    /// Number of shadow cascades of a sun light, 0 disables its shadows
+    pub shadow_cascades: u32,
+    /// Blend between even (0) and logarithmic (1) cascade splits
+    pub cascade_split_lambda: f32,
+    /// View distance up to which a sun light casts shadows
+    pub shadow_distance: f32,
+    /// Part of a cascade's border that is blended into the next cascade, from 0 to 1
+    pub cascade_blend: f32,
     pub _p1: u32,
 }
 
@@ -42,12 +50,16 @@ impl LightProxy {
             type_id: LightType::Point as u32,
             shadow_map_id: u32::MAX,
             shadow_mat_base: u32::MAX,
+            shadow_cascades: 0,
+            cascade_split_lambda: 0.75,
+            shadow_distance: 150.0,
+            cascade_blend: 0.1,
             _p1: 0,
         }
     }
 }
 
-ensure_aligned!(LightProxy { position, up, direction, color }, align <= 16 * 6 => size);
+ensure_aligned!(LightProxy { position, up, direction, color }, align <= 16 * 7 => size);
 
 #[repr(u32)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq, TryFromPrimitive)]
@@ -62,6 +74,8 @@ pub enum LightType {
 pub enum LightUniformIndex {
     Count = 0,
     Lights = 1,
+    ClusterParams = 2,
+    Clusters = 3,
 }
 
 #[repr(u8)]
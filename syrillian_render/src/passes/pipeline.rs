@@ -1,8 +1,11 @@
 use crate::cache::AssetCache;
 use crate::passes::post_process::{
-    BloomRenderPass, BloomSettings, FinalRenderPass, FxaaRenderPass, PostProcessPass,
-    PostProcessPassContext, PostProcessRoute, PostProcessSharedViews,
-    ScreenSpaceAmbientOcclusionRenderPass, ScreenSpaceReflectionRenderPass,
+    BloomRenderPass, BloomSettings, ColorGradingSettings, CustomPostProcess,
+    DepthOfFieldRenderPass, ExposureSettings, FinalRenderPass, FxaaRenderPass,
+    MotionBlurRenderPass, PostProcessId, PostProcessParams, PostProcessPass,
+    PostProcessPassContext, PostProcessRoute, PostProcessSharedViews, PostProcessStage,
+    ScreenSpaceAmbientOcclusionRenderPass, ScreenSpaceReflectionRenderPass, TaaRenderPass,
+    ToneMapRenderPass, VelocityRenderPass, taa_jitter,
 };
 use crate::passes::ui_pass::UiRenderPass;
 use crate::rendering::offscreen_surface::OffscreenSurface;
@@ -11,6 +14,7 @@ use crate::rendering::renderer::RenderedFrame;
 use crate::rendering::state::State;
 use crate::rendering::viewport::{RenderViewport, ViewportId};
 use crate::strobe::StrobeRenderer;
+use glamx::{Mat4, Vec2};
 use syrillian_utils::{AntiAliasingMode, EngineArgs};
 use wgpu::{
     CommandEncoder, Device, Extent3d, Queue, SurfaceConfiguration, Texture, TextureDescriptor,
@@ -22,13 +26,18 @@ const COLOR_ID_BASE: u32 = 0;
 const COLOR_ID_POST_A: u32 = 1;
 const COLOR_ID_POST_B: u32 = 2;
 const COLOR_ID_FINAL_A: u32 = 3;
+const COLOR_ID_DISPLAY_A: u32 = 4;
+const COLOR_ID_DISPLAY_B: u32 = 5;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct PostProcessRouting {
     run_ssr: bool,
     run_ssao: bool,
+    run_dof: bool,
+    run_motion_blur: bool,
     run_bloom: bool,
     run_fxaa: bool,
+    run_taa: bool,
 }
 
 impl PostProcessRouting {
@@ -36,8 +45,11 @@ impl PostProcessRouting {
         Self {
             run_ssr: !EngineArgs::get().no_ssr,
             run_ssao: !EngineArgs::get().no_ssao,
+            run_dof: !EngineArgs::get().no_dof,
+            run_motion_blur: !EngineArgs::get().no_motion_blur,
             run_bloom: !EngineArgs::get().no_bloom,
             run_fxaa: matches!(EngineArgs::aa_mode(), AntiAliasingMode::Fxaa),
+            run_taa: matches!(EngineArgs::aa_mode(), AntiAliasingMode::Taa),
         }
     }
 }
@@ -45,9 +57,56 @@ impl PostProcessRouting {
 struct ActivePostProcessRoutes {
     ssr: PostProcessRoute,
     ssao: PostProcessRoute,
+    dof: PostProcessRoute,
+    motion_blur: PostProcessRoute,
     bloom: PostProcessRoute,
     fxaa: PostProcessRoute,
+    taa: PostProcessRoute,
+    tonemap: PostProcessRoute,
     final_pass: PostProcessRoute,
+    /// Routes of the custom passes, `None` for disabled passes
+    custom: Vec<Option<PostProcessRoute>>,
+}
+
+/// Hands out the ping-pong targets of the post process chain, one pass after the other.
+struct RouteChain {
+    current_id: u32,
+    current_view: TextureView,
+    write_to_a: bool,
+    post_a_view: TextureView,
+    post_b_view: TextureView,
+}
+
+impl RouteChain {
+    fn next(&mut self) -> PostProcessRoute {
+        let (output_id, output_view) = if self.write_to_a {
+            (COLOR_ID_POST_A, self.post_a_view.clone())
+        } else {
+            (COLOR_ID_POST_B, self.post_b_view.clone())
+        };
+        self.write_to_a = !self.write_to_a;
+
+        let route = PostProcessRoute {
+            input_id: self.current_id,
+            output_id,
+            input_color: self.current_view.clone(),
+            output_color: output_view.clone(),
+        };
+        self.current_id = output_id;
+        self.current_view = output_view;
+        route
+    }
+
+    fn next_custom(
+        &mut self,
+        custom: &[CustomPostProcess],
+        stage: PostProcessStage,
+        routes: &mut Vec<Option<PostProcessRoute>>,
+    ) {
+        for pass in custom.iter().filter(|pass| pass.stage == stage) {
+            routes.push(pass.enabled.then(|| self.next()));
+        }
+    }
 }
 
 pub struct FinalFrameContext<'a> {
@@ -63,7 +122,10 @@ pub struct RenderPipeline {
     pub depth_texture: Texture,
     pub offscreen_surface: OffscreenSurface,
     pub final_surfaces: [OffscreenSurface; 2],
+    /// HDR ping-pong targets of the passes before tonemapping
     post_process_surfaces: [OffscreenSurface; 2],
+    /// Display range targets after tonemapping, written by the tonemap pass and then FXAA
+    display_surfaces: [OffscreenSurface; 2],
     pub g_normal: Texture,
     pub g_material: Texture,
     pub g_velocity: Texture,
@@ -71,13 +133,25 @@ pub struct RenderPipeline {
 
     pub ssr_pass: ScreenSpaceReflectionRenderPass,
     pub ssao_pass: ScreenSpaceAmbientOcclusionRenderPass,
+    pub dof_pass: DepthOfFieldRenderPass,
+    pub motion_blur_pass: MotionBlurRenderPass,
     pub fxaa_pass: FxaaRenderPass,
+    pub taa_pass: TaaRenderPass,
+    pub velocity_pass: VelocityRenderPass,
     pub bloom_pass: BloomRenderPass,
+    pub tonemap_pass: ToneMapRenderPass,
     pub final_pass: FinalRenderPass,
 
+    custom_passes: Vec<CustomPostProcess>,
+    custom_routes_dirty: bool,
+
     route_key: PostProcessRouting,
     bloom_settings: BloomSettings,
     bloom_settings_dirty: bool,
+    exposure_settings: ExposureSettings,
+    color_grading: ColorGradingSettings,
+    /// The unjittered view projection of the last frame, `None` after a camera cut
+    prev_view_proj: Option<Mat4>,
 }
 
 impl RenderPipeline {
@@ -98,7 +172,7 @@ impl RenderPipeline {
         let offscreen_surface = OffscreenSurface::new_with(
             device,
             config,
-            TextureFormat::Rgba8Unorm,
+            TextureFormat::Rgba16Float,
             TextureUsages::empty(),
         );
 
@@ -106,15 +180,30 @@ impl RenderPipeline {
             OffscreenSurface::new_with(
                 device,
                 config,
-                TextureFormat::Rgba8Unorm,
+                TextureFormat::Rgba16Float,
                 TextureUsages::STORAGE_BINDING,
             ),
+            OffscreenSurface::new_with(
+                device,
+                config,
+                TextureFormat::Rgba16Float,
+                TextureUsages::STORAGE_BINDING,
+            ),
+        ];
+
+        let display_surfaces = [
             OffscreenSurface::new_with(
                 device,
                 config,
                 TextureFormat::Rgba8Unorm,
                 TextureUsages::STORAGE_BINDING,
             ),
+            OffscreenSurface::new_with(
+                device,
+                config,
+                TextureFormat::Rgba8Unorm,
+                TextureUsages::empty(),
+            ),
         ];
 
         let final_surfaces = [
@@ -127,9 +216,14 @@ impl RenderPipeline {
 
         let routes = Self::compose_routes(
             routing,
+            &[],
             offscreen_surface.view().clone(),
             post_process_surfaces[0].view().clone(),
             post_process_surfaces[1].view().clone(),
+            [
+                display_surfaces[0].view().clone(),
+                display_surfaces[1].view().clone(),
+            ],
             final_surfaces[0].view().clone(),
         );
 
@@ -151,6 +245,19 @@ impl RenderPipeline {
             &routes.ssao,
         );
 
+        let dof_pass = DepthOfFieldRenderPass::new(
+            device,
+            cache.bgl_post_process_compute(),
+            &shared_views,
+            &routes.dof,
+        );
+        let motion_blur_pass = MotionBlurRenderPass::new(
+            device,
+            cache.bgl_post_process_compute(),
+            &shared_views,
+            &routes.motion_blur,
+        );
+
         let bloom_pass = BloomRenderPass::new(
             device,
             size.width,
@@ -161,6 +268,24 @@ impl RenderPipeline {
         );
 
         let fxaa_pass = FxaaRenderPass::new(device, &pp_bgl, &shared_views, &routes.fxaa);
+        let taa_pass = TaaRenderPass::new(
+            device,
+            size.width,
+            size.height,
+            cache.bgl_taa_compute(),
+            &shared_views,
+            &routes.taa,
+        );
+        let velocity_pass =
+            VelocityRenderPass::new(device, cache.bgl_velocity_compute(), &shared_views);
+
+        let tonemap_pass = ToneMapRenderPass::new(
+            device,
+            cache,
+            cache.bgl_exposure_compute(),
+            cache.bgl_tonemap_compute(),
+            &routes.tonemap,
+        );
 
         let final_pass = FinalRenderPass::new(device, &pp_bgl, &shared_views, &routes.final_pass);
 
@@ -170,25 +295,104 @@ impl RenderPipeline {
             offscreen_surface,
             final_surfaces,
             post_process_surfaces,
+            display_surfaces,
             g_normal: normal_texture,
             g_material: material_texture,
             g_velocity: velocity_texture,
             shared_views,
             ssr_pass,
             ssao_pass,
+            dof_pass,
+            motion_blur_pass,
             fxaa_pass,
+            taa_pass,
+            velocity_pass,
             bloom_pass,
+            tonemap_pass,
             final_pass,
+            custom_passes: Vec::new(),
+            custom_routes_dirty: false,
             route_key: routing,
             bloom_settings,
             bloom_settings_dirty: false,
+            exposure_settings: ExposureSettings::default(),
+            color_grading: ColorGradingSettings::default(),
+            prev_view_proj: None,
         }
     }
 
     pub fn recreate(&mut self, device: &Device, cache: &AssetCache, config: &SurfaceConfiguration) {
         let bloom_settings = self.bloom_settings;
+        let exposure_settings = self.exposure_settings;
+        let color_grading = self.color_grading;
+        let custom_passes = std::mem::take(&mut self.custom_passes);
         *self = Self::new(device, cache, config);
         self.set_bloom_settings(bloom_settings);
+        self.exposure_settings = exposure_settings;
+        self.color_grading = color_grading;
+        self.custom_passes = custom_passes;
+        self.custom_routes_dirty = !self.custom_passes.is_empty();
+    }
+
+    /// Adds a custom pass to the post process chain. Passes of the same stage run by ascending
+    /// `order`, and in the order they were added if that is equal as well.
+    pub fn add_custom_pass(
+        &mut self,
+        id: PostProcessId,
+        stage: PostProcessStage,
+        order: i32,
+        pass: Box<dyn PostProcessPass + Send>,
+    ) {
+        self.custom_passes.retain(|custom| custom.id != id);
+        self.custom_passes.push(CustomPostProcess {
+            id,
+            stage,
+            order,
+            enabled: true,
+            pass,
+        });
+        self.custom_passes
+            .sort_by_key(|custom| (custom.stage, custom.order));
+        self.custom_routes_dirty = true;
+    }
+
+    /// Removes a custom pass. Returns false if this pipeline has no pass with the id.
+    pub fn remove_custom_pass(&mut self, id: PostProcessId) -> bool {
+        let count = self.custom_passes.len();
+        self.custom_passes.retain(|custom| custom.id != id);
+        let removed = self.custom_passes.len() != count;
+        self.custom_routes_dirty |= removed;
+        removed
+    }
+
+    /// Turns a custom pass on or off. Returns false if this pipeline has no pass with the id.
+    pub fn set_custom_pass_enabled(&mut self, id: PostProcessId, enabled: bool) -> bool {
+        let Some(custom) = self.custom_passes.iter_mut().find(|custom| custom.id == id) else {
+            return false;
+        };
+        if custom.enabled != enabled {
+            custom.enabled = enabled;
+            self.custom_routes_dirty = true;
+        }
+        true
+    }
+
+    /// Sets the parameters of a custom pass. Returns false if this pipeline has no pass with the
+    /// id.
+    pub fn set_custom_pass_params(
+        &mut self,
+        id: PostProcessId,
+        params: &PostProcessParams,
+    ) -> bool {
+        let Some(custom) = self.custom_passes.iter_mut().find(|custom| custom.id == id) else {
+            return false;
+        };
+        custom.pass.set_params(params);
+        true
+    }
+
+    pub fn has_custom_pass(&self, id: PostProcessId) -> bool {
+        self.custom_passes.iter().any(|custom| custom.id == id)
     }
 
     #[inline]
@@ -198,9 +402,11 @@ impl RenderPipeline {
 
     fn compose_routes(
         key: PostProcessRouting,
+        custom_passes: &[CustomPostProcess],
         base_view: TextureView,
         post_a_view: TextureView,
         post_b_view: TextureView,
+        display_views: [TextureView; 2],
         final_a_view: TextureView,
     ) -> ActivePostProcessRoutes {
         let default_route = PostProcessRoute {
@@ -210,86 +416,87 @@ impl RenderPipeline {
             output_color: post_a_view.clone(),
         };
 
-        let mut ssr = default_route.clone();
-        let mut ssao = default_route.clone();
-        let mut bloom = default_route.clone();
-        let mut fxaa = default_route.clone();
-
-        let mut current_id = COLOR_ID_BASE;
-        let mut current_view = base_view;
-        let mut write_to_a = true;
-
-        let mut next_output = || {
-            if write_to_a {
-                write_to_a = false;
-                (COLOR_ID_POST_A, post_a_view.clone())
+        let mut chain = RouteChain {
+            current_id: COLOR_ID_BASE,
+            current_view: base_view,
+            write_to_a: true,
+            post_a_view,
+            post_b_view,
+        };
+        let mut custom = Vec::with_capacity(custom_passes.len());
+        let route_if = |chain: &mut RouteChain, run: bool| {
+            if run {
+                chain.next()
             } else {
-                write_to_a = true;
-                (COLOR_ID_POST_B, post_b_view.clone())
+                default_route.clone()
             }
         };
 
-        if key.run_ssr {
-            let (output_id, output_view) = next_output();
-            ssr = PostProcessRoute {
-                input_id: current_id,
-                output_id,
-                input_color: current_view.clone(),
-                output_color: output_view.clone(),
-            };
-            current_id = output_id;
-            current_view = output_view;
-        }
-
-        if key.run_ssao {
-            let (output_id, output_view) = next_output();
-            ssao = PostProcessRoute {
-                input_id: current_id,
-                output_id,
-                input_color: current_view.clone(),
-                output_color: output_view.clone(),
-            };
-            current_id = output_id;
-            current_view = output_view;
-        }
-
-        if key.run_bloom {
-            let (output_id, output_view) = next_output();
-            bloom = PostProcessRoute {
-                input_id: current_id,
-                output_id,
-                input_color: current_view.clone(),
-                output_color: output_view.clone(),
-            };
-            current_id = output_id;
-            current_view = output_view;
-        }
+        chain.next_custom(
+            custom_passes,
+            PostProcessStage::BeforeReflections,
+            &mut custom,
+        );
+        let ssr = route_if(&mut chain, key.run_ssr);
+        chain.next_custom(
+            custom_passes,
+            PostProcessStage::AfterReflections,
+            &mut custom,
+        );
+        let ssao = route_if(&mut chain, key.run_ssao);
+        chain.next_custom(
+            custom_passes,
+            PostProcessStage::AfterAmbientOcclusion,
+            &mut custom,
+        );
+        let dof = route_if(&mut chain, key.run_dof);
+        let motion_blur = route_if(&mut chain, key.run_motion_blur);
+        let bloom = route_if(&mut chain, key.run_bloom);
+        chain.next_custom(custom_passes, PostProcessStage::AfterBloom, &mut custom);
+        let taa = route_if(&mut chain, key.run_taa);
+        chain.next_custom(
+            custom_passes,
+            PostProcessStage::AfterAntiAliasing,
+            &mut custom,
+        );
+        let [display_a_view, display_b_view] = display_views;
+        let tonemap = PostProcessRoute {
+            input_id: chain.current_id,
+            output_id: COLOR_ID_DISPLAY_A,
+            input_color: chain.current_view,
+            output_color: display_a_view.clone(),
+        };
 
-        if key.run_fxaa {
-            let (output_id, output_view) = next_output();
-            fxaa = PostProcessRoute {
-                input_id: current_id,
-                output_id,
-                input_color: current_view.clone(),
-                output_color: output_view.clone(),
-            };
-            current_id = output_id;
-            current_view = output_view;
-        }
+        // FXAA works on display range colors, its edge thresholds don't fit HDR values
+        let fxaa = PostProcessRoute {
+            input_id: COLOR_ID_DISPLAY_A,
+            output_id: COLOR_ID_DISPLAY_B,
+            input_color: display_a_view.clone(),
+            output_color: display_b_view.clone(),
+        };
+        let (display_id, display_view) = match key.run_fxaa {
+            true => (COLOR_ID_DISPLAY_B, display_b_view),
+            false => (COLOR_ID_DISPLAY_A, display_a_view),
+        };
 
         let final_pass = PostProcessRoute {
-            input_id: current_id,
+            input_id: display_id,
             output_id: COLOR_ID_FINAL_A,
-            input_color: current_view,
+            input_color: display_view,
             output_color: final_a_view,
         };
 
         ActivePostProcessRoutes {
             ssr,
             ssao,
+            dof,
+            motion_blur,
             bloom,
             fxaa,
+            taa,
+            tonemap,
             final_pass,
+            custom,
         }
     }
 
@@ -365,10 +572,11 @@ impl RenderPipeline {
             mip_level_count: 1,
             sample_count: 1,
             dimension: TextureDimension::D2,
-            format: TextureFormat::Rg16Float,
+            format: TextureFormat::Rgba16Float,
             usage: TextureUsages::COPY_SRC
                 | TextureUsages::RENDER_ATTACHMENT
-                | TextureUsages::TEXTURE_BINDING,
+                | TextureUsages::TEXTURE_BINDING
+                | TextureUsages::STORAGE_BINDING,
             view_formats: &[],
         })
     }
@@ -376,9 +584,14 @@ impl RenderPipeline {
     fn rebuild_post_process_passes(&mut self, cache: &AssetCache, key: PostProcessRouting) {
         let routes = Self::compose_routes(
             key,
+            &self.custom_passes,
             self.offscreen_surface.view().clone(),
             self.post_process_surfaces[0].view().clone(),
             self.post_process_surfaces[1].view().clone(),
+            [
+                self.display_surfaces[0].view().clone(),
+                self.display_surfaces[1].view().clone(),
+            ],
             self.final_surfaces[0].view().clone(),
         );
 
@@ -400,6 +613,20 @@ impl RenderPipeline {
             &routes.ssao,
         );
 
+        self.dof_pass = DepthOfFieldRenderPass::new(
+            &self.device,
+            cache.bgl_post_process_compute(),
+            &self.shared_views,
+            &routes.dof,
+        );
+
+        self.motion_blur_pass = MotionBlurRenderPass::new(
+            &self.device,
+            cache.bgl_post_process_compute(),
+            &self.shared_views,
+            &routes.motion_blur,
+        );
+
         self.bloom_pass = BloomRenderPass::new(
             &self.device,
             size.width,
@@ -416,6 +643,23 @@ impl RenderPipeline {
             &routes.fxaa,
         );
 
+        self.taa_pass = TaaRenderPass::new(
+            &self.device,
+            size.width,
+            size.height,
+            cache.bgl_taa_compute(),
+            &self.shared_views,
+            &routes.taa,
+        );
+
+        self.tonemap_pass = ToneMapRenderPass::new(
+            &self.device,
+            cache,
+            cache.bgl_exposure_compute(),
+            cache.bgl_tonemap_compute(),
+            &routes.tonemap,
+        );
+
         self.final_pass = FinalRenderPass::new(
             &self.device,
             &cache.bgl_post_process(),
@@ -423,29 +667,50 @@ impl RenderPipeline {
             &routes.final_pass,
         );
 
+        for (custom, route) in self.custom_passes.iter_mut().zip(&routes.custom) {
+            if let Some(route) = route {
+                custom
+                    .pass
+                    .rebuild(&self.device, cache, &self.shared_views, route);
+            }
+        }
+
         self.route_key = key;
         self.bloom_settings_dirty = false;
+        self.custom_routes_dirty = false;
     }
 
     fn ensure_route_configuration(&mut self, cache: &AssetCache) {
         let desired = PostProcessRouting::current();
-        if desired != self.route_key {
+        if desired != self.route_key || self.custom_routes_dirty {
             self.rebuild_post_process_passes(cache, desired);
         }
+
+        self.tonemap_pass
+            .bind_lut(&self.device, cache, self.color_grading.lut);
     }
 
     pub fn prepare_frame(
         &mut self,
         render_data: &mut RenderUniformData,
         queue: &Queue,
-        _frame_count: usize,
+        frame_count: usize,
     ) {
         let base_view_proj =
             render_data.camera_data.projection_mat * render_data.camera_data.view_mat;
-        let view_proj = base_view_proj;
+        let jitter = if self.route_key.run_taa {
+            let size = self.offscreen_surface.texture().size();
+            taa_jitter(frame_count, size.width, size.height)
+        } else {
+            Vec2::ZERO
+        };
+        let view_proj = Mat4::from_translation(jitter.extend(0.0)) * base_view_proj;
 
         render_data.camera_data.proj_view_mat = view_proj;
         render_data.camera_data.inv_proj_view_mat = view_proj.inverse();
+        render_data.camera_data.prev_proj_view_mat = self.prev_view_proj.unwrap_or(base_view_proj);
+        render_data.camera_data.jitter = jitter;
+        self.prev_view_proj = Some(base_view_proj);
 
         render_data.upload_camera_data(queue);
 
@@ -456,6 +721,29 @@ impl RenderPipeline {
                 self.bloom_settings_dirty = false;
             }
         }
+
+        if self.route_key.run_taa {
+            self.taa_pass.update(queue);
+        }
+
+        self.tonemap_pass
+            .update(queue, &self.exposure_settings, &self.color_grading);
+
+        for custom in self
+            .custom_passes
+            .iter_mut()
+            .filter(|custom| custom.enabled)
+        {
+            custom.pass.update(queue);
+        }
+    }
+
+    /// Forgets the last frames, for camera cuts. The next frame is shown without temporal
+    /// blending and without motion from the previous camera.
+    pub fn reset_history(&mut self) {
+        self.prev_view_proj = None;
+        self.taa_pass.reset_history();
+        self.tonemap_pass.reset_history();
     }
 
     pub fn set_bloom_settings(&mut self, settings: BloomSettings) {
@@ -467,6 +755,22 @@ impl RenderPipeline {
         &self.bloom_settings
     }
 
+    pub fn set_exposure(&mut self, settings: ExposureSettings) {
+        self.exposure_settings = settings.sanitized();
+    }
+
+    pub fn exposure(&self) -> &ExposureSettings {
+        &self.exposure_settings
+    }
+
+    pub fn set_color_grading(&mut self, settings: ColorGradingSettings) {
+        self.color_grading = settings.sanitized();
+    }
+
+    pub fn color_grading(&self) -> &ColorGradingSettings {
+        &self.color_grading
+    }
+
     pub fn render_ui_onto_final_frame(
         &self,
         encoder: &mut CommandEncoder,
@@ -480,6 +784,29 @@ impl RenderPipeline {
         UiRenderPass::render(encoder, strobe, final_color.view(), viewport, cache, state);
     }
 
+    /// The velocity G-buffer, for drawing the motion of moving objects into it
+    pub fn velocity_view(&self) -> &TextureView {
+        &self.shared_views.g_velocity
+    }
+
+    /// Fills the velocity G-buffer with the motion the camera caused in every pixel. Has to run
+    /// after the main pass, as it reads back the depth buffer.
+    pub fn render_camera_velocity(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        camera_render_data: &RenderUniformData,
+        cache: &AssetCache,
+    ) {
+        let mut ctx = PostProcessPassContext {
+            camera_render_data,
+            encoder,
+            cache,
+        };
+
+        self.velocity_pass
+            .execute(&mut ctx, &self.shared_views.g_velocity);
+    }
+
     fn run_post_process_chain(
         &mut self,
         camera_render_data: &RenderUniformData,
@@ -494,28 +821,88 @@ impl RenderPipeline {
         };
 
         let mut ping_index = 0usize;
+        let surfaces = &self.post_process_surfaces;
+        let custom = &mut self.custom_passes;
+        let mut run_custom = |ctx: &mut PostProcessPassContext<'_>,
+                              ping_index: &mut usize,
+                              stage: PostProcessStage| {
+            for pass in custom
+                .iter_mut()
+                .filter(|pass| pass.stage == stage && pass.enabled)
+            {
+                pass.pass.execute(ctx, surfaces[*ping_index].view());
+                *ping_index = 1 - *ping_index;
+            }
+        };
+
+        run_custom(
+            &mut ctx,
+            &mut ping_index,
+            PostProcessStage::BeforeReflections,
+        );
 
         if self.route_key.run_ssr {
-            let output_color = self.post_process_surfaces[ping_index].view();
+            let output_color = surfaces[ping_index].view();
             self.ssr_pass.execute(&mut ctx, output_color);
             ping_index = 1 - ping_index;
         }
 
+        run_custom(
+            &mut ctx,
+            &mut ping_index,
+            PostProcessStage::AfterReflections,
+        );
+
         if self.route_key.run_ssao {
-            let output_color = self.post_process_surfaces[ping_index].view();
+            let output_color = surfaces[ping_index].view();
             self.ssao_pass.execute(&mut ctx, output_color);
             ping_index = 1 - ping_index;
         }
 
+        run_custom(
+            &mut ctx,
+            &mut ping_index,
+            PostProcessStage::AfterAmbientOcclusion,
+        );
+
+        if self.route_key.run_dof {
+            let output_color = surfaces[ping_index].view();
+            self.dof_pass.execute(&mut ctx, output_color);
+            ping_index = 1 - ping_index;
+        }
+
+        if self.route_key.run_motion_blur {
+            let output_color = surfaces[ping_index].view();
+            self.motion_blur_pass.execute(&mut ctx, output_color);
+            ping_index = 1 - ping_index;
+        }
+
         if self.route_key.run_bloom {
-            let output_color = self.post_process_surfaces[ping_index].view();
+            let output_color = surfaces[ping_index].view();
             self.bloom_pass.execute(&mut ctx, output_color);
             ping_index = 1 - ping_index;
         }
 
+        run_custom(&mut ctx, &mut ping_index, PostProcessStage::AfterBloom);
+
+        if self.route_key.run_taa {
+            let output_color = surfaces[ping_index].view();
+            self.taa_pass.execute(&mut ctx, output_color);
+            ping_index = 1 - ping_index;
+        }
+
+        run_custom(
+            &mut ctx,
+            &mut ping_index,
+            PostProcessStage::AfterAntiAliasing,
+        );
+
+        self.tonemap_pass
+            .execute(&mut ctx, self.display_surfaces[0].view());
+
         if self.route_key.run_fxaa {
-            let output_color = self.post_process_surfaces[ping_index].view();
-            self.fxaa_pass.execute(&mut ctx, output_color);
+            self.fxaa_pass
+                .execute(&mut ctx, self.display_surfaces[1].view());
         }
 
         self.final_pass.execute(&mut ctx, &final_output);
@@ -158,14 +158,14 @@ impl BloomRenderPass {
             device,
             half_width,
             half_height,
-            TextureFormat::Rgba8Unorm,
+            TextureFormat::Rgba16Float,
             TextureUsages::STORAGE_BINDING,
         );
         let half_b = OffscreenSurface::new_sized_with(
             device,
             half_width,
             half_height,
-            TextureFormat::Rgba8Unorm,
+            TextureFormat::Rgba16Float,
             TextureUsages::STORAGE_BINDING,
         );
 
@@ -0,0 +1,154 @@
+use crate::cache::AssetCache;
+use crate::passes::post_process::{
+    PostProcessData, PostProcessParams, PostProcessPass, PostProcessPassContext, PostProcessRoute,
+    PostProcessSharedViews,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use syrillian_asset::HShader;
+use wgpu::{
+    Color, Device, LoadOp, Operations, Queue, RenderPassColorAttachment, RenderPassDescriptor,
+    StoreOp, TextureView,
+};
+
+/// Where a custom post process pass runs, relative to the built-in passes.
+///
+/// Built-in passes that are turned off are skipped, the custom passes still run in order.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PostProcessStage {
+    /// Before screen space reflections, right after the scene was rendered
+    BeforeReflections,
+    AfterReflections,
+    AfterAmbientOcclusion,
+    #[default]
+    AfterBloom,
+    /// After temporal anti-aliasing, before tonemapping and FXAA. Passes here get the HDR scene
+    /// color, before exposure is applied and with values above one, not display range color.
+    AfterAntiAliasing,
+}
+
+/// Identifies a custom post process pass of a viewport.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct PostProcessId(pub u64);
+
+impl PostProcessId {
+    /// Returns a new id, which is unique in this process.
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+pub(crate) struct CustomPostProcess {
+    pub id: PostProcessId,
+    pub stage: PostProcessStage,
+    pub order: i32,
+    pub enabled: bool,
+    pub pass: Box<dyn PostProcessPass + Send>,
+}
+
+/// A custom post process pass drawing a full screen post process shader.
+///
+/// The shader must render to the linear color target of the post process chain, like the
+/// shaders made with [`Shader::new_post_process_material`](syrillian_asset::Shader::new_post_process_material)
+/// or [`Shader::new_post_process_fragment_linear`](syrillian_asset::Shader::new_post_process_fragment_linear).
+pub struct PostProcessMaterialPass {
+    shader: HShader,
+    params: PostProcessParams,
+    params_dirty: bool,
+    uniform: Option<PostProcessData>,
+}
+
+impl PostProcessMaterialPass {
+    pub fn new(shader: HShader) -> Self {
+        Self {
+            shader,
+            params: PostProcessParams::default(),
+            params_dirty: true,
+            uniform: None,
+        }
+    }
+
+    pub fn with_params(mut self, params: PostProcessParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn shader(&self) -> HShader {
+        self.shader
+    }
+
+    pub fn params(&self) -> &PostProcessParams {
+        &self.params
+    }
+}
+
+impl PostProcessPass for PostProcessMaterialPass {
+    fn name(&self) -> &'static str {
+        "Post Process Material"
+    }
+
+    fn execute(&mut self, ctx: &mut PostProcessPassContext<'_>, output_color: &TextureView) {
+        let Some(uniform) = &self.uniform else {
+            return;
+        };
+
+        let mut pass = ctx.encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Post Process Material Render Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: output_color,
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color::BLACK),
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            ..RenderPassDescriptor::default()
+        });
+
+        let shader = ctx.cache.shader(self.shader);
+        let groups = shader.bind_groups();
+        pass.set_pipeline(shader.solid_pipeline());
+        pass.set_bind_group(
+            groups.render,
+            ctx.camera_render_data.uniform.bind_group(),
+            &[],
+        );
+        if let Some(idx) = groups.post_process {
+            pass.set_bind_group(idx, uniform.uniform.bind_group(), &[]);
+        }
+        pass.draw(0..6, 0..1);
+    }
+
+    fn rebuild(
+        &mut self,
+        device: &Device,
+        cache: &AssetCache,
+        shared_views: &PostProcessSharedViews,
+        route: &PostProcessRoute,
+    ) {
+        self.uniform = Some(PostProcessData::new(
+            device,
+            cache.bgl_post_process(),
+            route.input_color.clone(),
+            shared_views,
+        ));
+        self.params_dirty = true;
+    }
+
+    fn update(&mut self, queue: &Queue) {
+        if !self.params_dirty {
+            return;
+        }
+        if let Some(uniform) = &self.uniform {
+            uniform.write_params(queue, &self.params);
+            self.params_dirty = false;
+        }
+    }
+
+    fn set_params(&mut self, params: &PostProcessParams) {
+        self.params = *params;
+        self.params_dirty = true;
+    }
+}
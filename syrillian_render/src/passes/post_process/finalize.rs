@@ -22,9 +22,7 @@ impl FinalRenderPass {
             device,
             post_process_bgl.clone(),
             route.input_color.clone(),
-            shared_views.depth.clone(),
-            shared_views.g_normal.clone(),
-            shared_views.g_material.clone(),
+            shared_views,
         );
 
         Self { uniform }
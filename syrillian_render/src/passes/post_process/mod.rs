@@ -1,20 +1,36 @@
 mod bloom;
+mod custom;
+mod dof;
 mod finalize;
 mod fxaa;
+mod motion_blur;
 mod ssao;
 mod ssr;
+mod taa;
+mod tonemap;
+mod velocity;
 
 use crate::cache::AssetCache;
 use crate::rendering::render_data::RenderUniformData;
 use crate::rendering::uniform::ShaderUniform;
 pub use bloom::{BloomRenderPass, BloomSettings};
+pub(crate) use custom::CustomPostProcess;
+pub use custom::{PostProcessId, PostProcessMaterialPass, PostProcessStage};
+pub use dof::DepthOfFieldRenderPass;
 pub use finalize::FinalRenderPass;
 pub use fxaa::FxaaRenderPass;
+pub use motion_blur::MotionBlurRenderPass;
 pub use ssao::ScreenSpaceAmbientOcclusionRenderPass;
 pub use ssr::ScreenSpaceReflectionRenderPass;
 use syrillian_macros::UniformIndex;
+pub use taa::{TaaRenderPass, taa_jitter};
+pub use tonemap::{
+    ColorGradingSettings, ExposureMode, ExposureSettings, ToneMapRenderPass, Tonemapper,
+    white_balance,
+};
+pub use velocity::VelocityRenderPass;
 use wgpu::{
-    AddressMode, BindGroupLayout, CommandEncoder, Device, FilterMode, MipmapFilterMode,
+    AddressMode, BindGroupLayout, CommandEncoder, Device, FilterMode, MipmapFilterMode, Queue,
     SamplerDescriptor, TextureView,
 };
 
@@ -23,6 +39,7 @@ pub struct PostProcessSharedViews {
     pub depth: TextureView,
     pub g_normal: TextureView,
     pub g_material: TextureView,
+    /// Screen space motion since the last frame, filled before the post process chain runs
     pub g_velocity: TextureView,
 }
 
@@ -35,6 +52,50 @@ pub struct PostProcessPassContext<'a> {
 pub trait PostProcessPass {
     fn name(&self) -> &'static str;
     fn execute(&mut self, ctx: &mut PostProcessPassContext<'_>, output_color: &TextureView);
+
+    /// Recreates the resources of the pass when its input changes, like after a resize or when
+    /// passes before it are turned on or off. Only called for custom passes.
+    fn rebuild(
+        &mut self,
+        _device: &Device,
+        _cache: &AssetCache,
+        _shared_views: &PostProcessSharedViews,
+        _route: &PostProcessRoute,
+    ) {
+    }
+
+    /// Uploads changed data before the frame is rendered. Only called for custom passes.
+    fn update(&mut self, _queue: &Queue) {}
+
+    /// Receives the parameters set from the game side. Only called for custom passes.
+    fn set_params(&mut self, _params: &PostProcessParams) {}
+}
+
+/// Parameters of a custom post process pass, readable in its shader with `post_param(index)`.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PostProcessParams {
+    pub values: [f32; 16],
+}
+
+impl PostProcessParams {
+    pub const COUNT: usize = 16;
+
+    pub fn get(&self, index: usize) -> f32 {
+        self.values.get(index).copied().unwrap_or(0.0)
+    }
+
+    /// Sets the parameter `index`. Indices of 16 and above are ignored.
+    pub fn set(&mut self, index: usize, value: f32) {
+        if let Some(slot) = self.values.get_mut(index) {
+            *slot = value;
+        }
+    }
+
+    pub fn with(mut self, index: usize, value: f32) -> Self {
+        self.set(index, value);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -53,6 +114,8 @@ pub enum PostProcessUniformIndex {
     Depth = 2,
     GNormal = 3,
     GMaterial = 4,
+    GVelocity = 5,
+    Params = 6,
 }
 
 pub struct PostProcessData {
@@ -64,9 +127,7 @@ impl PostProcessData {
         device: &Device,
         layout: BindGroupLayout,
         color_view: TextureView,
-        depth_view: TextureView,
-        g_normal_view: TextureView,
-        g_material_view: TextureView,
+        shared_views: &PostProcessSharedViews,
     ) -> Self {
         let sampler = device.create_sampler(&SamplerDescriptor {
             label: Some("PostProcess Sampler"),
@@ -79,14 +140,25 @@ impl PostProcessData {
             ..SamplerDescriptor::default()
         });
 
+        let params = PostProcessParams::default();
         let uniform = ShaderUniform::<PostProcessUniformIndex>::builder(layout)
             .with_texture(color_view)
             .with_sampler(sampler)
-            .with_texture(depth_view)
-            .with_texture(g_normal_view)
-            .with_texture(g_material_view)
+            .with_texture(shared_views.depth.clone())
+            .with_texture(shared_views.g_normal.clone())
+            .with_texture(shared_views.g_material.clone())
+            .with_texture(shared_views.g_velocity.clone())
+            .with_buffer_data(&params)
             .build(device);
 
         Self { uniform }
     }
+
+    pub fn write_params(&self, queue: &Queue, params: &PostProcessParams) {
+        queue.write_buffer(
+            self.uniform.buffer(PostProcessUniformIndex::Params),
+            0,
+            bytemuck::bytes_of(params),
+        );
+    }
 }
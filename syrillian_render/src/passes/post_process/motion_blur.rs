@@ -0,0 +1,83 @@
+use crate::passes::post_process::{
+    PostProcessPass, PostProcessPassContext, PostProcessRoute, PostProcessSharedViews,
+};
+use crate::rendering::uniform::ShaderUniform;
+use syrillian_asset::HComputeShader;
+use syrillian_macros::UniformIndex;
+use wgpu::{
+    AddressMode, BindGroupLayout, ComputePassDescriptor, Device, FilterMode, MipmapFilterMode,
+    SamplerDescriptor, TextureView,
+};
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, UniformIndex)]
+enum MotionBlurComputeUniformIndex {
+    Color = 0,
+    Sampler = 1,
+    Depth = 2,
+    GVelocity = 3,
+    GMaterial = 4,
+    Output = 5,
+}
+
+/// Blurs every pixel along its motion in the velocity G-buffer, which covers both the camera
+/// and objects moving on their own.
+pub struct MotionBlurRenderPass {
+    uniform: ShaderUniform<MotionBlurComputeUniformIndex>,
+}
+
+impl MotionBlurRenderPass {
+    pub fn new(
+        device: &Device,
+        post_process_compute_bgl: BindGroupLayout,
+        shared: &PostProcessSharedViews,
+        route: &PostProcessRoute,
+    ) -> Self {
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("Motion Blur Compute Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: MipmapFilterMode::Nearest,
+            ..SamplerDescriptor::default()
+        });
+
+        let uniform =
+            ShaderUniform::<MotionBlurComputeUniformIndex>::builder(post_process_compute_bgl)
+                .with_texture(route.input_color.clone())
+                .with_sampler(sampler)
+                .with_texture(shared.depth.clone())
+                .with_texture(shared.g_velocity.clone())
+                .with_texture(shared.g_material.clone())
+                .with_texture(route.output_color.clone())
+                .build(device);
+
+        Self { uniform }
+    }
+}
+
+impl PostProcessPass for MotionBlurRenderPass {
+    fn name(&self) -> &'static str {
+        "Motion Blur"
+    }
+
+    fn execute(&mut self, ctx: &mut PostProcessPassContext<'_>, _output_color: &TextureView) {
+        let width = ctx.camera_render_data.system_data.screen_size.x.max(1);
+        let height = ctx.camera_render_data.system_data.screen_size.y.max(1);
+
+        let mut pass = ctx.encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("Motion Blur Compute Pass"),
+            ..ComputePassDescriptor::default()
+        });
+
+        let shader = ctx
+            .cache
+            .compute_shader(HComputeShader::POST_PROCESS_MOTION_BLUR);
+        pass.set_pipeline(shader.pipeline());
+        pass.set_bind_group(0, ctx.camera_render_data.uniform.bind_group(), &[]);
+        pass.set_bind_group(1, self.uniform.bind_group(), &[]);
+        pass.dispatch_workgroups(width.div_ceil(8), height.div_ceil(8), 1);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::passes::post_process::{
+    PostProcessPass, PostProcessPassContext, PostProcessRoute, PostProcessSharedViews,
+};
+use crate::rendering::offscreen_surface::OffscreenSurface;
+use crate::rendering::uniform::ShaderUniform;
+use glamx::Vec2;
+use syrillian_asset::{HComputeShader, ensure_aligned};
+use syrillian_macros::UniformIndex;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    AddressMode, BindGroupLayout, Buffer, BufferUsages, ComputePassDescriptor, Device, FilterMode,
+    MipmapFilterMode, Queue, SamplerDescriptor, TextureFormat, TextureUsages, TextureView,
+};
+
+/// Number of jitter offsets before the sequence repeats
+const JITTER_PHASES: usize = 8;
+
+/// How much of the current frame goes into the result, the rest comes from the history
+const CURRENT_FRAME_WEIGHT: f32 = 0.1;
+
+/// The projection offset in NDC of a frame, following the Halton (2, 3) sequence so every
+/// pixel is sampled at different sub pixel positions over [`JITTER_PHASES`] frames.
+pub fn taa_jitter(frame: usize, width: u32, height: u32) -> Vec2 {
+    let index = frame % JITTER_PHASES + 1;
+    let offset = Vec2::new(halton(index, 2), halton(index, 3)) - 0.5;
+    offset * 2.0 / Vec2::new(width.max(1) as f32, height.max(1) as f32)
+}
+
+fn halton(mut index: usize, base: usize) -> f32 {
+    let mut fraction = 1.0;
+    let mut result = 0.0;
+    while index > 0 {
+        fraction /= base as f32;
+        result += fraction * (index % base) as f32;
+        index /= base;
+    }
+    result
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct TaaParams {
+    blend: f32,
+    history_valid: u32,
+    _pad0: f32,
+    _pad1: f32,
+}
+
+ensure_aligned!(TaaParams { blend }, align <= 4 * 4 => size);
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, UniformIndex)]
+enum TaaComputeUniformIndex {
+    Color = 0,
+    History = 1,
+    Sampler = 2,
+    Velocity = 3,
+    Params = 4,
+    Output = 5,
+    HistoryOutput = 6,
+}
+
+/// Temporal anti-aliasing. Blends the jittered frame with the history of the last frames,
+/// reprojected with the velocity G-buffer and clipped to the colors around each pixel.
+///
+/// The history is kept in two textures which swap roles every frame. It's thrown away when the
+/// pass is recreated, like after a resize, and on camera cuts.
+pub struct TaaRenderPass {
+    params: Buffer,
+    /// `uniforms[i]` reads `history[i]` and writes the other one
+    uniforms: [ShaderUniform<TaaComputeUniformIndex>; 2],
+    _history: [OffscreenSurface; 2],
+    current: usize,
+    history_valid: bool,
+}
+
+impl TaaRenderPass {
+    pub fn new(
+        device: &Device,
+        width: u32,
+        height: u32,
+        taa_compute_bgl: BindGroupLayout,
+        shared: &PostProcessSharedViews,
+        route: &PostProcessRoute,
+    ) -> Self {
+        let history = [(); 2].map(|_| {
+            OffscreenSurface::new_sized_with(
+                device,
+                width,
+                height,
+                TextureFormat::Rgba16Float,
+                TextureUsages::STORAGE_BINDING,
+            )
+        });
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("TAA History Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: MipmapFilterMode::Nearest,
+            ..SamplerDescriptor::default()
+        });
+
+        let params = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("TAA Params"),
+            contents: bytemuck::bytes_of(&Self::params_for(false)),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let uniforms = [0, 1].map(|read| {
+            ShaderUniform::<TaaComputeUniformIndex>::builder(taa_compute_bgl.clone())
+                .with_texture(route.input_color.clone())
+                .with_texture(history[read].view().clone())
+                .with_sampler(sampler.clone())
+                .with_texture(shared.g_velocity.clone())
+                .with_buffer(params.clone())
+                .with_texture(route.output_color.clone())
+                .with_texture(history[1 - read].view().clone())
+                .build(device)
+        });
+
+        Self {
+            params,
+            uniforms,
+            _history: history,
+            current: 0,
+            history_valid: false,
+        }
+    }
+
+    fn params_for(history_valid: bool) -> TaaParams {
+        TaaParams {
+            blend: CURRENT_FRAME_WEIGHT,
+            history_valid: history_valid as u32,
+            _pad0: 0.0,
+            _pad1: 0.0,
+        }
+    }
+
+    /// Drops the history, so the next frame is shown without blending in older frames.
+    pub fn reset_history(&mut self) {
+        self.history_valid = false;
+    }
+
+    pub fn has_history(&self) -> bool {
+        self.history_valid
+    }
+
+    pub fn update(&self, queue: &Queue) {
+        let params = Self::params_for(self.history_valid);
+        queue.write_buffer(&self.params, 0, bytemuck::bytes_of(&params));
+    }
+}
+
+impl PostProcessPass for TaaRenderPass {
+    fn name(&self) -> &'static str {
+        "TAA"
+    }
+
+    fn execute(&mut self, ctx: &mut PostProcessPassContext<'_>, _output_color: &TextureView) {
+        let width = ctx.camera_render_data.system_data.screen_size.x.max(1);
+        let height = ctx.camera_render_data.system_data.screen_size.y.max(1);
+
+        let mut pass = ctx.encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("TAA Resolve Compute Pass"),
+            ..ComputePassDescriptor::default()
+        });
+
+        let shader = ctx.cache.compute_shader(HComputeShader::POST_PROCESS_TAA);
+        pass.set_pipeline(shader.pipeline());
+        pass.set_bind_group(0, self.uniforms[self.current].bind_group(), &[]);
+        pass.dispatch_workgroups(width.div_ceil(8), height.div_ceil(8), 1);
+
+        self.current = 1 - self.current;
+        self.history_valid = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_within_half_a_pixel() {
+        let (width, height) = (1280, 720);
+        let pixel = Vec2::new(2.0 / width as f32, 2.0 / height as f32);
+
+        let mut sum = Vec2::ZERO;
+        for frame in 0..JITTER_PHASES {
+            let jitter = taa_jitter(frame, width, height);
+            assert!(jitter.abs().cmple(pixel * 0.5).all());
+            sum += jitter / pixel;
+        }
+
+        // the offsets are spread around the pixel center
+        assert!((sum / JITTER_PHASES as f32).length() < 0.1);
+        assert_eq!(
+            taa_jitter(3, width, height),
+            taa_jitter(3 + JITTER_PHASES, width, height)
+        );
+        assert_ne!(taa_jitter(0, width, height), taa_jitter(1, width, height));
+    }
+}
@@ -0,0 +1,514 @@
+use crate::cache::AssetCache;
+use crate::passes::post_process::{PostProcessPass, PostProcessPassContext, PostProcessRoute};
+use crate::rendering::uniform::ShaderUniform;
+use syrillian_asset::{HComputeShader, HTexture2D, ensure_aligned};
+use syrillian_macros::UniformIndex;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    AddressMode, BindGroupLayout, Buffer, BufferUsages, ComputePassDescriptor, Device, FilterMode,
+    MipmapFilterMode, Queue, Sampler, SamplerDescriptor, TextureView,
+};
+
+/// Bins of the luminance histogram, has to match the exposure shader
+const HISTOGRAM_BINS: usize = 256;
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum ExposureMode {
+    /// The exposure only comes from the compensation
+    #[default]
+    Manual,
+    /// The exposure adapts to the average brightness of the scene, like an eye does
+    Automatic,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ExposureSettings {
+    pub mode: ExposureMode,
+    /// Exposure offset in EV. In manual mode this is the whole exposure.
+    pub compensation: f32,
+    /// Darkest average luminance the automatic exposure adapts to, as log2
+    pub min_ev: f32,
+    /// Brightest average luminance the automatic exposure adapts to, as log2
+    pub max_ev: f32,
+    /// How fast the exposure adapts when the scene gets brighter
+    pub speed_up: f32,
+    /// How fast the exposure adapts when the scene gets darker
+    pub speed_down: f32,
+}
+
+impl Default for ExposureSettings {
+    fn default() -> Self {
+        Self {
+            mode: ExposureMode::Manual,
+            compensation: 0.0,
+            min_ev: -8.0,
+            max_ev: 2.0,
+            speed_up: 3.0,
+            speed_down: 1.0,
+        }
+    }
+}
+
+impl ExposureSettings {
+    /// Automatic exposure with the default range and speeds
+    pub fn automatic() -> Self {
+        Self {
+            mode: ExposureMode::Automatic,
+            ..Self::default()
+        }
+    }
+
+    /// A fixed exposure of `compensation` EV
+    pub fn manual(compensation: f32) -> Self {
+        Self {
+            mode: ExposureMode::Manual,
+            compensation,
+            ..Self::default()
+        }
+    }
+
+    pub fn sanitized(mut self) -> Self {
+        self.compensation = self.compensation.clamp(-16.0, 16.0);
+        self.min_ev = self.min_ev.clamp(-20.0, 19.5);
+        self.max_ev = self.max_ev.clamp(self.min_ev + 0.5, 20.0);
+        self.speed_up = self.speed_up.clamp(0.01, 100.0);
+        self.speed_down = self.speed_down.clamp(0.01, 100.0);
+        self
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Tonemapper {
+    /// Fitted ACES filmic curve, punchy with a strong shoulder
+    #[default]
+    Aces,
+    /// AgX, desaturates bright colors gracefully instead of skewing their hue
+    AgX,
+    /// Simple per channel Reinhard curve
+    Reinhard,
+    /// Khronos PBR Neutral, keeps base colors close to their authored values
+    Neutral,
+}
+
+impl Tonemapper {
+    fn shader_id(self) -> u32 {
+        match self {
+            Tonemapper::Aces => 0,
+            Tonemapper::AgX => 1,
+            Tonemapper::Reinhard => 2,
+            Tonemapper::Neutral => 3,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorGradingSettings {
+    pub tonemapper: Tonemapper,
+    /// White balance from -100 (cooler) to 100 (warmer)
+    pub temperature: f32,
+    /// White balance from -100 (green) to 100 (magenta)
+    pub tint: f32,
+    /// Contrast around middle grey, 1 leaves the image unchanged
+    pub contrast: f32,
+    /// 0 is greyscale, 1 leaves the image unchanged
+    pub saturation: f32,
+    /// Color lookup table applied after tonemapping. The texture is a horizontal strip of N
+    /// slices of N x N texels, blue selects the slice, red and green the texel in it.
+    pub lut: Option<HTexture2D>,
+    /// How much of the lookup table result is used, from 0 to 1
+    pub lut_contribution: f32,
+}
+
+impl Default for ColorGradingSettings {
+    fn default() -> Self {
+        Self {
+            tonemapper: Tonemapper::Aces,
+            temperature: 0.0,
+            tint: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            lut: None,
+            lut_contribution: 1.0,
+        }
+    }
+}
+
+impl ColorGradingSettings {
+    pub fn sanitized(mut self) -> Self {
+        self.temperature = self.temperature.clamp(-100.0, 100.0);
+        self.tint = self.tint.clamp(-100.0, 100.0);
+        self.contrast = self.contrast.clamp(0.0, 4.0);
+        self.saturation = self.saturation.clamp(0.0, 4.0);
+        self.lut_contribution = self.lut_contribution.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// LMS gains which shift the white point by `temperature` and `tint`, both from -100 to 100.
+/// Returns ones for a neutral white balance.
+pub fn white_balance(temperature: f32, tint: f32) -> [f32; 3] {
+    let t1 = temperature / 65.0;
+    let t2 = tint / 65.0;
+
+    // CIE xy chromaticity of the new white point, on the daylight locus
+    let x = 0.31271 - t1 * if t1 < 0.0 { 0.1 } else { 0.05 };
+    let standard_illuminant_y = 2.87 * x - 3.0 * x * x - 0.275_095_07;
+    let y = standard_illuminant_y + t2 * 0.05;
+
+    // D65 in LMS
+    let w1 = [0.949237, 1.03542, 1.08728];
+    let w2 = xy_to_lms(x, y);
+    [w1[0] / w2[0], w1[1] / w2[1], w1[2] / w2[2]]
+}
+
+fn xy_to_lms(x: f32, y: f32) -> [f32; 3] {
+    let big_y = 1.0;
+    let big_x = big_y * x / y;
+    let big_z = big_y * (1.0 - x - y) / y;
+
+    [
+        0.7328 * big_x + 0.4296 * big_y - 0.1624 * big_z,
+        -0.7036 * big_x + 1.6975 * big_y + 0.0061 * big_z,
+        0.0030 * big_x + 0.0136 * big_y + 0.9834 * big_z,
+    ]
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ExposureParams {
+    min_log_luminance: f32,
+    log_luminance_range: f32,
+    compensation: f32,
+    speed_up: f32,
+    speed_down: f32,
+    mode: u32,
+    reset: u32,
+    _pad0: f32,
+}
+
+ensure_aligned!(ExposureParams { min_log_luminance }, align <= 4 * 8 => size);
+
+impl ExposureParams {
+    fn new(settings: &ExposureSettings, reset: bool) -> Self {
+        Self {
+            min_log_luminance: settings.min_ev,
+            log_luminance_range: settings.max_ev - settings.min_ev,
+            compensation: settings.compensation,
+            speed_up: settings.speed_up,
+            speed_down: settings.speed_down,
+            mode: match settings.mode {
+                ExposureMode::Manual => 0,
+                ExposureMode::Automatic => 1,
+            },
+            reset: reset as u32,
+            _pad0: 0.0,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct ExposureState {
+    luminance: f32,
+    exposure: f32,
+    _pad0: f32,
+    _pad1: f32,
+}
+
+ensure_aligned!(ExposureState { luminance }, align <= 4 * 4 => size);
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct GradingParams {
+    balance: [f32; 3],
+    tonemapper: u32,
+    contrast: f32,
+    saturation: f32,
+    lut_contribution: f32,
+    lut_decode: u32,
+}
+
+ensure_aligned!(GradingParams { balance, contrast }, align <= 4 * 8 => size);
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, UniformIndex)]
+enum ExposureComputeUniformIndex {
+    Color = 0,
+    Histogram = 1,
+    State = 2,
+    Params = 3,
+}
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, UniformIndex)]
+enum TonemapComputeUniformIndex {
+    Color = 0,
+    Sampler = 1,
+    Lut = 2,
+    Exposure = 3,
+    Params = 4,
+    Output = 5,
+}
+
+/// The bound lookup table of the tonemap pass
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct BoundLut {
+    handle: Option<HTexture2D>,
+    /// The texture holds sRGB values which the sampler doesn't decode
+    decode: bool,
+}
+
+/// Exposure, color grading and tonemapping. Builds a luminance histogram of the frame, adapts
+/// the exposure towards its average over time and maps the exposed colors into display range.
+///
+/// The adapted luminance is kept on the GPU. It starts over when the pass is recreated and on
+/// camera cuts, where the exposure snaps to the new scene instead of adapting to it.
+pub struct ToneMapRenderPass {
+    exposure_params: Buffer,
+    grading_params: Buffer,
+    exposure_state: Buffer,
+    exposure_uniform: ShaderUniform<ExposureComputeUniformIndex>,
+    tonemap_uniform: ShaderUniform<TonemapComputeUniformIndex>,
+    tonemap_bgl: BindGroupLayout,
+    lut_sampler: Sampler,
+    input_color: TextureView,
+    output_color: TextureView,
+    lut: BoundLut,
+    reset: bool,
+}
+
+impl ToneMapRenderPass {
+    pub fn new(
+        device: &Device,
+        cache: &AssetCache,
+        exposure_compute_bgl: BindGroupLayout,
+        tonemap_compute_bgl: BindGroupLayout,
+        route: &PostProcessRoute,
+    ) -> Self {
+        let lut_sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("Color Grading LUT Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: MipmapFilterMode::Nearest,
+            ..SamplerDescriptor::default()
+        });
+
+        let exposure_params = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Exposure Params"),
+            contents: bytemuck::bytes_of(&ExposureParams::new(&ExposureSettings::default(), true)),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let grading_params = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Color Grading Params"),
+            contents: bytemuck::bytes_of(&Self::grading_params_for(
+                &ColorGradingSettings::default(),
+                false,
+            )),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let exposure_state = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Exposure State"),
+            contents: bytemuck::bytes_of(&ExposureState {
+                luminance: 0.0,
+                exposure: 1.0,
+                _pad0: 0.0,
+                _pad1: 0.0,
+            }),
+            usage: BufferUsages::STORAGE,
+        });
+        let exposure_uniform =
+            ShaderUniform::<ExposureComputeUniformIndex>::builder(exposure_compute_bgl)
+                .with_texture(route.input_color.clone())
+                .with_storage_buffer_data(&[0u32; HISTOGRAM_BINS])
+                .with_storage_buffer(exposure_state.clone())
+                .with_buffer(exposure_params.clone())
+                .build(device);
+
+        let lut = BoundLut {
+            handle: None,
+            decode: false,
+        };
+        let tonemap_uniform = Self::build_tonemap_uniform(
+            device,
+            tonemap_compute_bgl.clone(),
+            &route.input_color,
+            &lut_sampler,
+            &cache.texture_fallback().view,
+            &exposure_state,
+            &grading_params,
+            &route.output_color,
+        );
+
+        Self {
+            exposure_params,
+            grading_params,
+            exposure_state,
+            exposure_uniform,
+            tonemap_uniform,
+            tonemap_bgl: tonemap_compute_bgl,
+            lut_sampler,
+            input_color: route.input_color.clone(),
+            output_color: route.output_color.clone(),
+            lut,
+            reset: true,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build_tonemap_uniform(
+        device: &Device,
+        bgl: BindGroupLayout,
+        input_color: &TextureView,
+        sampler: &Sampler,
+        lut: &TextureView,
+        exposure_state: &Buffer,
+        grading_params: &Buffer,
+        output_color: &TextureView,
+    ) -> ShaderUniform<TonemapComputeUniformIndex> {
+        ShaderUniform::<TonemapComputeUniformIndex>::builder(bgl)
+            .with_texture(input_color.clone())
+            .with_sampler(sampler.clone())
+            .with_texture(lut.clone())
+            .with_storage_buffer(exposure_state.clone())
+            .with_buffer(grading_params.clone())
+            .with_texture(output_color.clone())
+            .build(device)
+    }
+
+    fn grading_params_for(settings: &ColorGradingSettings, lut_decode: bool) -> GradingParams {
+        GradingParams {
+            balance: white_balance(settings.temperature, settings.tint),
+            tonemapper: settings.tonemapper.shader_id(),
+            contrast: settings.contrast,
+            saturation: settings.saturation,
+            lut_contribution: settings.lut.map_or(0.0, |_| settings.lut_contribution),
+            lut_decode: lut_decode as u32,
+        }
+    }
+
+    /// Binds the lookup table of the grading settings, if it isn't bound already
+    pub fn bind_lut(&mut self, device: &Device, cache: &AssetCache, lut: Option<HTexture2D>) {
+        if self.lut.handle == lut {
+            return;
+        }
+
+        let texture = match lut {
+            Some(handle) => cache.texture(handle),
+            None => cache.texture_fallback(),
+        };
+        self.tonemap_uniform = Self::build_tonemap_uniform(
+            device,
+            self.tonemap_bgl.clone(),
+            &self.input_color,
+            &self.lut_sampler,
+            &texture.view,
+            &self.exposure_state,
+            &self.grading_params,
+            &self.output_color,
+        );
+        self.lut = BoundLut {
+            handle: lut,
+            decode: !texture.format.is_srgb(),
+        };
+    }
+
+    /// Snaps the exposure to the next frame instead of adapting to it
+    pub fn reset_history(&mut self) {
+        self.reset = true;
+    }
+
+    pub fn update(
+        &self,
+        queue: &Queue,
+        exposure: &ExposureSettings,
+        grading: &ColorGradingSettings,
+    ) {
+        let exposure_params = ExposureParams::new(exposure, self.reset);
+        queue.write_buffer(
+            &self.exposure_params,
+            0,
+            bytemuck::bytes_of(&exposure_params),
+        );
+
+        // a lookup table that isn't bound yet is left out until it is
+        let mut grading_params = Self::grading_params_for(grading, self.lut.decode);
+        if self.lut.handle != grading.lut {
+            grading_params.lut_contribution = 0.0;
+        }
+        queue.write_buffer(&self.grading_params, 0, bytemuck::bytes_of(&grading_params));
+    }
+}
+
+impl PostProcessPass for ToneMapRenderPass {
+    fn name(&self) -> &'static str {
+        "Tonemap"
+    }
+
+    fn execute(&mut self, ctx: &mut PostProcessPassContext<'_>, _output_color: &TextureView) {
+        let width = ctx.camera_render_data.system_data.screen_size.x.max(1);
+        let height = ctx.camera_render_data.system_data.screen_size.y.max(1);
+
+        let mut pass = ctx.encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("Tonemap Compute Pass"),
+            ..ComputePassDescriptor::default()
+        });
+
+        let histogram = ctx
+            .cache
+            .compute_shader(HComputeShader::POST_PROCESS_EXPOSURE_HISTOGRAM);
+        pass.set_pipeline(histogram.pipeline());
+        pass.set_bind_group(0, ctx.camera_render_data.uniform.bind_group(), &[]);
+        pass.set_bind_group(1, self.exposure_uniform.bind_group(), &[]);
+        pass.dispatch_workgroups(width.div_ceil(16), height.div_ceil(16), 1);
+
+        let average = ctx
+            .cache
+            .compute_shader(HComputeShader::POST_PROCESS_EXPOSURE_AVERAGE);
+        pass.set_pipeline(average.pipeline());
+        pass.dispatch_workgroups(1, 1, 1);
+
+        let tonemap = ctx
+            .cache
+            .compute_shader(HComputeShader::POST_PROCESS_TONEMAP);
+        pass.set_pipeline(tonemap.pipeline());
+        pass.set_bind_group(0, self.tonemap_uniform.bind_group(), &[]);
+        pass.dispatch_workgroups(width.div_ceil(8), height.div_ceil(8), 1);
+
+        self.reset = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn white_balance_is_neutral_by_default() {
+        for gain in white_balance(0.0, 0.0) {
+            assert!((gain - 1.0).abs() < 0.01, "{gain}");
+        }
+
+        let warm = white_balance(50.0, 0.0);
+        let cool = white_balance(-50.0, 0.0);
+        assert!(warm[0] / warm[2] > 1.0);
+        assert!(cool[0] / cool[2] < 1.0);
+    }
+
+    #[test]
+    fn exposure_range_stays_valid() {
+        let settings = ExposureSettings {
+            min_ev: 4.0,
+            max_ev: -4.0,
+            speed_up: -1.0,
+            ..ExposureSettings::automatic()
+        }
+        .sanitized();
+
+        assert!(settings.max_ev > settings.min_ev);
+        assert!(settings.speed_up > 0.0);
+        assert_eq!(settings.mode, ExposureMode::Automatic);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::passes::post_process::{
+    PostProcessPass, PostProcessPassContext, PostProcessSharedViews,
+};
+use crate::rendering::uniform::ShaderUniform;
+use syrillian_asset::HComputeShader;
+use syrillian_macros::UniformIndex;
+use wgpu::{BindGroupLayout, ComputePassDescriptor, Device, TextureView};
+
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, UniformIndex)]
+enum VelocityComputeUniformIndex {
+    Depth = 0,
+    Output = 1,
+}
+
+/// Fills the velocity G-buffer with the screen space motion caused by the camera since the last
+/// frame, reconstructed from the depth buffer.
+pub struct VelocityRenderPass {
+    uniform: ShaderUniform<VelocityComputeUniformIndex>,
+}
+
+impl VelocityRenderPass {
+    pub fn new(
+        device: &Device,
+        velocity_compute_bgl: BindGroupLayout,
+        shared: &PostProcessSharedViews,
+    ) -> Self {
+        let uniform = ShaderUniform::<VelocityComputeUniformIndex>::builder(velocity_compute_bgl)
+            .with_texture(shared.depth.clone())
+            .with_texture(shared.g_velocity.clone())
+            .build(device);
+
+        Self { uniform }
+    }
+}
+
+impl PostProcessPass for VelocityRenderPass {
+    fn name(&self) -> &'static str {
+        "Velocity"
+    }
+
+    fn execute(&mut self, ctx: &mut PostProcessPassContext<'_>, _output_color: &TextureView) {
+        let width = ctx.camera_render_data.system_data.screen_size.x.max(1);
+        let height = ctx.camera_render_data.system_data.screen_size.y.max(1);
+
+        let mut pass = ctx.encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("Velocity Compute Pass"),
+            ..ComputePassDescriptor::default()
+        });
+
+        let shader = ctx
+            .cache
+            .compute_shader(HComputeShader::POST_PROCESS_VELOCITY);
+        pass.set_pipeline(shader.pipeline());
+        pass.set_bind_group(0, ctx.camera_render_data.uniform.bind_group(), &[]);
+        pass.set_bind_group(1, self.uniform.bind_group(), &[]);
+        pass.dispatch_workgroups(width.div_ceil(8), height.div_ceil(8), 1);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::model_uniform::ModelUniform;
+use crate::proxies::mesh_proxy::{MeshMaterialDraw, MeshUniformIndex, materials_priority};
+use crate::proxies::{SceneProxy, SceneProxyBinding};
+use crate::rendering::picking::hash_to_rgba;
+use crate::rendering::renderer::Renderer;
+use crate::rendering::uniform::ShaderUniform;
+use crate::rendering::{GPUDrawCtx, RenderPassType};
+use crate::{proxy_data, proxy_data_mut};
+use glamx::{Affine3A, Mat4};
+use std::any::Any;
+use std::ops::Range;
+use syrillian_asset::mesh::MeshInstance;
+use syrillian_asset::mesh::bone::BoneData;
+use syrillian_asset::store::AssetStore;
+use syrillian_asset::{HMaterialInstance, HMesh};
+use syrillian_utils::BoundingSphere;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{Buffer, BufferUsages};
+
+#[derive(Debug)]
+pub struct InstancedMeshRenderData {
+    uniform: ShaderUniform<MeshUniformIndex>,
+    instance_buffer: Buffer,
+    instance_count: u32,
+}
+
+/// Draws one mesh many times with a single instanced draw per material slot. Every instance is
+/// placed relative to the object, and the whole proxy is culled and picked as that object.
+///
+/// Skinning, morph targets and simplified levels aren't applied, instances always draw the
+/// base mesh.
+#[derive(Debug, Clone)]
+pub struct InstancedMeshProxy {
+    pub mesh: HMesh,
+    pub materials: Vec<HMaterialInstance>,
+    pub material_ranges: Vec<Range<u32>>,
+    /// Bounds of the mesh itself, the bounds of the proxy enclose it at every instance.
+    pub mesh_bounds: Option<BoundingSphere>,
+    pub instances: Vec<Mat4>,
+    instances_dirty: bool,
+    translation: ModelUniform,
+}
+
+impl InstancedMeshProxy {
+    pub fn new(mesh: HMesh, materials: Vec<HMaterialInstance>, instances: Vec<Mat4>) -> Self {
+        Self {
+            mesh,
+            materials,
+            material_ranges: Vec::new(),
+            mesh_bounds: None,
+            instances,
+            instances_dirty: false,
+            translation: ModelUniform::empty(),
+        }
+    }
+
+    pub fn set_instances(&mut self, instances: Vec<Mat4>) {
+        self.instances = instances;
+        self.instances_dirty = true;
+    }
+
+    fn local_bounds(&self) -> Option<BoundingSphere> {
+        let mesh_bounds = self.mesh_bounds?;
+        self.instances
+            .iter()
+            .map(|instance| mesh_bounds.transformed(instance))
+            .reduce(|a, b| a.merged(&b))
+    }
+
+    fn write_instances(&self, renderer: &Renderer, data: &mut InstancedMeshRenderData) {
+        let instances = mesh_instances(&self.instances);
+        let bytes: &[u8] = bytemuck::cast_slice(&instances);
+        if data.instance_buffer.size() < bytes.len() as u64 {
+            data.instance_buffer = create_instance_buffer(renderer, &instances);
+        } else if !bytes.is_empty() {
+            renderer
+                .state
+                .queue
+                .write_buffer(&data.instance_buffer, 0, bytes);
+        }
+        data.instance_count = instances.len() as u32;
+    }
+
+    fn draw_instances(
+        &self,
+        renderer: &Renderer,
+        ctx: &GPUDrawCtx,
+        binding: &SceneProxyBinding,
+        pass_type: RenderPassType,
+    ) {
+        let data: &InstancedMeshRenderData = proxy_data!(binding.proxy_data());
+        if data.instance_count == 0 {
+            return;
+        }
+
+        let Some(mesh) = renderer.cache.mesh(self.mesh) else {
+            return;
+        };
+
+        let mut pass = ctx.pass.write();
+
+        match pass_type {
+            RenderPassType::Picking => {
+                let color = hash_to_rgba(binding.object_hash);
+                pass.set_immediates(0, bytemuck::bytes_of(&color));
+            }
+            RenderPassType::Velocity => {
+                let prev_transform = Mat4::from(binding.prev_local_to_world);
+                pass.set_immediates(0, bytemuck::bytes_of(&prev_transform));
+            }
+            _ => (),
+        }
+
+        let draw = MeshMaterialDraw::new(&mesh, &self.materials, &self.material_ranges, 0);
+        draw.draw(
+            ctx,
+            &renderer.cache,
+            &data.uniform,
+            &mut pass,
+            pass_type,
+            |range, pass| {
+                mesh.draw_instanced(range, &data.instance_buffer, 0..data.instance_count, pass);
+            },
+        );
+    }
+}
+
+/// The instance stream of the given transforms. Picking uses the color of the whole object.
+fn mesh_instances(transforms: &[Mat4]) -> Vec<MeshInstance> {
+    transforms
+        .iter()
+        .map(|transform| MeshInstance::new(*transform, [0.0; 4]))
+        .collect()
+}
+
+fn create_instance_buffer(renderer: &Renderer, instances: &[MeshInstance]) -> Buffer {
+    // wgpu doesn't allow empty vertex buffers to be bound, keep room for one instance
+    let contents = if instances.is_empty() {
+        bytemuck::bytes_of(&MeshInstance::IDENTITY)
+    } else {
+        bytemuck::cast_slice(instances)
+    };
+
+    renderer
+        .state
+        .device
+        .create_buffer_init(&BufferInitDescriptor {
+            label: Some("Mesh Instance Buffer"),
+            contents,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        })
+}
+
+impl SceneProxy for InstancedMeshProxy {
+    fn setup_render(
+        &mut self,
+        renderer: &Renderer,
+        local_to_world: &Affine3A,
+    ) -> Box<dyn Any + Send> {
+        self.translation.update(&(*local_to_world).into());
+
+        let uniform = ShaderUniform::<MeshUniformIndex>::builder(renderer.cache.bgl_model())
+            .with_buffer_data(&self.translation)
+            .with_buffer_data(&BoneData::DUMMY)
+            .build(&renderer.state.device);
+        self.instances_dirty = false;
+
+        let instances = mesh_instances(&self.instances);
+        Box::new(InstancedMeshRenderData {
+            uniform,
+            instance_buffer: create_instance_buffer(renderer, &instances),
+            instance_count: instances.len() as u32,
+        })
+    }
+
+    fn refresh_transform(
+        &mut self,
+        renderer: &Renderer,
+        data: &mut (dyn Any + Send),
+        local_to_world: &Affine3A,
+    ) {
+        let data: &mut InstancedMeshRenderData = proxy_data_mut!(data);
+
+        self.translation.update(&(*local_to_world).into());
+        renderer.state.queue.write_buffer(
+            data.uniform.buffer(MeshUniformIndex::MeshData),
+            0,
+            bytemuck::bytes_of(&self.translation),
+        );
+    }
+
+    fn update_render(
+        &mut self,
+        renderer: &Renderer,
+        data: &mut (dyn Any + Send),
+        _local_to_world: &Affine3A,
+    ) {
+        if !self.instances_dirty {
+            return;
+        }
+
+        let data: &mut InstancedMeshRenderData = proxy_data_mut!(data);
+        self.write_instances(renderer, data);
+        self.instances_dirty = false;
+    }
+
+    fn render(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        self.draw_instances(renderer, ctx, binding, RenderPassType::Color);
+    }
+
+    fn render_shadows(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        self.draw_instances(renderer, ctx, binding, RenderPassType::Shadow);
+    }
+
+    fn render_picking(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        debug_assert_ne!(ctx.pass_type, RenderPassType::Shadow);
+
+        self.draw_instances(renderer, ctx, binding, RenderPassType::Picking);
+    }
+
+    fn render_velocity(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        self.draw_instances(renderer, ctx, binding, RenderPassType::Velocity);
+    }
+
+    fn priority(&self, store: &AssetStore) -> u32 {
+        materials_priority(&self.materials, store)
+    }
+
+    fn bounds(&self, local_to_world: &Affine3A) -> Option<BoundingSphere> {
+        self.local_bounds()
+            .map(|b| b.transformed(&(*local_to_world).into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glamx::Vec3;
+
+    #[test]
+    fn bounds_cover_all_instances() {
+        let mut proxy = InstancedMeshProxy::new(
+            HMesh::UNIT_CUBE,
+            vec![],
+            vec![
+                Mat4::from_translation(Vec3::new(-4.0, 0.0, 0.0)),
+                Mat4::from_translation(Vec3::new(4.0, 0.0, 0.0)),
+            ],
+        );
+        assert!(proxy.local_bounds().is_none());
+
+        proxy.mesh_bounds = Some(BoundingSphere {
+            center: Vec3::ZERO,
+            radius: 1.0,
+        });
+        let bounds = proxy.local_bounds().unwrap();
+        assert!(bounds.center.length() < 1e-5);
+        assert!(bounds.radius >= 5.0 - 1e-5);
+
+        proxy.set_instances(vec![]);
+        assert!(proxy.local_bounds().is_none());
+    }
+}
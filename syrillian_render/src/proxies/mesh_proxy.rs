@@ -5,24 +5,28 @@ use crate::model_uniform::ModelUniform;
 use crate::proxies::{
     PROXY_PRIORITY_SOLID, PROXY_PRIORITY_TRANSPARENT, SceneProxy, SceneProxyBinding,
 };
+use crate::rendering::batching::MeshBatchKey;
 #[cfg(debug_assertions)]
 use crate::rendering::debug_renderer::DebugRenderer;
+use crate::rendering::lod::LodView;
 use crate::rendering::picking::hash_to_rgba;
 use crate::rendering::renderer::Renderer;
 use crate::rendering::uniform::ShaderUniform;
+use crate::rendering::viewport::ViewportId;
 use crate::rendering::{GPUDrawCtx, RenderPassType};
 use crate::{proxy_data, proxy_data_mut, try_activate_shader};
-use glamx::Affine3A;
+use glamx::{Affine3A, Mat4};
 use parking_lot::RwLockWriteGuard;
 use std::any::Any;
+use std::collections::HashMap;
 use std::mem::size_of;
 use std::ops::Range;
 use syrillian_asset::mesh::Vertex3D;
 use syrillian_asset::mesh::bone::BoneData;
 use syrillian_asset::store::{AssetStore, H, Store};
 use syrillian_asset::{
-    HComputeShader, HMaterialInstance, HMesh, HTexture2D, Material, MaterialInstance, Shader,
-    Texture2D,
+    AlphaMode, HComputeShader, HMaterialInstance, HMesh, HShader, HTexture2D, Material,
+    MaterialInstance, Shader, Texture2D,
 };
 use syrillian_macros::UniformIndex;
 use syrillian_shadergen::value::MaterialValue;
@@ -46,6 +50,13 @@ pub struct RuntimeMeshData {
     pub skinning_uniforms: Vec<ShaderUniform<MeshSkinningComputeUniformIndex>>,
     pub skinning_vertex_counts: Vec<u32>,
     pub skinning_mesh: Option<HMesh>,
+    /// Morphed copies of the meshlet vertex buffers, which skinning reads from if both are used.
+    pub morphed_buffers: Vec<Buffer>,
+    pub morph_uniforms: Vec<ShaderUniform<MeshMorphComputeUniformIndex>>,
+    pub morph_vertex_counts: Vec<u32>,
+    pub morph_weights: Option<Buffer>,
+    pub morph_target_count: u32,
+    pub morph_mesh: Option<HMesh>,
 }
 
 #[repr(C)]
@@ -66,6 +77,25 @@ pub enum MeshSkinningComputeUniformIndex {
     Dest = 3,
 }
 
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+struct MeshMorphParams {
+    vertex_count: u32,
+    first_vertex: u32,
+    mesh_vertex_count: u32,
+    target_count: u32,
+}
+
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, UniformIndex)]
+pub enum MeshMorphComputeUniformIndex {
+    Params = 0,
+    Weights = 1,
+    Deltas = 2,
+    Source = 3,
+    Dest = 4,
+}
+
 #[derive(Debug, Clone)]
 pub struct MeshSceneProxy {
     pub mesh: HMesh,
@@ -74,23 +104,79 @@ pub struct MeshSceneProxy {
     pub bone_data: BoneData,
     pub bones_dirty: bool,
     pub skinned: bool,
+    /// Morph target weights, missing ones count as zero.
+    pub morph_weights: Vec<f32>,
+    pub morph_dirty: bool,
     pub bounding: Option<BoundingSphere>,
+    pub lod: MeshLodState,
+}
+
+/// Level of detail bookkeeping of a [`MeshSceneProxy`].
+#[derive(Debug, Clone)]
+pub struct MeshLodState {
+    /// Multiplied with the global LOD bias of the renderer.
+    pub bias: f32,
+    /// Level used by the passes of the viewport currently being rendered.
+    pub active: usize,
+    /// Last selected level per viewport, so hysteresis works with multiple cameras.
+    levels: HashMap<ViewportId, usize>,
+}
+
+impl Default for MeshLodState {
+    fn default() -> Self {
+        Self {
+            bias: 1.0,
+            active: 0,
+            levels: HashMap::new(),
+        }
+    }
+}
+
+impl MeshLodState {
+    pub fn with_bias(bias: f32) -> Self {
+        Self {
+            bias,
+            ..Self::default()
+        }
+    }
+
+    /// Forgets all selected levels, e.g. after the mesh was swapped out.
+    pub fn reset(&mut self) {
+        self.active = 0;
+        self.levels.clear();
+    }
+
+    /// Forgets the level selected for a viewport that no longer exists.
+    pub fn forget_viewport(&mut self, viewport: ViewportId) {
+        self.levels.remove(&viewport);
+    }
 }
 
 impl RuntimeMeshData {
+    pub fn new(mesh_data: ModelUniform, uniform: ShaderUniform<MeshUniformIndex>) -> Self {
+        Self {
+            mesh_data,
+            uniform,
+            skinned_buffers: Vec::new(),
+            skinning_uniforms: Vec::new(),
+            skinning_vertex_counts: Vec::new(),
+            skinning_mesh: None,
+            morphed_buffers: Vec::new(),
+            morph_uniforms: Vec::new(),
+            morph_vertex_counts: Vec::new(),
+            morph_weights: None,
+            morph_target_count: 0,
+            morph_mesh: None,
+        }
+    }
+
     pub fn activate_shader(
         &self,
         shader: &RuntimeShader,
         ctx: &GPUDrawCtx,
         pass: &mut RenderPass,
     ) -> bool {
-        try_activate_shader!(shader, pass, ctx => return false);
-
-        if let Some(idx) = shader.bind_groups().model {
-            pass.set_bind_group(idx, self.uniform.bind_group(), &[]);
-        }
-
-        true
+        activate_mesh_shader(shader, &self.uniform, ctx, pass)
     }
 
     fn ensure_skinning_runtime(&mut self, renderer: &Renderer, mesh_handle: HMesh) -> bool {
@@ -115,8 +201,12 @@ impl RuntimeMeshData {
         let skinning_bgl = renderer.cache.bgl_mesh_skinning_compute();
         let bone_buffer = self.uniform.buffer(MeshUniformIndex::BoneData).clone();
 
-        for meshlet in mesh.meshlets() {
+        for (i, meshlet) in mesh.meshlets().iter().enumerate() {
             let vertex_count = meshlet.vertex_count;
+            let source = self
+                .morphed_buffers
+                .get(i)
+                .unwrap_or(&meshlet.vertex_buffer);
             let params = MeshSkinningParams {
                 vertex_count,
                 _pad0: 0,
@@ -135,7 +225,7 @@ impl RuntimeMeshData {
                 ShaderUniform::<MeshSkinningComputeUniformIndex>::builder(skinning_bgl.clone())
                     .with_buffer(bone_buffer.clone())
                     .with_buffer_data(&params)
-                    .with_storage_buffer(meshlet.vertex_buffer.clone())
+                    .with_storage_buffer(source.clone())
                     .with_storage_buffer(output.clone())
                     .build(device);
 
@@ -147,6 +237,129 @@ impl RuntimeMeshData {
         true
     }
 
+    /// Creates the morphed vertex buffers if the mesh has morph targets.
+    /// Returns true if the buffers were (re)created and need to be filled.
+    fn ensure_morph_runtime(&mut self, renderer: &Renderer, mesh_handle: HMesh) -> bool {
+        if self.morph_mesh == Some(mesh_handle) {
+            return false;
+        }
+
+        let Some(mesh) = renderer.cache.mesh(mesh_handle) else {
+            return false;
+        };
+
+        self.morphed_buffers.clear();
+        self.morph_uniforms.clear();
+        self.morph_vertex_counts.clear();
+        self.morph_weights = None;
+        self.morph_target_count = 0;
+        self.morph_mesh = Some(mesh_handle);
+        // skinning has to pick up the new source buffers
+        self.skinning_mesh = None;
+
+        let Some(targets) = mesh.morph_targets() else {
+            return false;
+        };
+
+        let device = &renderer.state.device;
+        let morph_bgl = renderer.cache.bgl_mesh_morph_compute();
+        let weights = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Mesh Morph Weights Buffer"),
+            size: (targets.target_count as u64 * size_of::<f32>() as u64).max(4),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        for meshlet in mesh.meshlets() {
+            let vertex_count = meshlet.vertex_count;
+            let params = MeshMorphParams {
+                vertex_count,
+                first_vertex: meshlet.first_vertex(),
+                mesh_vertex_count: mesh.total_vertex_count(),
+                target_count: targets.target_count,
+            };
+            let output_size = ((vertex_count as u64) * size_of::<Vertex3D>() as u64).max(4);
+            let output = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Morphed Mesh Vertex Buffer"),
+                size: output_size,
+                usage: BufferUsages::VERTEX | BufferUsages::STORAGE | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+
+            let uniform = ShaderUniform::<MeshMorphComputeUniformIndex>::builder(morph_bgl.clone())
+                .with_buffer_data(&params)
+                .with_storage_buffer(weights.clone())
+                .with_storage_buffer(targets.deltas.clone())
+                .with_storage_buffer(meshlet.vertex_buffer.clone())
+                .with_storage_buffer(output.clone())
+                .build(device);
+
+            self.morphed_buffers.push(output);
+            self.morph_uniforms.push(uniform);
+            self.morph_vertex_counts.push(vertex_count);
+        }
+
+        self.morph_weights = Some(weights);
+        self.morph_target_count = targets.target_count;
+
+        true
+    }
+
+    fn write_morph_weights(&self, renderer: &Renderer, weights: &[f32]) {
+        let Some(buffer) = &self.morph_weights else {
+            return;
+        };
+
+        let mut weights = weights.to_vec();
+        weights.resize(self.morph_target_count as usize, 0.0);
+        renderer
+            .state
+            .queue
+            .write_buffer(buffer, 0, bytemuck::cast_slice(&weights));
+    }
+
+    fn dispatch_morph(&self, renderer: &Renderer) {
+        if self.morph_uniforms.is_empty() {
+            return;
+        }
+
+        let mut encoder = renderer
+            .state
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Mesh Morph Compute Encoder"),
+            });
+
+        let shader = renderer.cache.compute_shader(HComputeShader::MESH_MORPH);
+        {
+            let mut pass = encoder.begin_compute_pass(&ComputePassDescriptor {
+                label: Some("Mesh Morph Compute Pass"),
+                ..ComputePassDescriptor::default()
+            });
+
+            pass.set_pipeline(shader.pipeline());
+            for (uniform, vertex_count) in self
+                .morph_uniforms
+                .iter()
+                .zip(self.morph_vertex_counts.iter().copied())
+            {
+                if vertex_count == 0 {
+                    continue;
+                }
+                pass.set_bind_group(0, uniform.bind_group(), &[]);
+                pass.dispatch_workgroups(vertex_count.div_ceil(64), 1, 1);
+            }
+        }
+
+        renderer.state.queue.submit(Some(encoder.finish()));
+    }
+
+    fn has_morphed_vertices(&self, mesh_handle: HMesh, mesh: &RuntimeMesh) -> bool {
+        self.morph_mesh == Some(mesh_handle)
+            && !self.morphed_buffers.is_empty()
+            && self.morphed_buffers.len() == mesh.meshlets().len()
+    }
+
     // TODO: Improve dispatching to be centralized so the driver can batch better
     fn dispatch_skinning(&self, renderer: &Renderer) {
         if self.skinning_uniforms.is_empty() {
@@ -223,6 +436,19 @@ impl SceneProxy for MeshSceneProxy {
 
         let mut skinning_needs_dispatch = false;
 
+        if data.ensure_morph_runtime(renderer, self.mesh) {
+            self.morph_dirty = true;
+        }
+
+        if self.morph_dirty {
+            self.morph_dirty = false;
+            if !data.morph_uniforms.is_empty() {
+                data.write_morph_weights(renderer, &self.morph_weights);
+                data.dispatch_morph(renderer);
+                skinning_needs_dispatch = true;
+            }
+        }
+
         if self.bones_dirty {
             renderer.state.queue.write_buffer(
                 data.uniform.buffer(MeshUniformIndex::BoneData),
@@ -292,22 +518,104 @@ impl SceneProxy for MeshSceneProxy {
         self.draw_mesh_picking(ctx, &renderer.cache, &mesh, data, &mut pass);
     }
 
+    fn render_velocity(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        let data: &RuntimeMeshData = proxy_data!(binding.proxy_data());
+
+        let Some(mesh) = renderer.cache.mesh(self.mesh) else {
+            return;
+        };
+
+        let mut pass = ctx.pass.write();
+
+        // skinned and morphed meshes only get the motion of their transform
+        let prev_transform = Mat4::from(binding.prev_local_to_world);
+        pass.set_immediates(0, bytemuck::bytes_of(&prev_transform));
+
+        self.draw_materials(
+            ctx,
+            &renderer.cache,
+            &mesh,
+            data,
+            &mut pass,
+            RenderPassType::Velocity,
+        );
+    }
+
     fn priority(&self, store: &AssetStore) -> u32 {
-        if self.materials.iter().any(|m| {
-            let instance = store.material_instances.get(*m);
-            let material = store.materials.get(instance.material).clone();
-            instance_is_transparent(&instance, &material, &store.textures)
-        }) {
-            PROXY_PRIORITY_TRANSPARENT
-        } else {
-            PROXY_PRIORITY_SOLID
+        materials_priority(&self.materials, store)
+    }
+
+    fn select_lod(&mut self, renderer: &Renderer, view: &LodView, local_to_world: &Affine3A) {
+        // skinned meshes have no bounds and draw through their skinned base vertex buffers
+        let bounds = self.bounds(local_to_world);
+        let (Some(bounds), false) = (bounds, self.skinned) else {
+            self.lod.active = 0;
+            return;
+        };
+
+        let Some(mesh) = renderer.cache.mesh(self.mesh) else {
+            return;
+        };
+
+        // morphed vertices only exist for the base mesh
+        if mesh.morph_targets().is_some() {
+            self.lod.active = 0;
+            return;
         }
+
+        let view = LodView {
+            bias: view.bias * self.lod.bias,
+            ..*view
+        };
+        let current = self.lod.levels.get(&view.viewport).copied().unwrap_or(0);
+        let level = view.select_level(mesh.lod_errors(), view.projected_diameter(&bounds), current);
+
+        self.lod.levels.insert(view.viewport, level);
+        self.lod.active = level;
+    }
+
+    fn viewport_removed(&mut self, viewport: ViewportId) {
+        self.lod.forget_viewport(viewport);
     }
 
     fn bounds(&self, local_to_world: &Affine3A) -> Option<BoundingSphere> {
         self.bounding
             .map(|b| b.transformed(&(*local_to_world).into()))
     }
+
+    fn batch_key(&self, renderer: &Renderer) -> Option<MeshBatchKey> {
+        // the debug overlays are drawn per proxy
+        #[cfg(debug_assertions)]
+        if DebugRenderer::mesh_edges() || DebugRenderer::mesh_vertex_normals() {
+            return None;
+        }
+
+        // deformed vertices are unique to this proxy
+        if self.skinned {
+            return None;
+        }
+
+        let mesh = renderer.cache.mesh(self.mesh)?;
+        if mesh.morph_targets().is_some() {
+            return None;
+        }
+
+        // transparent slots are sorted back to front, which batches would break
+        if self
+            .materials
+            .iter()
+            .any(|m| renderer.cache.material_instance(*m).transparent)
+        {
+            return None;
+        }
+
+        Some(MeshBatchKey {
+            mesh: self.mesh,
+            materials: self.materials.clone(),
+            material_ranges: self.material_ranges.clone(),
+            lod: self.lod.active,
+        })
+    }
 }
 
 impl MeshSceneProxy {
@@ -353,18 +661,107 @@ impl MeshSceneProxy {
         pass: &mut RwLockWriteGuard<RenderPass>,
         pass_type: RenderPassType,
     ) {
-        let mut current_shader: Option<H<Shader>> = None;
+        let has_skinned_vertices = self.skinned
+            && runtime.skinning_mesh == Some(self.mesh)
+            && runtime.skinned_buffers.len() == mesh.meshlets().len();
+        let has_morphed_vertices = runtime.has_morphed_vertices(self.mesh, mesh);
+
+        let draw = MeshMaterialDraw::new(
+            mesh,
+            &self.materials,
+            &self.material_ranges,
+            self.lod.active,
+        );
+
+        draw.draw(
+            ctx,
+            cache,
+            &runtime.uniform,
+            pass,
+            pass_type,
+            |range, pass| {
+                if has_skinned_vertices {
+                    mesh.draw_with_vertex_buffers(range, &runtime.skinned_buffers, pass);
+                } else if has_morphed_vertices {
+                    mesh.draw_with_vertex_buffers(range, &runtime.morphed_buffers, pass);
+                } else {
+                    mesh.draw_lod(self.lod.active, range, pass);
+                }
+            },
+        );
+    }
+
+    fn setup_mesh_data(
+        &mut self,
+        renderer: &Renderer,
+        local_to_world: &Affine3A,
+    ) -> RuntimeMeshData {
+        let device = &renderer.state.device;
+        let model_bgl = renderer.cache.bgl_model();
+        let mesh_data = ModelUniform::from_matrix(&(*local_to_world).into());
+
+        let uniform = ShaderUniform::<MeshUniformIndex>::builder(model_bgl)
+            .with_buffer_data(&mesh_data)
+            .with_buffer_data_slice(self.bone_data.bones.as_slice())
+            .build(device);
+
+        let mut data = RuntimeMeshData::new(mesh_data, uniform);
+
+        if data.ensure_morph_runtime(renderer, self.mesh) {
+            self.morph_dirty = true;
+        }
+
+        if self.skinned {
+            let _ = data.ensure_skinning_runtime(renderer, self.mesh);
+            self.bones_dirty = true;
+        }
+
+        data
+    }
+}
+
+/// The material slots of one mesh draw, shared by single and instanced mesh proxies.
+pub(crate) struct MeshMaterialDraw<'a> {
+    materials: &'a [HMaterialInstance],
+    ranges: Vec<Range<u32>>,
+}
 
-        let ranges: Vec<Range<u32>> = if self.material_ranges.is_empty() {
+impl<'a> MeshMaterialDraw<'a> {
+    /// Resolves the ranges of every material slot for the given level of the mesh.
+    pub fn new(
+        mesh: &RuntimeMesh,
+        materials: &'a [HMaterialInstance],
+        material_ranges: &[Range<u32>],
+        lod: usize,
+    ) -> Self {
+        let ranges = if let Some(lod_ranges) = mesh.lod_material_ranges(lod) {
+            lod_ranges.to_vec()
+        } else if material_ranges.is_empty() {
             vec![Range {
                 start: 0,
                 end: mesh.total_point_count(),
             }]
         } else {
-            self.material_ranges.clone()
+            material_ranges.to_vec()
         };
 
-        for (i, range) in ranges.iter().enumerate() {
+        Self { materials, ranges }
+    }
+
+    /// Activates the shader and material of every slot that is drawn in this pass, and calls
+    /// `draw` with its range.
+    pub fn draw(
+        &self,
+        ctx: &GPUDrawCtx,
+        cache: &AssetCache,
+        model: &ShaderUniform<MeshUniformIndex>,
+        pass: &mut RenderPass,
+        pass_type: RenderPassType,
+        mut draw: impl FnMut(Range<u32>, &mut RenderPass),
+    ) {
+        let mut current_shader: Option<H<Shader>> = None;
+
+        for (i, range) in self.ranges.iter().enumerate() {
             let h_mat = self
                 .materials
                 .get(i)
@@ -376,6 +773,7 @@ impl MeshSceneProxy {
             let target_shader = match pass_type {
                 RenderPassType::Picking | RenderPassType::PickingUi => shader_set.picking,
                 RenderPassType::Shadow => shader_set.shadow,
+                RenderPassType::Velocity => HShader::DIM3_VELOCITY,
                 _ => shader_set.base,
             };
 
@@ -389,10 +787,14 @@ impl MeshSceneProxy {
                 continue;
             }
 
+            if pass_type == RenderPassType::Velocity && material.transparent {
+                continue;
+            }
+
             let shader = cache.shader(target_shader);
 
             if current_shader != Some(target_shader) {
-                if !runtime.activate_shader(&shader, ctx, pass) {
+                if !activate_mesh_shader(&shader, model, ctx, pass) {
                     return;
                 }
                 current_shader = Some(target_shader);
@@ -414,47 +816,25 @@ impl MeshSceneProxy {
                 pass.set_immediates(0, &material.immediates);
             }
 
-            let has_skinned_vertices = self.skinned
-                && runtime.skinning_mesh == Some(self.mesh)
-                && runtime.skinned_buffers.len() == mesh.meshlets().len();
-            if has_skinned_vertices {
-                mesh.draw_with_vertex_buffers(range.clone(), &runtime.skinned_buffers, pass);
-            } else {
-                mesh.draw(range.clone(), pass);
-            }
+            draw(range.clone(), pass);
         }
     }
+}
 
-    fn setup_mesh_data(
-        &mut self,
-        renderer: &Renderer,
-        local_to_world: &Affine3A,
-    ) -> RuntimeMeshData {
-        let device = &renderer.state.device;
-        let model_bgl = renderer.cache.bgl_model();
-        let mesh_data = ModelUniform::from_matrix(&(*local_to_world).into());
-
-        let uniform = ShaderUniform::<MeshUniformIndex>::builder(model_bgl)
-            .with_buffer_data(&mesh_data)
-            .with_buffer_data_slice(self.bone_data.bones.as_slice())
-            .build(device);
-
-        let mut data = RuntimeMeshData {
-            mesh_data,
-            uniform,
-            skinned_buffers: Vec::new(),
-            skinning_uniforms: Vec::new(),
-            skinning_vertex_counts: Vec::new(),
-            skinning_mesh: None,
-        };
-
-        if self.skinned {
-            let _ = data.ensure_skinning_runtime(renderer, self.mesh);
-            self.bones_dirty = true;
-        }
+/// Activates a mesh shader and binds the model uniform, if the shader uses one.
+pub(crate) fn activate_mesh_shader(
+    shader: &RuntimeShader,
+    model: &ShaderUniform<MeshUniformIndex>,
+    ctx: &GPUDrawCtx,
+    pass: &mut RenderPass,
+) -> bool {
+    try_activate_shader!(shader, pass, ctx => return false);
 
-        data
+    if let Some(idx) = shader.bind_groups().model {
+        pass.set_bind_group(idx, model.bind_group(), &[]);
     }
+
+    true
 }
 
 #[cfg(debug_assertions)]
@@ -466,7 +846,6 @@ fn draw_edges(
     pass: &mut RenderPass,
 ) {
     use glamx::Vec4;
-    use syrillian_asset::HShader;
 
     const COLOR: Vec4 = Vec4::new(1.0, 0.0, 1.0, 1.0);
 
@@ -488,8 +867,6 @@ fn draw_vertex_normals(
     runtime: &RuntimeMeshData,
     pass: &mut RenderPass,
 ) {
-    use syrillian_asset::HShader;
-
     let shader = cache.shader(HShader::DEBUG_VERTEX_NORMALS);
     if !runtime.activate_shader(&shader, ctx, pass) {
         return;
@@ -498,6 +875,19 @@ fn draw_vertex_normals(
     mesh.draw_all_as_instances(0..2, pass);
 }
 
+/// Draw priority of a mesh with the given material slots.
+pub(crate) fn materials_priority(materials: &[HMaterialInstance], store: &AssetStore) -> u32 {
+    if materials.iter().any(|m| {
+        let instance = store.material_instances.get(*m);
+        let material = store.materials.get(instance.material).clone();
+        instance_is_transparent(&instance, &material, &store.textures)
+    }) {
+        PROXY_PRIORITY_TRANSPARENT
+    } else {
+        PROXY_PRIORITY_SOLID
+    }
+}
+
 fn instance_value_f32(
     instance: &MaterialInstance,
     material: &Material,
@@ -533,6 +923,17 @@ fn instance_is_transparent(
     material: &Material,
     textures: &Store<Texture2D>,
 ) -> bool {
+    let alpha_mode = match instance.value_u32("alpha_mode") {
+        Some(mode) => AlphaMode::from_u32(mode),
+        None => match material.layout().default_value("alpha_mode") {
+            Some(MaterialValue::U32(mode)) => AlphaMode::from_u32(*mode),
+            _ => None,
+        },
+    };
+    if alpha_mode.is_some_and(|mode| mode != AlphaMode::Blend) {
+        return false;
+    }
+
     let alpha = instance_value_f32(instance, material, "alpha", 1.0);
     let has_transparency = instance_value_bool(instance, material, "has_transparency", false);
 
@@ -546,3 +947,20 @@ fn instance_is_transparent(
 
     alpha < 1.0 || has_transparency || diffuse.has_transparency
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removed_viewports_are_forgotten() {
+        let mut lod = MeshLodState::default();
+        lod.levels.insert(ViewportId::PRIMARY, 1);
+        lod.levels.insert(ViewportId(1), 2);
+
+        lod.forget_viewport(ViewportId(1));
+
+        assert_eq!(lod.levels.get(&ViewportId::PRIMARY), Some(&1));
+        assert!(!lod.levels.contains_key(&ViewportId(1)));
+    }
+}
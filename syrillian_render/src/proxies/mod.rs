@@ -3,14 +3,21 @@ use std::any::Any;
 use std::fmt::Debug;
 
 pub mod debug_proxy;
+pub mod instanced_mesh_proxy;
 pub mod mesh_proxy;
+pub mod sprite_proxy;
 pub mod text_proxy;
 
 use crate::ObjectHash;
+use crate::rendering::batching::MeshBatchKey;
+use crate::rendering::lod::LodView;
 use crate::rendering::renderer::Renderer;
+use crate::rendering::viewport::ViewportId;
 use crate::rendering::{GPUDrawCtx, RenderPassType};
 pub use debug_proxy::*;
+pub use instanced_mesh_proxy::*;
 pub use mesh_proxy::*;
+pub use sprite_proxy::*;
 use syrillian_asset::store::AssetStore;
 use syrillian_utils::BoundingSphere;
 use syrillian_utils::component_id::TypedComponentId;
@@ -120,17 +127,51 @@ pub trait SceneProxy: Send + Any + Debug {
     ) {
     }
 
+    /// Draws the screen space motion since the last frame into the velocity G-buffer. Only
+    /// called for proxies whose transform changed, the rest moves with the camera.
+    fn render_velocity(
+        &self,
+        _renderer: &Renderer,
+        _ctx: &GPUDrawCtx,
+        _binding: &SceneProxyBinding,
+    ) {
+    }
+
     fn priority(&self, store: &AssetStore) -> u32;
 
+    /// Called once per frame and viewport before any pass is drawn, so the proxy can pick the
+    /// level of detail it renders for the given camera.
+    fn select_lod(&mut self, _renderer: &Renderer, _view: &LodView, _local_to_world: &Affine3A) {}
+
+    /// Called when a viewport was removed, so per viewport state can be dropped.
+    fn viewport_removed(&mut self, _viewport: ViewportId) {}
+
     fn bounds(&self, _local_to_world: &Affine3A) -> Option<BoundingSphere> {
         None
     }
+
+    /// Proxies returning the same key in a pass are drawn together in one instanced draw, with
+    /// their transform taken from the binding. Such a proxy isn't asked to render itself in the
+    /// color, shadow and picking passes of that frame. `None` always draws the proxy on its own.
+    fn batch_key(&self, _renderer: &Renderer) -> Option<MeshBatchKey> {
+        None
+    }
+
+    /// Proxies returning the same key that are drawn right after each other in the color and
+    /// picking passes have their sprites drawn together in one instanced draw, with the transform
+    /// taken from the binding. Such a proxy isn't asked to render itself in those passes.
+    /// `None` always draws the proxy on its own.
+    fn sprite_batch(&self) -> Option<SpriteBatch<'_>> {
+        None
+    }
 }
 
 pub struct SceneProxyBinding {
     pub component_id: TypedComponentId,
     pub object_hash: ObjectHash,
     pub local_to_world: Affine3A,
+    /// The transform the last frame was rendered with
+    pub prev_local_to_world: Affine3A,
     transform_dirty: bool,
     proxy_data: Box<dyn Any + Send>,
     pub proxy: Box<dyn SceneProxy>,
@@ -149,6 +190,7 @@ impl SceneProxyBinding {
             component_id,
             object_hash,
             local_to_world,
+            prev_local_to_world: local_to_world,
             transform_dirty: false,
             proxy_data,
             proxy,
@@ -173,11 +215,29 @@ impl SceneProxyBinding {
         }
     }
 
+    /// Whether the transform changed since the last frame was rendered
+    pub fn has_moved(&self) -> bool {
+        self.prev_local_to_world != self.local_to_world
+    }
+
+    /// Keeps the current transform as the one of the last frame, once all viewports rendered.
+    pub fn finish_frame(&mut self) {
+        self.prev_local_to_world = self.local_to_world;
+    }
+
     pub fn update(&mut self, renderer: &Renderer) {
         self.proxy
             .update_render(renderer, self.proxy_data.as_mut(), &self.local_to_world);
     }
 
+    pub fn select_lod(&mut self, renderer: &Renderer, view: &LodView) {
+        self.proxy.select_lod(renderer, view, &self.local_to_world);
+    }
+
+    pub fn viewport_removed(&mut self, viewport: ViewportId) {
+        self.proxy.viewport_removed(viewport);
+    }
+
     pub fn bounds(&self) -> Option<BoundingSphere> {
         self.proxy.bounds(&self.local_to_world)
     }
@@ -203,6 +263,7 @@ impl SceneProxyBinding {
             RenderPassType::Picking | RenderPassType::PickingUi => {
                 self.proxy.render_picking(renderer, ctx, self)
             }
+            RenderPassType::Velocity => self.proxy.render_velocity(renderer, ctx, self),
         }
     }
 }
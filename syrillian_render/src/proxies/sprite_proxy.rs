@@ -0,0 +1,457 @@
+use crate::model_uniform::ModelUniform;
+use crate::proxies::mesh_proxy::MeshUniformIndex;
+use crate::proxies::{PROXY_PRIORITY_TRANSPARENT, SceneProxy, SceneProxyBinding};
+use crate::rendering::picking::hash_to_rgba;
+use crate::rendering::renderer::Renderer;
+use crate::rendering::uniform::ShaderUniform;
+use crate::rendering::{GPUDrawCtx, RenderPassType};
+use crate::{proxy_data, proxy_data_mut, try_activate_shader};
+use bytemuck::Zeroable;
+use glamx::{Affine3A, Vec2, Vec3, Vec4};
+use std::any::Any;
+use std::ops::Range;
+use syrillian_asset::mesh::bone::BoneData;
+use syrillian_asset::store::AssetStore;
+use syrillian_asset::{HMaterialInstance, HShader, HTexture2D};
+use syrillian_utils::BoundingSphere;
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{Buffer, BufferUsages};
+
+/// The sorting layer of a sprite.
+///
+/// Sprites on higher layers are drawn over sprites on lower layers. Sprites on the same layer are
+/// drawn by their order in the layer, and then back to front.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortingLayer(pub i8);
+
+impl SortingLayer {
+    pub const BACKGROUND: SortingLayer = SortingLayer(-64);
+    pub const DEFAULT: SortingLayer = SortingLayer(0);
+    pub const FOREGROUND: SortingLayer = SortingLayer(64);
+
+    /// The proxy priority of sprites on this layer, with the given order in the layer.
+    /// Sprites are drawn after all other transparent proxies.
+    pub fn priority(self, order: i16) -> u32 {
+        let layer = (self.0 as i32 - i8::MIN as i32) as u32;
+        let order = (order as i32 - i16::MIN as i32) as u32;
+        PROXY_PRIORITY_TRANSPARENT + 1 + ((layer << 16) | order)
+    }
+}
+
+/// A single sprite quad, as it's sent to the GPU.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SpriteInstance {
+    /// xy = bottom left corner, zw = size, in the local space of the object
+    pub rect: Vec4,
+    /// xy = uv of the top left corner, zw = uv of the bottom right corner
+    pub uv_rect: Vec4,
+    pub color: Vec4,
+}
+
+impl SpriteInstance {
+    pub fn min(&self) -> Vec2 {
+        Vec2::new(self.rect.x, self.rect.y)
+    }
+
+    pub fn max(&self) -> Vec2 {
+        Vec2::new(self.rect.x + self.rect.z, self.rect.y + self.rect.w)
+    }
+}
+
+/// A sprite quad as it's sent to the GPU, given by its bottom left corner and the two edges
+/// leaving it.
+///
+/// Sprites drawn by their own proxy stay in the local space of their object. Batched sprites are
+/// moved into world space up front and carry the picking color of their object.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct SpriteQuad {
+    pub origin: Vec4,
+    pub axis_x: Vec4,
+    pub axis_y: Vec4,
+    /// xy = uv of the top left corner, zw = uv of the bottom right corner
+    pub uv_rect: Vec4,
+    pub color: Vec4,
+    /// Added to the picking color of the draw
+    pub pick_color: Vec4,
+}
+
+impl SpriteQuad {
+    pub fn new(sprite: &SpriteInstance, transform: &Affine3A, pick_color: [f32; 4]) -> Self {
+        let origin = transform.transform_point3(sprite.min().extend(0.0));
+        let axis_x = transform.transform_vector3(Vec3::X * sprite.rect.z);
+        let axis_y = transform.transform_vector3(Vec3::Y * sprite.rect.w);
+
+        Self {
+            origin: origin.extend(1.0),
+            axis_x: axis_x.extend(0.0),
+            axis_y: axis_y.extend(0.0),
+            uv_rect: sprite.uv_rect,
+            color: sprite.color,
+            pick_color: Vec4::from(pick_color),
+        }
+    }
+
+    /// The quad of a sprite in the local space of its object.
+    pub fn local(sprite: &SpriteInstance) -> Self {
+        Self::new(sprite, &Affine3A::IDENTITY, [0.0; 4])
+    }
+}
+
+/// The material part of a [`SpriteBatchKey`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SpriteBatchMaterial {
+    /// A material the sprite renderer made for its sheet. These only differ in their texture,
+    /// so sprites on the same sheet are batched even if every renderer made its own.
+    Sheet(HTexture2D),
+    /// Any other material, which is only batched with itself
+    Instance(HMaterialInstance),
+}
+
+/// Everything that has to match for the sprites of two proxies to be drawn in one batch.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SpriteBatchKey {
+    pub material: SpriteBatchMaterial,
+    pub layer: SortingLayer,
+    pub order: i16,
+}
+
+/// The sprites a proxy adds to a batch.
+#[derive(Debug, Copy, Clone)]
+pub struct SpriteBatch<'a> {
+    pub key: SpriteBatchKey,
+    /// The material of the proxy. A batch is drawn with the material of its first proxy.
+    pub material: HMaterialInstance,
+    pub sprites: &'a [SpriteInstance],
+}
+
+#[derive(Debug)]
+pub struct SpriteRenderData {
+    uniform: ShaderUniform<MeshUniformIndex>,
+    instance_buffer: Buffer,
+    instance_count: u32,
+}
+
+/// Draws all sprites of a sprite renderer with a single instanced draw call.
+///
+/// Proxies with the same [`SpriteBatchKey`] that are drawn right after each other are batched
+/// into one draw call by the renderer instead.
+#[derive(Debug, Clone)]
+pub struct SpriteProxy {
+    pub material: HMaterialInstance,
+    /// The sheet texture `material` was made for, if the sprite renderer made it
+    pub sheet_texture: Option<HTexture2D>,
+    pub instances: Vec<SpriteInstance>,
+    pub layer: SortingLayer,
+    pub order: i16,
+    instances_dirty: bool,
+    translation: ModelUniform,
+}
+
+impl SpriteProxy {
+    pub fn new(material: HMaterialInstance, instances: Vec<SpriteInstance>) -> Self {
+        Self {
+            material,
+            sheet_texture: None,
+            instances,
+            layer: SortingLayer::DEFAULT,
+            order: 0,
+            instances_dirty: false,
+            translation: ModelUniform::empty(),
+        }
+    }
+
+    pub fn set_instances(&mut self, instances: Vec<SpriteInstance>) {
+        self.instances = instances;
+        self.instances_dirty = true;
+    }
+
+    fn local_bounds(&self) -> Option<BoundingSphere> {
+        let first = self.instances.first()?;
+        let (min, max) = self
+            .instances
+            .iter()
+            .fold((first.min(), first.max()), |(min, max), sprite| {
+                (min.min(sprite.min()), max.max(sprite.max()))
+            });
+
+        Some(BoundingSphere {
+            center: ((min + max) * 0.5).extend(0.0),
+            radius: ((max - min) * 0.5).length(),
+        })
+    }
+
+    fn write_instances(&self, renderer: &Renderer, data: &mut SpriteRenderData) {
+        let quads = local_quads(&self.instances);
+        let bytes: &[u8] = bytemuck::cast_slice(&quads);
+        if data.instance_buffer.size() < bytes.len() as u64 {
+            data.instance_buffer = create_instance_buffer(renderer, &quads);
+        } else if !bytes.is_empty() {
+            renderer
+                .state
+                .queue
+                .write_buffer(&data.instance_buffer, 0, bytes);
+        }
+        data.instance_count = quads.len() as u32;
+    }
+}
+
+fn local_quads(instances: &[SpriteInstance]) -> Vec<SpriteQuad> {
+    instances.iter().map(SpriteQuad::local).collect()
+}
+
+fn create_instance_buffer(renderer: &Renderer, quads: &[SpriteQuad]) -> Buffer {
+    // wgpu doesn't allow empty vertex buffers to be bound, keep room for one sprite
+    let empty = SpriteQuad::zeroed();
+    let contents = if quads.is_empty() {
+        bytemuck::bytes_of(&empty)
+    } else {
+        bytemuck::cast_slice(quads)
+    };
+
+    renderer
+        .state
+        .device
+        .create_buffer_init(&BufferInitDescriptor {
+            label: Some("Sprite Instance Buffer"),
+            contents,
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+        })
+}
+
+/// Draws the `quads` of `buffer` with the sprite shader of the current color or picking pass.
+/// `pick_color` is the picking color of the whole draw.
+pub(crate) fn draw_sprites(
+    renderer: &Renderer,
+    ctx: &GPUDrawCtx,
+    model: &ShaderUniform<MeshUniformIndex>,
+    material: HMaterialInstance,
+    buffer: &Buffer,
+    quads: Range<u32>,
+    pick_color: [f32; 4],
+) {
+    let picking = ctx.pass_type == RenderPassType::Picking;
+    let shader = if picking {
+        renderer.cache.shader(HShader::SPRITE_PICKING)
+    } else {
+        renderer.cache.shader(HShader::SPRITE)
+    };
+    let material = renderer.cache.material_instance(material);
+
+    let mut pass = ctx.pass.write();
+    try_activate_shader!(shader, &mut pass, ctx => return);
+
+    let groups = shader.bind_groups();
+    if let Some(idx) = groups.model {
+        pass.set_bind_group(idx, model.bind_group(), &[]);
+    }
+    if let Some(idx) = groups.material {
+        pass.set_bind_group(idx, &material.bind_group, &[]);
+    }
+
+    if picking {
+        pass.set_immediates(0, bytemuck::bytes_of(&pick_color));
+    }
+    pass.set_vertex_buffer(0, buffer.slice(..));
+    pass.draw(0..6, quads);
+}
+
+impl SceneProxy for SpriteProxy {
+    fn setup_render(
+        &mut self,
+        renderer: &Renderer,
+        local_to_world: &Affine3A,
+    ) -> Box<dyn Any + Send> {
+        self.translation.update(&(*local_to_world).into());
+
+        let uniform = ShaderUniform::<MeshUniformIndex>::builder(renderer.cache.bgl_model())
+            .with_buffer_data(&self.translation)
+            .with_buffer_data(&BoneData::DUMMY)
+            .build(&renderer.state.device);
+        self.instances_dirty = false;
+
+        Box::new(SpriteRenderData {
+            uniform,
+            instance_buffer: create_instance_buffer(renderer, &local_quads(&self.instances)),
+            instance_count: self.instances.len() as u32,
+        })
+    }
+
+    fn refresh_transform(
+        &mut self,
+        renderer: &Renderer,
+        data: &mut (dyn Any + Send),
+        local_to_world: &Affine3A,
+    ) {
+        let data: &mut SpriteRenderData = proxy_data_mut!(data);
+
+        self.translation.update(&(*local_to_world).into());
+        renderer.state.queue.write_buffer(
+            data.uniform.buffer(MeshUniformIndex::MeshData),
+            0,
+            bytemuck::bytes_of(&self.translation),
+        );
+    }
+
+    fn update_render(
+        &mut self,
+        renderer: &Renderer,
+        data: &mut (dyn Any + Send),
+        _local_to_world: &Affine3A,
+    ) {
+        if !self.instances_dirty {
+            return;
+        }
+
+        let data: &mut SpriteRenderData = proxy_data_mut!(data);
+        self.write_instances(renderer, data);
+        self.instances_dirty = false;
+    }
+
+    fn render(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        if !ctx.transparency_pass || ctx.pass_type != RenderPassType::Color {
+            return;
+        }
+
+        let data: &SpriteRenderData = proxy_data!(binding.proxy_data());
+        if data.instance_count == 0 {
+            return;
+        }
+
+        draw_sprites(
+            renderer,
+            ctx,
+            &data.uniform,
+            self.material,
+            &data.instance_buffer,
+            0..data.instance_count,
+            [0.0; 4],
+        );
+    }
+
+    fn render_picking(&self, renderer: &Renderer, ctx: &GPUDrawCtx, binding: &SceneProxyBinding) {
+        debug_assert_ne!(ctx.pass_type, RenderPassType::Shadow);
+
+        let data: &SpriteRenderData = proxy_data!(binding.proxy_data());
+        if data.instance_count == 0 {
+            return;
+        }
+
+        draw_sprites(
+            renderer,
+            ctx,
+            &data.uniform,
+            self.material,
+            &data.instance_buffer,
+            0..data.instance_count,
+            hash_to_rgba(binding.object_hash),
+        );
+    }
+
+    fn priority(&self, _store: &AssetStore) -> u32 {
+        self.layer.priority(self.order)
+    }
+
+    fn bounds(&self, local_to_world: &Affine3A) -> Option<BoundingSphere> {
+        self.local_bounds()
+            .map(|b| b.transformed(&(*local_to_world).into()))
+    }
+
+    fn sprite_batch(&self) -> Option<SpriteBatch<'_>> {
+        let material = match self.sheet_texture {
+            Some(texture) => SpriteBatchMaterial::Sheet(texture),
+            None => SpriteBatchMaterial::Instance(self.material),
+        };
+        let key = SpriteBatchKey {
+            material,
+            layer: self.layer,
+            order: self.order,
+        };
+        Some(SpriteBatch {
+            key,
+            material: self.material,
+            sprites: &self.instances,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_sort_before_order() {
+        let back = SortingLayer::BACKGROUND.priority(i16::MAX);
+        let default_low = SortingLayer::DEFAULT.priority(-1);
+        let default_high = SortingLayer::DEFAULT.priority(1);
+        let front = SortingLayer::FOREGROUND.priority(i16::MIN);
+
+        assert!(PROXY_PRIORITY_TRANSPARENT < SortingLayer(i8::MIN).priority(i16::MIN));
+        assert!(back < default_low);
+        assert!(default_low < default_high);
+        assert!(default_high < front);
+        assert!(SortingLayer(i8::MAX).priority(i16::MAX) < u32::MAX);
+    }
+
+    #[test]
+    fn quads_follow_the_object_transform() {
+        let sprite = SpriteInstance {
+            rect: Vec4::new(1.0, 2.0, 3.0, 4.0),
+            uv_rect: Vec4::new(0.0, 0.0, 1.0, 1.0),
+            color: Vec4::ONE,
+        };
+
+        let local = SpriteQuad::local(&sprite);
+        assert_eq!(local.origin, Vec4::new(1.0, 2.0, 0.0, 1.0));
+        assert_eq!(local.axis_x, Vec4::new(3.0, 0.0, 0.0, 0.0));
+        assert_eq!(local.axis_y, Vec4::new(0.0, 4.0, 0.0, 0.0));
+        assert_eq!(local.pick_color, Vec4::ZERO);
+
+        let transform = Affine3A::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            glamx::Quat::from_rotation_z(std::f32::consts::FRAC_PI_2),
+            Vec3::new(10.0, 0.0, 5.0),
+        );
+        let world = SpriteQuad::new(&sprite, &transform, [1.0, 0.0, 0.0, 1.0]);
+        assert!(
+            world
+                .origin
+                .abs_diff_eq(Vec4::new(6.0, 2.0, 5.0, 1.0), 1e-5)
+        );
+        assert!(
+            world
+                .axis_x
+                .abs_diff_eq(Vec4::new(0.0, 6.0, 0.0, 0.0), 1e-5)
+        );
+        assert!(
+            world
+                .axis_y
+                .abs_diff_eq(Vec4::new(-8.0, 0.0, 0.0, 0.0), 1e-5)
+        );
+        assert_eq!(world.uv_rect, sprite.uv_rect);
+        assert_eq!(world.pick_color, Vec4::new(1.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn bounds_cover_all_sprites() {
+        let sprite = |x: f32, y: f32| SpriteInstance {
+            rect: Vec4::new(x, y, 1.0, 1.0),
+            uv_rect: Vec4::new(0.0, 0.0, 1.0, 1.0),
+            color: Vec4::ONE,
+        };
+        let proxy = SpriteProxy::new(
+            HMaterialInstance::DEFAULT,
+            vec![sprite(-2.0, 0.0), sprite(1.0, 0.0)],
+        );
+
+        let bounds = proxy.local_bounds().unwrap();
+        assert_eq!(bounds.center, Vec3::new(0.0, 0.5, 0.0));
+        assert!(bounds.radius >= 2.0);
+        assert!(
+            SpriteProxy::new(HMaterialInstance::DEFAULT, vec![])
+                .local_bounds()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,479 @@
+//! Automatic instancing of scene proxies.
+//!
+//! Proxies that draw the same mesh with the same materials and level of detail only differ in
+//! their transform. For every scene pass, the [`Renderer`] groups them by their [`MeshBatchKey`]
+//! and draws each group with one instanced draw per material slot. The transforms are read from
+//! a per-pass instance stream, while the model uniform of the batch stays the identity. The
+//! instance buffers are kept across frames and only grow when a pass needs more room.
+//!
+//! Shaders that bring their own vertex stage have to apply `instance_transform(instance)` for
+//! their meshes to show up in the right place when batched.
+//!
+//! Sprites are transparent, so their draw order has to stay intact. Only runs of proxies that
+//! are drawn right after each other and share a [`SpriteBatchKey`] are batched, with their quads
+//! moved into world space on the CPU.
+
+use crate::ObjectHash;
+use crate::cache::AssetCache;
+use crate::model_uniform::ModelUniform;
+use crate::proxies::{
+    MeshMaterialDraw, MeshUniformIndex, SceneProxyBinding, SpriteBatchKey, SpriteQuad, draw_sprites,
+};
+use crate::rendering::picking::hash_to_rgba;
+use crate::rendering::renderer::Renderer;
+use crate::rendering::uniform::ShaderUniform;
+use crate::rendering::{GPUDrawCtx, RenderPassType};
+use glamx::{Affine3A, Mat4};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use syrillian_asset::mesh::MeshInstance;
+use syrillian_asset::mesh::bone::BoneData;
+use syrillian_asset::{HMaterialInstance, HMesh};
+use syrillian_utils::TypedComponentId;
+use wgpu::{Buffer, BufferDescriptor, BufferUsages, Device};
+
+/// Smallest number of proxies that is drawn as one instanced batch.
+pub const MIN_BATCH_SIZE: usize = 2;
+
+/// Everything that has to match for two proxies to be drawn in one instanced batch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MeshBatchKey {
+    pub mesh: HMesh,
+    pub materials: Vec<HMaterialInstance>,
+    pub material_ranges: Vec<Range<u32>>,
+    pub lod: usize,
+}
+
+/// Proxies sharing a [`MeshBatchKey`], in the order they were sorted in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshBatch {
+    pub key: MeshBatchKey,
+    pub members: Vec<TypedComponentId>,
+}
+
+/// Groups proxies by their batch key, ordered by the first member of every group.
+///
+/// Proxies without a key and groups smaller than [`MIN_BATCH_SIZE`] are left out, those are
+/// drawn one by one.
+pub fn group_batches(
+    proxies: impl IntoIterator<Item = (TypedComponentId, Option<MeshBatchKey>)>,
+) -> Vec<MeshBatch> {
+    let mut batches: Vec<MeshBatch> = Vec::new();
+    let mut lookup: HashMap<MeshBatchKey, usize> = HashMap::new();
+
+    for (id, key) in proxies {
+        let Some(key) = key else {
+            continue;
+        };
+
+        match lookup.get(&key) {
+            Some(&idx) => batches[idx].members.push(id),
+            None => {
+                lookup.insert(key.clone(), batches.len());
+                batches.push(MeshBatch {
+                    key,
+                    members: vec![id],
+                });
+            }
+        }
+    }
+
+    batches.retain(|batch| batch.members.len() >= MIN_BATCH_SIZE);
+    batches
+}
+
+/// The instance of a proxy in a batch, picked as the object the proxy belongs to.
+pub fn batch_instance(local_to_world: &Affine3A, object_hash: ObjectHash) -> MeshInstance {
+    MeshInstance::new(Mat4::from(*local_to_world), hash_to_rgba(object_hash))
+}
+
+/// Model uniform of batched draws. The transforms all come from the instance stream.
+pub(crate) fn identity_model_uniform(
+    cache: &AssetCache,
+    device: &Device,
+) -> ShaderUniform<MeshUniformIndex> {
+    ShaderUniform::<MeshUniformIndex>::builder(cache.bgl_model())
+        .with_buffer_data(&ModelUniform::empty())
+        .with_buffer_data_slice(BoneData::DUMMY.as_slice())
+        .build(device)
+}
+
+/// The scene pass an instance buffer belongs to. Shadow passes use their shadow layer,
+/// all other passes layer `0`.
+pub(crate) type BatchSlot = (RenderPassType, u32);
+
+/// Instance buffers of the batched draws, one per [`BatchSlot`], kept across frames.
+///
+/// Queued buffer writes only land when the next submission starts, so passes that are submitted
+/// together need separate slots.
+#[derive(Default)]
+pub(crate) struct BatchInstanceBuffers {
+    buffers: RefCell<HashMap<BatchSlot, Buffer>>,
+}
+
+impl BatchInstanceBuffers {
+    /// Writes `bytes` into the buffer of `slot`, reallocating it if they don't fit.
+    fn write(&self, renderer: &Renderer, slot: BatchSlot, bytes: &[u8]) -> Buffer {
+        let mut buffers = self.buffers.borrow_mut();
+
+        let buffer = match buffers.get(&slot) {
+            Some(buffer) if buffer.size() >= bytes.len() as u64 => buffer.clone(),
+            _ => {
+                // leave headroom, so a few more instances don't reallocate again
+                let size = (bytes.len() as u64).next_power_of_two();
+                let buffer = renderer.state.device.create_buffer(&BufferDescriptor {
+                    label: Some("Batch Instance Buffer"),
+                    size,
+                    usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                buffers.insert(slot, buffer.clone());
+                buffer
+            }
+        };
+
+        renderer.state.queue.write_buffer(&buffer, 0, bytes);
+        buffer
+    }
+}
+
+/// The instanced draws of one scene pass.
+#[derive(Default)]
+pub(crate) struct MeshBatches {
+    batched: HashSet<TypedComponentId>,
+    draws: Vec<(MeshBatchKey, Range<u32>)>,
+    instances: Option<Buffer>,
+}
+
+impl MeshBatches {
+    /// Batches the sorted proxies of a pass and uploads the instances of all batches into the
+    /// instance buffer of `slot`.
+    pub fn collect(
+        renderer: &Renderer,
+        bindings: &HashMap<TypedComponentId, SceneProxyBinding>,
+        proxies: &[TypedComponentId],
+        slot: BatchSlot,
+    ) -> Self {
+        match slot.0 {
+            RenderPassType::Color | RenderPassType::Shadow | RenderPassType::Picking => (),
+            RenderPassType::Color2D | RenderPassType::PickingUi | RenderPassType::Velocity => {
+                return Self::default();
+            }
+        }
+
+        let keyed = proxies.iter().filter_map(|id| {
+            let binding = bindings.get(id)?;
+            Some((*id, binding.proxy.batch_key(renderer)))
+        });
+        let batches = group_batches(keyed);
+        if batches.is_empty() {
+            return Self::default();
+        }
+
+        let mut batched = HashSet::new();
+        let mut draws = Vec::with_capacity(batches.len());
+        let mut instances = Vec::new();
+
+        for batch in batches {
+            let start = instances.len() as u32;
+            for id in batch.members {
+                let binding = &bindings[&id];
+                instances.push(batch_instance(&binding.local_to_world, binding.object_hash));
+                batched.insert(id);
+            }
+            draws.push((batch.key, start..instances.len() as u32));
+        }
+
+        let buffer =
+            renderer
+                .batch_instances()
+                .write(renderer, slot, bytemuck::cast_slice(&instances));
+
+        Self {
+            batched,
+            draws,
+            instances: Some(buffer),
+        }
+    }
+
+    pub fn contains(&self, id: &TypedComponentId) -> bool {
+        self.batched.contains(id)
+    }
+
+    pub fn render(&self, renderer: &Renderer, ctx: &GPUDrawCtx) {
+        let Some(instance_buffer) = &self.instances else {
+            return;
+        };
+
+        let mut pass = ctx.pass.write();
+        let picking = ctx.pass_type == RenderPassType::Picking;
+
+        for (key, instances) in &self.draws {
+            let Some(mesh) = renderer.cache.mesh(key.mesh) else {
+                continue;
+            };
+
+            let draw = MeshMaterialDraw::new(&mesh, &key.materials, &key.material_ranges, key.lod);
+            draw.draw(
+                ctx,
+                &renderer.cache,
+                renderer.batch_model(),
+                &mut pass,
+                ctx.pass_type,
+                |range, pass| {
+                    if picking {
+                        // the instances carry the picking colors
+                        pass.set_immediates(0, bytemuck::bytes_of(&[0.0f32; 4]));
+                    }
+                    mesh.draw_lod_instanced(
+                        key.lod,
+                        range,
+                        instance_buffer,
+                        instances.clone(),
+                        pass,
+                    );
+                },
+            );
+        }
+    }
+}
+
+/// Proxies drawn right after each other that share a [`SpriteBatchKey`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpriteRun {
+    pub key: SpriteBatchKey,
+    pub members: Vec<TypedComponentId>,
+}
+
+/// Groups consecutive proxies with the same sprite batch key, so the draw order is kept.
+///
+/// Proxies without a key break up runs. Runs shorter than [`MIN_BATCH_SIZE`] are left out,
+/// those are drawn one by one.
+pub fn group_sprite_runs(
+    proxies: impl IntoIterator<Item = (TypedComponentId, Option<SpriteBatchKey>)>,
+) -> Vec<SpriteRun> {
+    let mut runs: Vec<SpriteRun> = Vec::new();
+    let mut open = false;
+
+    for (id, key) in proxies {
+        let Some(key) = key else {
+            open = false;
+            continue;
+        };
+
+        match runs.last_mut() {
+            Some(run) if open && run.key == key => run.members.push(id),
+            _ => runs.push(SpriteRun {
+                key,
+                members: vec![id],
+            }),
+        }
+        open = true;
+    }
+
+    runs.retain(|run| run.members.len() >= MIN_BATCH_SIZE);
+    runs
+}
+
+/// The batched sprite draws of one scene pass.
+#[derive(Default)]
+pub(crate) struct SpriteBatches {
+    batched: HashSet<TypedComponentId>,
+    /// The material and quads of every run, by the first proxy of the run
+    runs: HashMap<TypedComponentId, (HMaterialInstance, Range<u32>)>,
+    quads: Option<Buffer>,
+}
+
+impl SpriteBatches {
+    /// Batches the sorted proxies of a pass and uploads the quads of all runs into the sprite
+    /// buffer of `slot`.
+    pub fn collect(
+        renderer: &Renderer,
+        bindings: &HashMap<TypedComponentId, SceneProxyBinding>,
+        proxies: &[TypedComponentId],
+        slot: BatchSlot,
+    ) -> Self {
+        match slot.0 {
+            RenderPassType::Color | RenderPassType::Picking => (),
+            RenderPassType::Color2D
+            | RenderPassType::Shadow
+            | RenderPassType::PickingUi
+            | RenderPassType::Velocity => return Self::default(),
+        }
+
+        let keyed = proxies.iter().map(|id| {
+            let key = bindings
+                .get(id)
+                .and_then(|binding| binding.proxy.sprite_batch())
+                .map(|batch| batch.key);
+            (*id, key)
+        });
+        let sprite_runs = group_sprite_runs(keyed);
+
+        let mut batched = HashSet::new();
+        let mut runs = HashMap::with_capacity(sprite_runs.len());
+        let mut quads = Vec::new();
+
+        for run in sprite_runs {
+            let start = quads.len() as u32;
+            let mut material = None;
+            for id in &run.members {
+                let binding = &bindings[id];
+                let Some(batch) = binding.proxy.sprite_batch() else {
+                    continue;
+                };
+                material.get_or_insert(batch.material);
+
+                let pick_color = hash_to_rgba(binding.object_hash);
+                quads.extend(
+                    batch
+                        .sprites
+                        .iter()
+                        .map(|sprite| SpriteQuad::new(sprite, &binding.local_to_world, pick_color)),
+                );
+                batched.insert(*id);
+            }
+            if let Some(material) = material {
+                runs.insert(run.members[0], (material, start..quads.len() as u32));
+            }
+        }
+
+        if quads.is_empty() {
+            return Self::default();
+        }
+
+        let buffer =
+            renderer
+                .sprite_batch_instances()
+                .write(renderer, slot, bytemuck::cast_slice(&quads));
+
+        Self {
+            batched,
+            runs,
+            quads: Some(buffer),
+        }
+    }
+
+    pub fn contains(&self, id: &TypedComponentId) -> bool {
+        self.batched.contains(id)
+    }
+
+    /// Draws the run that starts with `id`, in the same passes a sprite proxy draws itself in.
+    pub fn render_run(&self, renderer: &Renderer, ctx: &GPUDrawCtx, id: &TypedComponentId) {
+        let drawn = match ctx.pass_type {
+            RenderPassType::Color => ctx.transparency_pass,
+            RenderPassType::Picking => true,
+            _ => false,
+        };
+        let (Some(buffer), Some((material, quads))) = (&self.quads, self.runs.get(id)) else {
+            return;
+        };
+        if !drawn || quads.is_empty() {
+            return;
+        }
+
+        // the quads carry the picking colors
+        draw_sprites(
+            renderer,
+            ctx,
+            renderer.batch_model(),
+            *material,
+            buffer,
+            quads.clone(),
+            [0.0; 4],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proxies::{SortingLayer, SpriteBatchMaterial};
+    use syrillian_utils::{ComponentId, Key};
+
+    struct Marker<const N: usize>;
+
+    fn id<const N: usize>() -> TypedComponentId {
+        TypedComponentId::new::<Marker<N>>(ComponentId::null())
+    }
+
+    fn key(mesh: u32) -> MeshBatchKey {
+        MeshBatchKey {
+            mesh: HMesh::new(mesh),
+            materials: vec![HMaterialInstance::FALLBACK],
+            material_ranges: vec![0..12, 12..36],
+            lod: 0,
+        }
+    }
+
+    #[test]
+    fn groups_matching_keys_in_first_appearance_order() {
+        let batches = group_batches([
+            (id::<0>(), Some(key(7))),
+            (id::<1>(), Some(key(3))),
+            (id::<2>(), Some(key(7))),
+            (id::<3>(), Some(key(3))),
+            (id::<4>(), Some(key(7))),
+        ]);
+
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].key, key(7));
+        assert_eq!(batches[0].members, vec![id::<0>(), id::<2>(), id::<4>()]);
+        assert_eq!(batches[1].key, key(3));
+        assert_eq!(batches[1].members, vec![id::<1>(), id::<3>()]);
+    }
+
+    #[test]
+    fn single_and_unbatchable_proxies_stay_unbatched() {
+        let mut lod = key(7);
+        lod.lod = 1;
+
+        let batches = group_batches([
+            (id::<0>(), Some(key(7))),
+            (id::<1>(), None),
+            (id::<2>(), Some(lod)),
+            (id::<3>(), None),
+        ]);
+
+        assert!(batches.is_empty());
+    }
+
+    fn sprite_key(order: i16) -> SpriteBatchKey {
+        SpriteBatchKey {
+            material: SpriteBatchMaterial::Instance(HMaterialInstance::FALLBACK),
+            layer: SortingLayer::DEFAULT,
+            order,
+        }
+    }
+
+    #[test]
+    fn sprite_runs_keep_the_draw_order() {
+        let runs = group_sprite_runs([
+            (id::<0>(), Some(sprite_key(0))),
+            (id::<1>(), Some(sprite_key(0))),
+            (id::<2>(), Some(sprite_key(1))),
+            (id::<3>(), Some(sprite_key(0))),
+            (id::<4>(), Some(sprite_key(0))),
+            (id::<5>(), None),
+            (id::<6>(), Some(sprite_key(0))),
+            (id::<7>(), Some(sprite_key(0))),
+            (id::<8>(), Some(sprite_key(0))),
+        ]);
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].members, vec![id::<0>(), id::<1>()]);
+        assert_eq!(runs[1].members, vec![id::<3>(), id::<4>()]);
+        assert_eq!(runs[2].members, vec![id::<6>(), id::<7>(), id::<8>()]);
+        assert!(runs.iter().all(|run| run.key == sprite_key(0)));
+    }
+
+    #[test]
+    fn batch_instance_carries_transform_and_pick_color() {
+        let transform = Affine3A::from_translation(glamx::Vec3::new(1.0, 2.0, 3.0));
+        let instance = batch_instance(&transform, 0x0403_0201);
+
+        assert_eq!(instance.transform, Mat4::from(transform));
+        assert_eq!(instance.pick_color, hash_to_rgba(0x0403_0201));
+    }
+}
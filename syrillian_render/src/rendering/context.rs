@@ -9,13 +9,15 @@ pub struct FrameCtx {
     pub depth_view: TextureView,
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum RenderPassType {
     Color,
     Color2D,
     Shadow,
     Picking,
     PickingUi,
+    /// Writes the motion of moving objects into the velocity G-buffer
+    Velocity,
 }
 
 pub struct GPUDrawCtx<'a> {
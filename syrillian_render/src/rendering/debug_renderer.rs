@@ -10,6 +10,7 @@ pub struct DebugRenderer {
     pub colliders_edges: bool,
     pub text_geometry: bool,
     pub light: bool,
+    pub light_clusters: bool,
 }
 
 impl DebugRenderer {
@@ -21,6 +22,7 @@ impl DebugRenderer {
             rays: false,
             text_geometry: false,
             light: false,
+            light_clusters: false,
         }
     }
 
@@ -60,6 +62,12 @@ impl DebugRenderer {
         inner.light
     }
 
+    /// Tints lit surfaces by the number of lights in their light cluster
+    pub fn light_clusters() -> bool {
+        let inner = DEBUG_RENDERER.read();
+        inner.light_clusters
+    }
+
     pub fn off() {
         let mut inner = DEBUG_RENDERER.write();
         inner._off();
@@ -83,6 +91,8 @@ impl DebugRenderer {
             5
         } else if self.light {
             6
+        } else if self.light_clusters {
+            7
         } else {
             0
         }
@@ -102,6 +112,7 @@ impl DebugRenderer {
             4 => self.colliders_edges = true,
             5 => self.text_geometry = true,
             6 => self.light = true,
+            7 => self.light_clusters = true,
             _ => return 0,
         }
         mode
@@ -114,5 +125,6 @@ impl DebugRenderer {
         self.rays = false;
         self.text_geometry = false;
         self.light = false;
+        self.light_clusters = false;
     }
 }
@@ -0,0 +1,164 @@
+//! Level of detail selection for scene proxies.
+//!
+//! Once per frame and viewport, the [`Renderer`](crate::rendering::renderer::Renderer) hands every
+//! enabled proxy a [`LodView`] of the viewing camera. Proxies use it to estimate how large their
+//! bounds appear on screen and pick the level they'll draw in all passes of that frame.
+
+use crate::rendering::render_data::CameraUniform;
+use crate::rendering::viewport::ViewportId;
+use glamx::Vec3;
+use syrillian_utils::{BoundingSphere, EngineArgs};
+
+/// Largest screen-space error in pixels that a level may introduce before a more detailed one is
+/// used.
+pub const LOD_PIXEL_ERROR_THRESHOLD: f32 = 1.0;
+
+/// Fraction of the error threshold a coarser level has to stay under before it replaces the current
+/// one. This keeps objects around a switching distance from flickering between two levels.
+pub const LOD_HYSTERESIS: f32 = 0.25;
+
+/// The camera a LOD level is selected for.
+#[derive(Debug, Copy, Clone)]
+pub struct LodView {
+    pub viewport: ViewportId,
+    pub camera_pos: Vec3,
+    /// Vertical projection scale, `cot(fov / 2)` for perspective cameras.
+    pub projection_scale: f32,
+    pub orthographic: bool,
+    pub screen_height: f32,
+    /// Multiplies the tolerated screen-space error. Values above 1 favor coarser levels.
+    pub bias: f32,
+}
+
+impl LodView {
+    pub fn new(
+        viewport: ViewportId,
+        camera: &CameraUniform,
+        screen_height: u32,
+        bias: f32,
+    ) -> Self {
+        let projection = camera.projection_mat;
+        Self {
+            viewport,
+            camera_pos: camera.pos,
+            projection_scale: projection.y_axis.y.abs(),
+            orthographic: projection.w_axis.w == 1.0,
+            screen_height: screen_height.max(1) as f32,
+            bias: bias.max(0.0),
+        }
+    }
+
+    /// Diameter of the bounding sphere on screen, in pixels.
+    pub fn projected_diameter(&self, bounds: &BoundingSphere) -> f32 {
+        let extent = bounds.radius * self.projection_scale * self.screen_height;
+        if self.orthographic {
+            return extent;
+        }
+
+        let distance = self.camera_pos.distance(bounds.center);
+        if distance <= bounds.radius {
+            return f32::INFINITY;
+        }
+
+        extent / distance
+    }
+
+    /// Picks the level to draw, given the relative simplification `errors` of every level.
+    ///
+    /// Index 0 is the base mesh and should have an error of `0.0`. Errors are relative to the
+    /// mesh extents, so they're scaled by the projected diameter to get a pixel error.
+    pub fn select_level(&self, errors: &[f32], projected_diameter: f32, current: usize) -> usize {
+        if errors.len() <= 1 || self.bias == 0.0 {
+            return 0;
+        }
+
+        let threshold = LOD_PIXEL_ERROR_THRESHOLD * self.bias;
+        let pixel_error = |level: usize| errors[level] * projected_diameter;
+        let coarsest_within = |limit: f32| {
+            (0..errors.len())
+                .rev()
+                .find(|&level| pixel_error(level) <= limit)
+                .unwrap_or(0)
+        };
+
+        let current = current.min(errors.len() - 1);
+        if pixel_error(current) > threshold {
+            return coarsest_within(threshold);
+        }
+
+        coarsest_within(threshold * (1.0 - LOD_HYSTERESIS)).max(current)
+    }
+}
+
+/// The global LOD bias the renderer starts with, taken from the engine arguments.
+pub fn default_lod_bias() -> f32 {
+    EngineArgs::get().lod_bias.unwrap_or(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(bias: f32) -> LodView {
+        LodView {
+            viewport: ViewportId::PRIMARY,
+            camera_pos: Vec3::ZERO,
+            projection_scale: 1.0,
+            orthographic: false,
+            screen_height: 1000.0,
+            bias,
+        }
+    }
+
+    const ERRORS: [f32; 4] = [0.0, 0.001, 0.01, 0.1];
+
+    #[test]
+    fn projected_diameter_shrinks_with_distance() {
+        let view = view(1.0);
+        let near = BoundingSphere {
+            center: Vec3::new(0.0, 0.0, -10.0),
+            radius: 1.0,
+        };
+        let far = BoundingSphere {
+            center: Vec3::new(0.0, 0.0, -100.0),
+            radius: 1.0,
+        };
+
+        assert_eq!(view.projected_diameter(&near), 100.0);
+        assert_eq!(view.projected_diameter(&far), 10.0);
+        assert!(
+            view.projected_diameter(&BoundingSphere::default())
+                .is_infinite()
+        );
+    }
+
+    #[test]
+    fn select_level_uses_coarsest_level_within_threshold() {
+        let view = view(1.0);
+        assert_eq!(view.select_level(&ERRORS, 5000.0, 0), 0);
+        assert_eq!(view.select_level(&ERRORS, 500.0, 0), 1);
+        assert_eq!(view.select_level(&ERRORS, 50.0, 0), 2);
+        assert_eq!(view.select_level(&ERRORS, 5.0, 0), 3);
+        assert_eq!(view.select_level(&[0.0], 0.1, 0), 0);
+    }
+
+    #[test]
+    fn select_level_keeps_current_level_inside_hysteresis_band() {
+        let view = view(1.0);
+
+        // level 2 would fit, but not with the hysteresis margin
+        assert_eq!(view.select_level(&ERRORS, 90.0, 1), 1);
+        assert_eq!(view.select_level(&ERRORS, 70.0, 1), 2);
+
+        // refining happens as soon as the current level exceeds the threshold
+        assert_eq!(view.select_level(&ERRORS, 90.0, 2), 2);
+        assert_eq!(view.select_level(&ERRORS, 110.0, 2), 1);
+    }
+
+    #[test]
+    fn bias_scales_tolerated_error() {
+        assert_eq!(view(2.0).select_level(&ERRORS, 150.0, 0), 2);
+        assert_eq!(view(0.5).select_level(&ERRORS, 150.0, 0), 1);
+        assert_eq!(view(0.0).select_level(&ERRORS, 1.0, 3), 0);
+    }
+}
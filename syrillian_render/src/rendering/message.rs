@@ -1,5 +1,9 @@
 use crate::ObjectHash;
 use crate::lighting::proxy::LightProxy;
+use crate::passes::post_process::{
+    ColorGradingSettings, ExposureSettings, PostProcessId, PostProcessParams, PostProcessPass,
+    PostProcessStage,
+};
 use crate::proxies::SceneProxy;
 use crate::rendering::picking::PickRequest;
 use crate::rendering::render_data::CameraUniform;
@@ -10,7 +14,7 @@ use crossbeam_channel::Sender;
 use glamx::Affine3A;
 use std::fmt::{Debug, Formatter};
 use std::path::PathBuf;
-use syrillian_asset::{HCubemap, HTexture2D};
+use syrillian_asset::{HCubemap, HRenderTexture2D, HTexture2D};
 use syrillian_utils::TypedComponentId;
 
 #[derive(Debug, Clone, Copy)]
@@ -31,6 +35,7 @@ pub enum RenderMsg {
     ProxyUpdate(TypedComponentId, ProxyUpdateCommand),
     LightProxyUpdate(TypedComponentId, LightProxyCommand),
     UpdateActiveCamera(ViewportId, CameraUpdateCommand),
+    CameraCut(ViewportId),
     ProxyState(TypedComponentId, bool), // enabled
     PickRequest(PickRequest),
     CommandBatch(Vec<RenderMsg>),
@@ -41,6 +46,21 @@ pub enum RenderMsg {
     SetSkybox(ViewportId, Option<HCubemap>),
     SetSkyboxMode(ViewportId, SkyboxMode),
     SetSkyAtmosphere(ViewportId, SkyAtmosphereSettings),
+    SetExposure(ViewportId, ExposureSettings),
+    SetColorGrading(ViewportId, ColorGradingSettings),
+    SetLodBias(f32),
+    AddTextureTarget(ViewportId, HRenderTexture2D),
+    RemoveTextureTarget(ViewportId),
+    AddPostProcess(
+        ViewportId,
+        PostProcessId,
+        PostProcessStage,
+        i32,
+        Box<dyn PostProcessPass + Send>,
+    ),
+    RemovePostProcess(PostProcessId),
+    SetPostProcessEnabled(PostProcessId, bool),
+    SetPostProcessParams(PostProcessId, PostProcessParams),
     UpdateStrobe(StrobeFrame),
     FrameEnd(ViewportId, Sender<()>),
 }
@@ -55,6 +75,7 @@ impl Debug for RenderMsg {
             RenderMsg::ProxyUpdate(..) => "Proxy Update",
             RenderMsg::LightProxyUpdate(..) => "Light Proxy Update",
             RenderMsg::UpdateActiveCamera(..) => "Update Active Camera",
+            RenderMsg::CameraCut(_) => "Camera Cut",
             RenderMsg::ProxyState(_, enable) => &format!("Proxy Enabled: {enable}"),
             RenderMsg::PickRequest(..) => "Pick Request",
             RenderMsg::CommandBatch(inner) => &format!("Command Batch {inner:?}"),
@@ -65,6 +86,15 @@ impl Debug for RenderMsg {
             RenderMsg::SetSkybox(_, _) => "Set Skybox",
             RenderMsg::SetSkyboxMode(_, _) => "Set Skybox Mode",
             RenderMsg::SetSkyAtmosphere(_, _) => "Set Sky Atmosphere",
+            RenderMsg::SetExposure(_, _) => "Set Exposure",
+            RenderMsg::SetColorGrading(_, _) => "Set Color Grading",
+            RenderMsg::SetLodBias(_) => "Set LOD Bias",
+            RenderMsg::AddTextureTarget(..) => "Add Texture Target",
+            RenderMsg::RemoveTextureTarget(_) => "Remove Texture Target",
+            RenderMsg::AddPostProcess(..) => "Add Post Process",
+            RenderMsg::RemovePostProcess(_) => "Remove Post Process",
+            RenderMsg::SetPostProcessEnabled(..) => "Set Post Process Enabled",
+            RenderMsg::SetPostProcessParams(..) => "Set Post Process Params",
             RenderMsg::UpdateStrobe(_) => "Update Strobe Draw List",
             RenderMsg::FrameEnd(_, _) => "Frame End",
         };
@@ -27,7 +27,9 @@
 //!
 //! You can create scene proxies in [`Components`](syrillian::engine::components)
 
+pub mod batching;
 pub mod context;
+pub mod lod;
 pub mod message;
 pub mod offscreen_surface;
 pub mod picking;
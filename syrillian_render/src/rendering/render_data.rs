@@ -1,6 +1,8 @@
+use crate::lighting::cascades::ShadowCascade;
+use crate::lighting::environment::EnvironmentViews;
 use crate::lighting::proxy::LightProxy;
 use crate::rendering::uniform::ShaderUniform;
-use glamx::{Mat4, UVec2, Vec3};
+use glamx::{Mat4, UVec2, Vec2, Vec3};
 use std::f32::consts::FRAC_PI_2;
 use syrillian_asset::ensure_aligned;
 use syrillian_macros::UniformIndex;
@@ -21,6 +23,17 @@ pub struct CameraUniform {
     pub far: f32,
     pub fov_target: f32,
     pub zoom_speed: f32,
+    /// The unjittered `proj_view_mat` of the last frame
+    pub prev_proj_view_mat: Mat4,
+    /// Offset of the projection in NDC, for temporal anti-aliasing
+    pub jitter: Vec2,
+    /// Distance to the plane in focus for depth of field, in world units
+    pub focus_distance: f32,
+    /// The f-number of the lens, zero keeps everything in focus
+    pub aperture: f32,
+    /// Focal length of the lens in millimeters
+    pub focal_length: f32,
+    pub _pad0: [f32; 3],
 }
 
 ensure_aligned!(
@@ -29,9 +42,10 @@ ensure_aligned!(
         view_mat,
         projection_mat,
         proj_view_mat,
-        inv_proj_view_mat
+        inv_proj_view_mat,
+        prev_proj_view_mat
     },
-    align <= 16 * 18 => size
+    align <= 16 * 24 => size
 );
 
 #[repr(C)]
@@ -203,6 +217,10 @@ pub enum RenderUniformIndex {
     Skybox = 2,
     SkyboxSampler = 3,
     Sky = 4,
+    Irradiance = 5,
+    Specular = 6,
+    BrdfLut = 7,
+    EnvironmentSampler = 8,
 }
 
 pub struct RenderUniformData {
@@ -227,6 +245,12 @@ impl Default for CameraUniform {
             far: 1000.0,
             fov_target: 60.0,
             zoom_speed: 1.0,
+            prev_proj_view_mat: proj_view_mat,
+            jitter: Vec2::ZERO,
+            focus_distance: 10.0,
+            aperture: 0.0,
+            focal_length: 50.0,
+            _pad0: [0.0; 3],
         }
     }
 }
@@ -244,6 +268,12 @@ impl CameraUniform {
             far: 1000.0,
             fov_target: 60.0,
             zoom_speed: 1.0,
+            prev_proj_view_mat: Mat4::IDENTITY,
+            jitter: Vec2::ZERO,
+            focus_distance: 10.0,
+            aperture: 0.0,
+            focal_length: 50.0,
+            _pad0: [0.0; 3],
         }
     }
 
@@ -277,6 +307,7 @@ impl RenderUniformData {
         render_bgl: &BindGroupLayout,
         skybox_view: TextureView,
         skybox_sampler: Sampler,
+        environment: &EnvironmentViews,
     ) -> Self {
         let camera_data = CameraUniform::empty();
         let system_data = SystemUniform::empty();
@@ -287,6 +318,10 @@ impl RenderUniformData {
             .with_texture(skybox_view)
             .with_sampler(skybox_sampler)
             .with_buffer_data(&sky_data)
+            .with_texture(environment.irradiance.clone())
+            .with_texture(environment.specular.clone())
+            .with_texture(environment.brdf_lut.clone())
+            .with_sampler(environment.sampler.clone())
             .build(device);
 
         RenderUniformData {
@@ -342,6 +377,12 @@ impl RenderUniformData {
         self.upload_camera_data(queue);
     }
 
+    pub fn update_shadow_camera_for_cascade(&mut self, cascade: &ShadowCascade, queue: &Queue) {
+        self.camera_data
+            .update(&cascade.projection, &cascade.position, &cascade.view);
+        self.upload_camera_data(queue);
+    }
+
     pub fn upload_camera_data(&self, queue: &Queue) {
         queue.write_buffer(
             self.uniform.buffer(RenderUniformIndex::Camera),
@@ -384,6 +425,7 @@ impl RenderUniformData {
         render_bgl: &BindGroupLayout,
         skybox_view: TextureView,
         skybox_sampler: Sampler,
+        environment: &EnvironmentViews,
     ) {
         self.uniform = ShaderUniform::<RenderUniformIndex>::builder((*render_bgl).clone())
             .with_buffer_data(&self.camera_data)
@@ -391,6 +433,10 @@ impl RenderUniformData {
             .with_texture(skybox_view)
             .with_sampler(skybox_sampler)
             .with_buffer_data(&self.sky_data)
+            .with_texture(environment.irradiance.clone())
+            .with_texture(environment.specular.clone())
+            .with_texture(environment.brdf_lut.clone())
+            .with_sampler(environment.sampler.clone())
             .build(device);
     }
 }
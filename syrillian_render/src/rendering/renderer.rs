@@ -10,14 +10,19 @@ use crate::error::*;
 use crate::lighting::manager::LightManager;
 use crate::lighting::proxy::LightType;
 use crate::passes::pipeline::FinalFrameContext;
-use crate::proxies::{SceneProxy, SceneProxyBinding};
+use crate::proxies::{MeshUniformIndex, SceneProxy, SceneProxyBinding};
+use crate::rendering::batching::{
+    BatchInstanceBuffers, MeshBatches, SpriteBatches, identity_model_uniform,
+};
 #[cfg(debug_assertions)]
 use crate::rendering::debug_renderer::DebugRenderer;
+use crate::rendering::lod::{LodView, default_lod_bias};
 use crate::rendering::message::{GBufferDebugTargets, ProxyUpdateCommand, RenderMsg};
 use crate::rendering::picking::{PickRequest, PickResult, color_bytes_to_hash};
 use crate::rendering::render_data::{CameraUniform, RenderUniformData, SkyboxMode};
 use crate::rendering::state::State;
 use crate::rendering::texture_export::{TextureExportError, save_texture_to_png};
+use crate::rendering::uniform::ShaderUniform;
 use crate::rendering::viewport::{RenderViewport, ViewportId};
 use crate::rendering::{FrameCtx, GPUDrawCtx, RenderPassType};
 use crate::strobe::StrobeRenderer;
@@ -32,7 +37,7 @@ use std::convert::TryFrom;
 use std::mem;
 use std::sync::Arc;
 use syrillian_asset::store::AssetStore;
-use syrillian_asset::{HShader, HTexture2D};
+use syrillian_asset::{HRenderTexture2D, HShader, HTexture2D, RenderTexture2D};
 use syrillian_utils::frustum::FrustumSide;
 use syrillian_utils::{EngineArgs, Frustum, TypedComponentId, debug_panic};
 use tracing::{info, instrument, trace, warn};
@@ -61,6 +66,15 @@ pub struct Renderer {
     pending_pick_requests: Vec<PickRequest>,
     pub lights: LightManager,
     gbuffer_debug: HashMap<ViewportId, GBufferDebugTargets>,
+    /// Viewports that render into a render texture instead of a window
+    texture_targets: HashMap<ViewportId, HRenderTexture2D>,
+    lod_bias: f32,
+    /// Identity model uniform shared by all batched mesh draws
+    batch_model: ShaderUniform<MeshUniformIndex>,
+    /// Instance buffers of batched mesh draws, one per scene pass
+    batch_instances: BatchInstanceBuffers,
+    /// Quad buffers of batched sprite draws, one per scene pass
+    sprite_batch_instances: BatchInstanceBuffers,
 }
 
 impl Renderer {
@@ -73,6 +87,7 @@ impl Renderer {
         let cache = AssetCache::new(store, state.as_ref());
 
         let lights = LightManager::new(&cache, &state.device);
+        let batch_model = identity_model_uniform(&cache, &state.device);
         let start_time = Instant::now();
 
         info!("Render Pipeline AA mode: {:?}", EngineArgs::aa_mode());
@@ -94,6 +109,11 @@ impl Renderer {
             pending_pick_requests: Vec::new(),
             lights,
             gbuffer_debug: HashMap::new(),
+            texture_targets: HashMap::new(),
+            lod_bias: default_lod_bias(),
+            batch_model,
+            batch_instances: BatchInstanceBuffers::default(),
+            sprite_batch_instances: BatchInstanceBuffers::default(),
         })
     }
 
@@ -203,6 +223,31 @@ impl Renderer {
         self.start_time
     }
 
+    pub fn lod_bias(&self) -> f32 {
+        self.lod_bias
+    }
+
+    /// Scales the screen-space error tolerated by level of detail selection.
+    /// Values above 1 switch to coarser levels earlier, `0.0` always draws full detail.
+    pub fn set_lod_bias(&mut self, bias: f32) {
+        self.lod_bias = bias.max(0.0);
+    }
+
+    /// Identity model uniform bound by instanced batch draws.
+    pub(crate) fn batch_model(&self) -> &ShaderUniform<MeshUniformIndex> {
+        &self.batch_model
+    }
+
+    /// Instance buffers the batched draws of every scene pass are written to.
+    pub(crate) fn batch_instances(&self) -> &BatchInstanceBuffers {
+        &self.batch_instances
+    }
+
+    /// Quad buffers the batched sprites of every scene pass are written to.
+    pub(crate) fn sprite_batch_instances(&self) -> &BatchInstanceBuffers {
+        &self.sprite_batch_instances
+    }
+
     pub fn resize(&mut self, target_id: ViewportId, config: SurfaceConfiguration) -> bool {
         let Some(viewport) = self.viewports.get_mut(&target_id) else {
             warn!("Invalid Viewport {target_id:?} referenced");
@@ -242,6 +287,23 @@ impl Renderer {
         sorted_enabled_proxy_ids(&self.proxies, self.cache.store(), Some(&frustum))
     }
 
+    #[instrument(skip_all)]
+    #[profiling::function]
+    fn select_lods(&mut self, viewport: &RenderViewport) {
+        let view = LodView::new(
+            viewport.id,
+            &viewport.render_data.camera_data,
+            viewport.size().height,
+            self.lod_bias,
+        );
+
+        let mut proxies = mem::take(&mut self.proxies);
+        for proxy in proxies.values_mut().filter(|p| p.enabled) {
+            proxy.select_lod(self, &view);
+        }
+        self.proxies = proxies;
+    }
+
     #[instrument(skip_all)]
     #[profiling::function]
     fn render_frame_inner(&mut self, viewport: &mut RenderViewport) -> RenderedFrame {
@@ -251,6 +313,7 @@ impl Renderer {
         }
 
         viewport.refresh_skybox_binding(&self.state.device, &self.cache);
+        viewport.refresh_environment(&self.state.device, &self.state.queue, &self.cache);
 
         let mut ctx = viewport.begin_render();
         let frame_count = viewport.frame_count();
@@ -269,21 +332,106 @@ impl Renderer {
         self.render_frame_inner(viewport)
     }
 
+    /// Renders all viewports. Viewports rendering into render textures are rendered first, so
+    /// windows show their latest contents, and only the frames of windows are returned.
     #[profiling::function]
     pub fn render_all(&mut self) -> Vec<RenderedFrame> {
         let mut targets = mem::take(&mut self.viewports);
         let mut frames = Vec::with_capacity(targets.len());
 
+        let texture_targets = self
+            .texture_targets
+            .iter()
+            .map(|(id, texture)| (*id, *texture))
+            .sorted_by_key(|(id, _)| *id)
+            .collect_vec();
+        for (id, texture) in texture_targets {
+            if let Some(viewport) = targets.get_mut(&id) {
+                self.render_texture_target(viewport, texture);
+            }
+        }
+
         for target in targets.values_mut() {
+            if self.texture_targets.contains_key(&target.id) {
+                continue;
+            }
             let frame = self.render_frame(target);
             frames.push(frame);
         }
 
         self.viewports = targets;
 
+        for proxy in self.proxies.values_mut() {
+            proxy.finish_frame();
+        }
+
         frames
     }
 
+    fn render_texture_target(&mut self, viewport: &mut RenderViewport, texture: HRenderTexture2D) {
+        let Some(gpu_texture) = self.cache.render_texture(texture) else {
+            return;
+        };
+
+        let size = viewport.size();
+        if size.width != gpu_texture.size.width || size.height != gpu_texture.size.height {
+            let config = texture_target_config(&gpu_texture);
+            viewport.resize(config, &self.state.device, &self.cache);
+        }
+
+        let frame = self.render_frame(viewport);
+
+        let mut encoder = self
+            .state
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Render Texture Copy Encoder"),
+            });
+        encoder.copy_texture_to_texture(
+            frame.frame.as_image_copy(),
+            gpu_texture.texture.as_image_copy(),
+            Extent3d {
+                width: frame.size.width.min(gpu_texture.size.width),
+                height: frame.size.height.min(gpu_texture.size.height),
+                depth_or_array_layers: 1,
+            },
+        );
+        self.state.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Renders the viewport `target_id` into the render `texture` every frame, instead of
+    /// presenting it in a window. The viewport is created if it doesn't exist yet.
+    pub fn add_texture_target(&mut self, target_id: ViewportId, texture: HRenderTexture2D) {
+        let Some(gpu_texture) = self.cache.render_texture(texture) else {
+            warn!("Render texture {texture:?} doesn't exist, can't render into it");
+            return;
+        };
+        if gpu_texture.format.remove_srgb_suffix()
+            != RenderTexture2D::CAMERA_FORMAT.remove_srgb_suffix()
+        {
+            warn!(
+                "Can't render into render texture {texture:?} with format {:?}",
+                gpu_texture.format
+            );
+            return;
+        }
+
+        let config = texture_target_config(&gpu_texture);
+        if let Some(viewport) = self.viewports.get_mut(&target_id) {
+            viewport.resize(config, &self.state.device, &self.cache);
+        } else {
+            let viewport = RenderViewport::new(target_id, config, &self.state.device, &self.cache);
+            self.viewports.insert(target_id, viewport);
+        }
+        self.texture_targets.insert(target_id, texture);
+    }
+
+    pub fn remove_texture_target(&mut self, target_id: ViewportId) {
+        if self.texture_targets.remove(&target_id).is_some() {
+            self.remove_viewport(target_id);
+        }
+    }
+
     #[instrument(skip_all)]
     #[profiling::function]
     fn picking_pass(
@@ -334,6 +482,7 @@ impl Renderer {
                 RenderPassType::Picking,
                 sorted_proxies,
                 &viewport.render_data,
+                0,
             );
         }
 
@@ -434,7 +583,9 @@ impl Renderer {
     }
 
     #[instrument(skip_all)]
-    fn render(&mut self, viewport: &RenderViewport, ctx: &mut FrameCtx) {
+    fn render(&mut self, viewport: &mut RenderViewport, ctx: &mut FrameCtx) {
+        self.select_lods(viewport);
+
         let main_sorted_proxies = self.sorted_proxies(&viewport.render_data.camera_data);
 
         if let Some(request) = self.take_pick_request(viewport.id) {
@@ -443,15 +594,17 @@ impl Renderer {
 
         if !EngineArgs::get().no_shadows {
             // TODO: Make sure to switch to dynamically generated shaders that dont incorporate shadows automatically
-            self.shadow_pass(ctx);
+            self.shadow_pass(ctx, &viewport.render_data.camera_data);
         }
 
         self.main_pass(viewport, ctx, &main_sorted_proxies);
+        self.velocity_pass(viewport, ctx, &main_sorted_proxies);
     }
 
     #[instrument(skip_all)]
     #[profiling::function]
-    fn shadow_pass(&mut self, ctx: &mut FrameCtx) {
+    fn shadow_pass(&mut self, ctx: &mut FrameCtx, camera: &CameraUniform) {
+        self.lights.set_view_camera(camera);
         self.lights
             .update(&self.cache, &self.state.queue, &self.state.device);
 
@@ -500,10 +653,9 @@ impl Renderer {
                     self.prepare_shadow_map(&mut encoder, ctx, render_data, assignment.layer);
                 }
                 LightType::Spot => debug_panic!("Requested to render more than one spotlight face"),
-                LightType::Point => {
+                LightType::Point | LightType::Sun => {
                     self.prepare_shadow_map(&mut encoder, ctx, render_data, assignment.layer);
                 }
-                LightType::Sun => {}
             }
         }
 
@@ -533,6 +685,7 @@ impl Renderer {
             RenderPassType::Shadow,
             &sorted_proxies,
             render_data,
+            layer,
         );
     }
 
@@ -550,6 +703,13 @@ impl Renderer {
                 label: Some("Main Encoder"),
             });
 
+        self.lights.cull_lights(
+            &mut encoder,
+            &self.cache,
+            &self.state.queue,
+            &viewport.render_data,
+        );
+
         {
             let mut pass = self.prepare_skybox_render_pass(&mut encoder, viewport);
             self.draw_skybox_background(viewport, &mut pass);
@@ -564,6 +724,51 @@ impl Renderer {
                 RenderPassType::Color,
                 sorted_proxies,
                 &viewport.render_data,
+                0,
+            );
+        }
+
+        self.state.queue.submit(Some(encoder.finish()));
+    }
+
+    /// Fills the velocity G-buffer with the camera motion of every pixel, then draws the motion
+    /// of the objects that moved since the last frame on top.
+    #[instrument(skip_all)]
+    fn velocity_pass(
+        &self,
+        viewport: &mut RenderViewport,
+        ctx: &mut FrameCtx,
+        sorted_proxies: &[TypedComponentId],
+    ) {
+        let mut encoder = self
+            .state
+            .device
+            .create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("Velocity Encoder"),
+            });
+
+        viewport.render_pipeline.render_camera_velocity(
+            &mut encoder,
+            &viewport.render_data,
+            &self.cache,
+        );
+
+        let moved_proxies = sorted_proxies
+            .iter()
+            .filter(|id| self.proxies.get(id).is_some_and(|p| p.has_moved()))
+            .copied()
+            .collect_vec();
+
+        if !moved_proxies.is_empty() {
+            let pass = self.prepare_velocity_render_pass(&mut encoder, viewport, ctx);
+
+            self.render_scene(
+                ctx,
+                pass,
+                RenderPassType::Velocity,
+                &moved_proxies,
+                &viewport.render_data,
+                0,
             );
         }
 
@@ -604,12 +809,14 @@ impl Renderer {
         pass_type: RenderPassType,
         proxies: &[TypedComponentId],
         render_uniform: &RenderUniformData,
+        layer: u32,
     ) {
         let shadow_bind_group = match pass_type {
             RenderPassType::Color | RenderPassType::Color2D => self.lights.shadow_uniform(),
-            RenderPassType::Shadow | RenderPassType::Picking | RenderPassType::PickingUi => {
-                self.lights.placeholder_shadow_uniform()
-            }
+            RenderPassType::Shadow
+            | RenderPassType::Picking
+            | RenderPassType::PickingUi
+            | RenderPassType::Velocity => self.lights.placeholder_shadow_uniform(),
         }
         .bind_group();
 
@@ -623,7 +830,7 @@ impl Renderer {
             transparency_pass: false,
         };
 
-        self.render_proxies(&mut draw_ctx, proxies);
+        self.render_proxies(&mut draw_ctx, proxies, layer);
 
         #[cfg(debug_assertions)]
         if DebugRenderer::light() && pass_type == RenderPassType::Color {
@@ -632,11 +839,24 @@ impl Renderer {
     }
 
     #[instrument(skip_all)]
-    fn render_proxies(&self, ctx: &mut GPUDrawCtx, proxies: &[TypedComponentId]) {
+    fn render_proxies(&self, ctx: &mut GPUDrawCtx, proxies: &[TypedComponentId], layer: u32) {
         ctx.transparency_pass = false;
 
-        for proxy in proxies {
-            let Some(proxy) = self.proxies.get(proxy) else {
+        let slot = (ctx.pass_type, layer);
+        let batches = MeshBatches::collect(self, &self.proxies, proxies, slot);
+        let sprites = SpriteBatches::collect(self, &self.proxies, proxies, slot);
+        batches.render(self, ctx);
+
+        for id in proxies {
+            if batches.contains(id) {
+                continue;
+            }
+            if sprites.contains(id) {
+                sprites.render_run(self, ctx, id);
+                continue;
+            }
+
+            let Some(proxy) = self.proxies.get(id) else {
                 debug_panic!("Sorted proxy not in proxy list");
                 continue;
             };
@@ -646,7 +866,7 @@ impl Renderer {
 
         match ctx.pass_type {
             RenderPassType::Color | RenderPassType::Shadow => (),
-            RenderPassType::Picking => return,
+            RenderPassType::Picking | RenderPassType::Velocity => return,
             RenderPassType::Color2D | RenderPassType::PickingUi => {
                 debug_panic!("Shouldn't render scene in 2D passes");
                 return;
@@ -655,8 +875,17 @@ impl Renderer {
 
         ctx.transparency_pass = true;
 
-        for proxy in proxies {
-            let Some(proxy) = self.proxies.get(proxy) else {
+        // batched meshes are opaque
+        for id in proxies {
+            if batches.contains(id) {
+                continue;
+            }
+            if sprites.contains(id) {
+                sprites.render_run(self, ctx, id);
+                continue;
+            }
+
+            let Some(proxy) = self.proxies.get(id) else {
                 debug_panic!("Sorted proxy not in proxy list");
                 continue;
             };
@@ -791,6 +1020,11 @@ impl Renderer {
                     vp.update_view_camera_data(&self.state.queue);
                 }
             }
+            RenderMsg::CameraCut(target) => {
+                if let Some(vp) = self.viewports.get_mut(&target) {
+                    vp.render_pipeline.reset_history();
+                }
+            }
             RenderMsg::ProxyState(cid, enabled) => {
                 if let Some(binding) = self.proxies.get_mut(&cid) {
                     binding.enabled = enabled;
@@ -845,6 +1079,47 @@ impl Renderer {
                     viewport.set_sky_atmosphere(settings);
                 }
             }
+            RenderMsg::SetExposure(target, settings) => {
+                if let Some(viewport) = self.viewports.get_mut(&target) {
+                    viewport.render_pipeline.set_exposure(settings);
+                }
+            }
+            RenderMsg::SetColorGrading(target, settings) => {
+                if let Some(viewport) = self.viewports.get_mut(&target) {
+                    viewport.render_pipeline.set_color_grading(settings);
+                }
+            }
+            RenderMsg::SetLodBias(bias) => self.set_lod_bias(bias),
+            RenderMsg::AddTextureTarget(target, texture) => {
+                self.add_texture_target(target, texture)
+            }
+            RenderMsg::RemoveTextureTarget(target) => self.remove_texture_target(target),
+            RenderMsg::AddPostProcess(target, id, stage, order, pass) => {
+                if let Some(viewport) = self.viewports.get_mut(&target) {
+                    viewport
+                        .render_pipeline
+                        .add_custom_pass(id, stage, order, pass);
+                } else {
+                    warn!("Couldn't add post process pass to missing viewport #{target:?}");
+                }
+            }
+            RenderMsg::RemovePostProcess(id) => {
+                for viewport in self.viewports.values_mut() {
+                    viewport.render_pipeline.remove_custom_pass(id);
+                }
+            }
+            RenderMsg::SetPostProcessEnabled(id, enabled) => {
+                for viewport in self.viewports.values_mut() {
+                    viewport
+                        .render_pipeline
+                        .set_custom_pass_enabled(id, enabled);
+                }
+            }
+            RenderMsg::SetPostProcessParams(id, params) => {
+                for viewport in self.viewports.values_mut() {
+                    viewport.render_pipeline.set_custom_pass_params(id, &params);
+                }
+            }
             RenderMsg::UpdateStrobe(frame) => {
                 self.strobe.borrow_mut().update_frame(frame);
             }
@@ -904,6 +1179,9 @@ impl Renderer {
     pub fn remove_viewport(&mut self, target_id: ViewportId) {
         self.viewports.remove(&target_id);
         self.gbuffer_debug.remove(&target_id);
+        for proxy in self.proxies.values_mut() {
+            proxy.viewport_removed(target_id);
+        }
     }
 
     #[instrument(skip_all)]
@@ -986,6 +1264,35 @@ impl Renderer {
         })
     }
 
+    fn prepare_velocity_render_pass<'a>(
+        &self,
+        encoder: &'a mut CommandEncoder,
+        viewport: &RenderViewport,
+        ctx: &FrameCtx,
+    ) -> RenderPass<'a> {
+        encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Velocity Render Pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: viewport.render_pipeline.velocity_view(),
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: &ctx.depth_view,
+                depth_ops: Some(Operations {
+                    load: LoadOp::Load,
+                    store: StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            ..RenderPassDescriptor::default()
+        })
+    }
+
     fn prepare_skybox_render_pass<'a>(
         &self,
         encoder: &'a mut CommandEncoder,
@@ -1040,6 +1347,19 @@ fn sorted_enabled_proxy_ids(
         .collect()
 }
 
+fn texture_target_config(texture: &GpuTexture) -> SurfaceConfiguration {
+    SurfaceConfiguration {
+        usage: TextureUsages::RENDER_ATTACHMENT,
+        format: RenderTexture2D::CAMERA_FORMAT,
+        width: texture.size.width.max(1),
+        height: texture.size.height.max(1),
+        present_mode: PresentMode::AutoVsync,
+        desired_maximum_frame_latency: 2,
+        alpha_mode: CompositeAlphaMode::Auto,
+        view_formats: Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
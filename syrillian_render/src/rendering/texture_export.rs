@@ -40,6 +40,7 @@ fn is_supported(format: TextureFormat) -> bool {
             | TextureFormat::Bgra8Unorm
             | TextureFormat::Bgra8UnormSrgb
             | TextureFormat::Rg16Float
+            | TextureFormat::Rgba16Float
             | TextureFormat::Depth32Float
     )
 }
@@ -176,6 +177,15 @@ pub fn read_texture_as_rgba(
                 ];
                 pixels.extend_from_slice(&mapped)
             }
+        } else if format == TextureFormat::Rgba16Float {
+            let (chunks, leftover) = row_data.as_chunks::<2>();
+            debug_assert!(leftover.is_empty());
+
+            // HDR colors are clipped to display range
+            for chunk in chunks {
+                let value = f16::from_le_bytes(*chunk).to_f32().clamp(0.0, 1.0);
+                pixels.push((value * 255.0).round() as u8);
+            }
         } else if bytes_per_pixel == 4 && format.has_color_aspect() {
             pixels.extend_from_slice(&data[start..end]);
         } else if format == TextureFormat::Depth32Float {
@@ -242,6 +252,7 @@ mod tests {
         assert!(is_supported(TextureFormat::Bgra8Unorm));
         assert!(is_supported(TextureFormat::Bgra8UnormSrgb));
         assert!(is_supported(TextureFormat::Rg16Float));
+        assert!(is_supported(TextureFormat::Rgba16Float));
         assert!(is_supported(TextureFormat::Depth32Float));
     }
 
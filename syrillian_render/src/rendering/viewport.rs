@@ -1,4 +1,5 @@
 use crate::cache::AssetCache;
+use crate::lighting::environment::{EnvironmentLighting, EnvironmentSource};
 use crate::lighting::proxy::{LightProxy, LightType};
 use crate::passes::pipeline::RenderPipeline;
 use crate::rendering::FrameCtx;
@@ -8,7 +9,7 @@ use glamx::UVec2;
 use std::time::{Duration, Instant};
 use syrillian_asset::{HCubemap, HRenderTexture2D};
 use tracing::instrument;
-use wgpu::{Device, Queue, SurfaceConfiguration, TextureViewDescriptor};
+use wgpu::{CommandEncoderDescriptor, Device, Queue, SurfaceConfiguration, TextureViewDescriptor};
 use winit::dpi::PhysicalSize;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
@@ -49,6 +50,7 @@ pub struct RenderViewport {
     desired_skybox: Option<HCubemap>,
     resolved_skybox: Option<HCubemap>,
     sky_mode: SkyboxMode,
+    environment: EnvironmentLighting,
 }
 
 impl RenderViewport {
@@ -64,11 +66,13 @@ impl RenderViewport {
 
         let picking_surface = PickingSurface::new(device, &config);
         let fallback_skybox = cache.cubemap_fallback();
+        let environment = EnvironmentLighting::new(device, cache);
         let render_data = RenderUniformData::empty(
             device,
             &render_bgl,
             fallback_skybox.view.clone(),
             fallback_skybox.sampler.clone(),
+            environment.views(),
         );
         let post_pipeline = RenderPipeline::new(device, cache, &config);
 
@@ -85,6 +89,7 @@ impl RenderViewport {
             desired_skybox: None,
             resolved_skybox: None,
             sky_mode: SkyboxMode::Cubemap,
+            environment,
         }
     }
 
@@ -209,7 +214,44 @@ impl RenderViewport {
             &render_bgl,
             skybox.view.clone(),
             skybox.sampler.clone(),
+            self.environment.views(),
         );
         self.resolved_skybox = resolved;
     }
+
+    /// Regenerates the image based lighting maps when the sky they were captured from changed.
+    /// Expects the skybox binding and the sky data to be up to date.
+    pub fn refresh_environment(&mut self, device: &Device, queue: &Queue, cache: &AssetCache) {
+        // the procedural sky doesn't read the skybox, so swapping it doesn't change the capture
+        let skybox = match self.sky_mode {
+            SkyboxMode::Cubemap => self.resolved_skybox,
+            SkyboxMode::Procedural => None,
+        };
+        let source = EnvironmentSource {
+            skybox,
+            sky: self.render_data.sky_data,
+        };
+
+        if !self.environment.needs_refresh(&source) {
+            return;
+        }
+
+        let skybox = self
+            .resolved_skybox
+            .and_then(|handle| cache.cubemap(handle))
+            .unwrap_or_else(|| cache.cubemap_fallback());
+
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("IBL Encoder"),
+        });
+        self.environment.generate(
+            &mut encoder,
+            device,
+            cache,
+            &self.render_data,
+            &skybox,
+            source,
+        );
+        queue.submit(Some(encoder.finish()));
+    }
 }
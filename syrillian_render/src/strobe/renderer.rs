@@ -118,14 +118,7 @@ impl<'a, 'b, 'c, 'd, 'e> UiDrawContext<'a, 'b, 'c, 'd, 'e> {
                 .with_buffer_data(&BoneData::DUMMY)
                 .build(&self.state.device);
 
-            RuntimeMeshData {
-                mesh_data,
-                uniform,
-                skinned_buffers: Vec::new(),
-                skinning_uniforms: Vec::new(),
-                skinning_vertex_counts: Vec::new(),
-                skinning_mesh: None,
-            }
+            RuntimeMeshData::new(mesh_data, uniform)
         })
     }
 }
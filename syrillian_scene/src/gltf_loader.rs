@@ -11,7 +11,9 @@ use syrillian::assets::{HMaterialInstance, Mesh};
 use syrillian::core::GameObjectId;
 use syrillian::math::{Quat, Vec3, quat};
 use syrillian::tracing::trace;
-use syrillian::utils::animation::{AnimationClip, Channel, TransformKeys};
+use syrillian::utils::animation::{
+    AnimationClip, AnimationEvent, Channel, Interpolation, Tangents, TransformKeys,
+};
 use syrillian_components::light::Light;
 use syrillian_components::{
     AnimationComponent, MeshRenderer, PointLightComponent, SkeletalComponent, SpotLightComponent,
@@ -224,15 +226,48 @@ fn animations_from_scene(scene: &GltfScene) -> Vec<AnimationClip> {
 /// Converts a glTF animation into an engine animation clip.
 fn build_animation_clip(scene: &GltfScene, anim: gltf::Animation) -> AnimationClip {
     let name = anim.name().unwrap_or("Animation").to_string();
+    let events = read_animation_events(&anim);
     let (channels, duration) = collect_animation_channels(scene, anim);
 
     AnimationClip {
         name,
         duration,
         channels,
+        events,
     }
 }
 
+/// Reads clip events from the animation extras, which look like
+/// `{ "events": [{ "name": "footstep", "time": 0.25 }] }`.
+fn read_animation_events(anim: &gltf::Animation) -> Vec<AnimationEvent> {
+    let Some(extras) = anim.extras() else {
+        return Vec::new();
+    };
+
+    let extras = match serde_json::de::from_str::<serde_json::Value>(extras.get()) {
+        Ok(extras) => extras,
+        Err(_) => {
+            debug_panic!("Animation extras \"{extras}\" couldn't be read");
+            return Vec::new();
+        }
+    };
+
+    let Some(events) = extras.get("events").and_then(|e| e.as_array()) else {
+        return Vec::new();
+    };
+
+    let mut events: Vec<AnimationEvent> = events
+        .iter()
+        .filter_map(|event| {
+            let name = event.get("name")?.as_str()?;
+            let time = event.get("time")?.as_f64()?;
+            Some(AnimationEvent::new(name, time as f32))
+        })
+        .collect();
+    events.sort_by(|a, b| a.time.total_cmp(&b.time));
+    events
+}
+
 /// Collects all channels of a glTF animation.
 fn collect_animation_channels(scene: &GltfScene, anim: gltf::Animation) -> (Vec<Channel>, f32) {
     let mut channels = Vec::new();
@@ -260,43 +295,105 @@ fn read_channel(scene: &GltfScene, channel: gltf::animation::Channel) -> Option<
     let times: Vec<f32> = reader.read_inputs()?.collect();
     let duration = times.last().copied().unwrap_or(0.0);
     let outputs = reader.read_outputs()?;
-    let keys = build_transform_keys(outputs, &times);
+    let keys = build_transform_keys(outputs, &times, channel.sampler().interpolation());
 
     Some((Channel { target_name, keys }, duration))
 }
 
 /// Builds transform keyframes from glTF animation outputs.
-fn build_transform_keys(outputs: ReadOutputs, times: &[f32]) -> TransformKeys {
+fn build_transform_keys(
+    outputs: ReadOutputs,
+    times: &[f32],
+    interpolation: gltf::animation::Interpolation,
+) -> TransformKeys {
+    let interpolation = match interpolation {
+        gltf::animation::Interpolation::Linear => Interpolation::Linear,
+        gltf::animation::Interpolation::Step => Interpolation::Step,
+        gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+    };
+
     match outputs {
         ReadOutputs::Translations(values) => {
-            let translations: Vec<Vec3> = values.into_iter().map(Vec3::from_array).collect();
+            let translations = values.into_iter().map(Vec3::from_array).collect();
+            let (t_values, t_tangents) = split_spline_outputs(translations, interpolation);
             TransformKeys {
                 t_times: times.to_vec(),
-                t_values: translations,
+                t_values,
+                t_interpolation: interpolation,
+                t_tangents,
                 ..TransformKeys::default()
             }
         }
         ReadOutputs::Rotations(values) => {
-            let rotations: Vec<Quat> = values
+            let rotations = values
                 .into_f32()
                 .map(|q| quat(q[0], q[1], q[2], q[3]))
                 .collect();
+            let (r_values, r_tangents) = split_spline_outputs(rotations, interpolation);
             TransformKeys {
                 r_times: times.to_vec(),
-                r_values: rotations,
+                r_values,
+                r_interpolation: interpolation,
+                r_tangents,
                 ..TransformKeys::default()
             }
         }
         ReadOutputs::Scales(values) => {
-            let scales: Vec<Vec3> = values.into_iter().map(Vec3::from_array).collect();
+            let scales = values.into_iter().map(Vec3::from_array).collect();
+            let (s_values, s_tangents) = split_spline_outputs(scales, interpolation);
             TransformKeys {
                 s_times: times.to_vec(),
-                s_values: scales,
+                s_values,
+                s_interpolation: interpolation,
+                s_tangents,
                 ..TransformKeys::default()
             }
         }
-        _ => TransformKeys::default(),
+        ReadOutputs::MorphTargetWeights(values) => {
+            let values: Vec<f32> = values.into_f32().collect();
+            let per_key = values.len() / times.len().max(1);
+            let target_count = match interpolation {
+                Interpolation::CubicSpline => per_key / 3,
+                _ => per_key,
+            };
+            if target_count == 0 {
+                return TransformKeys::default();
+            }
+
+            let keys = values.chunks(target_count).map(<[f32]>::to_vec).collect();
+            let (w_values, w_tangents) = split_spline_outputs(keys, interpolation);
+            TransformKeys {
+                w_times: times.to_vec(),
+                w_values,
+                w_interpolation: interpolation,
+                w_tangents,
+                ..TransformKeys::default()
+            }
+        }
+    }
+}
+
+/// Cubic spline samplers store an in tangent, the value and an out tangent for every key.
+/// Splits those apart, other samplers only store values and get no tangents.
+fn split_spline_outputs<T: Clone>(
+    outputs: Vec<T>,
+    interpolation: Interpolation,
+) -> (Vec<T>, Vec<Tangents<T>>) {
+    if interpolation != Interpolation::CubicSpline {
+        return (outputs, Vec::new());
     }
+
+    let (triples, _) = outputs.as_chunks::<3>();
+    triples
+        .iter()
+        .map(|[in_tangent, value, out_tangent]| {
+            let tangents = Tangents {
+                in_tangent: in_tangent.clone(),
+                out_tangent: out_tangent.clone(),
+            };
+            (value.clone(), tangents)
+        })
+        .unzip()
 }
 
 /// Attaches lights defined on a glTF node.
@@ -5,8 +5,9 @@ use gltf::{self, Node};
 use itertools::izip;
 use std::collections::HashMap;
 use syrillian::assets::Mesh;
+use syrillian::assets::mesh::MorphTarget;
 use syrillian::core::{Bones, Vertex3D};
-use syrillian::math::{Vec2, Vec3};
+use syrillian::math::{Vec2, Vec3, Vec4};
 use syrillian::tracing::warn;
 
 /// Mesh and associated material indices for each sub-mesh range.
@@ -29,12 +30,22 @@ impl<'a> From<&'a SkinAttributes> for SkinSlices<'a> {
     }
 }
 
+/// Displacements of one morph target, indexed like the attributes of the primitive.
+struct MorphSource {
+    positions: Option<Vec<[f32; 3]>>,
+    normals: Option<Vec<[f32; 3]>>,
+    tangents: Option<Vec<[f32; 3]>>,
+}
+
 struct VertexSources<'a> {
     positions: &'a [[f32; 3]],
     normals: Option<&'a Vec<[f32; 3]>>,
     tangents: Option<&'a Vec<[f32; 4]>>,
     tex_coords: Option<&'a Vec<[f32; 2]>>,
+    tex_coords1: Option<&'a Vec<[f32; 2]>>,
+    colors: Option<&'a Vec<[f32; 4]>>,
     skin: Option<SkinSlices<'a>>,
+    morph_targets: &'a [MorphSource],
     joint_map: &'a HashMap<usize, usize>,
 }
 
@@ -66,12 +77,20 @@ pub(super) fn load_mesh(scene: &GltfScene, node: Node) -> Option<(Mesh, Vec<u32>
         );
     }
 
+    // node weights override the defaults of the mesh they instantiate
+    let morph_weights = node
+        .weights()
+        .or_else(|| gltf_mesh.weights())
+        .map(<[f32]>::to_vec)
+        .unwrap_or_default();
+
     let mut buffers = read_mesh_primitives(scene, gltf_mesh, &joint_node_index_of)?;
 
     if buffers.is_empty() {
         return None;
     }
 
+    buffers.morph_weights = morph_weights;
     buffers.fill_missing();
     Some(buffers.build_mesh(bones))
 }
@@ -102,7 +121,7 @@ fn read_mesh_primitives(
         match extract_primitive_data(scene, prim, joint_node_index_of) {
             Some(PrimitiveOutcome::Ready(result)) => {
                 let count = result.vertex_count();
-                buffers.extend(result, start_vertex);
+                buffers.extend(*result, start_vertex);
                 start_vertex += count;
             }
             Some(PrimitiveOutcome::Skip) => continue,
@@ -115,7 +134,7 @@ fn read_mesh_primitives(
 
 /// Outcome of attempting to read a primitive.
 enum PrimitiveOutcome {
-    Ready(PrimitiveResult),
+    Ready(Box<PrimitiveResult>),
     Skip,
 }
 
@@ -135,6 +154,18 @@ fn extract_primitive_data(
     let normals = reader.read_normals().map(|it| it.collect::<Vec<_>>());
     let tangents = reader.read_tangents().map(|it| it.collect::<Vec<_>>());
     let tex_coords = reader.read_tex_coords(0).map(convert_tex_coords);
+    let tex_coords1 = reader.read_tex_coords(1).map(convert_tex_coords);
+    let colors = reader
+        .read_colors(0)
+        .map(|it| it.into_rgba_f32().collect::<Vec<_>>());
+    let morph_targets = reader
+        .read_morph_targets()
+        .map(|(positions, normals, tangents)| MorphSource {
+            positions: positions.map(Iterator::collect),
+            normals: normals.map(Iterator::collect),
+            tangents: tangents.map(Iterator::collect),
+        })
+        .collect::<Vec<_>>();
     let joints_raw = reader.read_joints(0);
     let weights_raw = reader.read_weights(0);
     let indices: Vec<u32> = if let Some(ind) = reader.read_indices() {
@@ -150,20 +181,26 @@ fn extract_primitive_data(
         normals: normals.as_ref(),
         tangents: tangents.as_ref(),
         tex_coords: tex_coords.as_ref(),
+        tex_coords1: tex_coords1.as_ref(),
+        colors: colors.as_ref(),
         skin: skin_slices,
+        morph_targets: &morph_targets,
         joint_map: joint_node_index_of,
     };
 
     let material_index = prim.material().index().map(|i| i as u32).unwrap_or(0);
 
     let mut result = PrimitiveResult::new(material_index);
+    result.has_colors = sources.colors.is_some();
+    result.has_tex_coords1 = sources.tex_coords1.is_some();
+    result.morph_targets = vec![MorphTarget::default(); morph_targets.len()];
     for chunk in indices.chunks_exact(3) {
         for &index in chunk {
             result.push_vertex(index as usize, &sources);
         }
     }
 
-    Some(PrimitiveOutcome::Ready(result))
+    Some(PrimitiveOutcome::Ready(Box::new(result)))
 }
 
 /// Normalizes texture coordinates from the glTF accessor format.
@@ -249,11 +286,17 @@ fn compute_bitangent(normal: Vec3, tangent: Vec3, handedness: f32) -> Vec3 {
 struct PrimitiveBuffers {
     positions: Vec<Vec3>,
     tex_coords: Vec<Vec2>,
+    tex_coords1: Vec<Vec2>,
+    colors: Vec<Vec4>,
+    has_tex_coords1: bool,
+    has_colors: bool,
     normals: Vec<Vec3>,
     tangents: Vec<Vec3>,
     bitangents: Vec<Vec3>,
     bone_indices: Vec<Vec<u32>>,
     bone_weights: Vec<Vec<f32>>,
+    morph_targets: Vec<MorphTarget>,
+    morph_weights: Vec<f32>,
     ranges: Vec<std::ops::Range<u32>>,
     materials: Vec<u32>,
 }
@@ -264,17 +307,28 @@ impl PrimitiveBuffers {
         let PrimitiveResult {
             positions,
             tex_coords,
+            tex_coords1,
+            colors,
+            has_tex_coords1,
+            has_colors,
             normals,
             tangents,
             bitangents,
             bone_indices,
             bone_weights,
+            morph_targets,
             material_index,
         } = data;
 
+        self.extend_morph_targets(morph_targets, positions.len());
+
         let vertex_count = positions.len() as u32;
         self.positions.extend(positions);
         self.tex_coords.extend(tex_coords);
+        self.tex_coords1.extend(tex_coords1);
+        self.colors.extend(colors);
+        self.has_tex_coords1 |= has_tex_coords1;
+        self.has_colors |= has_colors;
         self.normals.extend(normals);
         self.tangents.extend(tangents);
         self.bitangents.extend(bitangents);
@@ -286,6 +340,25 @@ impl PrimitiveBuffers {
         self.materials.push(material_index);
     }
 
+    /// Appends the morph targets of a primitive. Primitives may disagree on the target count, so
+    /// missing targets are padded with zero deltas on either side.
+    fn extend_morph_targets(&mut self, targets: Vec<MorphTarget>, vertex_count: usize) {
+        if self.morph_targets.len() < targets.len() {
+            let previous = MorphTarget::zeroed(self.positions.len());
+            self.morph_targets.resize(targets.len(), previous);
+        }
+
+        let mut targets = targets.into_iter();
+        for target in &mut self.morph_targets {
+            let source = targets
+                .next()
+                .unwrap_or_else(|| MorphTarget::zeroed(vertex_count));
+            target.positions.extend(source.positions);
+            target.normals.extend(source.normals);
+            target.tangents.extend(source.tangents);
+        }
+    }
+
     /// Returns true when no vertex data has been collected yet.
     fn is_empty(&self) -> bool {
         self.positions.is_empty()
@@ -309,11 +382,17 @@ impl PrimitiveBuffers {
         let PrimitiveBuffers {
             positions,
             tex_coords,
+            tex_coords1,
+            colors,
+            has_tex_coords1,
+            has_colors,
             normals,
             tangents,
             bitangents,
             bone_indices,
             bone_weights,
+            morph_targets,
+            morph_weights,
             ranges,
             materials,
         } = self;
@@ -330,10 +409,22 @@ impl PrimitiveBuffers {
         .map(Vertex3D::from)
         .collect();
 
-        let mesh = Mesh::builder(vertices)
+        let mut builder = Mesh::builder(vertices)
             .with_many_textures(ranges)
-            .with_bones(bones)
-            .build();
+            .with_bones(bones);
+        if has_colors {
+            builder = builder.with_vertex_colors(colors);
+        }
+        if has_tex_coords1 {
+            builder = builder.with_uv1(tex_coords1);
+        }
+        if !morph_targets.is_empty() {
+            builder = builder
+                .with_morph_targets(morph_targets)
+                .with_morph_weights(morph_weights);
+        }
+
+        let mesh = builder.build();
         (mesh, materials)
     }
 }
@@ -341,11 +432,16 @@ impl PrimitiveBuffers {
 struct PrimitiveResult {
     positions: Vec<Vec3>,
     tex_coords: Vec<Vec2>,
+    tex_coords1: Vec<Vec2>,
+    colors: Vec<Vec4>,
+    has_tex_coords1: bool,
+    has_colors: bool,
     normals: Vec<Vec3>,
     tangents: Vec<Vec3>,
     bitangents: Vec<Vec3>,
     bone_indices: Vec<Vec<u32>>,
     bone_weights: Vec<Vec<f32>>,
+    morph_targets: Vec<MorphTarget>,
     material_index: u32,
 }
 
@@ -355,11 +451,16 @@ impl PrimitiveResult {
         Self {
             positions: Vec::new(),
             tex_coords: Vec::new(),
+            tex_coords1: Vec::new(),
+            colors: Vec::new(),
+            has_tex_coords1: false,
+            has_colors: false,
             normals: Vec::new(),
             tangents: Vec::new(),
             bitangents: Vec::new(),
             bone_indices: Vec::new(),
             bone_weights: Vec::new(),
+            morph_targets: Vec::new(),
             material_index,
         }
     }
@@ -399,6 +500,28 @@ impl PrimitiveResult {
         });
         self.tex_coords.push(uv);
 
+        // primitives without a secondary uv set or vertex colors get the same defaults the
+        // renderer would use, so mixing primitives with and without them stays consistent
+        let uv1 = sources
+            .tex_coords1
+            .map_or(uv, |list| Vec2::from(list[index]));
+        self.tex_coords1.push(uv1);
+
+        let color = sources
+            .colors
+            .map_or(Vec4::ONE, |list| Vec4::from(list[index]));
+        self.colors.push(color);
+
+        for (target, source) in self.morph_targets.iter_mut().zip(sources.morph_targets) {
+            let delta = |list: &Option<Vec<[f32; 3]>>| {
+                list.as_ref()
+                    .map_or(Vec3::ZERO, |list| Vec3::from(list[index]))
+            };
+            target.positions.push(delta(&source.positions));
+            target.normals.push(delta(&source.normals));
+            target.tangents.push(delta(&source.tangents));
+        }
+
         if let Some(skin) = sources.skin {
             let joint = skin.joints[index];
             let weight = skin.weights[index];
@@ -3,9 +3,12 @@ use gltf::image::Format;
 use std::collections::HashMap;
 use syrillian::World;
 use syrillian::assets::store::StoreType;
-use syrillian::assets::{HMaterial, HMaterialInstance, HTexture2D, MaterialInstance, Texture2D};
-use syrillian::math::Vec3;
+use syrillian::assets::{
+    AlphaMode, HMaterial, HMaterialInstance, HTexture2D, MaterialInstance, Texture2D,
+};
+use syrillian::math::{Vec2, Vec3};
 use syrillian::rendering::rendering::TextureFormat;
+use syrillian::tracing::warn;
 use syrillian_utils::debug_panic;
 
 /// Loads all materials defined in the glTF scene and stores them in the asset store.
@@ -24,32 +27,200 @@ pub(super) fn load_materials(
         let alpha = base[3];
         let metallic = pbr.metallic_factor();
         let roughness = pbr.roughness_factor();
+        let emissive = Vec3::from(mat.emissive_factor());
+        let emissive_strength = mat.emissive_strength().unwrap_or(1.0);
+        let occlusion_strength = mat.occlusion_texture().map_or(1.0, |tex| tex.strength());
+
+        let (alpha_mode, has_transparency) = match mat.alpha_mode() {
+            gltf::material::AlphaMode::Opaque => (AlphaMode::Opaque, false),
+            gltf::material::AlphaMode::Mask => (AlphaMode::Mask, false),
+            gltf::material::AlphaMode::Blend => (AlphaMode::Blend, true),
+        };
+        let alpha_cutoff = mat.alpha_cutoff().unwrap_or(0.5);
+
+        let slots = [
+            (
+                "diffuse",
+                pbr.base_color_texture().map(|info| info_uv(&info)),
+            ),
+            (
+                "normal",
+                mat.normal_texture().map(|tex| {
+                    extension_uv(tex.tex_coord(), tex.extension_value(TEXTURE_TRANSFORM))
+                }),
+            ),
+            (
+                "roughness",
+                pbr.metallic_roughness_texture().map(|info| info_uv(&info)),
+            ),
+            (
+                "metallic",
+                pbr.metallic_roughness_texture().map(|info| info_uv(&info)),
+            ),
+            (
+                "emissive",
+                mat.emissive_texture().map(|info| info_uv(&info)),
+            ),
+            (
+                "occlusion",
+                mat.occlusion_texture().map(|tex| {
+                    extension_uv(tex.tex_coord(), tex.extension_value(TEXTURE_TRANSFORM))
+                }),
+            ),
+        ];
+        let transform = material_transform(&name, &slots);
 
         let diffuse_texture = load_texture(scene, world, pbr.base_color_texture(), true);
         let normal_texture = load_texture(scene, world, mat.normal_texture(), false);
-        let roughness_texture = load_texture(scene, world, pbr.metallic_roughness_texture(), false);
+        let metallic_roughness_texture =
+            load_texture(scene, world, pbr.metallic_roughness_texture(), false);
+        let emissive_texture = load_texture(scene, world, mat.emissive_texture(), true);
+        let occlusion_texture = load_texture(scene, world, mat.occlusion_texture(), false);
 
         let lit = !mat.unlit();
 
-        let material = MaterialInstance::builder()
+        let mut material = MaterialInstance::builder()
             .name(name)
             .material(HMaterial::DEFAULT)
             .diffuse(color)
             .roughness(roughness)
             .metallic(metallic)
             .alpha(alpha)
+            .alpha_mode(alpha_mode)
+            .alpha_cutoff(alpha_cutoff)
+            .has_transparency(has_transparency)
+            .double_sided(mat.double_sided())
+            .emissive(emissive)
+            .emissive_strength(emissive_strength)
+            .occlusion_strength(occlusion_strength)
+            .uv_transform(transform.offset, transform.scale, transform.rotation)
             .diffuse_texture(diffuse_texture)
             .normal_texture(normal_texture)
-            .roughness_texture(roughness_texture)
-            .lit(lit)
-            .build();
+            .roughness_texture(metallic_roughness_texture)
+            .metallic_texture(metallic_roughness_texture)
+            .emissive_texture(emissive_texture)
+            .occlusion_texture(occlusion_texture)
+            .lit(lit);
+        for (slot, uv) in &slots {
+            if let Some(uv) = uv {
+                material = material.texture_uv_set(slot, uv.tex_coord);
+            }
+        }
 
-        map.insert(i as u32, world.assets.material_instances.add(material));
+        map.insert(
+            i as u32,
+            world.assets.material_instances.add(material.build()),
+        );
     }
 
     map
 }
 
+const TEXTURE_TRANSFORM: &str = "KHR_texture_transform";
+
+/// A `KHR_texture_transform`, or the identity for textures without one.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct UvTransform {
+    offset: Vec2,
+    scale: Vec2,
+    rotation: f32,
+}
+
+impl UvTransform {
+    const IDENTITY: UvTransform = UvTransform {
+        offset: Vec2::ZERO,
+        scale: Vec2::ONE,
+        rotation: 0.0,
+    };
+}
+
+/// The uv set and transform a texture slot is sampled with.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct SlotUv {
+    tex_coord: u32,
+    transform: UvTransform,
+}
+
+fn info_uv(info: &gltf::texture::Info) -> SlotUv {
+    let transform = info.texture_transform();
+    let tex_coord = transform
+        .as_ref()
+        .and_then(|t| t.tex_coord())
+        .unwrap_or(info.tex_coord());
+    let transform = transform.map_or(UvTransform::IDENTITY, |t| UvTransform {
+        offset: Vec2::from(t.offset()),
+        scale: Vec2::from(t.scale()),
+        rotation: t.rotation(),
+    });
+
+    slot_uv(tex_coord, transform)
+}
+
+/// Normal and occlusion textures only expose the transform as raw extension json.
+fn extension_uv(tex_coord: u32, transform: Option<&serde_json::Value>) -> SlotUv {
+    let Some(transform) = transform else {
+        return slot_uv(tex_coord, UvTransform::IDENTITY);
+    };
+
+    let vec2 = |key: &str, default: Vec2| {
+        transform
+            .get(key)
+            .and_then(|v| {
+                Some(Vec2::new(
+                    v.get(0)?.as_f64()? as f32,
+                    v.get(1)?.as_f64()? as f32,
+                ))
+            })
+            .unwrap_or(default)
+    };
+    let tex_coord = transform
+        .get("texCoord")
+        .and_then(|v| v.as_u64())
+        .map_or(tex_coord, |set| set as u32);
+    let transform = UvTransform {
+        offset: vec2("offset", Vec2::ZERO),
+        scale: vec2("scale", Vec2::ONE),
+        rotation: transform
+            .get("rotation")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32,
+    };
+
+    slot_uv(tex_coord, transform)
+}
+
+fn slot_uv(tex_coord: u32, transform: UvTransform) -> SlotUv {
+    if tex_coord > 1 {
+        warn!("Only uv sets 0 and 1 are imported, got TEXCOORD_{tex_coord}. Using uv set 1");
+    }
+
+    SlotUv {
+        tex_coord: tex_coord.min(1),
+        transform,
+    }
+}
+
+/// Materials only have one uv transform, which is taken from the first textured slot.
+fn material_transform(name: &str, slots: &[(&str, Option<SlotUv>)]) -> UvTransform {
+    let mut textured = slots
+        .iter()
+        .filter_map(|(slot, uv)| Some((*slot, uv.as_ref()?.transform)));
+    let Some((first_slot, transform)) = textured.next() else {
+        return UvTransform::IDENTITY;
+    };
+
+    for (slot, other) in textured {
+        if other != transform {
+            warn!(
+                "Material \"{name}\" has a different texture transform on its {slot} texture. \
+                 Using the transform of its {first_slot} texture"
+            );
+        }
+    }
+
+    transform
+}
+
 /// Converts a glTF texture reference into an engine texture handle.
 pub(super) fn load_texture<'a, T>(
     scene: &'a GltfScene,
@@ -1,12 +1,48 @@
 use std::path::PathBuf;
 
 use syrillian::World;
+use syrillian::assets::AlphaMode;
+use syrillian::math::{Vec2, Vec3};
+use syrillian::shadergen::value::MaterialValue;
+use syrillian::utils::animation::{AnimationEvent, Interpolation, sample_translation};
+use syrillian_components::AnimationComponent;
 use syrillian_scene::SceneLoader;
 
 fn asset_path(relative: &str) -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(relative)
 }
 
+/// Packs a glTF json document and its binary buffer into a glb container.
+fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+    let pad = |mut bytes: Vec<u8>, fill: u8| {
+        bytes.resize(bytes.len().next_multiple_of(4), fill);
+        bytes
+    };
+    let json = pad(json.as_bytes().to_vec(), b' ');
+    let bin = pad(bin.to_vec(), 0);
+
+    let mut out = Vec::new();
+    out.extend(b"glTF");
+    out.extend(2u32.to_le_bytes());
+    out.extend((12 + 8 + json.len() as u32 + 8 + bin.len() as u32).to_le_bytes());
+    out.extend((json.len() as u32).to_le_bytes());
+    out.extend(b"JSON");
+    out.extend(json);
+    out.extend((bin.len() as u32).to_le_bytes());
+    out.extend(b"BIN\0");
+    out.extend(bin);
+    out
+}
+
+/// A 1x1 RGBA png.
+const PIXEL_PNG: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0xfc, 0xcf, 0xc0, 0xf0,
+    0x1f, 0x00, 0x05, 0x05, 0x02, 0x00, 0x5f, 0xc8, 0xf1, 0xd2, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+    0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
 #[test]
 fn load_first_mesh_from_file_has_vertices() {
     let path = asset_path("../syrillian/testmodels/hampter/hampter.glb");
@@ -69,3 +105,283 @@ fn load_scene_from_buffer_spawns_world_objects() {
         "expected child nodes to be spawned under the scene root"
     );
 }
+
+#[test]
+fn load_materials_maps_gltf_material_properties() {
+    let gltf = br#"{
+        "asset": { "version": "2.0" },
+        "extensionsUsed": ["KHR_materials_emissive_strength"],
+        "scenes": [{ "nodes": [] }],
+        "materials": [
+            {
+                "name": "Leaves",
+                "alphaMode": "MASK",
+                "alphaCutoff": 0.3,
+                "doubleSided": false,
+                "emissiveFactor": [1.0, 0.5, 0.0],
+                "extensions": {
+                    "KHR_materials_emissive_strength": { "emissiveStrength": 4.0 }
+                }
+            },
+            {
+                "name": "Glass",
+                "alphaMode": "BLEND",
+                "doubleSided": true,
+                "pbrMetallicRoughness": { "baseColorFactor": [1.0, 1.0, 1.0, 0.2] }
+            }
+        ]
+    }"#;
+
+    let (mut world, _render_rx, _event_rx, _pick_tx) = World::fresh();
+    SceneLoader::load_buffer(world.as_mut(), gltf).expect("scene should load into the world");
+
+    let material = |name: &str| {
+        world
+            .assets
+            .material_instances
+            .items()
+            .find(|item| item.value().name == name)
+            .map(|item| item.value().clone())
+            .expect("material should be loaded")
+    };
+
+    let leaves = material("Leaves");
+    assert_eq!(leaves.alpha_mode(), AlphaMode::Mask);
+    assert_eq!(leaves.value_f32("alpha_cutoff"), Some(0.3));
+    assert_eq!(leaves.value_bool("double_sided"), Some(false));
+    assert_eq!(leaves.value_f32("emissive_strength"), Some(4.0));
+    assert!(matches!(
+        leaves.value("emissive"),
+        Some(MaterialValue::Vec3(v)) if *v == Vec3::new(1.0, 0.5, 0.0)
+    ));
+
+    let glass = material("Glass");
+    assert_eq!(glass.alpha_mode(), AlphaMode::Blend);
+    assert_eq!(glass.value_bool("has_transparency"), Some(true));
+    assert_eq!(glass.value_bool("double_sided"), Some(true));
+    assert_eq!(glass.value_f32("alpha"), Some(0.2));
+}
+
+#[test]
+fn load_materials_maps_texture_uv_sets() {
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "extensionsUsed": ["KHR_texture_transform"],
+        "scenes": [{ "nodes": [] }],
+        "buffers": [{ "byteLength": 70 }],
+        "bufferViews": [{ "buffer": 0, "byteLength": 70 }],
+        "images": [{ "bufferView": 0, "mimeType": "image/png" }],
+        "textures": [{ "source": 0 }],
+        "materials": [
+            {
+                "name": "Baked",
+                "pbrMetallicRoughness": {
+                    "baseColorTexture": {
+                        "index": 0,
+                        "extensions": {
+                            "KHR_texture_transform": { "offset": [0.5, 0.0], "scale": [2.0, 2.0] }
+                        }
+                    }
+                },
+                "normalTexture": {
+                    "index": 0,
+                    "extensions": {
+                        "KHR_texture_transform": { "offset": [0.5, 0.0], "scale": [2.0, 2.0], "texCoord": 1 }
+                    }
+                },
+                "occlusionTexture": { "index": 0, "texCoord": 1 }
+            }
+        ]
+    }"#;
+
+    let (mut world, _render_rx, _event_rx, _pick_tx) = World::fresh();
+    SceneLoader::load_buffer(world.as_mut(), &glb(json, PIXEL_PNG))
+        .expect("scene should load into the world");
+
+    let baked = world
+        .assets
+        .material_instances
+        .items()
+        .find(|item| item.value().name == "Baked")
+        .map(|item| item.value().clone())
+        .expect("material should be loaded");
+
+    // normal (bit 1) and occlusion (bit 5) read TEXCOORD_1, the diffuse texture TEXCOORD_0
+    assert_eq!(baked.value_u32("uv_sets"), Some(0b10_0010));
+    assert!(matches!(
+        baked.value("uv_offset"),
+        Some(MaterialValue::Vec2(v)) if *v == Vec2::new(0.5, 0.0)
+    ));
+    assert!(matches!(
+        baked.value("uv_scale"),
+        Some(MaterialValue::Vec2(v)) if *v == Vec2::new(2.0, 2.0)
+    ));
+}
+
+#[test]
+fn load_morph_targets_and_weight_animation() {
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "name": "Face", "mesh": 0 }],
+        "meshes": [{
+            "weights": [0.25],
+            "primitives": [{
+                "attributes": { "POSITION": 0 },
+                "targets": [{ "POSITION": 1 }]
+            }]
+        }],
+        "animations": [{
+            "name": "Smile",
+            "channels": [{ "sampler": 0, "target": { "node": 0, "path": "weights" } }],
+            "samplers": [{ "input": 2, "output": 3 }]
+        }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+              "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+            { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3",
+              "min": [0.0, 0.0, 0.0], "max": [0.0, 0.0, 1.0] },
+            { "bufferView": 2, "componentType": 5126, "count": 2, "type": "SCALAR",
+              "min": [0.0], "max": [1.0] },
+            { "bufferView": 3, "componentType": 5126, "count": 2, "type": "SCALAR" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 72, "byteLength": 8 },
+            { "buffer": 0, "byteOffset": 80, "byteLength": 8 }
+        ],
+        "buffers": [{ "byteLength": 88 }]
+    }"#;
+
+    let floats: [f32; 22] = [
+        0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, // base triangle
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, // target lifts the top corner
+        0.0, 1.0, // key times
+        0.0, 1.0, // weight per key
+    ];
+    let bin: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+    let bytes = glb(json, &bin);
+
+    let (mesh, _) = SceneLoader::load_first_mesh_from_buffer(&bytes)
+        .expect("scene should load from memory")
+        .expect("mesh should be present");
+    assert_eq!(mesh.morph_target_count(), 1);
+    assert_eq!(mesh.morph_weights, vec![0.25]);
+
+    let morphed = mesh.morphed_vertices(&[1.0]);
+    assert_eq!(morphed[2].position, Vec3::new(0.0, 1.0, 1.0));
+    assert_eq!(morphed[0].position, mesh.vertices()[0].position);
+
+    let (mut world, _render_rx, _event_rx, _pick_tx) = World::fresh();
+    let root = SceneLoader::load_buffer(world.as_mut(), &bytes).expect("scene should load");
+    let anim = root
+        .get_component::<AnimationComponent>()
+        .expect("animation should be attached to the scene root");
+
+    let keys = &anim.clips()[0].channels[0].keys;
+    assert!(keys.has_morph_weights());
+    assert_eq!(keys.w_times, vec![0.0, 1.0]);
+    assert_eq!(keys.w_values, vec![vec![0.0], vec![1.0]]);
+}
+
+#[test]
+fn load_cubic_spline_channel_keeps_tangents() {
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "name": "Piston" }],
+        "animations": [{
+            "name": "Pump",
+            "channels": [{ "sampler": 0, "target": { "node": 0, "path": "translation" } }],
+            "samplers": [{ "input": 0, "output": 1, "interpolation": "CUBICSPLINE" }]
+        }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 2, "type": "SCALAR",
+              "min": [0.0], "max": [1.0] },
+            { "bufferView": 1, "componentType": 5126, "count": 6, "type": "VEC3" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 8 },
+            { "buffer": 0, "byteOffset": 8, "byteLength": 72 }
+        ],
+        "buffers": [{ "byteLength": 80 }]
+    }"#;
+
+    let floats: [f32; 20] = [
+        0.0, 1.0, // key times
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, // in tangent, value, out tangent
+        0.0, -2.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, // in tangent, value, out tangent
+    ];
+    let bin: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+    let (mut world, _render_rx, _event_rx, _pick_tx) = World::fresh();
+    let root =
+        SceneLoader::load_buffer(world.as_mut(), &glb(json, &bin)).expect("scene should load");
+    let anim = root
+        .get_component::<AnimationComponent>()
+        .expect("animation should be attached to the scene root");
+
+    let keys = &anim.clips()[0].channels[0].keys;
+    assert_eq!(keys.t_interpolation, Interpolation::CubicSpline);
+    assert_eq!(keys.t_values, vec![Vec3::ZERO, Vec3::Y]);
+    assert_eq!(keys.t_tangents[0].out_tangent, Vec3::Y * 2.0);
+    assert_eq!(keys.t_tangents[1].in_tangent, Vec3::Y * -2.0);
+
+    // the steep tangents already reach the end value halfway through
+    let mid = sample_translation(keys, 0.5).unwrap();
+    assert!(mid.abs_diff_eq(Vec3::new(0.0, 1.0, 0.0), 1e-6));
+}
+
+#[test]
+fn load_animation_events_from_extras() {
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "scenes": [{ "nodes": [0] }],
+        "nodes": [{ "name": "Foot" }],
+        "animations": [{
+            "name": "Walk",
+            "channels": [{ "sampler": 0, "target": { "node": 0, "path": "translation" } }],
+            "samplers": [{ "input": 0, "output": 1 }],
+            "extras": {
+                "events": [
+                    { "name": "footstep_right", "time": 0.75 },
+                    { "name": "footstep_left", "time": 0.25 },
+                    { "name": "missing_time" }
+                ]
+            }
+        }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 2, "type": "SCALAR",
+              "min": [0.0], "max": [1.0] },
+            { "bufferView": 1, "componentType": 5126, "count": 2, "type": "VEC3" }
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 8 },
+            { "buffer": 0, "byteOffset": 8, "byteLength": 24 }
+        ],
+        "buffers": [{ "byteLength": 32 }]
+    }"#;
+
+    let floats: [f32; 8] = [0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0];
+    let bin: Vec<u8> = floats.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+    let (mut world, _render_rx, _event_rx, _pick_tx) = World::fresh();
+    let root =
+        SceneLoader::load_buffer(world.as_mut(), &glb(json, &bin)).expect("scene should load");
+    let anim = root
+        .get_component::<AnimationComponent>()
+        .expect("animation should be attached to the scene root");
+
+    let clip = &anim.clips()[0];
+    assert_eq!(
+        clip.events,
+        vec![
+            AnimationEvent::new("footstep_left", 0.25),
+            AnimationEvent::new("footstep_right", 0.75),
+        ]
+    );
+
+    let passed: Vec<_> = clip.events_between(0.5, 0.5, true).collect();
+    assert_eq!(passed, vec![&clip.events[0]]);
+}
@@ -6,6 +6,8 @@ pub(crate) struct NodeExpressionInput {
     output_index: u32,
 }
 
+use crate::compiler::PbrSurface;
+use syrillian_utils::debug_panic;
 use NodeExpressionInput as ExpressionInput;
 
 impl NodeExpressionInput {
@@ -56,7 +58,18 @@ pub trait NodeChunk {
 
 static EMPTY_DEPS: [NodeId; 0] = [];
 
-pub(crate) struct VertexUvNode;
+pub(crate) struct VertexUvNode {
+    set: u32,
+}
+
+impl VertexUvNode {
+    pub fn new(set: u32) -> Self {
+        if set > 1 {
+            debug_panic!("Only uv sets 0 and 1 are available, got {set}. Using uv set 1");
+        }
+        Self { set: set.min(1) }
+    }
+}
 
 impl NodeChunk for VertexUvNode {
     fn deps(&self) -> &[NodeId] {
@@ -68,7 +81,71 @@ impl NodeChunk for VertexUvNode {
     }
 
     fn expr(&self, _id: NodeId, _ctx: &EmitCtx) -> String {
-        "in.uv".to_string()
+        match self.set {
+            0 => "in.uv".to_string(),
+            _ => "in.uv1".to_string(),
+        }
+    }
+}
+
+pub(crate) struct VertexColorNode;
+
+impl NodeChunk for VertexColorNode {
+    fn deps(&self) -> &[NodeId] {
+        &EMPTY_DEPS
+    }
+
+    fn emit(&self, _id: NodeId, _ctx: &EmitCtx) -> Option<String> {
+        None
+    }
+
+    fn expr(&self, _id: NodeId, _ctx: &EmitCtx) -> String {
+        "in.color".to_string()
+    }
+}
+
+pub(crate) struct UvSetSelectNode {
+    uv0: ExpressionInput,
+    uv1: ExpressionInput,
+    uv_sets: ExpressionInput,
+    bit: u32,
+    deps: [NodeId; 3],
+}
+
+impl UvSetSelectNode {
+    pub fn new(
+        uv0: ExpressionInput,
+        uv1: ExpressionInput,
+        uv_sets: ExpressionInput,
+        bit: u32,
+    ) -> Self {
+        Self {
+            uv0,
+            uv1,
+            uv_sets,
+            bit,
+            deps: [uv0.node(), uv1.node(), uv_sets.node()],
+        }
+    }
+}
+
+impl NodeChunk for UvSetSelectNode {
+    fn deps(&self) -> &[NodeId] {
+        &self.deps
+    }
+
+    fn emit(&self, id: NodeId, ctx: &EmitCtx) -> Option<String> {
+        let uv0 = self.uv0.expr(ctx);
+        let uv1 = self.uv1.expr(ctx);
+        let uv_sets = self.uv_sets.expr(ctx);
+        let bit = self.bit;
+        Some(format!(
+            "let _pv{id}: vec2<f32> = select({uv0}, {uv1}, (({uv_sets} >> {bit}u) & 1u) != 0u);"
+        ))
+    }
+
+    fn expr(&self, id: NodeId, _ctx: &EmitCtx) -> String {
+        format!("_pv{id}")
     }
 }
 
@@ -204,32 +281,36 @@ impl NodeChunk for MaterialBaseColorNode {
     }
 }
 
-pub(crate) struct MaterialRoughnessNode {
+/// A scalar material value that is replaced by one channel of a texture when it's in use.
+pub(crate) struct MaterialChannelNode {
     uv: ExpressionInput,
-    roughness: ExpressionInput,
+    value: ExpressionInput,
     use_texture: ExpressionInput,
     texture: ExpressionInput,
     sampler: ExpressionInput,
+    channel: &'static str,
     deps: [NodeId; 5],
 }
 
-impl MaterialRoughnessNode {
+impl MaterialChannelNode {
     pub fn new(
         uv: ExpressionInput,
-        roughness: ExpressionInput,
+        value: ExpressionInput,
         use_texture: ExpressionInput,
         texture: ExpressionInput,
         sampler: ExpressionInput,
+        channel: &'static str,
     ) -> Self {
         Self {
             uv,
-            roughness,
+            value,
             use_texture,
             texture,
             sampler,
+            channel,
             deps: [
                 uv.node(),
-                roughness.node(),
+                value.node(),
                 use_texture.node(),
                 texture.node(),
                 sampler.node(),
@@ -238,24 +319,140 @@ impl MaterialRoughnessNode {
     }
 }
 
-impl NodeChunk for MaterialRoughnessNode {
+impl NodeChunk for MaterialChannelNode {
     fn deps(&self) -> &[NodeId] {
         &self.deps
     }
 
     fn emit(&self, id: NodeId, ctx: &EmitCtx) -> Option<String> {
         let uv = self.uv.expr(ctx);
-        let roughness = self.roughness.expr(ctx);
+        let value = self.value.expr(ctx);
         let use_tex = self.use_texture.expr(ctx);
         let tex = self.texture.expr(ctx);
         let sampler = self.sampler.expr(ctx);
         Some(format!(
-            "var _pv{id}: f32;\n    if ({use_tex} != 0) {{\n        _pv{id} = textureSample({tex}, {sampler}, {uv}).g;\n    }} else {{\n        _pv{id} = {roughness};\n    }}",
+            "var _pv{id}: f32;\n    if ({use_tex} != 0) {{\n        _pv{id} = textureSample({tex}, {sampler}, {uv}).{channel};\n    }} else {{\n        _pv{id} = {value};\n    }}",
             tex = tex,
             sampler = sampler,
             uv = uv,
-            roughness = roughness,
+            value = value,
             use_tex = use_tex,
+            channel = self.channel,
+        ))
+    }
+
+    fn expr(&self, id: NodeId, _ctx: &EmitCtx) -> String {
+        format!("_pv{id}")
+    }
+}
+
+pub(crate) struct MaterialEmissiveNode {
+    uv: ExpressionInput,
+    color: ExpressionInput,
+    strength: ExpressionInput,
+    use_texture: ExpressionInput,
+    texture: ExpressionInput,
+    sampler: ExpressionInput,
+    deps: [NodeId; 6],
+}
+
+impl MaterialEmissiveNode {
+    pub fn new(
+        uv: ExpressionInput,
+        color: ExpressionInput,
+        strength: ExpressionInput,
+        use_texture: ExpressionInput,
+        texture: ExpressionInput,
+        sampler: ExpressionInput,
+    ) -> Self {
+        Self {
+            uv,
+            color,
+            strength,
+            use_texture,
+            texture,
+            sampler,
+            deps: [
+                uv.node(),
+                color.node(),
+                strength.node(),
+                use_texture.node(),
+                texture.node(),
+                sampler.node(),
+            ],
+        }
+    }
+}
+
+impl NodeChunk for MaterialEmissiveNode {
+    fn deps(&self) -> &[NodeId] {
+        &self.deps
+    }
+
+    fn emit(&self, id: NodeId, ctx: &EmitCtx) -> Option<String> {
+        let uv = self.uv.expr(ctx);
+        let color = self.color.expr(ctx);
+        let strength = self.strength.expr(ctx);
+        let use_tex = self.use_texture.expr(ctx);
+        let tex = self.texture.expr(ctx);
+        let sampler = self.sampler.expr(ctx);
+        Some(format!(
+            "var _pv{id}: vec3f = {color} * {strength};\n    if ({use_tex} != 0) {{\n        _pv{id} *= textureSample({tex}, {sampler}, {uv}).rgb;\n    }}",
+        ))
+    }
+
+    fn expr(&self, id: NodeId, _ctx: &EmitCtx) -> String {
+        format!("_pv{id}")
+    }
+}
+
+pub(crate) struct MaterialOcclusionNode {
+    uv: ExpressionInput,
+    strength: ExpressionInput,
+    use_texture: ExpressionInput,
+    texture: ExpressionInput,
+    sampler: ExpressionInput,
+    deps: [NodeId; 5],
+}
+
+impl MaterialOcclusionNode {
+    pub fn new(
+        uv: ExpressionInput,
+        strength: ExpressionInput,
+        use_texture: ExpressionInput,
+        texture: ExpressionInput,
+        sampler: ExpressionInput,
+    ) -> Self {
+        Self {
+            uv,
+            strength,
+            use_texture,
+            texture,
+            sampler,
+            deps: [
+                uv.node(),
+                strength.node(),
+                use_texture.node(),
+                texture.node(),
+                sampler.node(),
+            ],
+        }
+    }
+}
+
+impl NodeChunk for MaterialOcclusionNode {
+    fn deps(&self) -> &[NodeId] {
+        &self.deps
+    }
+
+    fn emit(&self, id: NodeId, ctx: &EmitCtx) -> Option<String> {
+        let uv = self.uv.expr(ctx);
+        let strength = self.strength.expr(ctx);
+        let use_tex = self.use_texture.expr(ctx);
+        let tex = self.texture.expr(ctx);
+        let sampler = self.sampler.expr(ctx);
+        Some(format!(
+            "var _pv{id}: f32 = 1.0;\n    if ({use_tex} != 0) {{\n        _pv{id} = mix(1.0, textureSample({tex}, {sampler}, {uv}).r, {strength});\n    }}",
         ))
     }
 
@@ -350,6 +547,34 @@ impl NodeChunk for PostSurfaceSamplerNode {
     }
 }
 
+pub(crate) struct PostParamNode {
+    index: u32,
+}
+
+impl PostParamNode {
+    pub fn new(index: u32) -> Self {
+        debug_assert!(
+            index < 16,
+            "Only post process parameters 0 to 15 exist, got {index}"
+        );
+        Self { index }
+    }
+}
+
+impl NodeChunk for PostParamNode {
+    fn deps(&self) -> &[NodeId] {
+        &EMPTY_DEPS
+    }
+
+    fn emit(&self, _id: NodeId, _ctx: &EmitCtx) -> Option<String> {
+        None
+    }
+
+    fn expr(&self, _id: NodeId, _ctx: &EmitCtx) -> String {
+        format!("post_param({}u)", self.index)
+    }
+}
+
 pub(crate) struct TextureSampleNode {
     deps: [NodeId; 3],
 }
@@ -464,6 +689,52 @@ impl NodeChunk for PbrShaderNode {
     }
 }
 
+pub(crate) struct PbrSurfaceNode {
+    deps: [NodeId; 13],
+}
+
+impl PbrSurfaceNode {
+    pub fn new(surface: PbrSurface) -> Self {
+        Self {
+            deps: [
+                surface.base_color,
+                surface.normal,
+                surface.roughness,
+                surface.metallic,
+                surface.alpha,
+                surface.lit,
+                surface.cast_shadows,
+                surface.grayscale,
+                surface.emissive,
+                surface.occlusion,
+                surface.alpha_mode,
+                surface.alpha_cutoff,
+                surface.double_sided,
+            ],
+        }
+    }
+}
+
+impl NodeChunk for PbrSurfaceNode {
+    fn deps(&self) -> &[NodeId] {
+        &self.deps
+    }
+
+    fn emit(&self, id: NodeId, ctx: &EmitCtx) -> Option<String> {
+        let args: Vec<String> = self.deps.iter().map(|&d| ctx.expr(d)).collect();
+        Some(format!(
+            "let _pv{id}: FOutput = pbr_fragment_surface(in, front_facing, {});",
+            args.join(", ")
+        ))
+    }
+
+    fn expr(&self, id: NodeId, _ctx: &EmitCtx) -> String {
+        format!("_pv{id}")
+    }
+}
+
+/// Picking color of the drawn object. Single draws only set the immediate and instanced draws
+/// only the color of every instance, so the two can be added.
 pub(crate) struct PickColorNode;
 
 impl NodeChunk for PickColorNode {
@@ -476,7 +747,7 @@ impl NodeChunk for PickColorNode {
     }
 
     fn expr(&self, _id: NodeId, _ctx: &EmitCtx) -> String {
-        "pick.color".to_string()
+        "(pick.color + in.pick_color)".to_string()
     }
 }
 
@@ -1,8 +1,10 @@
 use crate::chunks::{
-    ConstantF32Node, EmitCtx, FunctionCallNode, MaterialBaseColorNode, MaterialInputNode,
-    MaterialNormalNode, MaterialRoughnessNode, MaterialSamplerNode, MaterialTextureNode, MathNode,
-    MathOp, NodeChunk, NodeExpressionInput, PbrShaderNode, PickColorNode, PostSurfaceSamplerNode,
-    PostSurfaceTextureNode, RawChunk, SwizzleNode, TextureSampleNode, VertexUvNode,
+    ConstantF32Node, EmitCtx, FunctionCallNode, MaterialBaseColorNode, MaterialChannelNode,
+    MaterialEmissiveNode, MaterialInputNode, MaterialNormalNode, MaterialOcclusionNode,
+    MaterialSamplerNode, MaterialTextureNode, MathNode, MathOp, NodeChunk, NodeExpressionInput,
+    PbrShaderNode, PbrSurfaceNode, PickColorNode, PostParamNode, PostSurfaceSamplerNode,
+    PostSurfaceTextureNode, RawChunk, SwizzleNode, TextureSampleNode, UvSetSelectNode,
+    VertexColorNode, VertexUvNode,
 };
 use crate::function::{
     ExpressionInput, ExpressionTexture, MaterialExpression, MaterialPinType,
@@ -10,9 +12,29 @@ use crate::function::{
 };
 use crate::generator::{MaterialShaderSetCode, MeshPass, ShaderCompilationOutput};
 use crate::{NodeId, ShaderGenerator};
-use glamx::Vec3;
+use glamx::{Vec2, Vec3};
 use syrillian_utils::debug_panic;
 
+/// Inputs of [`MaterialCompiler::pbr_surface`]. Extends the [`MaterialCompiler::pbr_shader`]
+/// inputs by emission, ambient occlusion, alpha modes and double-sidedness.
+#[derive(Debug, Copy, Clone)]
+pub struct PbrSurface {
+    pub base_color: NodeId,
+    pub normal: NodeId,
+    pub roughness: NodeId,
+    pub metallic: NodeId,
+    pub alpha: NodeId,
+    pub lit: NodeId,
+    pub cast_shadows: NodeId,
+    pub grayscale: NodeId,
+    pub emissive: NodeId,
+    pub occlusion: NodeId,
+    /// `0` opaque, `1` alpha mask, `2` blend
+    pub alpha_mode: NodeId,
+    pub alpha_cutoff: NodeId,
+    pub double_sided: NodeId,
+}
+
 #[derive(Default)]
 pub struct MaterialCompiler {
     nodes: Vec<RawChunk>,
@@ -32,8 +54,32 @@ impl MaterialCompiler {
         id
     }
 
-    pub fn vertex_uv(&mut self) -> NodeId {
-        self.allocate(VertexUvNode)
+    /// Vertex uv coordinates of the given set. Set `0` is the primary uv set, set `1` the
+    /// secondary one (falls back to the primary set on meshes without it). Higher sets are
+    /// reported as an error and read set `1`.
+    pub fn vertex_uv(&mut self, set: u32) -> NodeId {
+        self.allocate(VertexUvNode::new(set))
+    }
+
+    /// Linear RGBA vertex color. White on meshes without vertex colors.
+    pub fn vertex_color(&mut self) -> NodeId {
+        self.allocate(VertexColorNode)
+    }
+
+    /// Picks `uv1` if bit `bit` of the `uv_sets` mask is set, `uv0` otherwise.
+    pub fn select_uv_set(
+        &mut self,
+        uv0: NodeId,
+        uv1: NodeId,
+        uv_sets: &ExpressionInput<u32>,
+        bit: u32,
+    ) -> NodeId {
+        self.allocate(UvSetSelectNode::new(
+            NodeExpressionInput::new(uv0, 0),
+            NodeExpressionInput::new(uv1, 0),
+            uv_sets.as_chunk_input(),
+            bit,
+        ))
     }
 
     pub fn input_value(&mut self, name: &'static str) -> NodeId {
@@ -100,15 +146,84 @@ impl MaterialCompiler {
         use_texture: &ExpressionInput<bool>,
         texture: &ExpressionTexture,
     ) -> NodeId {
-        self.allocate(MaterialRoughnessNode::new(
+        self.allocate(MaterialChannelNode::new(
             NodeExpressionInput::new(uv, 0),
             roughness.as_chunk_input(),
             use_texture.as_chunk_input(),
             texture.texture_input(),
             texture.sampler_input(),
+            "g",
+        ))
+    }
+
+    /// Metallic factor, or the blue channel of a (glTF metallic-roughness) texture.
+    pub fn metallic(
+        &mut self,
+        uv: NodeId,
+        metallic: &ExpressionInput<f32>,
+        use_texture: &ExpressionInput<bool>,
+        texture: &ExpressionTexture,
+    ) -> NodeId {
+        self.allocate(MaterialChannelNode::new(
+            NodeExpressionInput::new(uv, 0),
+            metallic.as_chunk_input(),
+            use_texture.as_chunk_input(),
+            texture.texture_input(),
+            texture.sampler_input(),
+            "b",
+        ))
+    }
+
+    /// Emitted color scaled by `strength`, multiplied with the texture when it's in use.
+    pub fn emissive(
+        &mut self,
+        uv: NodeId,
+        color: &ExpressionInput<Vec3>,
+        strength: &ExpressionInput<f32>,
+        use_texture: &ExpressionInput<bool>,
+        texture: &ExpressionTexture,
+    ) -> NodeId {
+        self.allocate(MaterialEmissiveNode::new(
+            NodeExpressionInput::new(uv, 0),
+            color.as_chunk_input(),
+            strength.as_chunk_input(),
+            use_texture.as_chunk_input(),
+            texture.texture_input(),
+            texture.sampler_input(),
         ))
     }
 
+    /// Ambient occlusion from the red channel of the texture, blended towards `1.0` by `strength`.
+    pub fn occlusion(
+        &mut self,
+        uv: NodeId,
+        strength: &ExpressionInput<f32>,
+        use_texture: &ExpressionInput<bool>,
+        texture: &ExpressionTexture,
+    ) -> NodeId {
+        self.allocate(MaterialOcclusionNode::new(
+            NodeExpressionInput::new(uv, 0),
+            strength.as_chunk_input(),
+            use_texture.as_chunk_input(),
+            texture.texture_input(),
+            texture.sampler_input(),
+        ))
+    }
+
+    /// Applies a `KHR_texture_transform` style offset, scale and rotation (radians) to `uv`.
+    pub fn transform_uv(
+        &mut self,
+        uv: NodeId,
+        offset: &ExpressionInput<Vec2>,
+        scale: &ExpressionInput<Vec2>,
+        rotation: &ExpressionInput<f32>,
+    ) -> NodeId {
+        self.call(
+            "transform_uv",
+            vec![uv, offset.node(), scale.node(), rotation.node()],
+        )
+    }
+
     pub fn normal(
         &mut self,
         uv: NodeId,
@@ -147,6 +262,10 @@ impl MaterialCompiler {
         ))
     }
 
+    pub fn pbr_surface(&mut self, surface: PbrSurface) -> NodeId {
+        self.allocate(PbrSurfaceNode::new(surface))
+    }
+
     pub fn pick_color(&mut self) -> NodeId {
         self.allocate(PickColorNode)
     }
@@ -233,7 +352,7 @@ impl PostProcessCompiler {
     }
 
     pub fn vertex_uv(&mut self) -> NodeId {
-        self.allocate(VertexUvNode)
+        self.allocate(VertexUvNode::new(0))
     }
 
     pub fn post_surface_input(&mut self) -> (NodeId, NodeId) {
@@ -247,6 +366,31 @@ impl PostProcessCompiler {
         self.allocate(TextureSampleNode::new(texture, sampler, uv))
     }
 
+    /// Scene depth at `uv`, as stored in the depth buffer.
+    pub fn post_depth(&mut self, uv: NodeId) -> NodeId {
+        self.call("post_depth", vec![uv])
+    }
+
+    /// World space normal at `uv`.
+    pub fn post_normal(&mut self, uv: NodeId) -> NodeId {
+        self.call("post_normal", vec![uv])
+    }
+
+    /// Material properties at `uv`.
+    pub fn post_material(&mut self, uv: NodeId) -> NodeId {
+        self.call("post_material", vec![uv])
+    }
+
+    /// Screen space motion at `uv` since the last frame.
+    pub fn post_velocity(&mut self, uv: NodeId) -> NodeId {
+        self.call("post_velocity", vec![uv])
+    }
+
+    /// Parameter `index` of the pass, from 0 to 15. Parameters are set from the game side.
+    pub fn post_param(&mut self, index: u32) -> NodeId {
+        self.allocate(PostParamNode::new(index))
+    }
+
     #[allow(dead_code)]
     pub fn constant_f32(&mut self, value: f32) -> NodeId {
         self.allocate(ConstantF32Node::new(value))
@@ -1,6 +1,6 @@
 use crate::MaterialCompiler;
 use crate::chunks::{NodeExpressionInput as ChunkInput, NodeId};
-use crate::compiler::PostProcessCompiler;
+use crate::compiler::{PbrSurface, PostProcessCompiler};
 use crate::value::MaterialValueType;
 use glamx::{Vec2, Vec3, Vec4};
 use std::marker::PhantomData;
@@ -290,10 +290,39 @@ pub struct PbrShader {
     pub use_roughness_texture: ExpressionInput<bool>,
     pub roughness_texture: ExpressionTexture,
     pub metallic: ExpressionInput<f32>,
+    pub use_metallic_texture: ExpressionInput<bool>,
+    pub metallic_texture: ExpressionTexture,
     pub alpha: ExpressionInput<f32>,
     pub lit: ExpressionInput<bool>,
     pub cast_shadows: ExpressionInput<bool>,
     pub grayscale_diffuse: ExpressionInput<bool>,
+    pub emissive: ExpressionInput<Vec3>,
+    pub emissive_strength: ExpressionInput<f32>,
+    pub use_emissive_texture: ExpressionInput<bool>,
+    pub emissive_texture: ExpressionTexture,
+    pub occlusion_strength: ExpressionInput<f32>,
+    pub use_occlusion_texture: ExpressionInput<bool>,
+    pub occlusion_texture: ExpressionTexture,
+    pub alpha_mode: ExpressionInput<u32>,
+    pub alpha_cutoff: ExpressionInput<f32>,
+    pub double_sided: ExpressionInput<bool>,
+    pub uv_offset: ExpressionInput<Vec2>,
+    pub uv_scale: ExpressionInput<Vec2>,
+    pub uv_rotation: ExpressionInput<f32>,
+    /// Bit mask of textures sampled with uv set `1`, indexed like [`PbrShader::UV_SET_TEXTURES`].
+    pub uv_sets: ExpressionInput<u32>,
+}
+
+impl PbrShader {
+    /// Texture slots in the order of their bits in the `uv_sets` mask.
+    pub const UV_SET_TEXTURES: [&str; 6] = [
+        "diffuse",
+        "normal",
+        "roughness",
+        "metallic",
+        "emissive",
+        "occlusion",
+    ];
 }
 
 impl Default for PbrShader {
@@ -308,10 +337,26 @@ impl Default for PbrShader {
             use_roughness_texture: ExpressionInput::material("use_roughness_texture"),
             roughness_texture: ExpressionTexture::material("roughness"),
             metallic: ExpressionInput::material("metallic"),
+            use_metallic_texture: ExpressionInput::material("use_metallic_texture"),
+            metallic_texture: ExpressionTexture::material("metallic"),
             alpha: ExpressionInput::material("alpha"),
             lit: ExpressionInput::material("lit"),
             cast_shadows: ExpressionInput::material("cast_shadows"),
             grayscale_diffuse: ExpressionInput::material("grayscale_diffuse"),
+            emissive: ExpressionInput::material("emissive"),
+            emissive_strength: ExpressionInput::material("emissive_strength"),
+            use_emissive_texture: ExpressionInput::material("use_emissive_texture"),
+            emissive_texture: ExpressionTexture::material("emissive"),
+            occlusion_strength: ExpressionInput::material("occlusion_strength"),
+            use_occlusion_texture: ExpressionInput::material("use_occlusion_texture"),
+            occlusion_texture: ExpressionTexture::material("occlusion"),
+            alpha_mode: ExpressionInput::material("alpha_mode"),
+            alpha_cutoff: ExpressionInput::material("alpha_cutoff"),
+            double_sided: ExpressionInput::material("double_sided"),
+            uv_offset: ExpressionInput::material("uv_offset"),
+            uv_scale: ExpressionInput::material("uv_scale"),
+            uv_rotation: ExpressionInput::material("uv_rotation"),
+            uv_sets: ExpressionInput::material("uv_sets"),
         }
     }
 }
@@ -327,10 +372,26 @@ impl MaterialExpression for PbrShader {
         self.use_roughness_texture.bind(compiler);
         self.roughness_texture.bind(compiler);
         self.metallic.bind(compiler);
+        self.use_metallic_texture.bind(compiler);
+        self.metallic_texture.bind(compiler);
         self.alpha.bind(compiler);
         self.lit.bind(compiler);
         self.cast_shadows.bind(compiler);
         self.grayscale_diffuse.bind(compiler);
+        self.emissive.bind(compiler);
+        self.emissive_strength.bind(compiler);
+        self.use_emissive_texture.bind(compiler);
+        self.emissive_texture.bind(compiler);
+        self.occlusion_strength.bind(compiler);
+        self.use_occlusion_texture.bind(compiler);
+        self.occlusion_texture.bind(compiler);
+        self.alpha_mode.bind(compiler);
+        self.alpha_cutoff.bind(compiler);
+        self.double_sided.bind(compiler);
+        self.uv_offset.bind(compiler);
+        self.uv_scale.bind(compiler);
+        self.uv_rotation.bind(compiler);
+        self.uv_sets.bind(compiler);
     }
 
     fn outputs(&self) -> Vec<MaterialExpressionValue> {
@@ -343,38 +404,82 @@ impl MaterialExpression for PbrShader {
     fn compile(&self, compiler: &mut MaterialCompiler, output_index: u32) -> NodeId {
         debug_assert_eq!(output_index, 0, "output_index must be 0 for PBR shader");
 
-        let uv = compiler.vertex_uv();
-        let base_color = compiler.base_color(
-            uv,
+        let vertex_uv0 = compiler.vertex_uv(0);
+        let uv0 = compiler.transform_uv(
+            vertex_uv0,
+            &self.uv_offset,
+            &self.uv_scale,
+            &self.uv_rotation,
+        );
+        let vertex_uv1 = compiler.vertex_uv(1);
+        let uv1 = compiler.transform_uv(
+            vertex_uv1,
+            &self.uv_offset,
+            &self.uv_scale,
+            &self.uv_rotation,
+        );
+        let [
+            diffuse_uv,
+            normal_uv,
+            roughness_uv,
+            metallic_uv,
+            emissive_uv,
+            occlusion_uv,
+        ] = std::array::from_fn(|bit| compiler.select_uv_set(uv0, uv1, &self.uv_sets, bit as u32));
+        let material_color = compiler.base_color(
+            diffuse_uv,
             &self.diffuse,
             &self.use_diffuse_texture,
             &self.diffuse_texture,
         );
+        let vertex_color = compiler.vertex_color();
+        let base_color = compiler.mul(material_color, vertex_color);
         let roughness = compiler.roughness(
-            uv,
+            roughness_uv,
             &self.roughness,
             &self.use_roughness_texture,
             &self.roughness_texture,
         );
-        let normal = compiler.normal(uv, &self.use_normal_texture, &self.normal_texture);
-        let metallic = self.metallic.node();
-        let alpha = self.alpha.node();
-        let lit = self.lit.node();
-        let cast_shadows = self.cast_shadows.node();
-        let grayscale = self.grayscale_diffuse.node();
-        compiler.pbr_shader(
+        let metallic = compiler.metallic(
+            metallic_uv,
+            &self.metallic,
+            &self.use_metallic_texture,
+            &self.metallic_texture,
+        );
+        let normal = compiler.normal(normal_uv, &self.use_normal_texture, &self.normal_texture);
+        let emissive = compiler.emissive(
+            emissive_uv,
+            &self.emissive,
+            &self.emissive_strength,
+            &self.use_emissive_texture,
+            &self.emissive_texture,
+        );
+        let occlusion = compiler.occlusion(
+            occlusion_uv,
+            &self.occlusion_strength,
+            &self.use_occlusion_texture,
+            &self.occlusion_texture,
+        );
+        compiler.pbr_surface(PbrSurface {
             base_color,
             normal,
             roughness,
             metallic,
-            alpha,
-            lit,
-            cast_shadows,
-            grayscale,
-        )
+            alpha: self.alpha.node(),
+            lit: self.lit.node(),
+            cast_shadows: self.cast_shadows.node(),
+            grayscale: self.grayscale_diffuse.node(),
+            emissive,
+            occlusion,
+            alpha_mode: self.alpha_mode.node(),
+            alpha_cutoff: self.alpha_cutoff.node(),
+            double_sided: self.double_sided.node(),
+        })
     }
 }
 
+/// Copies the input unchanged. Used by the final pass, as exposure, grading and tonemapping
+/// already happened in the post process chain.
 pub struct PostProcessPassthroughMaterial;
 
 impl PostProcessMaterialExpression for PostProcessPassthroughMaterial {
@@ -389,7 +494,6 @@ impl PostProcessMaterialExpression for PostProcessPassthroughMaterial {
         debug_assert_eq!(output_index, 0, "output_index must be 0 for passthrough");
         let uv = compiler.vertex_uv();
         let (tex, sampler) = compiler.post_surface_input();
-        let sampled = compiler.texture_sample(tex, sampler, uv);
-        compiler.call("post_color_grade", vec![sampled])
+        compiler.texture_sample(tex, sampler, uv)
     }
 }
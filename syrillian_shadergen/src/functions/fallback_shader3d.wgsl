@@ -1,8 +1,8 @@
 @vertex
-fn fallback_vs_main(in: VInput) -> FInput {
+fn fallback_vs_main(in: VInput, instance: InstanceInput) -> FInput {
     var out: FInput;
 
-    let mvp_matrix = camera.view_proj_mat * model.transform;
+    let mvp_matrix = camera.view_proj_mat * model.transform * instance_transform(instance);
 
     out.clip = mvp_matrix * vec4<f32>(in.position, 1.0);
     out.uv = in.uv;
@@ -2,6 +2,18 @@ const AMBIENT_STRENGTH: f32 = 0.0;
 const IBL_STRENGTH: f32 = 1.0;
 const EPS: f32 = 1e-7;
 
+const ALPHA_MODE_OPAQUE: u32 = 0u;
+const ALPHA_MODE_MASK: u32 = 1u;
+const ALPHA_MODE_BLEND: u32 = 2u;
+
+// KHR_texture_transform: scale, then rotate (counter-clockwise in uv space), then offset
+fn transform_uv(uv: vec2<f32>, offset: vec2<f32>, scale: vec2<f32>, rotation: f32) -> vec2<f32> {
+    let c = cos(rotation);
+    let s = sin(rotation);
+    let scaled = uv * scale;
+    return offset + vec2<f32>(c * scaled.x + s * scaled.y, c * scaled.y - s * scaled.x);
+}
+
 // orthonormalize t against n to build a stable tbn basis
 fn ortho_tangent(T: vec3<f32>, N: vec3<f32>) -> vec3<f32> {
     return safe_normalize(T - N * dot(N, T));
@@ -83,15 +95,6 @@ fn fresnel_schlick_roughness(NdotV: f32, F0: vec3<f32>, roughness: f32) -> vec3<
     return F0 + (F90 - F0) * x5;
 }
 
-fn env_brdf_approx(roughness: f32, NdotV: f32) -> vec2<f32> {
-    let c0 = vec4<f32>(-1.0, -0.0275, -0.572, 0.022);
-    let c1 = vec4<f32>( 1.0,  0.0425,  1.04, -0.04);
-    let r = roughness * c0 + c1;
-
-    let a004 = min(r.x * r.x, exp2(-9.28 * saturate(NdotV))) * r.x + r.y;
-    return vec2<f32>(-1.04, 1.04) * a004 + r.zw;
-}
-
 fn ibl_term(
     N: vec3<f32>,
     V: vec3<f32>,
@@ -110,21 +113,19 @@ fn ibl_term(
     let Fd = fresnel_schlick_roughness(NdotV, F0, perceptual_roughness);
     let kD = (vec3<f32>(1.0) - Fd) * (1.0 - metallic);
 
-    let mip_count = f32(textureNumLevels(skybox_map));
-    let max_mip = max(mip_count - 1.0, 0.0);
-
-    let diffuse_lod = max(max_mip - 2.0, 0.0);
-    let env_diffuse = textureSampleLevel(skybox_map, skybox_sampler, Nn, diffuse_lod).rgb;
+    let irradiance = textureSampleLevel(ibl_irradiance_map, ibl_sampler, Nn, 0.0).rgb;
 
+    // the specular mips are prefiltered for evenly spaced perceptual roughness
+    let max_mip = max(f32(textureNumLevels(ibl_specular_map)) - 1.0, 0.0);
     let R = reflect(-Vn, Nn);
-    let spec_lod = (perceptual_roughness * perceptual_roughness) * max_mip;
-    let prefiltered = textureSampleLevel(skybox_map, skybox_sampler, R, spec_lod).rgb;
+    let prefiltered =
+        textureSampleLevel(ibl_specular_map, ibl_sampler, R, perceptual_roughness * max_mip).rgb;
 
-    let brdf = env_brdf_approx(perceptual_roughness, NdotV);
+    let lut_uv = vec2<f32>(NdotV, perceptual_roughness);
+    let brdf = textureSampleLevel(ibl_brdf_lut, ibl_sampler, lut_uv, 0.0).rg;
     let specular = prefiltered * (F0 * brdf.x + brdf.y);
 
-    // if using real irradiance later, this becomes straight passthrough
-    let diffuse = env_diffuse * base * kD;
+    let diffuse = irradiance * base * kD;
 
     return (diffuse + specular) * IBL_STRENGTH;
 }
@@ -282,6 +283,71 @@ fn shadow_visibility_point(
     return visibility;
 }
 
+// Samples one cascade of a sun shadow. Returns the visibility and how far the position is
+// from the border of the cascade in uv space, negative if it's outside.
+fn sample_sun_cascade(
+    light: Light,
+    cascade: u32,
+    in_pos: vec3<f32>,
+    N: vec3<f32>,
+    L: vec3<f32>
+) -> vec2<f32> {
+    let M = shadow_mats[light.shadow_mat_base + cascade];
+
+    // the normal offset grows with the texel size of the cascade
+    let row0 = vec3<f32>(M[0].x, M[1].x, M[2].x);
+    let texel_world = 2.0 / max(length(row0), 1e-6) * shadow_texel.x;
+    let slope = 1.0 - max(dot(N, L), 0.0);
+    let world_pos_bias = in_pos + N * texel_world * (1.0 + slope);
+
+    let uvz = shadow_uvz_from_mat(M, world_pos_bias);
+    let border = min(min(uvz.x, 1.0 - uvz.x), min(uvz.y, 1.0 - uvz.y));
+    if (border < 0.0 || uvz.z < 0.0 || uvz.z > 1.0) {
+        return vec2<f32>(1.0, -1.0);
+    }
+
+    let layer = i32(light.shadow_map_id + cascade);
+    let bias = 0.0005 * slope;
+    let vis = pcf_3x3_fast(shadow_maps, shadow_sampler, uvz.xy, uvz.z - bias, layer);
+    return vec2<f32>(vis, border);
+}
+
+fn shadow_visibility_sun(
+    in_pos: vec3<f32>,
+    N: vec3<f32>,
+    L: vec3<f32>,
+    light: Light,
+    cast_shadows: bool
+) -> f32 {
+    if (!cast_shadows || light.shadow_map_id == 0xffffffffu || light.shadow_mat_base == 0xffffffffu) { return 1.0; }
+
+    // the border runs from 0 at the edge to 0.5 in the center of a cascade
+    let blend = max(light.cascade_blend, 1e-4) * 0.5;
+    let count = min(light.shadow_cascades, 4u);
+
+    for (var c: u32 = 0u; c < count; c = c + 1u) {
+        let current = sample_sun_cascade(light, c, in_pos, N, L);
+        if (current.y < 0.0) { continue; }
+
+        let t = saturate(current.y / blend);
+        if (c + 1u >= count) {
+            // shadows fade out at the end of the last cascade
+            return mix(1.0, current.x, t);
+        }
+        if (t >= 1.0) {
+            return current.x;
+        }
+
+        let next = sample_sun_cascade(light, c + 1u, in_pos, N, L);
+        if (next.y < 0.0) {
+            return current.x;
+        }
+        return mix(next.x, current.x, t);
+    }
+
+    return 1.0;
+}
+
 fn eval_spot(
     in_pos: vec3<f32>, N: vec3<f32>, V: vec3<f32>,
     base: vec3<f32>, metallic: f32, roughness: f32, light: Light, cast_shadows: bool
@@ -356,16 +422,57 @@ fn pbr_fragment(
     lit: u32,
     cast_shadows: u32,
     grayscale_diffuse: u32
+) -> FOutput {
+    return pbr_fragment_surface(
+        in, true, base_rgba, normal_in, roughness_in, metallic_in, alpha_in, lit, cast_shadows,
+        grayscale_diffuse, vec3<f32>(0.0), 1.0, ALPHA_MODE_BLEND, 0.0, 1u
+    );
+}
+
+fn pbr_fragment_surface(
+    in: FInput,
+    front_facing: bool,
+    base_rgba: vec4<f32>,
+    normal_in: vec3<f32>,
+    roughness_in: f32,
+    metallic_in: f32,
+    alpha_in: f32,
+    lit: u32,
+    cast_shadows: u32,
+    grayscale_diffuse: u32,
+    emissive: vec3<f32>,
+    occlusion: f32,
+    alpha_mode: u32,
+    alpha_cutoff: f32,
+    double_sided: u32
 ) -> FOutput {
     var out: FOutput;
 
+    if !front_facing && double_sided == 0u {
+        discard;
+    }
+
+    var alpha = base_rgba.a * alpha_in;
+    if alpha_mode == ALPHA_MODE_MASK {
+        if alpha < alpha_cutoff {
+            discard;
+        }
+        alpha = 1.0;
+    } else if alpha_mode == ALPHA_MODE_OPAQUE {
+        alpha = 1.0;
+    }
+
     let base = saturate(base_rgba.rgb);
 
     let metallic = clamp(metallic_in, 0.0, 1.0);
     let roughness = clamp(roughness_in, 0.045, 1.0);
+    let ao = clamp(occlusion, 0.0, 1.0);
 
-    // World normal
-    let N = safe_normalize(normal_in);
+    // World normal, facing the viewer on back faces of double-sided surfaces
+    var N = safe_normalize(normal_in);
+    if !front_facing {
+        N = -N;
+    }
     let V = safe_normalize(camera.position - in.position);   // to viewer
     let n_enc = oct_encode(N);
     out.out_normal = vec4(n_enc, 0.0, 1.0);
@@ -381,17 +488,18 @@ fn pbr_fragment(
     if lit == 0 {
         Lo = base;
     } else {
-        Lo += ibl_term(N, V, base, metallic, roughness);
-        Lo += base * (AMBIENT_STRENGTH * (1.0 - 0.04));
+        Lo += ibl_term(N, V, base, metallic, roughness) * ao;
+        Lo += base * (AMBIENT_STRENGTH * (1.0 - 0.04)) * ao;
     }
 
     let can_cast_shadows = cast_shadows != 0;
+    var sun_visibility = 1.0;
 
-    // Lights
-    const MAX_LIGHTS: u32 = 64u;
-    for (var i: u32 = 0u; i < MAX_LIGHTS; i = i + 1u) {
-        if (i >= light_count) { break; }
-        let Ld = lights[i];
+    // Point and spot lights of the cluster this fragment is in
+    let cluster = light_cluster_index(in.position);
+    let cluster_lights = light_cluster_count(cluster);
+    for (var i: u32 = 0u; i < cluster_lights; i = i + 1u) {
+        let Ld = lights[light_cluster_light(cluster, i)];
         if (Ld.type_id == LIGHT_TYPE_POINT) {
             Lo += eval_point(in.position, N, V, base, metallic, roughness, Ld, can_cast_shadows);
         } else if (Ld.type_id == LIGHT_TYPE_SPOT) {
@@ -399,11 +507,25 @@ fn pbr_fragment(
         }
     }
 
+    // only the primary sun has cascades, it's the one lighting the sky
+    let primary_sun = light_cluster_params.primary_sun;
+    if (primary_sun < light_count && lights[primary_sun].shadow_map_id != 0xffffffffu) {
+        let Ld = lights[primary_sun];
+        let L = safe_normalize(-Ld.direction);
+        sun_visibility = shadow_visibility_sun(in.position, N, L, Ld, can_cast_shadows);
+    }
+
     if (lit != 0) {
-        Lo += eval_sky_sun(N, V, base, metallic, roughness);
+        Lo += eval_sky_sun(N, V, base, metallic, roughness) * sun_visibility;
+    }
+
+    Lo += emissive;
+
+    if (light_cluster_params.debug_view != 0u) {
+        Lo = mix(Lo, light_cluster_heatmap(cluster), 0.75);
     }
 
-    out.out_color = vec4(Lo, base_rgba.a * alpha_in);
+    out.out_color = vec4(Lo, alpha);
     return out;
 }
 
@@ -197,13 +197,15 @@ impl ShaderGenerator {
         out.push_str(MESH3D_VERTEX);
         out.push('\n');
 
-        let ret = match pass {
-            MeshPass::Picking => "@location(0) vec4f",
-            MeshPass::Base | MeshPass::Shadow => "FOutput",
+        let signature = match pass {
+            MeshPass::Picking => "fs_main(in: FInput) -> @location(0) vec4f",
+            MeshPass::Base | MeshPass::Shadow => {
+                "fs_main(in: FInput, @builtin(front_facing) front_facing: bool) -> FOutput"
+            }
         };
 
-        out.push_str("@fragment\nfn fs_main(in: FInput) -> ");
-        out.push_str(ret);
+        out.push_str("@fragment\nfn ");
+        out.push_str(signature);
         out.push_str(" {\n");
         append_compilation_output(&mut out, compiled);
         out.push_str("}\n");
@@ -213,6 +215,8 @@ impl ShaderGenerator {
 
     pub fn build_post_process_shader(compiled: &ShaderCompilationOutput) -> String {
         let mut out = String::new();
+        out.push_str(MATH_HELPERS);
+        out.push('\n');
         out.push_str(POST_PROCESS_GROUP);
         out.push('\n');
         out.push_str(POST_PROCESS_VERTEX);
@@ -325,11 +329,9 @@ fn append_use_group(
         ),
         "light" | "shadow" => append_block_once(out, &mut imported.light, LIGHT_GROUP),
         "post_process" => append_block_once(out, &mut imported.post_process, POST_PROCESS_GROUP),
-        "default_vertex" if allow_default_vertex => {
-            if !imported.default_vertex {
-                push_default_mesh_groups(out);
-                imported.default_vertex = true;
-            }
+        "default_vertex" if allow_default_vertex && !imported.default_vertex => {
+            push_default_mesh_groups(out);
+            imported.default_vertex = true;
         }
         _ => {}
     }
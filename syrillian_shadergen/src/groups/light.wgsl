@@ -17,16 +17,70 @@ struct Light {
     type_id: u32,
     shadow_map_id: u32,
     shadow_mat_base: u32,
+    shadow_cascades: u32,
+    cascade_split_lambda: f32,
+    shadow_distance: f32,
+    cascade_blend: f32,
+}
+
+struct LightClusterParams {
+    grid: vec3<u32>,
+    // u32 per cluster, the light count followed by the light indices
+    stride: u32,
+    light_count: u32,
+    primary_sun: u32,
+    debug_view: u32,
+    _pad0: u32,
 }
 
 @group(3) @binding(0) var<uniform> light_count: u32;
 @group(3) @binding(1) var<storage, read> lights: array<Light>;
+@group(3) @binding(2) var<uniform> light_cluster_params: LightClusterParams;
+@group(3) @binding(3) var<storage, read> light_clusters: array<u32>;
 
 @group(4) @binding(0) var shadow_maps: texture_depth_2d_array;
 @group(4) @binding(1) var shadow_sampler: sampler_comparison;
 @group(4) @binding(2) var<storage, read> shadow_mats: array<mat4x4<f32>>;
 @group(4) @binding(3) var<uniform> shadow_texel: vec2<f32>;
 
+// The cluster of the view frustum that contains the world position. Clusters are screen tiles,
+// split into depth slices that are spaced logarithmically between the near and far plane.
+fn light_cluster_index(world_pos: vec3<f32>) -> u32 {
+    let grid = light_cluster_params.grid;
+
+    let clip = camera.view_proj_mat * vec4<f32>(world_pos, 1.0);
+    let ndc = clip.xy / max(abs(clip.w), 1e-6) * sign(clip.w);
+    let tile = clamp(
+        vec2<i32>(floor((ndc * 0.5 + 0.5) * vec2<f32>(grid.xy))),
+        vec2<i32>(0),
+        vec2<i32>(grid.xy) - 1,
+    );
+
+    let near = max(camera.near, 1e-4);
+    let far = max(camera.far, near * 1.001);
+    let depth = -(camera.view_mat * vec4<f32>(world_pos, 1.0)).z;
+    let slice_f = log(max(depth, near) / near) / log(far / near) * f32(grid.z);
+    let slice = min(u32(max(slice_f, 0.0)), grid.z - 1u);
+
+    return (slice * grid.y + u32(tile.y)) * grid.x + u32(tile.x);
+}
+
+fn light_cluster_count(cluster: u32) -> u32 {
+    return light_clusters[cluster * light_cluster_params.stride];
+}
+
+fn light_cluster_light(cluster: u32, i: u32) -> u32 {
+    return light_clusters[cluster * light_cluster_params.stride + 1u + i];
+}
+
+// Heatmap of the light count in a cluster, from dark blue for no lights to red for full ones
+fn light_cluster_heatmap(cluster: u32) -> vec3<f32> {
+    let capacity = max(light_cluster_params.stride - 1u, 1u);
+    let t = saturate(f32(light_cluster_count(cluster)) / f32(capacity));
+    let cold = mix(vec3<f32>(0.0, 0.0, 0.2), vec3<f32>(0.0, 1.0, 0.0), saturate(t * 2.0));
+    return mix(cold, vec3<f32>(1.0, 0.0, 0.0), saturate(t * 2.0 - 1.0));
+}
+
 const LUMA: vec3<f32> = vec3<f32>(0.2126, 0.7152, 0.0722);
 
 fn luma(c: vec3<f32>) -> f32 { return dot(c, LUMA); }
@@ -138,6 +192,13 @@ fn atmosphere_eval(view_dir: vec3<f32>, sun_dir: vec3<f32>) -> AtmosphereEval {
     return AtmosphereEval(T_view, T_sun, sky_L, sun_color, haze);
 }
 
+// The skybox cubemap color seen through the atmosphere, above the horizon
+fn sky_over_environment(env: vec3<f32>, view_dir: vec3<f32>, atm: AtmosphereEval) -> vec3<f32> {
+    let hemi = smoothstep(-0.02, 0.02, view_dir.y);
+    let atm_applied = env * atm.transmittance_view + atm.sky_radiance;
+    return mix(env, atm_applied, hemi);
+}
+
 fn sky_sun_disk_add(view_dir: vec3<f32>, sun_dir: vec3<f32>, sky_color: vec3<f32>, atm: AtmosphereEval) -> vec3<f32> {
     let sun_strength = max(sky.sun_strength, 0.0);
     let sun_intensity = max(sky.sun_intensity, 0.0);
@@ -9,5 +9,18 @@ struct Material {
     lit: u32,
     cast_shadows: u32,
     grayscale_diffuse: u32,
+    emissive: vec3<f32>,
+    emissive_strength: f32,
+    occlusion_strength: f32,
+    alpha_cutoff: f32,
+    alpha_mode: u32,
+    double_sided: u32,
+    use_emissive_texture: u32,
+    use_occlusion_texture: u32,
+    uv_offset: vec2<f32>,
+    uv_scale: vec2<f32>,
+    uv_rotation: f32,
+    use_metallic_texture: u32,
+    uv_sets: u32,
 };
 var<immediate> material: Material;
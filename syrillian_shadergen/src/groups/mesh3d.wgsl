@@ -2,7 +2,27 @@ struct VInput {
     @location(0) position: vec3<f32>,
     @location(1) uv:       vec2<f32>,
     @location(2) normal:   vec3<f32>,
-    @location(3) tangent:  vec3<f32>,
+    @location(3) tangent:  vec4<f32>, // w is the handedness of the tangent frame
+    @location(6) color:    vec4<f32>,
+    @location(7) uv1:      vec2<f32>,
+}
+
+// Per-instance stream, the identity for draws that aren't instanced
+struct InstanceInput {
+    @location(8)  transform_0: vec4<f32>,
+    @location(9)  transform_1: vec4<f32>,
+    @location(10) transform_2: vec4<f32>,
+    @location(11) transform_3: vec4<f32>,
+    @location(12) pick_color:  vec4<f32>,
+}
+
+fn instance_transform(instance: InstanceInput) -> mat4x4<f32> {
+    return mat4x4<f32>(
+        instance.transform_0,
+        instance.transform_1,
+        instance.transform_2,
+        instance.transform_3,
+    );
 }
 
 struct FInput {
@@ -12,6 +32,9 @@ struct FInput {
     @location(2) normal:     vec3<f32>,
     @location(3) tangent:    vec3<f32>,
     @location(4) bitangent:  vec3<f32>,
+    @location(5) color:      vec4<f32>,
+    @location(6) uv1:        vec2<f32>,
+    @location(7) @interpolate(flat) pick_color: vec4<f32>,
 }
 
 struct FOutput {
@@ -13,32 +13,44 @@ var postDepth: texture_depth_2d;
 var postNormal: texture_2d<f32>;
 @group(1) @binding(4)
 var postMaterial: texture_2d<f32>;
+@group(1) @binding(5)
+var postVelocity: texture_2d<f32>;
 
-fn post_rrt_and_odt_fit(v: vec3f) -> vec3f {
-    let a = v * (v + 0.0245786) - 0.000090537;
-    let b = v * (0.983729 * v + 0.4329510) + 0.238081;
-    return a / b;
+struct PostProcessParams {
+    values: array<vec4f, 4>,
+};
+
+@group(1) @binding(6)
+var<uniform> postParams: PostProcessParams;
+
+fn post_pixel(uv: vec2f, size: vec2u) -> vec2i {
+    let max_pixel = vec2i(size) - vec2i(1);
+    return clamp(vec2i(uv * vec2f(size)), vec2i(0), max_pixel);
+}
+
+// Scene depth at `uv`, as stored in the depth buffer
+fn post_depth(uv: vec2f) -> f32 {
+    return textureLoad(postDepth, post_pixel(uv, textureDimensions(postDepth)), 0);
+}
+
+// World space normal at `uv`
+fn post_normal(uv: vec2f) -> vec3f {
+    let encoded = textureLoad(postNormal, post_pixel(uv, textureDimensions(postNormal)), 0).xy;
+    return oct_decode(encoded);
+}
+
+// Material properties at `uv`, as written by the mesh shaders
+fn post_material(uv: vec2f) -> vec4f {
+    return textureLoad(postMaterial, post_pixel(uv, textureDimensions(postMaterial)), 0);
 }
 
-fn post_tonemap_aces(color: vec3f) -> vec3f {
-    let aces_in = mat3x3<f32>(
-        vec3f(0.59719, 0.07600, 0.02840),
-        vec3f(0.35458, 0.90834, 0.13383),
-        vec3f(0.04823, 0.01566, 0.83777),
-    );
-    let aces_out = mat3x3<f32>(
-        vec3f(1.60475, -0.10208, -0.00327),
-        vec3f(-0.53108, 1.10813, -0.07276),
-        vec3f(-0.07367, -0.00605, 1.07602),
-    );
-
-    let v = aces_in * color;
-    let r = post_rrt_and_odt_fit(v);
-    let o = aces_out * r;
-    return clamp(o, vec3f(0.0), vec3f(1.0));
+// Screen space motion at `uv` since the last frame
+fn post_velocity(uv: vec2f) -> vec2f {
+    return textureLoad(postVelocity, post_pixel(uv, textureDimensions(postVelocity)), 0).xy;
 }
 
-fn post_color_grade(color: vec4f) -> vec4f {
-    let graded = post_tonemap_aces(max(color.rgb, vec3f(0.0)));
-    return vec4f(graded, color.a);
+// Parameter `index` of the pass, 0 to 15
+fn post_param(index: u32) -> f32 {
+    let i = min(index, 15u);
+    return postParams.values[i / 4u][i % 4u];
 }
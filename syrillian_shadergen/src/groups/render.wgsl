@@ -9,6 +9,11 @@ struct CameraData {
     far: f32,
     fov_target: f32,
     zoom_speed: f32,
+    prev_view_proj_mat: mat4x4<f32>,
+    jitter: vec2<f32>,
+    focus_distance: f32,
+    aperture: f32,
+    focal_length: f32,
 }
 
 struct SystemData {
@@ -35,3 +40,7 @@ struct SkyData {
 @group(0) @binding(2) var skybox_map: texture_cube<f32>;
 @group(0) @binding(3) var skybox_sampler: sampler;
 @group(0) @binding(4) var<uniform> sky: SkyData;
+@group(0) @binding(5) var ibl_irradiance_map: texture_cube<f32>;
+@group(0) @binding(6) var ibl_specular_map: texture_cube<f32>;
+@group(0) @binding(7) var ibl_brdf_lut: texture_2d<f32>;
+@group(0) @binding(8) var ibl_sampler: sampler;
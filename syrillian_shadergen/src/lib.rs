@@ -5,5 +5,5 @@ pub mod generator;
 pub mod value;
 
 pub use chunks::NodeId;
-pub use compiler::{MaterialCompiler, PostProcessCompiler};
+pub use compiler::{MaterialCompiler, PbrSurface, PostProcessCompiler};
 pub use generator::ShaderGenerator;
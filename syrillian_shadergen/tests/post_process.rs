@@ -1,5 +1,9 @@
 use syrillian_shadergen::PostProcessCompiler;
-use syrillian_shadergen::function::PostProcessPassthroughMaterial;
+use syrillian_shadergen::chunks::NodeId;
+use syrillian_shadergen::function::{
+    MaterialExpressionValue, PostProcessMaterialExpression, PostProcessPassthroughMaterial,
+};
+use syrillian_shadergen::value::MaterialValueType;
 
 #[test]
 fn compiles_post_process_passthrough() {
@@ -11,3 +15,33 @@ fn compiles_post_process_passthrough() {
     assert!(wgsl.contains("fn fs_main(in: FInput) -> @location(0) vec4f"));
     assert!(wgsl.contains("textureSample(postTexture, postSampler, in.uv)"));
 }
+
+struct DepthTintMaterial;
+
+impl PostProcessMaterialExpression for DepthTintMaterial {
+    fn outputs(&self) -> Vec<MaterialExpressionValue> {
+        vec![MaterialExpressionValue {
+            name: "color",
+            value_type: MaterialValueType::Vec4,
+        }]
+    }
+
+    fn compile(&self, compiler: &mut PostProcessCompiler, _output_index: u32) -> NodeId {
+        let uv = compiler.vertex_uv();
+        let (tex, sampler) = compiler.post_surface_input();
+        let color = compiler.texture_sample(tex, sampler, uv);
+        let depth = compiler.post_depth(uv);
+        let strength = compiler.post_param(2);
+        let tint = compiler.mul(depth, strength);
+        compiler.mul(color, tint)
+    }
+}
+
+#[test]
+fn compiles_scene_inputs_and_params() {
+    let wgsl = PostProcessCompiler::compile_post_process(&DepthTintMaterial, 0);
+
+    assert!(wgsl.contains("post_depth(in.uv)"));
+    assert!(wgsl.contains("post_param(2u)"));
+    assert!(wgsl.contains("var<uniform> postParams: PostProcessParams"));
+}
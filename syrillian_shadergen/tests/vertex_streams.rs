@@ -0,0 +1,81 @@
+use syrillian_shadergen::MaterialCompiler;
+use syrillian_shadergen::chunks::NodeId;
+use syrillian_shadergen::function::{MaterialExpression, MaterialExpressionValue, PbrShader};
+use syrillian_shadergen::generator::MeshPass;
+use syrillian_shadergen::value::MaterialValueType;
+
+struct LightmapTintMaterial;
+
+impl MaterialExpression for LightmapTintMaterial {
+    fn outputs(&self) -> Vec<MaterialExpressionValue> {
+        vec![MaterialExpressionValue {
+            name: "out",
+            value_type: MaterialValueType::Vec4,
+        }]
+    }
+
+    fn compile(&self, compiler: &mut MaterialCompiler, _output_index: u32) -> NodeId {
+        let color = compiler.vertex_color();
+        let uv1 = compiler.vertex_uv(1);
+        let lightmap = compiler.swizzle(uv1, "x");
+        compiler.mul(color, lightmap)
+    }
+}
+
+#[test]
+fn emits_secondary_vertex_streams() {
+    let mut material = LightmapTintMaterial;
+    let wgsl = MaterialCompiler::compile_mesh(&mut material, 0, MeshPass::Picking);
+
+    assert!(wgsl.contains("@location(6) color"));
+    assert!(wgsl.contains("@location(7) uv1"));
+    assert!(wgsl.contains("(in.color * in.uv1.x)"));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Only uv sets 0 and 1 are available, got 2")]
+fn rejects_unknown_uv_sets() {
+    MaterialCompiler::new().vertex_uv(2);
+}
+
+#[test]
+fn pbr_base_color_is_tinted_by_vertex_color() {
+    let mut pbr = PbrShader::default();
+    let wgsl = MaterialCompiler::compile_mesh(&mut pbr, 0, MeshPass::Base);
+
+    assert!(wgsl.contains("* in.color)"));
+}
+
+#[test]
+fn pbr_textures_select_their_uv_set() {
+    let mut pbr = PbrShader::default();
+    let wgsl = MaterialCompiler::compile_mesh(&mut pbr, 0, MeshPass::Base);
+
+    let occlusion_bit = PbrShader::UV_SET_TEXTURES
+        .iter()
+        .position(|texture| *texture == "occlusion")
+        .unwrap();
+    assert!(wgsl.contains("transform_uv(in.uv1,"));
+    assert!(wgsl.contains(&format!(
+        "((material.uv_sets >> {occlusion_bit}u) & 1u) != 0u"
+    )));
+}
+
+#[test]
+fn mesh_vertex_applies_instance_stream() {
+    let mut pbr = PbrShader::default();
+    let wgsl = MaterialCompiler::compile_mesh(&mut pbr, 0, MeshPass::Base);
+
+    assert!(wgsl.contains("@location(8)  transform_0"));
+    assert!(wgsl.contains("fn vs_main(in: VInput, instance: InstanceInput) -> FInput"));
+    assert!(wgsl.contains("model.transform * instance_transform(instance)"));
+}
+
+#[test]
+fn picking_adds_instance_pick_color() {
+    let wgsl = MaterialCompiler::compile_mesh_picking();
+
+    assert!(wgsl.contains("out.pick_color = instance.pick_color"));
+    assert!(wgsl.contains("(pick.color + in.pick_color)"));
+}
@@ -147,6 +147,24 @@ impl BoundingSphere {
         }
     }
 
+    /// Smallest sphere enclosing both spheres.
+    pub fn merged(&self, other: &Self) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.length();
+
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+
+        let radius = (distance + self.radius + other.radius) * 0.5;
+        let center = self.center + offset * ((radius - self.radius) / distance);
+
+        Self { center, radius }
+    }
+
     pub fn from_corners(corners: &[Vec3; 8]) -> Self {
         let mut center = Vec3::ZERO;
         let mut count = 0;
@@ -174,3 +192,40 @@ impl BoundingSphere {
         Self { center, radius }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_sphere_encloses_both() {
+        let a = BoundingSphere {
+            center: Vec3::ZERO,
+            radius: 1.0,
+        };
+        let b = BoundingSphere {
+            center: Vec3::new(4.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+
+        let merged = a.merged(&b);
+        assert!((merged.center - Vec3::new(2.0, 0.0, 0.0)).length() < 1e-5);
+        assert!((merged.radius - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn merged_sphere_keeps_enclosing_sphere() {
+        let outer = BoundingSphere {
+            center: Vec3::ZERO,
+            radius: 5.0,
+        };
+        let inner = BoundingSphere {
+            center: Vec3::new(1.0, 1.0, 0.0),
+            radius: 1.0,
+        };
+
+        let merged = inner.merged(&outer);
+        assert_eq!(merged.center, outer.center);
+        assert_eq!(merged.radius, outer.radius);
+    }
+}
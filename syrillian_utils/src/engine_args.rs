@@ -8,6 +8,8 @@ pub enum AntiAliasingMode {
     Off,
     #[default]
     Fxaa,
+    /// Temporal anti-aliasing, blending jittered frames over time
+    Taa,
 }
 
 fn present_mode(mode: &str) -> Result<Option<wgpu::PresentMode>, String> {
@@ -64,6 +66,7 @@ fn aa_mode(mode: &str) -> Result<Option<AntiAliasingMode>, String> {
     let mode = match mode {
         "off" => AntiAliasingMode::Off,
         "fxaa" => AntiAliasingMode::Fxaa,
+        "taa" => AntiAliasingMode::Taa,
         _ => return Ok(None),
     };
     Ok(Some(mode))
@@ -86,6 +89,10 @@ pub struct EngineArgs {
     pub no_ssao: bool,
     #[argh(switch, hidden_help)]
     pub no_bloom: bool,
+    #[argh(switch, hidden_help)]
+    pub no_motion_blur: bool,
+    #[argh(switch, hidden_help)]
+    pub no_dof: bool,
 
     #[argh(option, hidden_help)]
     pub max_frames_in_flight: Option<u32>,
@@ -112,6 +119,8 @@ pub struct EngineArgs {
     pub bloom_clamp_max: Option<f32>,
     #[argh(option, hidden_help)]
     pub bloom_blur_passes: Option<u32>,
+    #[argh(option, hidden_help)]
+    pub lod_bias: Option<f32>,
 }
 
 impl EngineArgs {